@@ -1,5 +1,12 @@
 use {
-    crate::{append_vec::AppendVecStoredAccountMeta, storable_accounts::StorableAccounts},
+    crate::{
+        append_vec::AppendVecStoredAccountMeta,
+        storable_accounts::StorableAccounts,
+        tiered_storage::{
+            cold::ColdReadableAccount, hot::HotAccountMeta, index_to_offset,
+            readable::TieredReadableAccount,
+        },
+    },
     solana_sdk::{account::ReadableAccount, hash::Hash, pubkey::Pubkey, stake_history::Epoch},
     std::{borrow::Borrow, marker::PhantomData},
 };
@@ -100,66 +107,88 @@ impl<'a: 'b, 'b, T: ReadableAccount + Sync + 'b, U: StorableAccounts<'a, T>, V:
 #[derive(PartialEq, Eq, Debug)]
 pub enum StoredAccountMeta<'storage> {
     AppendVec(AppendVecStoredAccountMeta<'storage>),
+    Hot(TieredReadableAccount<'storage, HotAccountMeta>),
+    Cold(ColdReadableAccount<'storage>),
 }
 
+/// The hash returned for tiered accounts that do not persist their hash.
+static DEFAULT_ACCOUNT_HASH: Hash = Hash::new_from_array([0u8; 32]);
+
 impl<'storage> StoredAccountMeta<'storage> {
     pub fn pubkey(&self) -> &'storage Pubkey {
         match self {
             Self::AppendVec(av) => av.pubkey(),
+            Self::Hot(hot) => hot.address(),
+            Self::Cold(cold) => cold.address(),
         }
     }
 
-    pub fn hash(&self) -> &'storage Hash {
+    pub fn hash(&self) -> &Hash {
         match self {
             Self::AppendVec(av) => av.hash(),
+            Self::Hot(hot) => hot.hash().unwrap_or(&DEFAULT_ACCOUNT_HASH),
+            Self::Cold(cold) => cold.hash().unwrap_or(&DEFAULT_ACCOUNT_HASH),
         }
     }
 
     pub fn stored_size(&self) -> usize {
         match self {
             Self::AppendVec(av) => av.stored_size(),
+            Self::Hot(hot) => std::mem::size_of::<HotAccountMeta>() + hot.account_block.len(),
+            Self::Cold(cold) => cold.stored_size(),
         }
     }
 
     pub fn offset(&self) -> usize {
         match self {
             Self::AppendVec(av) => av.offset(),
+            Self::Hot(hot) => index_to_offset(hot.index()),
+            Self::Cold(cold) => index_to_offset(cold.index()),
         }
     }
 
-    pub fn data(&self) -> &'storage [u8] {
+    pub fn data(&self) -> &[u8] {
         match self {
             Self::AppendVec(av) => av.data(),
+            Self::Hot(hot) => hot.data(),
+            Self::Cold(cold) => cold.data(),
         }
     }
 
     pub fn data_len(&self) -> u64 {
         match self {
             Self::AppendVec(av) => av.data_len(),
+            Self::Hot(hot) => hot.data().len() as u64,
+            Self::Cold(cold) => cold.data().len() as u64,
         }
     }
 
     pub fn write_version(&self) -> StoredMetaWriteVersion {
         match self {
             Self::AppendVec(av) => av.write_version(),
+            Self::Hot(hot) => hot.write_version().unwrap_or_default(),
+            Self::Cold(cold) => cold.write_version().unwrap_or_default(),
         }
     }
 
     pub fn meta(&self) -> &StoredMeta {
         match self {
             Self::AppendVec(av) => av.meta(),
+            Self::Hot(_) | Self::Cold(_) => unreachable!("tiered accounts do not have StoredMeta"),
         }
     }
 
     pub fn set_meta(&mut self, meta: &'storage StoredMeta) {
         match self {
             Self::AppendVec(av) => av.set_meta(meta),
+            Self::Hot(_) | Self::Cold(_) => unreachable!("tiered accounts do not have StoredMeta"),
         }
     }
 
     pub(crate) fn sanitize(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.sanitize(),
+            Self::Hot(_) | Self::Cold(_) => true,
         }
     }
 }
@@ -168,26 +197,36 @@ impl<'storage> ReadableAccount for StoredAccountMeta<'storage> {
     fn lamports(&self) -> u64 {
         match self {
             Self::AppendVec(av) => av.lamports(),
+            Self::Hot(hot) => hot.lamports(),
+            Self::Cold(cold) => cold.lamports(),
         }
     }
     fn data(&self) -> &[u8] {
         match self {
             Self::AppendVec(av) => av.data(),
+            Self::Hot(hot) => hot.data(),
+            Self::Cold(cold) => cold.data(),
         }
     }
     fn owner(&self) -> &Pubkey {
         match self {
             Self::AppendVec(av) => av.owner(),
+            Self::Hot(hot) => hot.owner(),
+            Self::Cold(cold) => cold.owner(),
         }
     }
     fn executable(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.executable(),
+            Self::Hot(hot) => hot.executable(),
+            Self::Cold(cold) => cold.executable(),
         }
     }
     fn rent_epoch(&self) -> Epoch {
         match self {
             Self::AppendVec(av) => av.rent_epoch(),
+            Self::Hot(hot) => hot.rent_epoch(),
+            Self::Cold(cold) => cold.rent_epoch(),
         }
     }
 }
@@ -1022,6 +1022,113 @@ impl Accounts {
             .map(|_| collector)
     }
 
+    /// Loads, in pubkey order, the first `limit` accounts with a pubkey in `range` that are owned
+    /// by `program_id` and match `filter`. The accounts are found through the `index_key`
+    /// secondary index if any, and the scan stops as soon as `limit` accounts are found.
+    pub fn load_page_by_program_with_filter<R, F>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        index_key: Option<&IndexKey>,
+        range: R,
+        filter: F,
+        limit: usize,
+    ) -> ScanResult<Vec<TransactionAccount>>
+    where
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+        F: Fn(&LoadedAccount) -> bool,
+    {
+        let mut collector = Vec::new();
+        self.scan_page_by_program(
+            ancestors,
+            bank_id,
+            program_id,
+            index_key,
+            range,
+            filter,
+            limit,
+            |pubkey, loaded_account| collector.push((*pubkey, loaded_account.take_account())),
+        )
+        .map(|_| collector)
+    }
+
+    /// Same as `load_page_by_program_with_filter()`, but only returns the pubkeys of the
+    /// accounts, without copying them
+    pub fn load_page_keys_by_program_with_filter<R, F>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        index_key: Option<&IndexKey>,
+        range: R,
+        filter: F,
+        limit: usize,
+    ) -> ScanResult<Vec<Pubkey>>
+    where
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+        F: Fn(&LoadedAccount) -> bool,
+    {
+        let mut collector = Vec::new();
+        self.scan_page_by_program(
+            ancestors,
+            bank_id,
+            program_id,
+            index_key,
+            range,
+            filter,
+            limit,
+            |pubkey, _loaded_account| collector.push(*pubkey),
+        )
+        .map(|_| collector)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn scan_page_by_program<R, F, C>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        index_key: Option<&IndexKey>,
+        range: R,
+        filter: F,
+        limit: usize,
+        mut collect: C,
+    ) -> ScanResult<()>
+    where
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+        F: Fn(&LoadedAccount) -> bool,
+        C: FnMut(&Pubkey, LoadedAccount),
+    {
+        if limit == 0 {
+            return Ok(());
+        }
+        // the accounts are scanned in pubkey order, until the page is full
+        let config = ScanConfig::default().recreate_with_abort();
+        let mut num_collected = 0;
+        let scan_func = |pubkey: &Pubkey, loaded_account: LoadedAccount, _slot| {
+            if num_collected < limit
+                && Self::is_loadable(loaded_account.lamports())
+                && loaded_account.owner() == program_id
+                && filter(&loaded_account)
+            {
+                collect(pubkey, loaded_account);
+                num_collected += 1;
+                if num_collected == limit {
+                    config.abort();
+                }
+            }
+        };
+        match index_key {
+            Some(index_key) => self.accounts_db.index_scan_accounts_in_range(
+                ancestors, bank_id, *index_key, range, scan_func, &config,
+            ),
+            None => self
+                .accounts_db
+                .scan_accounts_in_range(ancestors, bank_id, range, scan_func, &config),
+        }
+    }
+
     fn calc_scan_result_size(account: &AccountSharedData) -> usize {
         account.data().len()
             + std::mem::size_of::<AccountSharedData>()
@@ -1496,6 +1603,7 @@ mod tests {
         std::{
             borrow::Cow,
             convert::TryFrom,
+            ops::Bound,
             sync::atomic::{AtomicBool, AtomicU64, Ordering},
             thread, time,
         },
@@ -1522,6 +1630,7 @@ mod tests {
                 status,
                 log_messages: None,
                 inner_instructions: None,
+                instruction_trace: None,
                 durable_nonce_fee: nonce.map(DurableNonceFee::from),
                 return_data: None,
                 executed_units: 0,
@@ -2449,6 +2558,88 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_page_by_program_with_filter() {
+        let program_id = Pubkey::new_unique();
+        let mut pubkeys = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        pubkeys.sort_unstable();
+        let account_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: [crate::accounts_index::AccountIndex::ProgramId]
+                .into_iter()
+                .collect(),
+        };
+
+        for account_indexes in [AccountSecondaryIndexes::default(), account_indexes] {
+            let index_key = account_indexes
+                .contains(&crate::accounts_index::AccountIndex::ProgramId)
+                .then_some(IndexKey::ProgramId(program_id));
+            let accounts = Accounts::new_with_config_for_tests(
+                Vec::new(),
+                &ClusterType::Development,
+                account_indexes,
+                AccountShrinkThreshold::default(),
+            );
+            // the accounts of odd index have data, and the last account has another owner
+            for (i, pubkey) in pubkeys.iter().enumerate() {
+                let owner = if i == 4 {
+                    Pubkey::new_unique()
+                } else {
+                    program_id
+                };
+                let account = AccountSharedData::new(1, i % 2, &owner);
+                accounts.store_slow_uncached(0, pubkey, &account);
+            }
+            let ancestors = Ancestors::from(vec![0]);
+
+            let load_page = |range, limit| {
+                accounts
+                    .load_page_keys_by_program_with_filter(
+                        &ancestors,
+                        0,
+                        &program_id,
+                        index_key.as_ref(),
+                        range,
+                        |_| true,
+                        limit,
+                    )
+                    .unwrap()
+            };
+            assert_eq!(
+                load_page((Bound::Unbounded, Bound::Unbounded), 2),
+                pubkeys[..2]
+            );
+            assert_eq!(
+                load_page((Bound::Excluded(pubkeys[1]), Bound::Unbounded), 2),
+                pubkeys[2..4]
+            );
+            assert_eq!(
+                load_page((Bound::Excluded(pubkeys[3]), Bound::Unbounded), 2),
+                Vec::<Pubkey>::new()
+            );
+            assert_eq!(
+                load_page((Bound::Unbounded, Bound::Unbounded), 0),
+                Vec::<Pubkey>::new()
+            );
+
+            let loaded = accounts
+                .load_page_by_program_with_filter(
+                    &ancestors,
+                    0,
+                    &program_id,
+                    index_key.as_ref(),
+                    (Bound::Excluded(pubkeys[0]), Bound::Unbounded),
+                    |account| account.data().len() == 1,
+                    1,
+                )
+                .unwrap();
+            assert_eq!(
+                loaded,
+                vec![(pubkeys[1], AccountSharedData::new(1, 1, &program_id))]
+            );
+        }
+    }
+
     #[test]
     fn test_load_accounts_executable_with_write_lock() {
         let mut accounts: Vec<TransactionAccount> = Vec::new();
@@ -31,7 +31,7 @@ use {
             AccountStorage, AccountStorageStatus, ShrinkInProgress,
         },
         accounts_cache::{AccountsCache, CachedAccount, SlotCache},
-        accounts_file::{AccountsFile, AccountsFileError},
+        accounts_file::{AccountsFile, AccountsFileError, AccountsFileProvider},
         accounts_hash::{
             AccountsDeltaHash, AccountsHash, AccountsHashKind, AccountsHasher,
             CalcAccountsHashConfig, CalculateHashIntermediate, HashStats, IncrementalAccountsHash,
@@ -41,9 +41,9 @@ use {
         accounts_index::{
             AccountIndexGetResult, AccountMapEntry, AccountSecondaryIndexes, AccountsIndex,
             AccountsIndexConfig, AccountsIndexRootsStats, AccountsIndexScanResult, DiskIndexValue,
-            IndexKey, IndexValue, IsCached, RefCount, ScanConfig, ScanResult, SlotList,
-            UpsertReclaim, ZeroLamport, ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS,
-            ACCOUNTS_INDEX_CONFIG_FOR_TESTING,
+            IndexKey, IndexValue, IsCached, RefCount, ScanConfig, ScanResult,
+            SecondaryIndexesSnapshot, SlotList, UpsertReclaim, ZeroLamport,
+            ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS, ACCOUNTS_INDEX_CONFIG_FOR_TESTING,
         },
         accounts_index_storage::Startup,
         accounts_partition::RentPayingAccountsByPartition,
@@ -67,6 +67,7 @@ use {
         rent_collector::RentCollector,
         sorted_storages::SortedStorages,
         storable_accounts::StorableAccounts,
+        tiered_storage::{cold::COLD_FORMAT, hot::HOT_FORMAT, TieredStorage, TieredStorageFormat},
         verify_accounts_hash_in_background::VerifyAccountsHashInBackground,
     },
     blake3::traits::digest::Digest,
@@ -86,7 +87,7 @@ use {
         clock::{BankId, Epoch, Slot},
         epoch_schedule::EpochSchedule,
         genesis_config::{ClusterType, GenesisConfig},
-        hash::Hash,
+        hash::{self, Hash, Hasher, HASH_BYTES},
         pubkey::Pubkey,
         rent::Rent,
         saturating_add_assign,
@@ -98,7 +99,7 @@ use {
         boxed::Box,
         collections::{hash_map, BTreeSet, HashMap, HashSet},
         hash::{Hash as StdHash, Hasher as StdHasher},
-        io::Result as IoResult,
+        io::{self, BufWriter, Result as IoResult, Seek, Write},
         ops::{Range, RangeBounds},
         path::{Path, PathBuf},
         str::FromStr,
@@ -106,7 +107,7 @@ use {
             atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
             Arc, Condvar, Mutex, RwLock,
         },
-        thread::{sleep, Builder},
+        thread::{sleep, Builder, JoinHandle},
         time::{Duration, Instant},
     },
     tempfile::TempDir,
@@ -141,6 +142,10 @@ const MAX_ITEMS_PER_CHUNK: Slot = 2_500;
 // This allows us to split up accounts index accesses across multiple threads.
 const SHRINK_COLLECT_CHUNK_SIZE: usize = 50;
 
+/// The most alive bytes that a single round of shrink rewrites to convert
+/// append vecs into the configured accounts file format.
+const MAX_CONVERSION_BYTES_PER_SHRINK: u64 = 1024 * 1024 * 1024;
+
 /// temporary enum during feature activation of
 /// ignore slot when calculating an account hash #28420
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -164,6 +169,8 @@ pub enum CreateAncientStorage {
     Append,
     /// ancient storages are created by 1-shot write to pack multiple accounts together more efficiently with new formats
     Pack,
+    /// same as Pack, but ancient storages are written in the cold tiered storage format
+    PackCold,
 }
 
 #[derive(Debug)]
@@ -478,6 +485,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::CompareResults,
+    accounts_file_provider: AccountsFileProvider::AppendVec,
+    persist_secondary_indexes: false,
 };
 pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig {
     index: Some(ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS),
@@ -490,6 +499,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::None,
+    accounts_file_provider: AccountsFileProvider::AppendVec,
+    persist_secondary_indexes: false,
 };
 
 pub type BinnedHashData = Vec<Vec<CalculateHashIntermediate>>;
@@ -554,6 +565,10 @@ pub struct AccountsDbConfig {
     /// how to create ancient storages
     pub create_ancient_storage: CreateAncientStorage,
     pub test_partitioned_epoch_rewards: TestPartitionedEpochRewards,
+    /// the file format of newly created accounts files
+    pub accounts_file_provider: AccountsFileProvider,
+    /// persist the secondary indexes at each full snapshot, to restore them at startup
+    pub persist_secondary_indexes: bool,
 }
 
 #[cfg(not(test))]
@@ -1066,6 +1081,21 @@ impl AccountStorageEntry {
         }
     }
 
+    /// Creates a new storage entry backed by a tiered accounts file of the
+    /// specified format.  The accounts file is written by the first call to
+    /// append_accounts and is read-only afterwards.
+    pub fn new_tiered(
+        path: &Path,
+        slot: Slot,
+        id: AppendVecId,
+        format: TieredStorageFormat,
+    ) -> Self {
+        let tail = AccountsFile::tiered_file_name(slot, id);
+        let path = Path::new(path).join(tail);
+        let accounts = AccountsFile::TieredStorage(TieredStorage::new_writable(path, format));
+        Self::new_existing(slot, id, accounts, 0)
+    }
+
     pub fn new_existing(
         slot: Slot,
         id: AppendVecId,
@@ -1452,9 +1482,11 @@ pub struct AccountsDb {
 
     pub storage: AccountStorage,
 
-    #[allow(dead_code)]
     /// from AccountsDbConfig
-    create_ancient_storage: CreateAncientStorage,
+    pub(crate) create_ancient_storage: CreateAncientStorage,
+
+    /// from AccountsDbConfig
+    pub(crate) accounts_file_provider: AccountsFileProvider,
 
     pub accounts_cache: AccountsCache,
 
@@ -1519,6 +1551,17 @@ pub struct AccountsDb {
 
     pub account_indexes: AccountSecondaryIndexes,
 
+    /// true if the secondary indexes are persisted at each full snapshot
+    persist_secondary_indexes: bool,
+
+    /// true if the secondary indexes were restored from those persisted at the snapshot this
+    /// accounts db was rebuilt from, so that they are not generated from the stored accounts
+    /// Slot of the full snapshot the secondary indexes were restored at
+    secondary_indexes_restored_slot: Option<Slot>,
+
+    /// the background thread writing the secondary indexes persisted at the last full snapshot
+    secondary_indexes_writer: Mutex<Option<JoinHandle<()>>>,
+
     /// Set of unique keys per slot which is used
     /// to drive clean_accounts
     /// Generated by calculate_accounts_delta_hash
@@ -2415,6 +2458,32 @@ impl<'a> AppendVecScan for ScanState<'a> {
     }
 }
 
+/// The secondary indexes persisted at a bank snapshot
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedSecondaryIndexes {
+    slot: Slot,
+    account_indexes: AccountSecondaryIndexes,
+    indexes: SecondaryIndexesSnapshot,
+}
+
+/// Hashes everything written to `inner`
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Hasher,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.hash(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PubkeyHashAccount {
     pub pubkey: Pubkey,
@@ -2424,6 +2493,7 @@ pub struct PubkeyHashAccount {
 
 impl AccountsDb {
     pub const DEFAULT_ACCOUNTS_HASH_CACHE_DIR: &'static str = "accounts_hash_cache";
+    pub const DEFAULT_SECONDARY_INDEXES_DIR: &'static str = "secondary_indexes";
 
     pub fn default_for_tests() -> Self {
         Self::default_with_accounts_index(AccountInfoAccountsIndex::default_for_tests(), None, None)
@@ -2468,6 +2538,7 @@ impl AccountsDb {
 
         AccountsDb {
             create_ancient_storage: CreateAncientStorage::Pack,
+            accounts_file_provider: AccountsFileProvider::default(),
             verify_accounts_hash_in_bg: VerifyAccountsHashInBackground::default(),
             filler_accounts_per_slot: AtomicU64::default(),
             filler_account_slots_remaining: AtomicU64::default(),
@@ -2513,6 +2584,9 @@ impl AccountsDb {
             stats: AccountsStats::default(),
             cluster_type: None,
             account_indexes: AccountSecondaryIndexes::default(),
+            persist_secondary_indexes: false,
+            secondary_indexes_restored_slot: None,
+            secondary_indexes_writer: Mutex::default(),
             #[cfg(test)]
             load_delay: u64::default(),
             #[cfg(test)]
@@ -2594,10 +2668,29 @@ impl AccountsDb {
             .map(|config| config.exhaustively_verify_refcounts)
             .unwrap_or_default();
 
+        let accounts_file_provider = accounts_db_config
+            .as_ref()
+            .map(|config| config.accounts_file_provider)
+            .unwrap_or_default();
+
+        let persist_secondary_indexes = accounts_db_config
+            .as_ref()
+            .map(|config| config.persist_secondary_indexes)
+            .unwrap_or_default();
+
         let create_ancient_storage = accounts_db_config
             .as_ref()
             .map(|config| config.create_ancient_storage)
             .unwrap_or(CreateAncientStorage::Append);
+        // tiered accounts files are written only once, so they cannot be
+        // appended to as ancient storages.
+        let create_ancient_storage = if accounts_file_provider != AccountsFileProvider::AppendVec
+            && create_ancient_storage == CreateAncientStorage::Append
+        {
+            CreateAncientStorage::Pack
+        } else {
+            create_ancient_storage
+        };
 
         let test_partitioned_epoch_rewards = accounts_db_config
             .as_ref()
@@ -2624,11 +2717,13 @@ impl AccountsDb {
             filler_accounts_config,
             filler_account_suffix,
             create_ancient_storage,
+            accounts_file_provider,
             write_cache_limit_bytes: accounts_db_config
                 .as_ref()
                 .and_then(|x| x.write_cache_limit_bytes),
             partitioned_epoch_rewards_config,
             exhaustively_verify_refcounts,
+            persist_secondary_indexes,
             ..Self::default_with_accounts_index(
                 accounts_index,
                 base_working_path,
@@ -2700,7 +2795,14 @@ impl AccountsDb {
     }
 
     fn new_storage_entry(&self, slot: Slot, path: &Path, size: u64) -> AccountStorageEntry {
-        AccountStorageEntry::new(path, slot, self.next_id(), size)
+        match self.accounts_file_provider {
+            AccountsFileProvider::AppendVec => {
+                AccountStorageEntry::new(path, slot, self.next_id(), size)
+            }
+            AccountsFileProvider::HotStorage => {
+                AccountStorageEntry::new_tiered(path, slot, self.next_id(), HOT_FORMAT)
+            }
+        }
     }
 
     pub fn expected_cluster_type(&self) -> ClusterType {
@@ -4074,7 +4176,9 @@ impl AccountsDb {
             self.shrink_collect::<AliveAccounts<'_>>(store, &unique_accounts, &self.shrink_stats);
 
         // This shouldn't happen if alive_bytes/approx_stored_count are accurate
-        if Self::should_not_shrink(shrink_collect.aligned_total_bytes, shrink_collect.capacity) {
+        if Self::should_not_shrink(shrink_collect.aligned_total_bytes, shrink_collect.capacity)
+            && !self.is_pending_conversion(store)
+        {
             self.shrink_stats
                 .skipped_shrink
                 .fetch_add(1, Ordering::Relaxed);
@@ -4244,6 +4348,38 @@ impl AccountsDb {
         self.storage.shrinking_in_progress(slot, shrunken_store)
     }
 
+    /// return a store backed by a cold tiered accounts file
+    /// All accounts to shrink must be written to it at once.
+    pub(crate) fn get_cold_store_for_shrink(&self, slot: Slot) -> ShrinkInProgress<'_> {
+        self.stats
+            .create_store_count
+            .fetch_add(1, Ordering::Relaxed);
+        let maybe_shrink_paths = self.shrink_paths.read().unwrap();
+        let shrink_paths = maybe_shrink_paths.as_ref().unwrap_or(&self.paths);
+        let path_index = thread_rng().gen_range(0..shrink_paths.len());
+        let shrunken_store = Arc::new(AccountStorageEntry::new_tiered(
+            &shrink_paths[path_index],
+            slot,
+            self.next_id(),
+            COLD_FORMAT,
+        ));
+        self.storage.shrinking_in_progress(slot, shrunken_store)
+    }
+
+    /// Returns true if `store` is an append vec while new storages are created
+    /// in another accounts file format.
+    ///
+    /// Such append vecs are not converted up front.  Instead, they become
+    /// shrink candidates as soon as any of their accounts becomes dead, and
+    /// shrinking rewrites their alive accounts into a storage of the
+    /// configured format.  Conversions are charged against the shrink budget
+    /// and limited to MAX_CONVERSION_BYTES_PER_SHRINK per round, so the rest
+    /// wait for later rounds.
+    fn is_pending_conversion(&self, store: &AccountStorageEntry) -> bool {
+        self.accounts_file_provider != AccountsFileProvider::AppendVec
+            && matches!(store.accounts, AccountsFile::AppendVec(_))
+    }
+
     // Reads all accounts in given slot's AppendVecs and filter only to alive,
     // then create a minimum AppendVec filled with the alive.
     fn shrink_slot_forced(&self, slot: Slot) {
@@ -4253,7 +4389,7 @@ impl AccountsDb {
             .storage
             .get_slot_storage_entry_shrinking_in_progress_ok(slot)
         {
-            if !Self::is_shrinking_productive(slot, &store) {
+            if !Self::is_shrinking_productive(slot, &store) && !self.is_pending_conversion(&store) {
                 return;
             }
             self.do_shrink_slot_store(slot, &store)
@@ -4288,6 +4424,9 @@ impl AccountsDb {
         let mut candidates_count: usize = 0;
         let mut total_bytes: u64 = 0;
         let mut total_candidate_stores: usize = 0;
+        let mut shrink_slots_to_convert = HashMap::new();
+        let mut shrink_slots_next_batch = ShrinkCandidates::new();
+        let mut conversion_bytes: u64 = 0;
         for slot in shrink_slots {
             if oldest_non_ancient_slot
                 .map(|oldest_non_ancient_slot| slot < &oldest_non_ancient_slot)
@@ -4300,6 +4439,23 @@ impl AccountsDb {
                 continue;
             };
             candidates_count += 1;
+            if self.is_pending_conversion(&store) {
+                // shrunk regardless of usage to convert it, as long as the conversion budget allows
+                let alive_bytes = Self::page_align(store.alive_bytes() as u64);
+                if conversion_bytes > 0
+                    && conversion_bytes.saturating_add(alive_bytes)
+                        > MAX_CONVERSION_BYTES_PER_SHRINK
+                {
+                    shrink_slots_next_batch.insert(*slot);
+                    continue;
+                }
+                conversion_bytes += alive_bytes;
+                // once converted, the store only holds its alive bytes
+                total_alive_bytes += alive_bytes;
+                total_bytes += alive_bytes;
+                shrink_slots_to_convert.insert(*slot, store);
+                continue;
+            }
             total_alive_bytes += Self::page_align(store.alive_bytes() as u64);
             total_bytes += store.capacity();
             let alive_ratio =
@@ -4319,8 +4475,7 @@ impl AccountsDb {
 
         // Working from the beginning of store_usage which are the most sparse and see when we can stop
         // shrinking while still achieving the overall goals.
-        let mut shrink_slots = HashMap::new();
-        let mut shrink_slots_next_batch = ShrinkCandidates::new();
+        let mut shrink_slots = shrink_slots_to_convert;
         for usage in &store_usage {
             let store = &usage.store;
             let alive_ratio = (total_alive_bytes as f64) / (total_bytes as f64);
@@ -4878,6 +5033,81 @@ impl AccountsDb {
         Ok(())
     }
 
+    /// Scans the accounts with a pubkey in `range`, in pubkey order unless
+    /// `config.collect_all_unsorted` is set. Unlike `scan_accounts()`, the accounts are not
+    /// copied, which is left to `scan_func`.
+    pub fn scan_accounts_in_range<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        range: R,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<()>
+    where
+        F: FnMut(&Pubkey, LoadedAccount, Slot),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        self.accounts_index.scan_accounts_in_range(
+            ancestors,
+            bank_id,
+            range,
+            |pubkey, (account_info, slot)| {
+                if let Some(loaded_account) = self
+                    .get_account_accessor(slot, pubkey, &account_info.storage_location())
+                    .get_loaded_account()
+                {
+                    scan_func(pubkey, loaded_account, slot)
+                }
+            },
+            config,
+        )
+    }
+
+    /// Scans the accounts of `index_key` with a pubkey in `range`, in pubkey order unless
+    /// `config.collect_all_unsorted` is set, through the secondary index if the key is indexed.
+    /// Unlike `index_scan_accounts()`, the accounts are not copied, which is left to `scan_func`.
+    pub fn index_scan_accounts_in_range<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: IndexKey,
+        range: R,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<()>
+    where
+        F: FnMut(&Pubkey, LoadedAccount, Slot),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        let key = match &index_key {
+            IndexKey::ProgramId(key) => key,
+            IndexKey::SplTokenMint(key) => key,
+            IndexKey::SplTokenOwner(key) => key,
+            IndexKey::ProgramOffset { key, .. } => key,
+        };
+        if !self.account_indexes.include_key(key) {
+            // the requested key was not indexed in the secondary index, so do a normal scan
+            return self.scan_accounts_in_range(ancestors, bank_id, range, scan_func, config);
+        }
+
+        self.accounts_index.index_scan_accounts_in_range(
+            ancestors,
+            bank_id,
+            index_key,
+            range,
+            |pubkey, (account_info, slot)| {
+                if let Some(loaded_account) = self
+                    .get_account_accessor(slot, pubkey, &account_info.storage_location())
+                    .get_loaded_account()
+                {
+                    scan_func(pubkey, loaded_account, slot)
+                }
+            },
+            config,
+        )
+    }
+
     pub fn unchecked_scan_accounts<F>(
         &self,
         metric_name: &'static str,
@@ -4955,6 +5185,7 @@ impl AccountsDb {
             IndexKey::ProgramId(key) => key,
             IndexKey::SplTokenMint(key) => key,
             IndexKey::SplTokenOwner(key) => key,
+            IndexKey::ProgramOffset { key, .. } => key,
         };
         if !self.account_indexes.include_key(key) {
             // the requested key was not indexed in the secondary index, so do a normal scan
@@ -5611,6 +5842,10 @@ impl AccountsDb {
         min_size: u64,
         max_size: u64,
     ) -> Option<Arc<AccountStorageEntry>> {
+        if self.accounts_file_provider != AccountsFileProvider::AppendVec {
+            // only append vecs are recyclable
+            return None;
+        }
         let mut max = 0;
         let mut min = std::u64::MAX;
         let mut avail = 0;
@@ -8213,8 +8448,9 @@ impl AccountsDb {
                 if count == 0 {
                     self.dirty_stores.insert(*slot, store.clone());
                     dead_slots.insert(*slot);
-                } else if Self::is_shrinking_productive(*slot, &store)
-                    && self.is_candidate_for_shrink(&store, false)
+                } else if self.is_pending_conversion(&store)
+                    || (Self::is_shrinking_productive(*slot, &store)
+                        && self.is_candidate_for_shrink(&store, false))
                 {
                     // Checking that this single storage entry is ready for shrinking,
                     // should be a sufficient indication that the slot is ready to be shrunk
@@ -8958,7 +9194,12 @@ impl AccountsDb {
             return SlotIndexGenerationInfo::default();
         }
 
-        let secondary = !self.account_indexes.is_empty();
+        // the restored secondary indexes already hold the accounts of the full snapshot, but not
+        // those of an incremental snapshot on top of it
+        let secondary = !self.account_indexes.is_empty()
+            && self
+                .secondary_indexes_restored_slot
+                .map_or(true, |restored_slot| slot > restored_slot);
 
         let mut rent_paying_accounts_by_partition = Vec::default();
         let mut accounts_data_len = 0;
@@ -9110,6 +9351,182 @@ impl AccountsDb {
         );
     }
 
+    fn secondary_indexes_path(&self) -> PathBuf {
+        self.base_working_path
+            .join(Self::DEFAULT_SECONDARY_INDEXES_DIR)
+    }
+
+    /// Persists the secondary indexes at the full snapshot of `slot`, if enabled. They are only
+    /// restored once sealed with the accounts hash of `slot` by
+    /// `seal_persisted_secondary_indexes()`.
+    ///
+    /// Must be called after cleaning up to the snapshot, and before cleaning any later root.
+    /// Keys are only removed from the secondary indexes by clean, so the indexes then hold every
+    /// account of the snapshot. Only copying the indexes is done by the caller, while they are
+    /// serialized straight to a file in the background.
+    pub fn persist_secondary_indexes(&self, slot: Slot) {
+        if !self.persist_secondary_indexes || self.account_indexes.is_empty() {
+            return;
+        }
+        let persisted = PersistedSecondaryIndexes {
+            slot,
+            account_indexes: self.account_indexes.clone(),
+            indexes: self
+                .accounts_index
+                .secondary_indexes_snapshot(&self.account_indexes),
+        };
+        let dir = self.secondary_indexes_path();
+        let mut secondary_indexes_writer = self.secondary_indexes_writer.lock().unwrap();
+        Self::join_secondary_indexes_writer(&mut secondary_indexes_writer);
+        *secondary_indexes_writer = Some(
+            Builder::new()
+                .name("solPersistSecIx".to_string())
+                .spawn(move || {
+                    let mut measure = Measure::start("persist_secondary_indexes");
+                    let path = dir.join(format!("{slot}.pre"));
+                    let result = fs_err::create_dir_all(&dir)
+                        .and_then(|()| Self::write_persisted_secondary_indexes(&path, &persisted));
+                    measure.stop();
+                    match result {
+                        Ok(()) => info!(
+                            "Persisted secondary indexes at slot {slot} to {}, {measure}",
+                            path.display()
+                        ),
+                        Err(err) => {
+                            warn!("Failed to persist secondary indexes at slot {slot}: {err}");
+                            let _ = fs_err::remove_file(&path);
+                        }
+                    }
+                })
+                .unwrap(),
+        );
+    }
+
+    /// Serializes `persisted` into the file at `path`, after the checksum of the serialized
+    /// indexes. The checksum is computed while serializing and written last.
+    fn write_persisted_secondary_indexes(
+        path: &Path,
+        persisted: &PersistedSecondaryIndexes,
+    ) -> io::Result<()> {
+        let mut file = fs_err::File::create(path)?;
+        file.write_all(&[0; HASH_BYTES])?;
+        let mut writer = HashingWriter {
+            inner: BufWriter::new(&mut file),
+            hasher: Hasher::default(),
+        };
+        bincode::serialize_into(&mut writer, persisted)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        let HashingWriter { inner, hasher } = writer;
+        inner.into_inner().map_err(|err| err.into_error())?;
+        file.rewind()?;
+        file.write_all(hasher.result().as_ref())
+    }
+
+    fn join_secondary_indexes_writer(secondary_indexes_writer: &mut Option<JoinHandle<()>>) {
+        if let Some(writer) = secondary_indexes_writer.take() {
+            if writer.join().is_err() {
+                warn!("Failed to persist secondary indexes: the writer thread panicked");
+            }
+        }
+    }
+
+    /// Seals the secondary indexes persisted at the full snapshot of `slot` with its accounts
+    /// hash, once they are written, and removes those persisted at older slots
+    pub fn seal_persisted_secondary_indexes(&self, slot: Slot, accounts_hash: &AccountsHash) {
+        Self::join_secondary_indexes_writer(&mut self.secondary_indexes_writer.lock().unwrap());
+        let dir = self.secondary_indexes_path();
+        let pre_path = dir.join(format!("{slot}.pre"));
+        if !pre_path.exists() {
+            return;
+        }
+        let AccountsHash(accounts_hash) = accounts_hash;
+        if let Err(err) = fs_err::rename(&pre_path, dir.join(format!("{slot}-{accounts_hash}"))) {
+            warn!("Failed to seal persisted secondary indexes at slot {slot}: {err}");
+            return;
+        }
+        let Ok(entries) = fs_err::read_dir(&dir) else {
+            return;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let file_name = entry.file_name();
+            let persisted_slot = file_name
+                .to_str()
+                .and_then(|name| name.split(['.', '-']).next())
+                .and_then(|persisted_slot| persisted_slot.parse::<Slot>().ok());
+            if persisted_slot.map_or(false, |persisted_slot| persisted_slot < slot) {
+                if let Err(err) = fs_err::remove_file(entry.path()) {
+                    warn!("Failed to remove persisted secondary indexes: {err}");
+                }
+            }
+        }
+    }
+
+    /// Restores the secondary indexes persisted at the full snapshot of `slot`, if they were
+    /// sealed with the accounts hash of the snapshot, match their checksum, and were persisted
+    /// for the same account indexes. Only the accounts of the slots after `slot`, those of an
+    /// incremental snapshot on top of the full snapshot, are then added to the secondary indexes
+    /// when generating the accounts index.
+    ///
+    /// Returns true if the secondary indexes were restored.
+    pub fn restore_secondary_indexes(&mut self, slot: Slot) -> bool {
+        if self.account_indexes.is_empty() {
+            return false;
+        }
+        let Some((AccountsHash(accounts_hash), _)) = self.get_accounts_hash(slot) else {
+            return false;
+        };
+        let path = self
+            .secondary_indexes_path()
+            .join(format!("{slot}-{accounts_hash}"));
+        if !path.exists() {
+            info!("No secondary indexes persisted at slot {slot} to restore");
+            return false;
+        }
+
+        let mut measure = Measure::start("restore_secondary_indexes");
+        let persisted = fs_err::read(&path).and_then(|contents| {
+            if contents.len() < HASH_BYTES
+                || hash::hash(&contents[HASH_BYTES..]).as_ref() != &contents[..HASH_BYTES]
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("checksum mismatch in {}", path.display()),
+                ));
+            }
+            bincode::deserialize::<PersistedSecondaryIndexes>(&contents[HASH_BYTES..])
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        });
+        let persisted = match persisted {
+            Ok(persisted) => persisted,
+            Err(err) => {
+                warn!("Failed to read persisted secondary indexes: {err}");
+                return false;
+            }
+        };
+        if persisted.slot != slot || persisted.account_indexes != self.account_indexes {
+            info!(
+                "Not restoring the secondary indexes persisted at slot {}: persisted for account \
+                 indexes {:?}",
+                persisted.slot, persisted.account_indexes,
+            );
+            return false;
+        }
+        self.accounts_index
+            .restore_secondary_indexes(persisted.indexes);
+        self.secondary_indexes_restored_slot = Some(slot);
+        measure.stop();
+        info!(
+            "Restored secondary indexes from {}, {measure}",
+            path.display()
+        );
+        true
+    }
+
+    /// Returns the slot of the full snapshot the secondary indexes were restored at, if any
+    pub fn secondary_indexes_restored_slot(&self) -> Option<Slot> {
+        self.secondary_indexes_restored_slot
+    }
+
     pub fn generate_index(
         &self,
         limit_load_slot_count_from_snapshot: Option<usize>,
@@ -9912,7 +10329,8 @@ pub mod tests {
             account_storage::meta::{AccountMeta, StoredMeta},
             accounts_hash::MERKLE_FANOUT,
             accounts_index::{
-                tests::*, AccountSecondaryIndexesIncludeExclude, ReadAccountMapEntry, RefCount,
+                tests::*, AccountIndex, AccountSecondaryIndexesIncludeExclude, ReadAccountMapEntry,
+                RefCount,
             },
             append_vec::{test_utils::TempFile, AppendVecStoredAccountMeta},
             cache_hash_data::CacheHashDataFile,
@@ -11286,6 +11704,101 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_accounts_file_provider_hot_storage() {
+        solana_logger::setup();
+        let mut db = AccountsDb::new_single_for_tests();
+        db.accounts_file_provider = AccountsFileProvider::HotStorage;
+        let slot = 0;
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let accounts = [
+            AccountSharedData::new(1, 10, &Pubkey::new_unique()),
+            AccountSharedData::new(2, 0, &Pubkey::default()),
+        ];
+
+        db.store_for_tests(slot, &[(&keys[0], &accounts[0]), (&keys[1], &accounts[1])]);
+        db.add_root_and_flush_write_cache(slot);
+
+        let storage = db.storage.get_slot_storage_entry(slot).unwrap();
+        assert!(matches!(storage.accounts, AccountsFile::TieredStorage(_)));
+        assert!(AccountsFile::is_tiered_storage_path(&storage.get_path()));
+        assert!(!storage.accounts.is_recyclable());
+        for (key, account) in keys.iter().zip(accounts.iter()) {
+            assert_eq!(
+                db.load_without_fixed_root(&Ancestors::default(), key),
+                Some((account.clone(), slot))
+            );
+        }
+    }
+
+    #[test]
+    fn test_shrink_converts_append_vecs_to_accounts_file_provider() {
+        solana_logger::setup();
+        let mut db = AccountsDb::new_single_for_tests();
+        let slot = 0;
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let account = AccountSharedData::new(1, 10, &Pubkey::new_unique());
+        db.store_for_tests(slot, &[(&keys[0], &account), (&keys[1], &account)]);
+        db.add_root_and_flush_write_cache(slot);
+        let append_vec = db.storage.get_slot_storage_entry(slot).unwrap();
+        assert!(matches!(append_vec.accounts, AccountsFile::AppendVec(_)));
+        let append_vec_path = append_vec.get_path();
+
+        // changing the provider alone leaves existing append vecs untouched
+        db.accounts_file_provider = AccountsFileProvider::HotStorage;
+        assert!(db.is_pending_conversion(&append_vec));
+        assert!(db.shrink_candidate_slots.lock().unwrap().is_empty());
+        drop(append_vec);
+
+        // one dead account is enough to make the append vec a shrink candidate
+        let new_slot = slot + 1;
+        db.store_for_tests(new_slot, &[(&keys[1], &account)]);
+        db.add_root_and_flush_write_cache(new_slot);
+        db.clean_accounts_for_tests();
+        assert!(db.shrink_candidate_slots.lock().unwrap().contains(&slot));
+        db.shrink_candidate_slots(&EpochSchedule::default());
+
+        let storage = db.storage.get_slot_storage_entry(slot).unwrap();
+        assert!(matches!(storage.accounts, AccountsFile::TieredStorage(_)));
+        assert!(!db.is_pending_conversion(&storage));
+        assert!(!append_vec_path.exists());
+        let stored_accounts = storage.accounts.accounts(0);
+        assert_eq!(stored_accounts.len(), 1);
+        assert_eq!(stored_accounts[0].pubkey(), &keys[0]);
+        assert!(accounts_equal(&stored_accounts[0], &account));
+    }
+
+    #[test]
+    fn test_select_candidates_by_total_usage_conversion_budget() {
+        solana_logger::setup();
+        let mut db = AccountsDb::new_single_for_tests();
+        db.accounts_file_provider = AccountsFileProvider::HotStorage;
+        let mut candidates = ShrinkCandidates::new();
+        let alive_bytes = (MAX_CONVERSION_BYTES_PER_SHRINK / 2 + 1) as usize;
+        let slots = [12, 13, 14];
+        for (id, slot) in slots.iter().enumerate() {
+            let store = Arc::new(AccountStorageEntry::new(
+                Path::new(""),
+                *slot,
+                id as AppendVecId,
+                PAGE_SIZE,
+            ));
+            store.alive_bytes.store(alive_bytes, Ordering::Release);
+            assert!(db.is_pending_conversion(&store));
+            db.storage.insert(*slot, store);
+            candidates.insert(*slot);
+        }
+
+        // only as many append vecs as the budget allows are converted in a round
+        let (selected_candidates, next_candidates) =
+            db.select_candidates_by_total_usage(&candidates, DEFAULT_ACCOUNTS_SHRINK_RATIO, None);
+        assert_eq!(1, selected_candidates.len());
+        assert_eq!(2, next_candidates.len());
+        assert!(slots
+            .iter()
+            .all(|slot| selected_candidates.contains_key(slot) || next_candidates.contains(slot)));
+    }
+
     #[test]
     fn test_accountsdb_latest_ancestor() {
         solana_logger::setup();
@@ -12147,6 +12660,90 @@ pub mod tests {
         assert_eq!(found_accounts, vec![pubkey2]);
     }
 
+    #[test]
+    fn test_persist_and_restore_secondary_indexes() {
+        let base_working_dir = TempDir::new().unwrap();
+        let new_accounts_db = |account_indexes| {
+            AccountsDb::new_with_config(
+                Vec::new(),
+                &ClusterType::Development,
+                account_indexes,
+                AccountShrinkThreshold::default(),
+                Some(AccountsDbConfig {
+                    base_working_path: Some(base_working_dir.path().to_path_buf()),
+                    persist_secondary_indexes: true,
+                    ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+                }),
+                None,
+                Arc::default(),
+            )
+        };
+
+        let accounts = new_accounts_db(spl_token_mint_index_enabled());
+        let pubkey = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut account_data_with_mint = vec![0; inline_spl_token::Account::get_packed_len()];
+        account_data_with_mint[..PUBKEY_BYTES].clone_from_slice(&(mint_key.to_bytes()));
+        let mut account = AccountSharedData::new(1, 0, &inline_spl_token::id());
+        account.set_data(account_data_with_mint);
+        let slot = 1;
+        accounts.store_for_tests(slot, &[(&pubkey, &account)]);
+        let indexes = accounts
+            .accounts_index
+            .secondary_indexes_snapshot(&accounts.account_indexes);
+        assert_eq!(
+            indexes,
+            SecondaryIndexesSnapshot {
+                indexes: vec![(AccountIndex::SplTokenMint, vec![(mint_key, vec![pubkey])])],
+            }
+        );
+        accounts.persist_secondary_indexes(slot);
+
+        // the persisted indexes are only restored once sealed with the accounts hash of the slot
+        let accounts_hash = Hash::new_unique();
+        let mut restored = new_accounts_db(spl_token_mint_index_enabled());
+        restored.set_accounts_hash(slot, (AccountsHash(accounts_hash), 1));
+        assert!(!restored.restore_secondary_indexes(slot));
+        accounts.seal_persisted_secondary_indexes(slot, &AccountsHash(accounts_hash));
+        assert!(restored.restore_secondary_indexes(slot));
+        assert_eq!(restored.secondary_indexes_restored_slot, Some(slot));
+        assert_eq!(
+            restored
+                .accounts_index
+                .secondary_indexes_snapshot(&restored.account_indexes),
+            indexes
+        );
+
+        // nor for another accounts hash, or other account indexes
+        let mut other = new_accounts_db(spl_token_mint_index_enabled());
+        other.set_accounts_hash(slot, (AccountsHash(Hash::new_unique()), 1));
+        assert!(!other.restore_secondary_indexes(slot));
+        let mut other = new_accounts_db(spl_token_owner_index_enabled());
+        other.set_accounts_hash(slot, (AccountsHash(accounts_hash), 1));
+        assert!(!other.restore_secondary_indexes(slot));
+        assert_eq!(other.secondary_indexes_restored_slot, None);
+
+        // nor if their checksum does not match
+        let path = accounts
+            .secondary_indexes_path()
+            .join(format!("{slot}-{accounts_hash}"));
+        let mut contents = std::fs::read(&path).unwrap();
+        *contents.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, &contents).unwrap();
+        let mut other = new_accounts_db(spl_token_mint_index_enabled());
+        other.set_accounts_hash(slot, (AccountsHash(accounts_hash), 1));
+        assert!(!other.restore_secondary_indexes(slot));
+        *contents.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, &contents).unwrap();
+
+        // sealing newer persisted indexes removes the older ones
+        accounts.persist_secondary_indexes(slot + 1);
+        accounts.seal_persisted_secondary_indexes(slot + 1, &AccountsHash(Hash::new_unique()));
+        let mut restored = new_accounts_db(spl_token_mint_index_enabled());
+        restored.set_accounts_hash(slot, (AccountsHash(accounts_hash), 1));
+        assert!(!restored.restore_secondary_indexes(slot));
+    }
+
     #[test]
     fn test_clean_max_slot_zero_lamport_account() {
         solana_logger::setup();
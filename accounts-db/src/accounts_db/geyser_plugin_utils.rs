@@ -136,11 +136,14 @@ impl AccountsDb {
             // 'accounts_to_stream' is already a hashmap, so there is already only entry per pubkey.
             // write_version is only used to order multiple entries with the same pubkey, so it doesn't matter what value it gets here.
             // Passing 0 for everyone's write_version is sufficiently correct.
-            let meta = StoredMeta {
+            // Tiered accounts do not carry a StoredMeta, so they are streamed as is.
+            let meta = matches!(account, StoredAccountMeta::AppendVec(_)).then(|| StoredMeta {
                 write_version_obsolete: local_write_version,
                 ..*account.meta()
-            };
-            account.set_meta(&meta);
+            });
+            if let Some(meta) = &meta {
+                account.set_meta(meta);
+            }
             let mut measure_pure_notify = Measure::start("accountsdb-plugin-notifying-accounts");
             notifier.notify_account_restore_from_snapshot(slot, &account);
             measure_pure_notify.stop();
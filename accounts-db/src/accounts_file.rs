@@ -5,7 +5,9 @@ use {
         },
         append_vec::{AppendVec, AppendVecError, MatchAccountOwnerError},
         storable_accounts::StorableAccounts,
-        tiered_storage::error::TieredStorageError,
+        tiered_storage::{
+            error::TieredStorageError, index_to_offset, offset_to_index, TieredStorage,
+        },
     },
     solana_sdk::{account::ReadableAccount, clock::Slot, hash::Hash, pubkey::Pubkey},
     std::{
@@ -13,6 +15,8 @@ use {
         mem,
         path::{Path, PathBuf},
     },
+    strum::VariantNames,
+    strum_macros::{Display, EnumString, EnumVariantNames, IntoStaticStr},
     thiserror::Error,
 };
 
@@ -41,11 +45,50 @@ pub enum AccountsFileError {
 
 pub type Result<T> = std::result::Result<T, AccountsFileError>;
 
+/// The extension of tiered storage file names.
+///
+/// The format of an accounts file is decided by its name only, as the
+/// contents of an append vec are partially controlled by users.
+pub const TIERED_STORAGE_FILE_EXTENSION: &str = "tiered";
+
+/// The file format used when creating new accounts files.
+///
+/// Regardless of this setting, existing accounts files of any format can
+/// always be opened, as the format is detected from the file name.
+/// Existing append vecs are rewritten in this format when they are shrunk.
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, EnumString, EnumVariantNames, IntoStaticStr, Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum AccountsFileProvider {
+    #[default]
+    AppendVec,
+    HotStorage,
+}
+
+impl AccountsFileProvider {
+    pub const fn cli_names() -> &'static [&'static str] {
+        Self::VARIANTS
+    }
+
+    pub fn cli_message() -> &'static str {
+        lazy_static! {
+            static ref MESSAGE: String = format!(
+                "The file format used for newly created accounts files [default: {}]",
+                AccountsFileProvider::default()
+            );
+        };
+
+        &MESSAGE
+    }
+}
+
 #[derive(Debug)]
 /// An enum for accessing an accounts file which can be implemented
 /// under different formats.
 pub enum AccountsFile {
     AppendVec(AppendVec),
+    TieredStorage(TieredStorage),
 }
 
 impl AccountsFile {
@@ -54,6 +97,14 @@ impl AccountsFile {
     /// The second element of the returned tuple is the number of accounts in the
     /// accounts file.
     pub fn new_from_file(path: impl AsRef<Path>, current_len: usize) -> Result<(Self, usize)> {
+        if Self::is_tiered_storage_path(path.as_ref()) {
+            let tiered_storage = TieredStorage::new_readonly(path.as_ref())?;
+            let num_accounts = tiered_storage
+                .reader()
+                .map_or(0, |reader| reader.num_accounts());
+            return Ok((Self::TieredStorage(tiered_storage), num_accounts));
+        }
+
         let (av, num_accounts) = AppendVec::new_from_file(path, current_len)?;
         Ok((Self::AppendVec(av), num_accounts))
     }
@@ -61,55 +112,92 @@ impl AccountsFile {
     pub fn flush(&self) -> Result<()> {
         match self {
             Self::AppendVec(av) => av.flush(),
+            // tiered storage files are immutable once written
+            Self::TieredStorage(_) => Ok(()),
         }
     }
 
     pub fn reset(&self) {
         match self {
             Self::AppendVec(av) => av.reset(),
+            Self::TieredStorage(_) => {}
         }
     }
 
     pub fn remaining_bytes(&self) -> u64 {
         match self {
             Self::AppendVec(av) => av.remaining_bytes(),
+            Self::TieredStorage(_) => 0,
         }
     }
 
     pub fn len(&self) -> usize {
         match self {
             Self::AppendVec(av) => av.len(),
+            Self::TieredStorage(ts) => ts.file_size().unwrap_or(0) as usize,
         }
     }
 
     pub fn is_empty(&self) -> bool {
         match self {
             Self::AppendVec(av) => av.is_empty(),
+            Self::TieredStorage(ts) => ts
+                .reader()
+                .map_or(true, |reader| reader.num_accounts() == 0),
         }
     }
 
     pub fn capacity(&self) -> u64 {
         match self {
             Self::AppendVec(av) => av.capacity(),
+            Self::TieredStorage(ts) => ts.file_size().unwrap_or(0),
         }
     }
 
     pub fn is_recyclable(&self) -> bool {
         match self {
             Self::AppendVec(_) => true,
+            Self::TieredStorage(_) => false,
         }
     }
 
+    /// Return the name of an append vec file for the specified slot and id.
     pub fn file_name(slot: Slot, id: impl std::fmt::Display) -> String {
         format!("{slot}.{id}")
     }
 
+    /// Return the name of a tiered storage file for the specified slot and id.
+    pub fn tiered_file_name(slot: Slot, id: impl std::fmt::Display) -> String {
+        format!("{slot}.{id}.{TIERED_STORAGE_FILE_EXTENSION}")
+    }
+
+    /// Return the name of an accounts file of the same format as `self` for
+    /// the specified slot and id.
+    pub fn file_name_for(&self, slot: Slot, id: impl std::fmt::Display) -> String {
+        match self {
+            Self::AppendVec(_) => Self::file_name(slot, id),
+            Self::TieredStorage(_) => Self::tiered_file_name(slot, id),
+        }
+    }
+
+    /// Return true if `path` is named like a tiered storage file.
+    pub fn is_tiered_storage_path(path: &Path) -> bool {
+        path.extension().map_or(false, |extension| {
+            extension == TIERED_STORAGE_FILE_EXTENSION
+        })
+    }
+
     /// Return (account metadata, next_index) pair for the account at the
     /// specified `index` if any.  Otherwise return None.   Also return the
     /// index of the next entry.
     pub fn get_account(&self, index: usize) -> Option<(StoredAccountMeta<'_>, usize)> {
         match self {
             Self::AppendVec(av) => av.get_account(index),
+            Self::TieredStorage(ts) => ts
+                .reader()?
+                .get_account(offset_to_index(index))
+                .ok()?
+                .map(|(account, next_index)| (account, index_to_offset(next_index))),
         }
     }
 
@@ -120,6 +208,14 @@ impl AccountsFile {
     ) -> std::result::Result<usize, MatchAccountOwnerError> {
         match self {
             Self::AppendVec(av) => av.account_matches_owners(offset, owners),
+            Self::TieredStorage(ts) => {
+                let reader = ts.reader().ok_or(MatchAccountOwnerError::UnableToLoad)?;
+                match reader.account_matches_owners(offset_to_index(offset), owners) {
+                    Ok(Some(index)) => Ok(index),
+                    Ok(None) => Err(MatchAccountOwnerError::NoMatch),
+                    Err(_) => Err(MatchAccountOwnerError::UnableToLoad),
+                }
+            }
         }
     }
 
@@ -127,6 +223,7 @@ impl AccountsFile {
     pub fn get_path(&self) -> PathBuf {
         match self {
             Self::AppendVec(av) => av.get_path(),
+            Self::TieredStorage(ts) => ts.path().to_path_buf(),
         }
     }
 
@@ -135,10 +232,19 @@ impl AccountsFile {
         AccountsFileIter::new(self)
     }
 
+    /// Return iterator for account metadata, starting from `offset`.
+    fn account_iter_from(&self, offset: usize) -> AccountsFileIter {
+        AccountsFileIter {
+            file_entry: self,
+            offset,
+        }
+    }
+
     /// Return a vector of account metadata for each account, starting from `offset`.
     pub fn accounts(&self, offset: usize) -> Vec<StoredAccountMeta> {
         match self {
             Self::AppendVec(av) => av.accounts(offset),
+            Self::TieredStorage(_) => self.account_iter_from(offset).collect(),
         }
     }
 
@@ -162,6 +268,17 @@ impl AccountsFile {
     ) -> Option<Vec<StoredAccountInfo>> {
         match self {
             Self::AppendVec(av) => av.append_accounts(accounts, skip),
+            // Tiered storage files are written all at once, so unlike append
+            // vecs, they never run out of space.  Any error here is fatal as
+            // retrying with another storage would not help.
+            Self::TieredStorage(ts) => {
+                Some(ts.write_accounts(accounts, skip).unwrap_or_else(|err| {
+                    panic!(
+                        "failed to write accounts to tiered storage {}: {err}",
+                        ts.path().display()
+                    )
+                }))
+            }
         }
     }
 }
@@ -195,12 +312,64 @@ impl<'a> Iterator for AccountsFileIter<'a> {
 
 #[cfg(test)]
 pub mod tests {
-    use crate::accounts_file::AccountsFile;
+    use {
+        crate::{
+            account_storage::meta::{
+                StorableAccountsWithHashesAndWriteVersions, StoredMetaWriteVersion,
+            },
+            accounts_file::AccountsFile,
+            tiered_storage::{hot::HOT_FORMAT, TieredStorage},
+        },
+        solana_sdk::{account::AccountSharedData, clock::Slot, hash::Hash, pubkey::Pubkey},
+        std::{mem::ManuallyDrop, path::Path},
+        tempfile::tempdir,
+    };
+
     impl AccountsFile {
         pub(crate) fn set_current_len_for_tests(&self, len: usize) {
             match self {
                 Self::AppendVec(av) => av.set_current_len_for_tests(len),
+                Self::TieredStorage(_) => {}
             }
         }
     }
+
+    #[test]
+    fn test_is_tiered_storage_path() {
+        assert!(AccountsFile::is_tiered_storage_path(Path::new(
+            &AccountsFile::tiered_file_name(123, 456)
+        )));
+        assert!(!AccountsFile::is_tiered_storage_path(Path::new(
+            &AccountsFile::file_name(123, 456)
+        )));
+    }
+
+    #[test]
+    fn test_new_from_file_by_name() {
+        let temp_dir = tempdir().unwrap();
+
+        // A tiered storage file with an append vec name is opened as an append vec,
+        // whatever its contents.
+        let path = temp_dir.path().join(AccountsFile::file_name(1, 2));
+        let tiered_storage =
+            ManuallyDrop::new(TieredStorage::new_writable(&path, HOT_FORMAT.clone()));
+        let account_refs = Vec::<(&Pubkey, &AccountSharedData)>::new();
+        let storable_accounts =
+            StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
+                &(Slot::MAX, account_refs.as_slice()),
+                Vec::<&Hash>::new(),
+                Vec::<StoredMetaWriteVersion>::new(),
+            );
+        tiered_storage
+            .write_accounts(&storable_accounts, 0)
+            .unwrap();
+        let file_size = tiered_storage.file_size().unwrap() as usize;
+        let result = AccountsFile::new_from_file(&path, file_size);
+        assert!(!matches!(result, Ok((AccountsFile::TieredStorage(_), _))));
+
+        let tiered_path = temp_dir.path().join(AccountsFile::tiered_file_name(1, 2));
+        std::fs::rename(&path, &tiered_path).unwrap();
+        let (accounts_file, _) = AccountsFile::new_from_file(&tiered_path, file_size).unwrap();
+        assert!(matches!(accounts_file, AccountsFile::TieredStorage(_)));
+    }
 }
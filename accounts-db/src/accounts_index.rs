@@ -19,14 +19,15 @@ use {
         iter::{IntoParallelIterator, ParallelIterator},
         ThreadPool,
     },
+    serde::{Deserialize, Serialize},
     solana_measure::measure::Measure,
     solana_sdk::{
         account::ReadableAccount,
         clock::{BankId, Slot},
-        pubkey::Pubkey,
+        pubkey::{Pubkey, PUBKEY_BYTES},
     },
     std::{
-        collections::{btree_map::BTreeMap, HashSet},
+        collections::{btree_map::BTreeMap, HashMap, HashSet},
         fmt::Debug,
         ops::{
             Bound,
@@ -43,6 +44,8 @@ use {
 };
 
 pub const ITER_BATCH_SIZE: usize = 1000;
+// Number of inner keys read at once from a secondary index entry by a range scan
+const SECONDARY_INDEX_SCAN_BATCH_SIZE: usize = 256;
 pub const BINS_DEFAULT: usize = 8192;
 pub const BINS_FOR_TESTING: usize = 2; // we want > 1, but each bin is a few disk files with a disk based index, so fewer is better
 pub const BINS_FOR_BENCHMARKS: usize = 8192;
@@ -155,7 +158,7 @@ pub enum ScanError {
 
 enum ScanTypes<R: RangeBounds<Pubkey>> {
     Unindexed(Option<R>),
-    Indexed(IndexKey),
+    Indexed(IndexKey, Option<R>),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -163,16 +166,29 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    /// accounts owned by `program_id` holding `key` at byte `offset` of their data
+    ProgramOffset {
+        program_id: Pubkey,
+        offset: usize,
+        key: Pubkey,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    /// index the accounts owned by `program_id` by the pubkey at byte `offset` of their data
+    ProgramOffset {
+        program_id: Pubkey,
+        offset: usize,
+    },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+type ProgramOffsetIndex = SecondaryIndex<ShardedSecondaryIndexEntry>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct AccountSecondaryIndexesIncludeExclude {
     pub exclude: bool,
     pub keys: HashSet<Pubkey>,
@@ -207,7 +223,7 @@ pub struct AccountsIndexConfig {
     pub started_from_validator: bool,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AccountSecondaryIndexes {
     pub keys: Option<AccountSecondaryIndexesIncludeExclude>,
     pub indexes: HashSet<AccountIndex>,
@@ -228,6 +244,12 @@ impl AccountSecondaryIndexes {
     }
 }
 
+/// The keys of the secondary indexes, each with the keys of the accounts it maps to
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecondaryIndexesSnapshot {
+    pub indexes: Vec<(AccountIndex, Vec<(Pubkey, Vec<Pubkey>)>)>,
+}
+
 #[derive(Debug, Default)]
 /// data per entry in in-mem accounts index
 /// used to keep track of consistency with disk index
@@ -676,9 +698,11 @@ pub enum AccountsIndexScanResult {
 pub struct AccountsIndex<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> {
     pub account_maps: LockMapType<T, U>,
     pub bin_calculator: PubkeyBinCalculator24,
-    program_id_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
-    spl_token_mint_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
+    program_id_index: SecondaryIndex<ShardedSecondaryIndexEntry>,
+    spl_token_mint_index: SecondaryIndex<ShardedSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    /// indexes by (program id, offset), created when first updated or scanned
+    program_offset_indexes: RwLock<HashMap<(Pubkey, usize), Arc<ProgramOffsetIndex>>>,
     pub roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -724,15 +748,16 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         Self {
             account_maps,
             bin_calculator,
-            program_id_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
+            program_id_index: SecondaryIndex::<ShardedSecondaryIndexEntry>::new(
                 "program_id_index_stats",
             ),
-            spl_token_mint_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
+            spl_token_mint_index: SecondaryIndex::<ShardedSecondaryIndexEntry>::new(
                 "spl_token_mint_index_stats",
             ),
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            program_offset_indexes: RwLock::default(),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -955,32 +980,53 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 // Pass "" not to log metrics, so RPC doesn't get spammy
                 self.do_scan_accounts(metric_name, ancestors, func, range, Some(max_root), config);
             }
-            ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
+            ScanTypes::Indexed(IndexKey::ProgramId(program_id), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.program_id_index,
                     &program_id,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_mint_index,
                     &mint_key,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_owner_index,
                     &owner_key,
+                    range,
+                    Some(max_root),
+                    config,
+                );
+            }
+            ScanTypes::Indexed(
+                IndexKey::ProgramOffset {
+                    program_id,
+                    offset,
+                    key,
+                },
+                range,
+            ) => {
+                self.do_scan_secondary_index(
+                    ancestors,
+                    func,
+                    &self.program_offset_index(&program_id, offset),
+                    &key,
+                    range,
                     Some(max_root),
                     config,
                 );
@@ -1092,8 +1138,11 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         }
     }
 
+    // Scan the accounts of `index_key` in a secondary index. Only the accounts in `range` are
+    // scanned if any, in pubkey order.
     fn do_scan_secondary_index<
         F,
+        R,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
         &self,
@@ -1101,26 +1150,66 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         mut func: F,
         index: &SecondaryIndex<SecondaryIndexEntryType>,
         index_key: &Pubkey,
+        range: Option<R>,
         max_root: Option<Slot>,
         config: &ScanConfig,
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey>,
     {
-        for pubkey in index.get(index_key) {
-            // Maybe these reads from the AccountsIndex can be batched every time it
-            // grabs the read lock as well...
-            if let AccountIndexGetResult::Found(list_r, index) =
-                self.get(&pubkey, Some(ancestors), max_root)
-            {
-                let entry = &list_r.slot_list()[index];
-                func(&pubkey, (&entry.1, entry.0));
+        let Some(range) = range else {
+            for pubkey in index.get(index_key) {
+                if !self.scan_secondary_index_key(ancestors, &mut func, &pubkey, max_root, config) {
+                    break;
+                }
             }
-            if config.is_aborted() {
+            return;
+        };
+        // The inner keys are ordered, so the range is read in batches from its start, without
+        // copying the keys out of it, until the scan is aborted
+        let mut start = range.start_bound().cloned();
+        loop {
+            let pubkeys = index.get_in_range(
+                index_key,
+                (start.as_ref(), range.end_bound()),
+                SECONDARY_INDEX_SCAN_BATCH_SIZE,
+            );
+            let Some(last) = pubkeys.last().copied() else {
                 break;
+            };
+            for pubkey in pubkeys {
+                if !self.scan_secondary_index_key(ancestors, &mut func, &pubkey, max_root, config) {
+                    return;
+                }
             }
+            start = Bound::Excluded(last);
         }
     }
 
+    // Calls `func` with the account of `pubkey` visible from `ancestors`, if any. Returns false
+    // once the scan is aborted.
+    fn scan_secondary_index_key<F>(
+        &self,
+        ancestors: &Ancestors,
+        func: &mut F,
+        pubkey: &Pubkey,
+        max_root: Option<Slot>,
+        config: &ScanConfig,
+    ) -> bool
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+    {
+        // Maybe these reads from the AccountsIndex can be batched every time it
+        // grabs the read lock as well...
+        if let AccountIndexGetResult::Found(list_r, index) =
+            self.get(pubkey, Some(ancestors), max_root)
+        {
+            let entry = &list_r.slot_list()[index];
+            func(pubkey, (&entry.1, entry.0));
+        }
+        !config.is_aborted()
+    }
+
     pub fn get_account_read_entry(&self, pubkey: &Pubkey) -> Option<ReadAccountMapEntry<T>> {
         let lock = self.get_bin(pubkey);
         self.get_account_read_entry_with_lock(pubkey, &lock)
@@ -1243,7 +1332,58 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             ancestors,
             scan_bank_id,
             func,
-            ScanTypes::<Range<Pubkey>>::Indexed(index_key),
+            ScanTypes::<Range<Pubkey>>::Indexed(index_key, None),
+            config,
+        )
+    }
+
+    /// call func with every pubkey in `range` and index visible from a given set of ancestors,
+    /// in pubkey order unless `config.collect_all_unsorted` is set
+    pub(crate) fn scan_accounts_in_range<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        range: R,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            func,
+            ScanTypes::Unindexed(Some(range)),
+            config,
+        )
+    }
+
+    /// call func with every pubkey in `range` of the `index_key` secondary index and index
+    /// visible from a given set of ancestors, in pubkey order
+    pub(crate) fn index_scan_accounts_in_range<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        index_key: IndexKey,
+        range: R,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            func,
+            ScanTypes::Indexed(index_key, Some(range)),
             config,
         )
     }
@@ -1498,6 +1638,51 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         }
     }
 
+    /// Returns the index of the accounts owned by `program_id` by the pubkey at byte `offset` of
+    /// their data, creating it if needed
+    fn program_offset_index(&self, program_id: &Pubkey, offset: usize) -> Arc<ProgramOffsetIndex> {
+        if let Some(index) = self
+            .program_offset_indexes
+            .read()
+            .unwrap()
+            .get(&(*program_id, offset))
+        {
+            return index.clone();
+        }
+        self.program_offset_indexes
+            .write()
+            .unwrap()
+            .entry((*program_id, offset))
+            .or_insert_with(|| Arc::new(ProgramOffsetIndex::new("program_offset_index_stats")))
+            .clone()
+    }
+
+    fn update_program_offset_secondary_indexes(
+        &self,
+        pubkey: &Pubkey,
+        account_owner: &Pubkey,
+        account_data: &[u8],
+        account_indexes: &AccountSecondaryIndexes,
+    ) {
+        for index in &account_indexes.indexes {
+            if let AccountIndex::ProgramOffset { program_id, offset } = index {
+                if account_owner != program_id {
+                    continue;
+                }
+                let Some(key) = account_data
+                    .get(*offset..offset.saturating_add(PUBKEY_BYTES))
+                    .and_then(|key| Pubkey::try_from(key).ok())
+                else {
+                    continue;
+                };
+                if account_indexes.include_key(&key) {
+                    self.program_offset_index(program_id, *offset)
+                        .insert(&key, pubkey);
+                }
+            }
+        }
+    }
+
     pub fn get_index_key_size(&self, index: &AccountIndex, index_key: &Pubkey) -> Option<usize> {
         match index {
             AccountIndex::ProgramId => self.program_id_index.index.get(index_key).map(|x| x.len()),
@@ -1511,6 +1696,49 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 .index
                 .get(index_key)
                 .map(|x| x.len()),
+            AccountIndex::ProgramOffset { program_id, offset } => self
+                .program_offset_indexes
+                .read()
+                .unwrap()
+                .get(&(*program_id, *offset))
+                .and_then(|index| index.index.get(index_key).map(|x| x.len())),
+        }
+    }
+
+    /// Returns the contents of the `account_indexes` secondary indexes
+    pub fn secondary_indexes_snapshot(
+        &self,
+        account_indexes: &AccountSecondaryIndexes,
+    ) -> SecondaryIndexesSnapshot {
+        let indexes = account_indexes
+            .indexes
+            .iter()
+            .map(|index| {
+                let entries = match index {
+                    AccountIndex::ProgramId => self.program_id_index.entries(),
+                    AccountIndex::SplTokenMint => self.spl_token_mint_index.entries(),
+                    AccountIndex::SplTokenOwner => self.spl_token_owner_index.entries(),
+                    AccountIndex::ProgramOffset { program_id, offset } => {
+                        self.program_offset_index(program_id, *offset).entries()
+                    }
+                };
+                (index.clone(), entries)
+            })
+            .collect();
+        SecondaryIndexesSnapshot { indexes }
+    }
+
+    /// Inserts the contents of secondary indexes returned by `secondary_indexes_snapshot()`
+    pub fn restore_secondary_indexes(&self, snapshot: SecondaryIndexesSnapshot) {
+        for (index, entries) in snapshot.indexes {
+            match index {
+                AccountIndex::ProgramId => self.program_id_index.insert_entries(entries),
+                AccountIndex::SplTokenMint => self.spl_token_mint_index.insert_entries(entries),
+                AccountIndex::SplTokenOwner => self.spl_token_owner_index.insert_entries(entries),
+                AccountIndex::ProgramOffset { program_id, offset } => self
+                    .program_offset_index(&program_id, offset)
+                    .insert_entries(entries),
+            }
         }
     }
 
@@ -1528,6 +1756,18 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        for ((program_id, offset), index) in self.program_offset_indexes.read().unwrap().iter() {
+            if !index.index.is_empty() {
+                info!(
+                    "secondary index: {:?}",
+                    AccountIndex::ProgramOffset {
+                        program_id: *program_id,
+                        offset: *offset,
+                    }
+                );
+                index.log_contents();
+            }
+        }
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -1576,6 +1816,12 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             account_data,
             account_indexes,
         );
+        self.update_program_offset_secondary_indexes(
+            pubkey,
+            account_owner,
+            account_data,
+            account_indexes,
+        );
     }
 
     pub(crate) fn get_bin(&self, pubkey: &Pubkey) -> AccountMaps<T, U> {
@@ -1751,6 +1997,19 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        for index in &account_indexes.indexes {
+            if let AccountIndex::ProgramOffset { program_id, offset } = index {
+                if let Some(index) = self
+                    .program_offset_indexes
+                    .read()
+                    .unwrap()
+                    .get(&(*program_id, *offset))
+                {
+                    index.remove_by_inner_key(inner_key);
+                }
+            }
+        }
     }
 
     fn purge_older_root_entries(
@@ -2002,7 +2261,7 @@ pub mod tests {
 
     pub enum SecondaryIndexTypes<'a> {
         RwLock(&'a SecondaryIndex<RwLockSecondaryIndexEntry>),
-        DashMap(&'a SecondaryIndex<DashMapSecondaryIndexEntry>),
+        Sharded(&'a SecondaryIndex<ShardedSecondaryIndexEntry>),
     }
 
     pub fn spl_token_mint_index_enabled() -> AccountSecondaryIndexes {
@@ -2023,11 +2282,11 @@ pub mod tests {
         }
     }
 
-    fn create_dashmap_secondary_index_state() -> (usize, usize, AccountSecondaryIndexes) {
+    fn create_sharded_secondary_index_state() -> (usize, usize, AccountSecondaryIndexes) {
         {
             // Check that we're actually testing the correct variant
             let index = AccountsIndex::<bool, bool>::default_for_tests();
-            let _type_check = SecondaryIndexTypes::DashMap(&index.spl_token_mint_index);
+            let _type_check = SecondaryIndexTypes::Sharded(&index.spl_token_mint_index);
         }
 
         (0, PUBKEY_BYTES, spl_token_mint_index_enabled())
@@ -3302,8 +3561,8 @@ pub mod tests {
     }
 
     #[test]
-    fn test_purge_exact_dashmap_secondary_index() {
-        let (key_start, key_end, secondary_indexes) = create_dashmap_secondary_index_state();
+    fn test_purge_exact_sharded_secondary_index() {
+        let (key_start, key_end, secondary_indexes) = create_sharded_secondary_index_state();
         let index = AccountsIndex::<bool, bool>::default_for_tests();
         run_test_purge_exact_secondary_index(
             &index,
@@ -3520,8 +3779,56 @@ pub mod tests {
     }
 
     #[test]
-    fn test_dashmap_secondary_index() {
-        let (key_start, key_end, secondary_indexes) = create_dashmap_secondary_index_state();
+    fn test_secondary_index_get_in_range() {
+        let key = Pubkey::new_unique();
+        // inner keys spread across the shards of a sharded entry
+        let mut inner_keys: Vec<_> = (0..=u8::MAX)
+            .step_by(15)
+            .map(|first_byte| {
+                let mut inner_key = Pubkey::new_unique().to_bytes();
+                inner_key[0] = first_byte;
+                Pubkey::from(inner_key)
+            })
+            .collect();
+        inner_keys.sort_unstable();
+        let sharded_index = SecondaryIndex::<ShardedSecondaryIndexEntry>::new("sharded");
+        let rwlock_index = SecondaryIndex::<RwLockSecondaryIndexEntry>::new("rwlock");
+        for inner_key in inner_keys.iter().rev() {
+            sharded_index.insert(&key, inner_key);
+            rwlock_index.insert(&key, inner_key);
+        }
+
+        let check = |get_in_range: &dyn Fn(InnerKeyRange, usize) -> Vec<Pubkey>| {
+            assert_eq!(get_in_range((Unbounded, Unbounded), usize::MAX), inner_keys);
+            assert_eq!(get_in_range((Unbounded, Unbounded), 3), inner_keys[..3]);
+            assert_eq!(
+                get_in_range((Excluded(&inner_keys[2]), Unbounded), 4),
+                inner_keys[3..7]
+            );
+            assert_eq!(
+                get_in_range(
+                    (Included(&inner_keys[2]), Included(&inner_keys[9])),
+                    usize::MAX
+                ),
+                inner_keys[2..=9]
+            );
+            assert!(
+                get_in_range((Excluded(&inner_keys[2]), Excluded(&inner_keys[2])), 10).is_empty()
+            );
+            assert!(
+                get_in_range((Included(&inner_keys[5]), Excluded(&inner_keys[2])), 10).is_empty()
+            );
+        };
+        check(&|range, limit| sharded_index.get_in_range(&key, range, limit));
+        check(&|range, limit| rwlock_index.get_in_range(&key, range, limit));
+        assert!(sharded_index
+            .get_in_range(&Pubkey::new_unique(), (Unbounded, Unbounded), 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sharded_secondary_index() {
+        let (key_start, key_end, secondary_indexes) = create_sharded_secondary_index_state();
         let index = AccountsIndex::<bool, bool>::default_for_tests();
         for token_id in [inline_spl_token::id(), inline_spl_token_2022::id()] {
             run_test_spl_token_secondary_indexes(
@@ -3551,6 +3858,75 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_program_offset_secondary_index() {
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let program_id = Pubkey::new_unique();
+        let offset = 8;
+        let account_index = AccountIndex::ProgramOffset { program_id, offset };
+        let mut secondary_indexes = AccountSecondaryIndexes::default();
+        secondary_indexes.indexes.insert(account_index.clone());
+
+        let account_key = Pubkey::new_unique();
+        let index_key = Pubkey::new_unique();
+        let mut account_data = vec![0; offset + PUBKEY_BYTES];
+        account_data[offset..].copy_from_slice(index_key.as_ref());
+
+        // Wrong program id
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data.clone(), Pubkey::new_unique(), false, 0),
+            &secondary_indexes,
+        );
+        assert_eq!(index.get_index_key_size(&account_index, &index_key), None);
+
+        // Account data too short to hold the key
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create(0, account_data[1..].to_vec(), program_id, false, 0),
+            &secondary_indexes,
+        );
+        assert_eq!(index.get_index_key_size(&account_index, &index_key), None);
+
+        // Just right. Inserting the same index multiple times should be ok
+        for _ in 0..2 {
+            index.upsert(
+                0,
+                0,
+                &account_key,
+                &AccountSharedData::create(0, account_data.clone(), program_id, false, 0),
+                &secondary_indexes,
+                true,
+                &mut vec![],
+                UPSERT_POPULATE_RECLAIMS,
+            );
+            check_secondary_index_mapping_correct(
+                &index.program_offset_index(&program_id, offset),
+                &[index_key],
+                &account_key,
+            );
+        }
+        assert_eq!(
+            index.get_index_key_size(&account_index, &index_key),
+            Some(1)
+        );
+        let other_account_index = AccountIndex::ProgramOffset {
+            program_id,
+            offset: 0,
+        };
+        assert_eq!(
+            index.get_index_key_size(&other_account_index, &index_key),
+            None
+        );
+
+        // Everything should be deleted
+        index.slot_list_mut(&account_key, |slot_list| slot_list.clear());
+        let _ = index.handle_dead_keys(&[&account_key], &secondary_indexes);
+        let secondary_index = index.program_offset_index(&program_id, offset);
+        assert!(secondary_index.index.is_empty());
+        assert!(secondary_index.reverse_index.is_empty());
+    }
+
     fn run_test_secondary_indexes_same_slot_and_forks<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
@@ -3641,8 +4017,8 @@ pub mod tests {
     }
 
     #[test]
-    fn test_dashmap_secondary_index_same_slot_and_forks() {
-        let (key_start, key_end, account_index) = create_dashmap_secondary_index_state();
+    fn test_sharded_secondary_index_same_slot_and_forks() {
+        let (key_start, key_end, account_index) = create_sharded_secondary_index_state();
         let index = AccountsIndex::<bool, bool>::default_for_tests();
         for token_id in [inline_spl_token::id(), inline_spl_token_2022::id()] {
             run_test_secondary_indexes_same_slot_and_forks(
@@ -7,15 +7,16 @@ use {
     crate::{
         account_storage::{meta::StoredAccountMeta, ShrinkInProgress},
         accounts_db::{
-            AccountStorageEntry, AccountsDb, AliveAccounts, GetUniqueAccountsResult, ShrinkCollect,
-            ShrinkCollectAliveSeparatedByRefs, ShrinkStatsSub, StoreReclaims,
-            INCLUDE_SLOT_IN_HASH_IRRELEVANT_APPEND_VEC_OPERATION,
+            AccountStorageEntry, AccountsDb, AliveAccounts, CreateAncientStorage,
+            GetUniqueAccountsResult, ShrinkCollect, ShrinkCollectAliveSeparatedByRefs,
+            ShrinkStatsSub, StoreReclaims, INCLUDE_SLOT_IN_HASH_IRRELEVANT_APPEND_VEC_OPERATION,
         },
         accounts_file::AccountsFile,
         accounts_index::{AccountsIndexScanResult, ZeroLamport},
         active_stats::ActiveStatItem,
         append_vec::aligned_stored_size,
         storable_accounts::{StorableAccounts, StorableAccountsBySlot},
+        tiered_storage::footer::AccountMetaFormat,
     },
     rand::{thread_rng, Rng},
     rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
@@ -371,8 +372,12 @@ impl AccountsDb {
         write_ancient_accounts: &mut WriteAncientAccounts<'b>,
     ) {
         let target_slot = accounts_to_write.target_slot();
-        let (shrink_in_progress, create_and_insert_store_elapsed_us) =
-            measure_us!(self.get_store_for_shrink(target_slot, bytes));
+        let pack_cold = self.create_ancient_storage == CreateAncientStorage::PackCold;
+        let (shrink_in_progress, create_and_insert_store_elapsed_us) = measure_us!(if pack_cold {
+            self.get_cold_store_for_shrink(target_slot)
+        } else {
+            self.get_store_for_shrink(target_slot, bytes)
+        });
         let (store_accounts_timing, rewrite_elapsed_us) = measure_us!(self.store_accounts_frozen(
             accounts_to_write,
             None::<Vec<Hash>>,
@@ -899,6 +904,10 @@ pub fn get_ancient_append_vec_capacity() -> u64 {
 pub fn is_ancient(storage: &AccountsFile) -> bool {
     match storage {
         AccountsFile::AppendVec(storage) => storage.capacity() >= get_ancient_append_vec_capacity(),
+        // cold storages are only created by packing ancient storages
+        AccountsFile::TieredStorage(storage) => storage.reader().map_or(false, |reader| {
+            reader.footer().account_meta_format == AccountMetaFormat::Cold
+        }),
     }
 }
 
@@ -908,7 +917,10 @@ pub mod tests {
         super::*,
         crate::{
             account_info::AccountInfo,
-            account_storage::meta::{AccountMeta, StoredAccountMeta, StoredMeta},
+            account_storage::meta::{
+                AccountMeta, StorableAccountsWithHashesAndWriteVersions, StoredAccountMeta,
+                StoredMeta, StoredMetaWriteVersion,
+            },
             accounts_db::{
                 get_temp_accounts_paths,
                 tests::{
@@ -921,6 +933,7 @@ pub mod tests {
             accounts_index::UpsertReclaim,
             append_vec::{aligned_stored_size, AppendVec, AppendVecStoredAccountMeta},
             storable_accounts::StorableAccountsBySlot,
+            tiered_storage::{cold::COLD_FORMAT, hot::HOT_FORMAT, TieredStorage},
         },
         solana_sdk::{
             account::{AccountSharedData, ReadableAccount, WritableAccount},
@@ -1890,6 +1903,28 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_is_ancient_tiered_storage() {
+        for (format, expected_ancient) in [(HOT_FORMAT, false), (COLD_FORMAT, true)] {
+            let tf = crate::append_vec::test_utils::get_append_vec_path("test_is_ancient");
+            let storage = TieredStorage::new_writable(&tf.path, format);
+            let account_refs = Vec::<(&Pubkey, &AccountSharedData)>::new();
+            let account_data = (Slot::MAX, account_refs.as_slice());
+            let storable_accounts =
+                StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
+                    &account_data,
+                    Vec::<&Hash>::new(),
+                    Vec::<StoredMetaWriteVersion>::new(),
+                );
+            storage.write_accounts(&storable_accounts, 0).unwrap();
+
+            assert_eq!(
+                expected_ancient,
+                is_ancient(&AccountsFile::TieredStorage(storage))
+            );
+        }
+    }
+
     fn get_one_packed_ancient_append_vec_and_others(
         alive: bool,
         num_normal_slots: usize,
@@ -1,4 +1,5 @@
 use {
+    crate::accounts_file::TIERED_STORAGE_FILE_EXTENSION,
     bzip2::bufread::BzDecoder,
     log::*,
     rand::{thread_rng, Rng},
@@ -410,6 +411,11 @@ fn all_digits(v: &str) -> bool {
 }
 
 fn like_storage(v: &str) -> bool {
+    // tiered storage files are named like append vecs, plus an extension
+    let v = v
+        .strip_suffix(TIERED_STORAGE_FILE_EXTENSION)
+        .and_then(|v| v.strip_suffix('.'))
+        .unwrap_or(v);
     let mut periods = 0;
     let mut saw_numbers = false;
     for x in v.chars() {
@@ -606,6 +612,10 @@ mod tests {
             &["accounts", "01829.077"],
             tar::EntryType::Regular
         ));
+        assert!(is_valid_snapshot_archive_entry(
+            &["accounts", "1.2.tiered"],
+            tar::EntryType::Regular
+        ));
 
         assert!(!is_valid_snapshot_archive_entry(
             &["accounts", "1.2.34"],
@@ -615,6 +625,14 @@ mod tests {
             &["accounts", "12."],
             tar::EntryType::Regular
         ));
+        assert!(!is_valid_snapshot_archive_entry(
+            &["accounts", "12.tiered"],
+            tar::EntryType::Regular
+        ));
+        assert!(!is_valid_snapshot_archive_entry(
+            &["accounts", "1.2.3.tiered"],
+            tar::EntryType::Regular
+        ));
         assert!(!is_valid_snapshot_archive_entry(
             &["accounts", ".12"],
             tar::EntryType::Regular
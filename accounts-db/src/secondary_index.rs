@@ -3,8 +3,9 @@ use {
     log::*,
     solana_sdk::{pubkey::Pubkey, timing::AtomicInterval},
     std::{
-        collections::HashSet,
+        collections::{BTreeSet, HashSet},
         fmt::Debug,
+        ops::Bound,
         sync::{
             atomic::{AtomicU64, Ordering},
             RwLock,
@@ -12,6 +13,13 @@ use {
     },
 };
 
+// Number of shards of a `ShardedSecondaryIndexEntry`, each holding the inner keys of a range of
+// leading bytes
+const SECONDARY_INDEX_ENTRY_SHARDS: usize = 16;
+
+/// Range of inner keys of a secondary index entry
+pub type InnerKeyRange<'a> = (Bound<&'a Pubkey>, Bound<&'a Pubkey>);
+
 // The only cases where an inner key should map to a different outer key is
 // if the key had different account data for the indexed key across different
 // slots. As this is rare, it should be ok to use a Vec here over a HashSet, even
@@ -24,52 +32,108 @@ pub trait SecondaryIndexEntry: Debug {
     fn remove_inner_key(&self, key: &Pubkey) -> bool;
     fn is_empty(&self) -> bool;
     fn keys(&self) -> Vec<Pubkey>;
+    // Returns the first `limit` keys in `range`, in order
+    fn keys_in_range(&self, range: InnerKeyRange, limit: usize) -> Vec<Pubkey>;
     fn len(&self) -> usize;
 }
 
+/// Returns false for the ranges `BTreeSet::range()` panics on, which hold no key
+fn is_valid_range(range: &InnerKeyRange) -> bool {
+    match *range {
+        (Bound::Excluded(start), Bound::Excluded(end)) => start < end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => start <= end,
+        _ => true,
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct SecondaryIndexStats {
     last_report: AtomicInterval,
     num_inner_keys: AtomicU64,
 }
 
+/// Secondary index entry for keys with many inner keys, spread across shards so that concurrent
+/// writers rarely contend. The shards hold consecutive ranges of inner keys, which keeps the
+/// entry ordered.
 #[derive(Debug, Default)]
-pub struct DashMapSecondaryIndexEntry {
-    account_keys: DashMap<Pubkey, ()>,
+pub struct ShardedSecondaryIndexEntry {
+    shards: [RwLock<BTreeSet<Pubkey>>; SECONDARY_INDEX_ENTRY_SHARDS],
 }
 
-impl SecondaryIndexEntry for DashMapSecondaryIndexEntry {
+impl ShardedSecondaryIndexEntry {
+    fn shard_index(key: &Pubkey) -> usize {
+        key.as_ref()[0] as usize * SECONDARY_INDEX_ENTRY_SHARDS / 256
+    }
+
+    fn shard(&self, key: &Pubkey) -> &RwLock<BTreeSet<Pubkey>> {
+        &self.shards[Self::shard_index(key)]
+    }
+}
+
+impl SecondaryIndexEntry for ShardedSecondaryIndexEntry {
     fn insert_if_not_exists(&self, key: &Pubkey, inner_keys_count: &AtomicU64) {
-        if self.account_keys.get(key).is_none() {
-            self.account_keys.entry(*key).or_insert_with(|| {
-                inner_keys_count.fetch_add(1, Ordering::Relaxed);
-            });
+        let shard = self.shard(key);
+        let exists = shard.read().unwrap().contains(key);
+        if !exists && shard.write().unwrap().insert(*key) {
+            inner_keys_count.fetch_add(1, Ordering::Relaxed);
         }
     }
 
     fn remove_inner_key(&self, key: &Pubkey) -> bool {
-        self.account_keys.remove(key).is_some()
+        self.shard(key).write().unwrap().remove(key)
     }
 
     fn is_empty(&self) -> bool {
-        self.account_keys.is_empty()
+        self.shards
+            .iter()
+            .all(|shard| shard.read().unwrap().is_empty())
     }
 
     fn keys(&self) -> Vec<Pubkey> {
-        self.account_keys
-            .iter()
-            .map(|entry_ref| *entry_ref.key())
-            .collect()
+        let mut keys = Vec::new();
+        for shard in &self.shards {
+            keys.extend(shard.read().unwrap().iter());
+        }
+        keys
+    }
+
+    fn keys_in_range(&self, range: InnerKeyRange, limit: usize) -> Vec<Pubkey> {
+        if !is_valid_range(&range) {
+            return vec![];
+        }
+        let first_shard = match range.0 {
+            Bound::Included(start) | Bound::Excluded(start) => Self::shard_index(start),
+            Bound::Unbounded => 0,
+        };
+        let last_shard = match range.1 {
+            Bound::Included(end) | Bound::Excluded(end) => Self::shard_index(end),
+            Bound::Unbounded => SECONDARY_INDEX_ENTRY_SHARDS - 1,
+        };
+        let mut keys = Vec::new();
+        for shard in &self.shards[first_shard..=last_shard] {
+            if keys.len() >= limit {
+                break;
+            }
+            let shard = shard.read().unwrap();
+            keys.extend(shard.range(range).take(limit - keys.len()).cloned());
+        }
+        keys
     }
 
     fn len(&self) -> usize {
-        self.account_keys.len()
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().len())
+            .sum()
     }
 }
 
 #[derive(Debug, Default)]
 pub struct RwLockSecondaryIndexEntry {
-    account_keys: RwLock<HashSet<Pubkey>>,
+    account_keys: RwLock<BTreeSet<Pubkey>>,
 }
 
 impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
@@ -94,6 +158,19 @@ impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
         self.account_keys.read().unwrap().iter().cloned().collect()
     }
 
+    fn keys_in_range(&self, range: InnerKeyRange, limit: usize) -> Vec<Pubkey> {
+        if !is_valid_range(&range) {
+            return vec![];
+        }
+        self.account_keys
+            .read()
+            .unwrap()
+            .range(range)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
     fn len(&self) -> usize {
         self.account_keys.read().unwrap().len()
     }
@@ -188,6 +265,23 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
+    /// Returns every key of the index, along with its inner keys
+    pub fn entries(&self) -> Vec<(Pubkey, Vec<Pubkey>)> {
+        self.index
+            .iter()
+            .map(|entry_ref| (*entry_ref.key(), entry_ref.value().keys()))
+            .collect()
+    }
+
+    /// Inserts every key returned by `entries()`, along with its inner keys
+    pub fn insert_entries(&self, entries: Vec<(Pubkey, Vec<Pubkey>)>) {
+        for (key, inner_keys) in entries {
+            for inner_key in inner_keys {
+                self.insert(&key, &inner_key);
+            }
+        }
+    }
+
     pub fn remove_by_inner_key(&self, inner_key: &Pubkey) {
         // Save off which keys in `self.index` had slots removed so we can remove them
         // after we purge the reverse index
@@ -223,6 +317,15 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
+    /// Returns, in order, the first `limit` inner keys of `key` in `range`
+    pub fn get_in_range(&self, key: &Pubkey, range: InnerKeyRange, limit: usize) -> Vec<Pubkey> {
+        if let Some(inner_keys_map) = self.index.get(key) {
+            inner_keys_map.keys_in_range(range, limit)
+        } else {
+            vec![]
+        }
+    }
+
     /// log top 20 (owner, # accounts) in descending order of # accounts
     pub fn log_contents(&self) {
         let mut entries = self
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod byte_block;
+pub mod cold;
 pub mod error;
 pub mod file;
 pub mod footer;
@@ -8,17 +9,20 @@ pub mod hot;
 pub mod index;
 pub mod meta;
 pub mod mmap_utils;
+pub mod owners;
 pub mod readable;
 pub mod writer;
 
 use {
     crate::{
         account_storage::meta::{StorableAccountsWithHashesAndWriteVersions, StoredAccountInfo},
+        accounts_file::ALIGN_BOUNDARY_OFFSET,
         storable_accounts::StorableAccounts,
     },
     error::TieredStorageError,
-    footer::{AccountBlockFormat, AccountMetaFormat, OwnersBlockFormat},
+    footer::{AccountBlockFormat, AccountMetaFormat},
     index::AccountIndexFormat,
+    owners::OwnersBlockFormat,
     readable::TieredStorageReader,
     solana_sdk::{account::ReadableAccount, hash::Hash},
     std::{
@@ -151,16 +155,32 @@ impl TieredStorage {
     }
 }
 
+/// Converts the index of an account inside a tiered accounts file into
+/// the offset used by AccountsDb to refer to the account.
+///
+/// AccountsDb requires offsets to be aligned, so the index is scaled by
+/// ALIGN_BOUNDARY_OFFSET.
+pub fn index_to_offset(index: usize) -> usize {
+    index * ALIGN_BOUNDARY_OFFSET
+}
+
+/// Converts the offset used by AccountsDb back into the index of an account
+/// inside a tiered accounts file.
+pub fn offset_to_index(offset: usize) -> usize {
+    offset / ALIGN_BOUNDARY_OFFSET
+}
+
 #[cfg(test)]
 mod tests {
     use {
         super::*,
         crate::account_storage::meta::{StoredMeta, StoredMetaWriteVersion},
+        cold::COLD_FORMAT,
         footer::{TieredStorageFooter, TieredStorageMagicNumber},
         hot::HOT_FORMAT,
         solana_accounts_db::rent_collector::RENT_EXEMPT_RENT_EPOCH,
         solana_sdk::{
-            account::{Account, AccountSharedData},
+            account::{accounts_equal, Account, AccountSharedData},
             clock::Slot,
             pubkey::Pubkey,
             system_instruction::MAX_PERMITTED_DATA_LENGTH,
@@ -198,7 +218,9 @@ mod tests {
                 Err(TieredStorageError::AttemptToUpdateReadOnly(_)),
                 Err(TieredStorageError::AttemptToUpdateReadOnly(_)),
             ) => {}
-            (Err(TieredStorageError::Unsupported()), Err(TieredStorageError::Unsupported())) => {}
+            (Ok(stored_infos), Ok(expected_infos)) => {
+                assert_eq!(stored_infos.len(), expected_infos.len());
+            }
             // we don't expect error type mis-match or other error types here
             _ => {
                 panic!("actual: {result:?}, expected: {expected_result:?}");
@@ -229,10 +251,7 @@ mod tests {
             assert_eq!(tiered_storage.path(), tiered_storage_path);
             assert_eq!(tiered_storage.file_size().unwrap(), 0);
 
-            // Expect the result to be TieredStorageError::Unsupported as the feature
-            // is not yet fully supported, but we can still check its partial results
-            // in the test.
-            write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+            write_zero_accounts(&tiered_storage, Ok(vec![]));
         }
 
         let tiered_storage_readonly = TieredStorage::new_readonly(&tiered_storage_path).unwrap();
@@ -257,10 +276,7 @@ mod tests {
         let tiered_storage_path = temp_dir.path().join("test_write_accounts_twice");
 
         let tiered_storage = TieredStorage::new_writable(&tiered_storage_path, HOT_FORMAT.clone());
-        // Expect the result to be TieredStorageError::Unsupported as the feature
-        // is not yet fully supported, but we can still check its partial results
-        // in the test.
-        write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+        write_zero_accounts(&tiered_storage, Ok(vec![]));
         // Expect AttemptToUpdateReadOnly error as write_accounts can only
         // be invoked once.
         write_zero_accounts(
@@ -279,7 +295,7 @@ mod tests {
         {
             let tiered_storage =
                 TieredStorage::new_writable(&tiered_storage_path, HOT_FORMAT.clone());
-            write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+            write_zero_accounts(&tiered_storage, Ok(vec![]));
         }
         // expect the file does not exists as it has been removed on drop
         assert!(!tiered_storage_path.try_exists().unwrap());
@@ -289,7 +305,7 @@ mod tests {
                 &tiered_storage_path,
                 HOT_FORMAT.clone(),
             ));
-            write_zero_accounts(&tiered_storage, Err(TieredStorageError::Unsupported()));
+            write_zero_accounts(&tiered_storage, Ok(vec![]));
         }
         // expect the file exists as we have ManuallyDrop this time.
         assert!(tiered_storage_path.try_exists().unwrap());
@@ -334,8 +350,8 @@ mod tests {
         (stored_meta, AccountSharedData::from(account))
     }
 
-    /// The helper function for all write_accounts tests.
-    /// Currently only supports hot accounts.
+    /// The helper function for all write_accounts tests, which writes the
+    /// accounts in the specified format and reads every account back.
     fn do_test_write_accounts(
         path_suffix: &str,
         account_data_sizes: &[u64],
@@ -345,62 +361,88 @@ mod tests {
             .iter()
             .map(|size| create_account(*size))
             .collect();
-
         let account_refs: Vec<_> = accounts
             .iter()
             .map(|account| (&account.0.pubkey, &account.1))
             .collect();
-
-        // Slot information is not used here
         let account_data = (Slot::MAX, &account_refs[..]);
         let hashes: Vec<_> = std::iter::repeat_with(Hash::new_unique)
             .take(account_data_sizes.len())
             .collect();
-        let write_versions: Vec<_> = accounts
-            .iter()
-            .map(|account| account.0.write_version_obsolete)
-            .collect();
-
+        let write_versions: Vec<_> = (0..accounts.len() as StoredMetaWriteVersion).collect();
         let storable_accounts =
             StorableAccountsWithHashesAndWriteVersions::new_with_hashes_and_write_versions(
                 &account_data,
-                hashes,
-                write_versions,
+                hashes.clone(),
+                write_versions.clone(),
             );
 
         let temp_dir = tempdir().unwrap();
         let tiered_storage_path = temp_dir.path().join(path_suffix);
         let tiered_storage = TieredStorage::new_writable(tiered_storage_path, format.clone());
-        _ = tiered_storage.write_accounts(&storable_accounts, 0);
-
-        verify_hot_storage(&tiered_storage, &accounts, format);
-    }
+        let stored_infos = tiered_storage
+            .write_accounts(&storable_accounts, 0)
+            .unwrap();
+        assert_eq!(stored_infos.len(), accounts.len());
+        // stored sizes are measured in the same unit as the file size.
+        assert!(
+            stored_infos
+                .iter()
+                .map(|info| info.size as u64)
+                .sum::<u64>()
+                <= tiered_storage.file_size().unwrap()
+        );
 
-    /// Verify the generated tiered storage in the test.
-    fn verify_hot_storage(
-        tiered_storage: &TieredStorage,
-        expected_accounts: &[(StoredMeta, AccountSharedData)],
-        expected_format: TieredStorageFormat,
-    ) {
         let reader = tiered_storage.reader().unwrap();
-        assert_eq!(reader.num_accounts(), expected_accounts.len());
-
+        assert_eq!(reader.num_accounts(), accounts.len());
         let footer = reader.footer();
-        let expected_footer = TieredStorageFooter {
-            account_meta_format: expected_format.account_meta_format,
-            owners_block_format: expected_format.owners_block_format,
-            account_index_format: expected_format.account_index_format,
-            account_block_format: expected_format.account_block_format,
-            account_entry_count: expected_accounts.len() as u32,
-            // Hash is not yet implemented, so we bypass the check
-            hash: footer.hash,
-            ..TieredStorageFooter::default()
-        };
+        assert_eq!(footer.account_meta_format, format.account_meta_format);
+        assert_eq!(footer.owners_block_format, format.owners_block_format);
+        assert_eq!(footer.account_index_format, format.account_index_format);
+        assert_eq!(footer.account_block_format, format.account_block_format);
+        assert_eq!(footer.account_entry_count as usize, accounts.len());
+        assert_eq!(footer.owner_count as usize, accounts.len());
+
+        for (index, (stored_meta, account)) in accounts.iter().enumerate() {
+            let (stored_account, next) = reader.get_account(index).unwrap().unwrap();
+            assert_eq!(next, index + 1);
+            assert_eq!(stored_account.offset(), stored_infos[index].offset);
+            assert_eq!(stored_account.stored_size(), stored_infos[index].size);
+            assert_eq!(stored_account.pubkey(), &stored_meta.pubkey);
+            assert_eq!(stored_account.hash(), &hashes[index]);
+            assert_eq!(stored_account.write_version(), write_versions[index]);
+            assert!(accounts_equal(&stored_account, account));
+
+            let owners = [&Pubkey::default(), account.owner()];
+            assert_eq!(
+                reader.account_matches_owners(index, &owners).unwrap(),
+                Some(1)
+            );
+        }
+        assert!(reader.get_account(accounts.len()).unwrap().is_none());
+    }
 
-        // TODO(yhchiang): verify account meta and data once the reader side
-        // is implemented in a separate PR.
+    #[test]
+    fn test_write_and_read_cold_accounts_small_accounts() {
+        do_test_write_accounts(
+            "test_write_and_read_cold_accounts_small_accounts",
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            COLD_FORMAT.clone(),
+        );
+    }
 
-        assert_eq!(*footer, expected_footer);
+    #[test]
+    fn test_write_and_read_cold_accounts_mixed_size() {
+        // contains accounts that span multiple account blocks as well as
+        // blob accounts that have their own account block.
+        do_test_write_accounts(
+            "test_write_and_read_cold_accounts_mixed_size",
+            &[
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1,
+                20_000, 100_000, 1, 2, 3,
+            ],
+            COLD_FORMAT.clone(),
+        );
     }
 
     #[test]
@@ -0,0 +1,740 @@
+//! The account meta and related structs for cold accounts.
+//!
+//! Unlike hot accounts, cold accounts are grouped into account blocks that
+//! are compressed as a whole.  A cold accounts file has the following layout:
+//!
+//! * account blocks, each of which is persisted as a u64 length followed by
+//!   the compressed bytes and padded to an 8-byte boundary
+//! * the account index block
+//! * the owners block
+//! * the footer
+//!
+//! Once decoded, an account block consists of one or more account entries,
+//! and each entry consists of a ColdAccountMeta, the account data, 0-7 bytes
+//! of padding, and the optional fields.
+
+use {
+    crate::{
+        account_storage::meta::{
+            StorableAccountsWithHashesAndWriteVersions, StoredAccountInfo, StoredAccountMeta,
+            StoredMetaWriteVersion,
+        },
+        storable_accounts::StorableAccounts,
+        tiered_storage::{
+            byte_block::{self, ByteBlockReader, ByteBlockWriter},
+            error::TieredStorageError,
+            file::TieredStorageFile,
+            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
+            index::{AccountIndexFormat, AccountIndexWriterEntry},
+            index_to_offset,
+            meta::{AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta},
+            mmap_utils::{get_slice, get_type},
+            owners::{OwnersBlockFormat, OwnersTable},
+            writer::{padding_bytes, AccountEntry, PADDING_BUFFER},
+            TieredStorageFormat, TieredStorageResult,
+        },
+    },
+    lru::LruCache,
+    memmap2::{Mmap, MmapOptions},
+    modular_bitfield::prelude::*,
+    solana_sdk::{account::ReadableAccount, hash::Hash, pubkey::Pubkey, stake_history::Epoch},
+    std::{
+        borrow::Borrow,
+        fs::OpenOptions,
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+};
+
+pub const COLD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    meta_entry_size: std::mem::size_of::<ColdAccountMeta>(),
+    account_meta_format: AccountMetaFormat::Cold,
+    owners_block_format: OwnersBlockFormat::LocalIndex,
+    account_index_format: AccountIndexFormat::AddressAndOffsets,
+    account_block_format: AccountBlockFormat::Lz4,
+};
+
+/// The default size of a cold account block before compression.  Accounts
+/// are appended to the current account block until adding the next account
+/// would exceed this size, in which case a new account block is started.
+/// An account bigger than this size has its own account block.
+pub const COLD_ACCOUNT_BLOCK_SIZE: u64 = 16 * 1024;
+
+/// The maximum number of decoded account blocks cached by a
+/// ColdStorageReader.
+const MAX_CACHED_COLD_ACCOUNT_BLOCKS: usize = 16;
+
+/// The maximum number of padding bytes used in a cold account entry.
+const MAX_COLD_PADDING: u8 = 7;
+
+/// The maximum allowed value for the owner index of a cold account.
+const MAX_COLD_OWNER_INDEX: u32 = (1 << 29) - 1;
+
+#[bitfield(bits = 32)]
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+struct ColdMetaPackedFields {
+    /// The number of padding bytes between the account data and the
+    /// optional fields of its cold account entry.
+    padding: B3,
+    /// The index to the owner of a cold account inside an AccountsFile.
+    owner_index: B29,
+}
+
+/// The storage and in-memory representation of the metadata entry for a
+/// cold account.
+#[derive(Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ColdAccountMeta {
+    /// The balance of this account.
+    lamports: u64,
+    /// The size of the account data.  Unlike hot accounts, this size cannot
+    /// be derived from the offsets of two consecutive entries as multiple
+    /// cold accounts share the same account block.
+    account_data_size: u64,
+    /// Stores important fields in a packed struct.
+    packed_fields: ColdMetaPackedFields,
+    /// Stores boolean flags and existence of each optional field.
+    flags: AccountMetaFlags,
+}
+
+impl ColdAccountMeta {
+    /// Returns the size of the account entry associated with this meta
+    /// inside its decoded account block.
+    pub fn entry_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.account_data_size as usize
+            + self.account_data_padding() as usize
+            + AccountMetaOptionalFields::size_from_flags(&self.flags)
+    }
+}
+
+impl TieredAccountMeta for ColdAccountMeta {
+    /// Construct a ColdAccountMeta instance.
+    fn new() -> Self {
+        ColdAccountMeta {
+            lamports: 0,
+            account_data_size: 0,
+            packed_fields: ColdMetaPackedFields::default(),
+            flags: AccountMetaFlags::new(),
+        }
+    }
+
+    /// A builder function that initializes lamports.
+    fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// A builder function that initializes the number of padding bytes
+    /// for the account data associated with the current meta.
+    fn with_account_data_padding(mut self, padding: u8) -> Self {
+        if padding > MAX_COLD_PADDING {
+            panic!("padding exceeds MAX_COLD_PADDING");
+        }
+        self.packed_fields.set_padding(padding);
+        self
+    }
+
+    /// A builder function that initializes the owner's index.
+    fn with_owner_index(mut self, owner_index: u32) -> Self {
+        if owner_index > MAX_COLD_OWNER_INDEX {
+            panic!("owner_index exceeds MAX_COLD_OWNER_INDEX");
+        }
+        self.packed_fields.set_owner_index(owner_index);
+        self
+    }
+
+    /// A builder function that initializes the account data size.
+    fn with_account_data_size(mut self, account_data_size: u64) -> Self {
+        self.account_data_size = account_data_size;
+        self
+    }
+
+    /// A builder function that initializes the AccountMetaFlags of the current
+    /// meta.
+    fn with_flags(mut self, flags: &AccountMetaFlags) -> Self {
+        self.flags = *flags;
+        self
+    }
+
+    /// Returns the balance of the lamports associated with the account.
+    fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    /// Returns the number of padding bytes for the associated account data
+    fn account_data_padding(&self) -> u8 {
+        self.packed_fields.padding()
+    }
+
+    /// Returns the index to the accounts' owner in the current AccountsFile.
+    fn owner_index(&self) -> u32 {
+        self.packed_fields.owner_index()
+    }
+
+    /// Returns the AccountMetaFlags of the current meta.
+    fn flags(&self) -> &AccountMetaFlags {
+        &self.flags
+    }
+
+    /// Always returns true as multiple cold accounts share the same
+    /// account block.
+    fn supports_shared_account_block() -> bool {
+        true
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  None will be returned if this account
+    /// does not persist this optional field.
+    fn rent_epoch(&self, account_block: &[u8]) -> Option<Epoch> {
+        self.flags()
+            .has_rent_epoch()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::rent_epoch_offset(self.flags());
+                byte_block::read_type::<Epoch>(account_block, offset).copied()
+            })
+            .flatten()
+    }
+
+    /// Returns the account hash by parsing the specified account block.  None
+    /// will be returned if this account does not persist this optional field.
+    fn account_hash<'a>(&self, account_block: &'a [u8]) -> Option<&'a Hash> {
+        self.flags()
+            .has_account_hash()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::account_hash_offset(self.flags());
+                byte_block::read_type::<Hash>(account_block, offset)
+            })
+            .flatten()
+    }
+
+    /// Returns the write version by parsing the specified account block.  None
+    /// will be returned if this account does not persist this optional field.
+    fn write_version(&self, account_block: &[u8]) -> Option<StoredMetaWriteVersion> {
+        self.flags
+            .has_write_version()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::write_version_offset(self.flags());
+                byte_block::read_type::<StoredMetaWriteVersion>(account_block, offset).copied()
+            })
+            .flatten()
+    }
+
+    /// Returns the offset of the optional fields based on the specified account
+    /// block.
+    ///
+    /// Note that the specified account block starts right after this meta
+    /// and may be followed by the entries of other accounts.
+    fn optional_fields_offset(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize + self.account_data_padding() as usize
+    }
+
+    /// Returns the length of the data associated to this account.
+    fn account_data_size(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize
+    }
+
+    /// Returns the data associated to this account based on the specified
+    /// account block.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
+        &account_block[..self.account_data_size(account_block)]
+    }
+}
+
+/// Persists the specified accounts (starting from `skip`) into the specified
+/// file in the cold format, and returns the StoredAccountInfo of each
+/// persisted account.
+pub fn write_cold_accounts<
+    'a,
+    'b,
+    T: ReadableAccount + Sync,
+    U: StorableAccounts<'a, T>,
+    V: Borrow<Hash>,
+>(
+    file: &TieredStorageFile,
+    accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
+    skip: usize,
+    format: &TieredStorageFormat,
+) -> TieredStorageResult<Vec<StoredAccountInfo>> {
+    let len = accounts.accounts.len();
+    let mut footer = TieredStorageFooter {
+        account_meta_format: format.account_meta_format,
+        owners_block_format: format.owners_block_format,
+        account_index_format: format.account_index_format,
+        account_block_format: format.account_block_format,
+        account_entry_count: len
+            .saturating_sub(skip)
+            .try_into()
+            .expect("num accounts <= u32::MAX"),
+        account_meta_entry_size: format.meta_entry_size as u32,
+        account_block_size: COLD_ACCOUNT_BLOCK_SIZE,
+        owner_entry_size: format.owners_block_format.entry_size() as u32,
+        ..TieredStorageFooter::default()
+    };
+
+    let mut owners_table = OwnersTable::default();
+    let mut index_entries = Vec::with_capacity(len.saturating_sub(skip));
+    let mut stored_infos = Vec::with_capacity(len.saturating_sub(skip));
+    let mut block_writer = ByteBlockWriter::new(format.account_block_format);
+    // the number of bytes persisted in the file so far, which is also the
+    // offset of the account block that is currently being written.
+    let mut cursor = 0;
+    // the position in stored_infos of the first account of the account
+    // block that is currently being written.
+    let mut block_start = 0;
+
+    for i in skip..len {
+        let entry = AccountEntry::new(accounts, i);
+        let padding = padding_bytes(entry.data.len());
+        let meta = ColdAccountMeta::new()
+            .with_lamports(entry.lamports)
+            .with_account_data_size(entry.data.len() as u64)
+            .with_account_data_padding(padding as u8)
+            .with_owner_index(owners_table.insert(entry.owner))
+            .with_flags(&entry.flags);
+        let entry_size = meta.entry_size();
+
+        // Start a new account block if the current one cannot fit this
+        // account entry.  A blob account ends up in its own account block.
+        if block_writer.raw_len() > 0
+            && block_writer.raw_len() + entry_size > COLD_ACCOUNT_BLOCK_SIZE as usize
+        {
+            let full_block = std::mem::replace(
+                &mut block_writer,
+                ByteBlockWriter::new(format.account_block_format),
+            );
+            cursor += write_account_block(file, full_block, &mut stored_infos[block_start..])?;
+            block_start = stored_infos.len();
+        }
+
+        index_entries.push(AccountIndexWriterEntry {
+            address: entry.address,
+            block_offset: cursor as u64,
+            intra_block_offset: block_writer.raw_len() as u64,
+        });
+        block_writer.write_type(&meta)?;
+        block_writer.write(entry.data)?;
+        block_writer.write(&PADDING_BUFFER[..padding])?;
+        block_writer.write_optional_fields(&entry.optional_fields)?;
+
+        // the size is converted into the stored size once the account block
+        // is persisted.
+        stored_infos.push(StoredAccountInfo {
+            offset: index_to_offset(i - skip),
+            size: entry_size,
+        });
+    }
+    if block_writer.raw_len() > 0 {
+        cursor += write_account_block(file, block_writer, &mut stored_infos[block_start..])?;
+    }
+
+    footer.account_index_offset = cursor as u64;
+    cursor += format
+        .account_index_format
+        .write_index_block(file, &index_entries)?;
+
+    footer.owners_offset = cursor as u64;
+    format
+        .owners_block_format
+        .write_owners_block(file, owners_table.owners())?;
+    footer.owner_count = owners_table.len() as u32;
+
+    if let Some(min_address) = index_entries.iter().map(|entry| entry.address).min() {
+        footer.min_account_address = *min_address;
+    }
+    if let Some(max_address) = index_entries.iter().map(|entry| entry.address).max() {
+        footer.max_account_address = *max_address;
+    }
+
+    footer.write_footer_block(file)?;
+
+    Ok(stored_infos)
+}
+
+/// Persists the specified account block and returns the number of bytes
+/// written, including the length prefix and the trailing padding.
+///
+/// The sizes of the specified `stored_infos`, which belong to the accounts
+/// inside this account block, are converted from their entry sizes into
+/// their stored sizes.
+fn write_account_block(
+    file: &TieredStorageFile,
+    block_writer: ByteBlockWriter,
+    stored_infos: &mut [StoredAccountInfo],
+) -> TieredStorageResult<usize> {
+    let raw_len = block_writer.raw_len();
+    let encoded = block_writer.finish()?;
+    let padding = padding_bytes(encoded.len());
+    let mut bytes_written = file.write_type(&(encoded.len() as u64))?;
+    bytes_written += file.write_bytes(&encoded)?;
+    bytes_written += file.write_bytes(&PADDING_BUFFER[..padding])?;
+    for stored_info in stored_infos {
+        stored_info.size = stored_size_in_block(stored_info.size, raw_len, bytes_written);
+    }
+    Ok(bytes_written)
+}
+
+/// Returns the stored size of a cold account, which is the share of its
+/// persisted account block attributed to its account entry.
+///
+/// AccountsDb compares the stored sizes of accounts against the size of
+/// their accounts file, so they must be measured in the same unit.  As
+/// shares are rounded down, the stored sizes of all the accounts inside
+/// a cold accounts file never add up to more than its size.
+fn stored_size_in_block(
+    entry_size: usize,
+    block_raw_len: usize,
+    block_stored_size: usize,
+) -> usize {
+    (entry_size as u128 * block_stored_size as u128 / block_raw_len.max(1) as u128) as usize
+}
+
+/// A cold account returned by ColdStorageReader.
+///
+/// Unlike TieredReadableAccount, which borrows its account block from the
+/// mmap, a cold account keeps its decoded account block alive by itself,
+/// so the reader is free to evict that block from its cache at any time.
+#[derive(PartialEq, Eq, Debug)]
+pub struct ColdReadableAccount<'accounts_file> {
+    /// The address of the account
+    address: &'accounts_file Pubkey,
+    /// The address of the account owner
+    owner: &'accounts_file Pubkey,
+    /// The index for accessing the account inside its belonging AccountsFile
+    index: usize,
+    /// The decoded account block that contains this account.  Note that this
+    /// account block may be shared with other accounts.
+    block: Arc<Vec<u8>>,
+    /// The offset of the account entry inside the decoded account block.
+    entry_offset: usize,
+    /// The stored size of this account.  See stored_size_in_block().
+    stored_size: usize,
+}
+
+impl<'accounts_file> ColdReadableAccount<'accounts_file> {
+    /// Returns the meta of this account.
+    pub fn meta(&self) -> &ColdAccountMeta {
+        // the meta has been validated when this account was read.
+        byte_block::read_type::<ColdAccountMeta>(&self.block, self.entry_offset).unwrap()
+    }
+
+    /// Returns the part of the account block that starts right after the
+    /// meta of this account.
+    fn account_block(&self) -> &[u8] {
+        &self.block[self.entry_offset + std::mem::size_of::<ColdAccountMeta>()..]
+    }
+
+    /// Returns the address of this account.
+    pub fn address(&self) -> &'accounts_file Pubkey {
+        self.address
+    }
+
+    /// Returns the hash of this account.
+    pub fn hash(&self) -> Option<&Hash> {
+        self.meta().account_hash(self.account_block())
+    }
+
+    /// Returns the index to this account in its AccountsFile.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the write version of the account.
+    pub fn write_version(&self) -> Option<StoredMetaWriteVersion> {
+        self.meta().write_version(self.account_block())
+    }
+
+    /// Returns the data associated to this account.
+    pub fn data(&self) -> &[u8] {
+        self.meta().account_data(self.account_block())
+    }
+
+    /// Returns the stored size of this account.
+    pub fn stored_size(&self) -> usize {
+        self.stored_size
+    }
+}
+
+impl<'accounts_file> ReadableAccount for ColdReadableAccount<'accounts_file> {
+    /// Returns the balance of the lamports of this account.
+    fn lamports(&self) -> u64 {
+        self.meta().lamports()
+    }
+
+    /// Returns the address of the owner of this account.
+    fn owner(&self) -> &'accounts_file Pubkey {
+        self.owner
+    }
+
+    /// Returns true if the data associated to this account is executable.
+    fn executable(&self) -> bool {
+        self.meta().flags().executable()
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  Epoch::MAX will be returned if the account
+    /// is rent-exempt.
+    fn rent_epoch(&self) -> Epoch {
+        self.meta()
+            .rent_epoch(self.account_block())
+            .unwrap_or(Epoch::MAX)
+    }
+
+    /// Returns the data associated to this account.
+    fn data(&self) -> &[u8] {
+        self.data()
+    }
+}
+
+/// The reader to a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageReader {
+    mmap: Mmap,
+    footer: TieredStorageFooter,
+    /// The recently decoded account blocks, keyed by their offsets.
+    decoded_blocks: Mutex<LruCache<u64, Arc<Vec<u8>>>>,
+}
+
+impl ColdStorageReader {
+    /// Constructs a ColdStorageReader from the specified path.
+    pub fn new_from_path(path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        let footer = TieredStorageFooter::new_from_mmap(&mmap)?.clone();
+
+        Ok(Self {
+            mmap,
+            footer,
+            decoded_blocks: Mutex::new(LruCache::new(MAX_CACHED_COLD_ACCOUNT_BLOCKS)),
+        })
+    }
+
+    /// Returns the footer of the underlying tiered-storage accounts file.
+    pub fn footer(&self) -> &TieredStorageFooter {
+        &self.footer
+    }
+
+    /// Returns the number of accounts inside the underlying tiered-storage
+    /// accounts file.
+    pub fn num_accounts(&self) -> usize {
+        self.footer.account_entry_count as usize
+    }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: usize) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .account_index_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the offset of the account block that contains the account
+    /// associated with the specified index.
+    fn get_account_block_offset(&self, index: usize) -> TieredStorageResult<u64> {
+        self.footer
+            .account_index_format
+            .get_account_block_offset(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the address of the owner associated with the specified
+    /// owner index.
+    fn get_owner_address(&self, owner_index: usize) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .owners_block_format
+            .get_owner_address(&self.mmap, &self.footer, owner_index)
+    }
+
+    /// Returns the offset of the account entry associated with the specified
+    /// index inside its decoded account block.
+    fn get_intra_block_offset(&self, index: usize) -> TieredStorageResult<u64> {
+        self.footer
+            .account_index_format
+            .get_intra_block_offset(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the decoded account block at the specified offset, together
+    /// with the number of bytes the account block takes in the file.
+    fn get_account_block(&self, block_offset: u64) -> TieredStorageResult<(Arc<Vec<u8>>, usize)> {
+        let (encoded_len, offset) = get_type::<u64>(&self.mmap, block_offset as usize)?;
+        let encoded_len = *encoded_len as usize;
+        let stored_size = std::mem::size_of::<u64>()
+            .saturating_add(encoded_len)
+            .saturating_add(padding_bytes(encoded_len));
+
+        if let Some(block) = self.decoded_blocks.lock().unwrap().get(&block_offset) {
+            return Ok((Arc::clone(block), stored_size));
+        }
+        // The account block is decoded without holding the lock.  Another
+        // thread might decode the same block concurrently, in which case
+        // either result ends up in the cache.
+        let (encoded, _) = get_slice(&self.mmap, offset, encoded_len)?;
+        let block = Arc::new(ByteBlockReader::decode(
+            self.footer.account_block_format,
+            encoded,
+        )?);
+        self.decoded_blocks
+            .lock()
+            .unwrap()
+            .put(block_offset, Arc::clone(&block));
+        Ok((block, stored_size))
+    }
+
+    /// Returns the account associated with the specified index.
+    fn get_readable_account(&self, index: usize) -> TieredStorageResult<ColdReadableAccount<'_>> {
+        if index >= self.num_accounts() {
+            return Err(TieredStorageError::InvalidAccountIndex(index));
+        }
+        let block_offset = self.get_account_block_offset(index)?;
+        let entry_offset = self.get_intra_block_offset(index)? as usize;
+        let (block, block_stored_size) = self.get_account_block(block_offset)?;
+
+        let meta = byte_block::read_type::<ColdAccountMeta>(&block, entry_offset)
+            .ok_or(TieredStorageError::CorruptedAccountBlock(index))?;
+        let entry_size = meta.entry_size();
+        if entry_offset.saturating_add(entry_size) > block.len() {
+            return Err(TieredStorageError::CorruptedAccountBlock(index));
+        }
+        let owner = self.get_owner_address(meta.owner_index() as usize)?;
+        let stored_size = stored_size_in_block(entry_size, block.len(), block_stored_size);
+
+        Ok(ColdReadableAccount {
+            address: self.get_account_address(index)?,
+            owner,
+            index,
+            block,
+            entry_offset,
+            stored_size,
+        })
+    }
+
+    /// Returns the account associated with the specified index and the index
+    /// of the next account, or None if the index is out of range.
+    pub fn get_account(
+        &self,
+        index: usize,
+    ) -> TieredStorageResult<Option<(StoredAccountMeta<'_>, usize)>> {
+        if index >= self.num_accounts() {
+            return Ok(None);
+        }
+        let account = self.get_readable_account(index)?;
+        Ok(Some((StoredAccountMeta::Cold(account), index + 1)))
+    }
+
+    /// Returns the index of the specified owner inside `owners` if the
+    /// account associated with the specified index is owned by one of them.
+    /// None is returned for zero-lamport accounts.
+    pub fn account_matches_owners(
+        &self,
+        index: usize,
+        owners: &[&Pubkey],
+    ) -> TieredStorageResult<Option<usize>> {
+        let account = self.get_readable_account(index)?;
+        if account.lamports() == 0 {
+            return Ok(None);
+        }
+        Ok(owners
+            .iter()
+            .position(|candidate| *candidate == account.owner()))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use {super::*, memoffset::offset_of};
+
+    #[test]
+    fn test_cold_account_meta_layout() {
+        assert_eq!(offset_of!(ColdAccountMeta, lamports), 0x00);
+        assert_eq!(offset_of!(ColdAccountMeta, account_data_size), 0x08);
+        assert_eq!(offset_of!(ColdAccountMeta, packed_fields), 0x10);
+        assert_eq!(offset_of!(ColdAccountMeta, flags), 0x14);
+        assert_eq!(std::mem::size_of::<ColdAccountMeta>(), 24);
+    }
+
+    #[test]
+    #[should_panic(expected = "padding exceeds MAX_COLD_PADDING")]
+    fn test_cold_meta_padding_exceeds_limit() {
+        ColdAccountMeta::new().with_account_data_padding(MAX_COLD_PADDING + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "owner_index exceeds MAX_COLD_OWNER_INDEX")]
+    fn test_cold_meta_owner_index_exceeds_limit() {
+        ColdAccountMeta::new().with_owner_index(MAX_COLD_OWNER_INDEX + 1);
+    }
+
+    #[test]
+    fn test_cold_account_meta_shared_block() {
+        let first_data = [11u8; 83];
+        let second_data = [22u8; 16];
+        let first_optional_fields = AccountMetaOptionalFields {
+            rent_epoch: Some(7),
+            account_hash: Some(Hash::new_unique()),
+            write_version: Some(3),
+        };
+        let second_optional_fields = AccountMetaOptionalFields {
+            rent_epoch: None,
+            account_hash: Some(Hash::new_unique()),
+            write_version: None,
+        };
+
+        let first_meta = ColdAccountMeta::new()
+            .with_lamports(1)
+            .with_account_data_size(first_data.len() as u64)
+            .with_account_data_padding(padding_bytes(first_data.len()) as u8)
+            .with_flags(&AccountMetaFlags::new_from(&first_optional_fields));
+        let second_meta = ColdAccountMeta::new()
+            .with_lamports(2)
+            .with_account_data_size(second_data.len() as u64)
+            .with_owner_index(1)
+            .with_flags(&AccountMetaFlags::new_from(&second_optional_fields));
+
+        let mut writer = ByteBlockWriter::new(AccountBlockFormat::Lz4);
+        writer.write_type(&first_meta).unwrap();
+        writer.write(&first_data).unwrap();
+        writer
+            .write(&PADDING_BUFFER[..padding_bytes(first_data.len())])
+            .unwrap();
+        writer
+            .write_optional_fields(&first_optional_fields)
+            .unwrap();
+        writer.write_type(&second_meta).unwrap();
+        writer.write(&second_data).unwrap();
+        writer
+            .write_optional_fields(&second_optional_fields)
+            .unwrap();
+        let encoded = writer.finish().unwrap();
+        let buffer = ByteBlockReader::decode(AccountBlockFormat::Lz4, &encoded).unwrap();
+
+        let meta = byte_block::read_type::<ColdAccountMeta>(&buffer, 0).unwrap();
+        assert_eq!(*meta, first_meta);
+        let account_block = &buffer[std::mem::size_of::<ColdAccountMeta>()..];
+        assert_eq!(meta.account_data(account_block), first_data);
+        assert_eq!(meta.rent_epoch(account_block), Some(7));
+        assert_eq!(
+            meta.account_hash(account_block),
+            first_optional_fields.account_hash.as_ref()
+        );
+        assert_eq!(meta.write_version(account_block), Some(3));
+
+        let meta =
+            byte_block::read_type::<ColdAccountMeta>(&buffer, first_meta.entry_size()).unwrap();
+        assert_eq!(*meta, second_meta);
+        let account_block =
+            &buffer[first_meta.entry_size() + std::mem::size_of::<ColdAccountMeta>()..];
+        assert_eq!(meta.account_data(account_block), second_data);
+        assert_eq!(meta.rent_epoch(account_block), None);
+        assert_eq!(
+            meta.account_hash(account_block),
+            second_optional_fields.account_hash.as_ref()
+        );
+        assert_eq!(meta.write_version(account_block), None);
+        assert_eq!(
+            first_meta.entry_size() + second_meta.entry_size(),
+            buffer.len()
+        );
+    }
+}
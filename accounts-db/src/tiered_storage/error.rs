@@ -14,6 +14,12 @@ pub enum TieredStorageError {
     #[error("UnknownFormat: the tiered storage format is unavailable for file {0}")]
     UnknownFormat(PathBuf),
 
+    #[error("InvalidAccountIndex: account index {0} is out of range")]
+    InvalidAccountIndex(usize),
+
+    #[error("CorruptedAccountBlock: unable to read account {0} from its account block")]
+    CorruptedAccountBlock(usize),
+
     #[error("Unsupported: the feature is not yet supported")]
     Unsupported(),
 }
@@ -1,7 +1,7 @@
 use {
     crate::tiered_storage::{
         error::TieredStorageError, file::TieredStorageFile, index::AccountIndexFormat,
-        mmap_utils::get_type, TieredStorageResult as TsResult,
+        mmap_utils::get_type, owners::OwnersBlockFormat, TieredStorageResult as TsResult,
     },
     memmap2::Mmap,
     solana_sdk::{hash::Hash, pubkey::Pubkey},
@@ -47,8 +47,7 @@ impl Default for TieredStorageMagicNumber {
 pub enum AccountMetaFormat {
     #[default]
     Hot = 0,
-    // Temporarily comment out to avoid unimplemented!() block
-    // Cold = 1,
+    Cold = 1,
 }
 
 #[repr(u16)]
@@ -69,23 +68,6 @@ pub enum AccountBlockFormat {
     Lz4 = 1,
 }
 
-#[repr(u16)]
-#[derive(
-    Clone,
-    Copy,
-    Debug,
-    Default,
-    Eq,
-    Hash,
-    PartialEq,
-    num_enum::IntoPrimitive,
-    num_enum::TryFromPrimitive,
-)]
-pub enum OwnersBlockFormat {
-    #[default]
-    LocalIndex = 0,
-}
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[repr(C)]
 pub struct TieredStorageFooter {
@@ -3,21 +3,30 @@
 
 use {
     crate::{
-        account_storage::meta::StoredMetaWriteVersion,
+        account_storage::meta::{
+            StorableAccountsWithHashesAndWriteVersions, StoredAccountInfo, StoredAccountMeta,
+            StoredMetaWriteVersion,
+        },
+        storable_accounts::StorableAccounts,
         tiered_storage::{
-            byte_block,
-            footer::{
-                AccountBlockFormat, AccountMetaFormat, OwnersBlockFormat, TieredStorageFooter,
-            },
-            index::AccountIndexFormat,
+            byte_block::{self, ByteBlockWriter},
+            error::TieredStorageError,
+            file::TieredStorageFile,
+            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
+            index::{AccountIndexFormat, AccountIndexWriterEntry},
+            index_to_offset,
             meta::{AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta},
+            mmap_utils::{get_slice, get_type},
+            owners::{OwnersBlockFormat, OwnersTable},
+            readable::TieredReadableAccount,
+            writer::{padding_bytes, AccountEntry, PADDING_BUFFER},
             TieredStorageFormat, TieredStorageResult,
         },
     },
     memmap2::{Mmap, MmapOptions},
     modular_bitfield::prelude::*,
-    solana_sdk::{hash::Hash, stake_history::Epoch},
-    std::{fs::OpenOptions, option::Option, path::Path},
+    solana_sdk::{account::ReadableAccount, hash::Hash, pubkey::Pubkey, stake_history::Epoch},
+    std::{borrow::Borrow, fs::OpenOptions, option::Option, path::Path},
 };
 
 pub const HOT_FORMAT: TieredStorageFormat = TieredStorageFormat {
@@ -202,6 +211,95 @@ impl TieredAccountMeta for HotAccountMeta {
     }
 }
 
+/// Persists the specified accounts (starting from `skip`) into the specified
+/// file in the hot format, and returns the StoredAccountInfo of each
+/// persisted account.
+pub fn write_hot_accounts<
+    'a,
+    'b,
+    T: ReadableAccount + Sync,
+    U: StorableAccounts<'a, T>,
+    V: Borrow<Hash>,
+>(
+    file: &TieredStorageFile,
+    accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
+    skip: usize,
+    format: &TieredStorageFormat,
+) -> TieredStorageResult<Vec<StoredAccountInfo>> {
+    let len = accounts.accounts.len();
+    let mut footer = TieredStorageFooter {
+        account_meta_format: format.account_meta_format,
+        owners_block_format: format.owners_block_format,
+        account_index_format: format.account_index_format,
+        account_block_format: format.account_block_format,
+        account_entry_count: len
+            .saturating_sub(skip)
+            .try_into()
+            .expect("num accounts <= u32::MAX"),
+        account_meta_entry_size: format.meta_entry_size as u32,
+        owner_entry_size: format.owners_block_format.entry_size() as u32,
+        ..TieredStorageFooter::default()
+    };
+
+    let mut owners_table = OwnersTable::default();
+    let mut index_entries = Vec::with_capacity(len.saturating_sub(skip));
+    let mut stored_infos = Vec::with_capacity(len.saturating_sub(skip));
+    // the number of bytes persisted in the file so far, which is also the
+    // offset of the next hot account entry.
+    let mut cursor = 0;
+
+    for i in skip..len {
+        let entry = AccountEntry::new(accounts, i);
+        let padding = padding_bytes(entry.data.len());
+        let meta = HotAccountMeta::new()
+            .with_lamports(entry.lamports)
+            .with_account_data_padding(padding as u8)
+            .with_owner_index(owners_table.insert(entry.owner))
+            .with_flags(&entry.flags);
+
+        let mut writer = ByteBlockWriter::new(format.account_block_format);
+        writer.write_type(&meta)?;
+        writer.write(entry.data)?;
+        writer.write(&PADDING_BUFFER[..padding])?;
+        writer.write_optional_fields(&entry.optional_fields)?;
+
+        index_entries.push(AccountIndexWriterEntry {
+            address: entry.address,
+            block_offset: cursor as u64,
+            intra_block_offset: 0,
+        });
+        let stored_size = file.write_bytes(&writer.finish()?)?;
+        cursor += stored_size;
+
+        stored_infos.push(StoredAccountInfo {
+            offset: index_to_offset(i - skip),
+            size: stored_size,
+        });
+    }
+
+    footer.account_index_offset = cursor as u64;
+    cursor += format
+        .account_index_format
+        .write_index_block(file, &index_entries)?;
+
+    footer.owners_offset = cursor as u64;
+    format
+        .owners_block_format
+        .write_owners_block(file, owners_table.owners())?;
+    footer.owner_count = owners_table.len() as u32;
+
+    if let Some(min_address) = index_entries.iter().map(|entry| entry.address).min() {
+        footer.min_account_address = *min_address;
+    }
+    if let Some(max_address) = index_entries.iter().map(|entry| entry.address).max() {
+        footer.max_account_address = *max_address;
+    }
+
+    footer.write_footer_block(file)?;
+
+    Ok(stored_infos)
+}
+
 /// The reader to a hot accounts file.
 #[derive(Debug)]
 pub struct HotStorageReader {
@@ -233,6 +331,98 @@ impl HotStorageReader {
     pub fn num_accounts(&self) -> usize {
         self.footer.account_entry_count as usize
     }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: usize) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .account_index_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the offset of the hot account entry associated with the
+    /// specified index.
+    fn get_account_offset(&self, index: usize) -> TieredStorageResult<usize> {
+        Ok(self.footer.account_index_format.get_account_block_offset(
+            &self.mmap,
+            &self.footer,
+            index,
+        )? as usize)
+    }
+
+    /// Returns the address of the owner associated with the specified
+    /// owner index.
+    fn get_owner_address(&self, owner_index: usize) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .owners_block_format
+            .get_owner_address(&self.mmap, &self.footer, owner_index)
+    }
+
+    /// Returns the meta of the account associated with the specified index,
+    /// together with its account block.
+    ///
+    /// As hot account entries are stored consecutively, the account block of
+    /// an entry ends where the next entry (or the index block) starts.
+    fn get_account_meta_and_block(
+        &self,
+        index: usize,
+    ) -> TieredStorageResult<(&HotAccountMeta, &[u8])> {
+        if index >= self.num_accounts() {
+            return Err(TieredStorageError::InvalidAccountIndex(index));
+        }
+        let offset = self.get_account_offset(index)?;
+        let end = if index + 1 < self.num_accounts() {
+            self.get_account_offset(index + 1)?
+        } else {
+            self.footer.account_index_offset as usize
+        };
+        let (meta, block_offset) = get_type::<HotAccountMeta>(&self.mmap, offset)?;
+        let block_len = end
+            .checked_sub(block_offset)
+            .ok_or(TieredStorageError::CorruptedAccountBlock(index))?;
+        let (account_block, _) = get_slice(&self.mmap, block_offset, block_len)?;
+        Ok((meta, account_block))
+    }
+
+    /// Returns the account associated with the specified index and the index
+    /// of the next account, or None if the index is out of range.
+    pub fn get_account(
+        &self,
+        index: usize,
+    ) -> TieredStorageResult<Option<(StoredAccountMeta<'_>, usize)>> {
+        if index >= self.num_accounts() {
+            return Ok(None);
+        }
+        let (meta, account_block) = self.get_account_meta_and_block(index)?;
+        let address = self.get_account_address(index)?;
+        let owner = self.get_owner_address(meta.owner_index() as usize)?;
+
+        Ok(Some((
+            StoredAccountMeta::Hot(TieredReadableAccount {
+                meta,
+                address,
+                owner,
+                index,
+                account_block,
+            }),
+            index + 1,
+        )))
+    }
+
+    /// Returns the index of the specified owner inside `owners` if the
+    /// account associated with the specified index is owned by one of them.
+    /// None is returned for zero-lamport accounts.
+    pub fn account_matches_owners(
+        &self,
+        index: usize,
+        owners: &[&Pubkey],
+    ) -> TieredStorageResult<Option<usize>> {
+        let (meta, _) = self.get_account_meta_and_block(index)?;
+        if meta.lamports() == 0 {
+            return Ok(None);
+        }
+        let owner = self.get_owner_address(meta.owner_index() as usize)?;
+        Ok(owners.iter().position(|candidate| *candidate == owner))
+    }
 }
 
 #[cfg(test)]
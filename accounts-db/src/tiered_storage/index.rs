@@ -1,7 +1,7 @@
 use {
     crate::tiered_storage::{
-        file::TieredStorageFile, footer::TieredStorageFooter, mmap_utils::get_type,
-        TieredStorageResult,
+        error::TieredStorageError, file::TieredStorageFile, footer::TieredStorageFooter,
+        mmap_utils::get_type, TieredStorageResult,
     },
     memmap2::Mmap,
     solana_sdk::pubkey::Pubkey,
@@ -36,6 +36,11 @@ pub enum AccountIndexFormat {
     /// block entries and index block entries in the same order.
     #[default]
     AddressAndOffset = 0,
+    /// In addition to what AddressAndOffset stores, this format also stores
+    /// the offset of each account entry inside its account block, so that
+    /// an account that shares its account block with other accounts can be
+    /// located without walking the entries before it.
+    AddressAndOffsets = 1,
 }
 
 impl AccountIndexFormat {
@@ -46,18 +51,19 @@ impl AccountIndexFormat {
         file: &TieredStorageFile,
         index_entries: &[AccountIndexWriterEntry],
     ) -> TieredStorageResult<usize> {
-        match self {
-            Self::AddressAndOffset => {
-                let mut bytes_written = 0;
-                for index_entry in index_entries {
-                    bytes_written += file.write_type(index_entry.address)?;
-                }
-                for index_entry in index_entries {
-                    bytes_written += file.write_type(&index_entry.block_offset)?;
-                }
-                Ok(bytes_written)
+        let mut bytes_written = 0;
+        for index_entry in index_entries {
+            bytes_written += file.write_type(index_entry.address)?;
+        }
+        for index_entry in index_entries {
+            bytes_written += file.write_type(&index_entry.block_offset)?;
+        }
+        if *self == Self::AddressAndOffsets {
+            for index_entry in index_entries {
+                bytes_written += file.write_type(&index_entry.intra_block_offset)?;
             }
         }
+        Ok(bytes_written)
     }
 
     /// Returns the address of the account given the specified index.
@@ -68,7 +74,7 @@ impl AccountIndexFormat {
         index: usize,
     ) -> TieredStorageResult<&'a Pubkey> {
         let offset = match self {
-            Self::AddressAndOffset => {
+            Self::AddressAndOffset | Self::AddressAndOffsets => {
                 footer.account_index_offset as usize + std::mem::size_of::<Pubkey>() * index
             }
         };
@@ -85,7 +91,7 @@ impl AccountIndexFormat {
         index: usize,
     ) -> TieredStorageResult<u64> {
         match self {
-            Self::AddressAndOffset => {
+            Self::AddressAndOffset | Self::AddressAndOffsets => {
                 let offset = footer.account_index_offset as usize
                     + std::mem::size_of::<Pubkey>() * footer.account_entry_count as usize
                     + index * std::mem::size_of::<u64>();
@@ -95,10 +101,34 @@ impl AccountIndexFormat {
         }
     }
 
+    /// Returns the offset of the account entry associated with the specified
+    /// index inside its decoded account block.
+    pub fn get_intra_block_offset(
+        &self,
+        map: &Mmap,
+        footer: &TieredStorageFooter,
+        index: usize,
+    ) -> TieredStorageResult<u64> {
+        match self {
+            Self::AddressAndOffset => Err(TieredStorageError::Unsupported()),
+            Self::AddressAndOffsets => {
+                let offset = footer.account_index_offset as usize
+                    + (std::mem::size_of::<Pubkey>() + std::mem::size_of::<u64>())
+                        * footer.account_entry_count as usize
+                    + index * std::mem::size_of::<u64>();
+                let (intra_block_offset, _) = get_type(map, offset)?;
+                Ok(*intra_block_offset)
+            }
+        }
+    }
+
     /// Returns the size of one index entry.
     pub fn entry_size(&self) -> usize {
         match self {
             Self::AddressAndOffset => std::mem::size_of::<Pubkey>() + std::mem::size_of::<u64>(),
+            Self::AddressAndOffsets => {
+                std::mem::size_of::<Pubkey>() + std::mem::size_of::<u64>() * 2
+            }
         }
     }
 }
@@ -152,6 +182,56 @@ mod tests {
             );
             let address = indexer.get_account_address(&map, &footer, i).unwrap();
             assert_eq!(index_entry.address, address);
+            assert!(indexer.get_intra_block_offset(&map, &footer, i).is_err());
+        }
+    }
+
+    #[test]
+    fn test_address_and_offsets_indexer() {
+        const ENTRY_COUNT: usize = 100;
+        let footer = TieredStorageFooter {
+            account_entry_count: ENTRY_COUNT as u32,
+            ..TieredStorageFooter::default()
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test_address_and_offsets_indexer");
+        let addresses: Vec<_> = std::iter::repeat_with(Pubkey::new_unique)
+            .take(ENTRY_COUNT)
+            .collect();
+        let mut rng = rand::thread_rng();
+        let index_entries: Vec<_> = addresses
+            .iter()
+            .map(|address| AccountIndexWriterEntry {
+                address,
+                block_offset: rng.gen_range(128..2048),
+                intra_block_offset: rng.gen_range(0..16384),
+            })
+            .collect();
+
+        let indexer = AccountIndexFormat::AddressAndOffsets;
+        {
+            let file = TieredStorageFile::new_writable(&path).unwrap();
+            let bytes_written = indexer.write_index_block(&file, &index_entries).unwrap();
+            assert_eq!(bytes_written, indexer.entry_size() * ENTRY_COUNT);
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .create(false)
+            .open(&path)
+            .unwrap();
+        let map = unsafe { MmapOptions::new().map(&file).unwrap() };
+        for (i, index_entry) in index_entries.iter().enumerate() {
+            assert_eq!(
+                index_entry.block_offset,
+                indexer.get_account_block_offset(&map, &footer, i).unwrap()
+            );
+            assert_eq!(
+                index_entry.intra_block_offset,
+                indexer.get_intra_block_offset(&map, &footer, i).unwrap()
+            );
+            let address = indexer.get_account_address(&map, &footer, i).unwrap();
+            assert_eq!(index_entry.address, address);
         }
     }
 }
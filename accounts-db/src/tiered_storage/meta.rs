@@ -17,8 +17,10 @@ pub struct AccountMetaFlags {
     pub has_account_hash: bool,
     /// whether the account meta has write version
     pub has_write_version: bool,
+    /// whether the account is executable
+    pub executable: bool,
     /// the reserved bits.
-    reserved: B29,
+    reserved: B28,
 }
 
 /// A trait that allows different implementations of the account meta that
@@ -179,6 +181,7 @@ pub mod tests {
         assert!(!flags.has_rent_epoch());
         assert!(!flags.has_account_hash());
         assert!(!flags.has_write_version());
+        assert!(!flags.executable());
         assert_eq!(flags.reserved(), 0u32);
 
         assert_eq!(
@@ -216,6 +219,14 @@ pub mod tests {
         assert!(flags.has_write_version());
         verify_flags_serialization(&flags);
 
+        flags.set_executable(true);
+
+        assert!(flags.has_rent_epoch());
+        assert!(flags.has_account_hash());
+        assert!(flags.has_write_version());
+        assert!(flags.executable());
+        verify_flags_serialization(&flags);
+
         // make sure the reserved bits are untouched.
         assert_eq!(flags.reserved(), 0u32);
     }
@@ -0,0 +1,164 @@
+use {
+    crate::tiered_storage::{
+        file::TieredStorageFile, footer::TieredStorageFooter, mmap_utils::get_type,
+        TieredStorageResult,
+    },
+    memmap2::Mmap,
+    solana_sdk::pubkey::Pubkey,
+    std::collections::HashMap,
+};
+
+/// The format of the owners block of a tiered accounts file.
+#[repr(u16)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    Hash,
+    PartialEq,
+    num_enum::IntoPrimitive,
+    num_enum::TryFromPrimitive,
+)]
+pub enum OwnersBlockFormat {
+    /// This format persists each owner address in the owners block.  Each
+    /// account meta refers to its owner by the index of the owner address
+    /// inside the owners block.
+    #[default]
+    LocalIndex = 0,
+}
+
+impl OwnersBlockFormat {
+    /// Persists the specified owners to the specified file and returns the
+    /// total number of bytes written.
+    pub fn write_owners_block(
+        &self,
+        file: &TieredStorageFile,
+        owners: &[&Pubkey],
+    ) -> TieredStorageResult<usize> {
+        match self {
+            Self::LocalIndex => {
+                let mut bytes_written = 0;
+                for owner in owners {
+                    bytes_written += file.write_type(*owner)?;
+                }
+                Ok(bytes_written)
+            }
+        }
+    }
+
+    /// Returns the owner address associated with the specified owner index.
+    pub fn get_owner_address<'a>(
+        &self,
+        map: &'a Mmap,
+        footer: &TieredStorageFooter,
+        owner_index: usize,
+    ) -> TieredStorageResult<&'a Pubkey> {
+        match self {
+            Self::LocalIndex => {
+                let offset =
+                    footer.owners_offset as usize + std::mem::size_of::<Pubkey>() * owner_index;
+                let (owner, _) = get_type::<Pubkey>(map, offset)?;
+                Ok(owner)
+            }
+        }
+    }
+
+    /// Returns the size of one owner entry.
+    pub fn entry_size(&self) -> usize {
+        match self {
+            Self::LocalIndex => std::mem::size_of::<Pubkey>(),
+        }
+    }
+}
+
+/// The in-memory struct that collects the unique owners of the accounts
+/// being written, and assigns each of them an owner index.
+#[derive(Debug, Default)]
+pub struct OwnersTable<'a> {
+    owners: Vec<&'a Pubkey>,
+    indexes: HashMap<&'a Pubkey, u32>,
+}
+
+impl<'a> OwnersTable<'a> {
+    /// Returns the owner index of the specified owner, inserting the owner
+    /// into the table if it does not yet exist.
+    pub fn insert(&mut self, owner: &'a Pubkey) -> u32 {
+        let next_index = self.owners.len() as u32;
+        *self.indexes.entry(owner).or_insert_with(|| {
+            self.owners.push(owner);
+            next_index
+        })
+    }
+
+    /// Returns the number of unique owners.
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    /// Returns true if the table contains no owner.
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+
+    /// Returns the owners ordered by their owner index.
+    pub fn owners(&self) -> &[&'a Pubkey] {
+        &self.owners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, memmap2::MmapOptions, std::fs::OpenOptions, tempfile::TempDir};
+
+    #[test]
+    fn test_owners_block() {
+        const NUM_OWNERS: usize = 10;
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test_owners_block");
+        let addresses: Vec<_> = std::iter::repeat_with(Pubkey::new_unique)
+            .take(NUM_OWNERS)
+            .collect();
+        let owners: Vec<_> = addresses.iter().collect();
+
+        {
+            let file = TieredStorageFile::new_writable(&path).unwrap();
+            OwnersBlockFormat::LocalIndex
+                .write_owners_block(&file, &owners)
+                .unwrap();
+        }
+
+        // the owners block is the only block in this file, so it starts at 0.
+        let footer = TieredStorageFooter {
+            owner_count: NUM_OWNERS as u32,
+            owners_offset: 0,
+            ..TieredStorageFooter::default()
+        };
+        let file = OpenOptions::new().read(true).open(&path).unwrap();
+        let map = unsafe { MmapOptions::new().map(&file).unwrap() };
+        for (i, address) in addresses.iter().enumerate() {
+            assert_eq!(
+                OwnersBlockFormat::LocalIndex
+                    .get_owner_address(&map, &footer, i)
+                    .unwrap(),
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn test_owners_table() {
+        let owner1 = Pubkey::new_unique();
+        let owner2 = Pubkey::new_unique();
+        let mut owners_table = OwnersTable::default();
+        assert!(owners_table.is_empty());
+
+        assert_eq!(owners_table.insert(&owner1), 0);
+        assert_eq!(owners_table.insert(&owner2), 1);
+        // inserting an existing owner returns its previous index
+        assert_eq!(owners_table.insert(&owner1), 0);
+        assert_eq!(owners_table.len(), 2);
+        assert_eq!(owners_table.owners(), &[&owner1, &owner2]);
+    }
+}
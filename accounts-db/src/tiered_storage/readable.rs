@@ -1,7 +1,8 @@
 use {
     crate::{
-        account_storage::meta::StoredMetaWriteVersion,
+        account_storage::meta::{StoredAccountMeta, StoredMetaWriteVersion},
         tiered_storage::{
+            cold::ColdStorageReader,
             footer::{AccountMetaFormat, TieredStorageFooter},
             hot::HotStorageReader,
             meta::TieredAccountMeta,
@@ -69,11 +70,8 @@ impl<'accounts_file, M: TieredAccountMeta> ReadableAccount
     }
 
     /// Returns true if the data associated to this account is executable.
-    ///
-    /// Temporarily unimplemented!() as program runtime v2 will use
-    /// a different API for executable.
     fn executable(&self) -> bool {
-        unimplemented!();
+        self.meta.flags().executable()
     }
 
     /// Returns the epoch that this account will next owe rent by parsing
@@ -95,6 +93,7 @@ impl<'accounts_file, M: TieredAccountMeta> ReadableAccount
 #[derive(Debug)]
 pub enum TieredStorageReader {
     Hot(HotStorageReader),
+    Cold(ColdStorageReader),
 }
 
 impl TieredStorageReader {
@@ -103,13 +102,15 @@ impl TieredStorageReader {
         let footer = TieredStorageFooter::new_from_path(&path)?;
         match footer.account_meta_format {
             AccountMetaFormat::Hot => Ok(Self::Hot(HotStorageReader::new_from_path(path)?)),
+            AccountMetaFormat::Cold => Ok(Self::Cold(ColdStorageReader::new_from_path(path)?)),
         }
     }
 
-    /// Returns the footer of the associated HotAccountsFile.
+    /// Returns the footer of the associated tiered accounts file.
     pub fn footer(&self) -> &TieredStorageFooter {
         match self {
             Self::Hot(hot) => hot.footer(),
+            Self::Cold(cold) => cold.footer(),
         }
     }
 
@@ -117,6 +118,32 @@ impl TieredStorageReader {
     pub fn num_accounts(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.num_accounts(),
+            Self::Cold(cold) => cold.num_accounts(),
+        }
+    }
+
+    /// Returns the account associated with the specified index and the index
+    /// of the next account, or None if the index is out of range.
+    pub fn get_account(
+        &self,
+        index: usize,
+    ) -> TieredStorageResult<Option<(StoredAccountMeta<'_>, usize)>> {
+        match self {
+            Self::Hot(hot) => hot.get_account(index),
+            Self::Cold(cold) => cold.get_account(index),
+        }
+    }
+
+    /// Returns the index of the specified owner inside `owners` if the
+    /// account associated with the specified index is owned by one of them.
+    pub fn account_matches_owners(
+        &self,
+        index: usize,
+        owners: &[&Pubkey],
+    ) -> TieredStorageResult<Option<usize>> {
+        match self {
+            Self::Hot(hot) => hot.account_matches_owners(index, owners),
+            Self::Cold(cold) => cold.account_matches_owners(index, owners),
         }
     }
 }
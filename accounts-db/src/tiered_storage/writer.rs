@@ -3,16 +3,84 @@
 use {
     crate::{
         account_storage::meta::{StorableAccountsWithHashesAndWriteVersions, StoredAccountInfo},
+        accounts_file::ALIGN_BOUNDARY_OFFSET,
+        rent_collector::RENT_EXEMPT_RENT_EPOCH,
         storable_accounts::StorableAccounts,
         tiered_storage::{
-            error::TieredStorageError, file::TieredStorageFile, footer::TieredStorageFooter,
+            cold::write_cold_accounts,
+            file::TieredStorageFile,
+            footer::AccountMetaFormat,
+            hot::write_hot_accounts,
+            meta::{AccountMetaFlags, AccountMetaOptionalFields},
             TieredStorageFormat, TieredStorageResult,
         },
     },
-    solana_sdk::{account::ReadableAccount, hash::Hash},
+    solana_sdk::{account::ReadableAccount, hash::Hash, pubkey::Pubkey},
     std::{borrow::Borrow, path::Path},
 };
 
+/// The zero bytes used to pad account data and account blocks.
+pub(super) const PADDING_BUFFER: [u8; 8] = [0u8; 8];
+
+/// The owner of accounts that have been removed (i.e. zero-lamport accounts
+/// without an AccountSharedData).
+const DEFAULT_OWNER: Pubkey = Pubkey::new_from_array([0u8; 32]);
+
+/// Returns the number of padding bytes required to align the specified
+/// length to an 8-byte boundary.
+pub(super) fn padding_bytes(len: usize) -> usize {
+    (ALIGN_BOUNDARY_OFFSET - (len % ALIGN_BOUNDARY_OFFSET)) % ALIGN_BOUNDARY_OFFSET
+}
+
+/// The fields of an account that are persisted in a tiered accounts file,
+/// regardless of its account meta format.
+pub(super) struct AccountEntry<'a> {
+    pub address: &'a Pubkey,
+    pub lamports: u64,
+    pub owner: &'a Pubkey,
+    pub data: &'a [u8],
+    pub flags: AccountMetaFlags,
+    pub optional_fields: AccountMetaOptionalFields,
+}
+
+impl<'a> AccountEntry<'a> {
+    /// Collects the fields of the account at the specified index.
+    pub fn new<'b, 'c, T: ReadableAccount + Sync, U: StorableAccounts<'b, T>, V: Borrow<Hash>>(
+        accounts: &'a StorableAccountsWithHashesAndWriteVersions<'b, 'c, T, U, V>,
+        index: usize,
+    ) -> Self {
+        let (account, address, hash, write_version) = accounts.get(index);
+        let (lamports, owner, data, executable, rent_epoch) = account
+            .map(|account| {
+                (
+                    account.lamports(),
+                    account.owner(),
+                    account.data(),
+                    account.executable(),
+                    account.rent_epoch(),
+                )
+            })
+            .unwrap_or((0, &DEFAULT_OWNER, &[], false, 0));
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: (rent_epoch != RENT_EXEMPT_RENT_EPOCH).then_some(rent_epoch),
+            account_hash: Some(*hash),
+            write_version: Some(write_version),
+        };
+        let mut flags = AccountMetaFlags::new_from(&optional_fields);
+        flags.set_executable(executable);
+
+        Self {
+            address,
+            lamports,
+            owner,
+            data,
+            flags,
+            optional_fields,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TieredStorageWriter<'format> {
     storage: TieredStorageFile,
@@ -41,22 +109,13 @@ impl<'format> TieredStorageWriter<'format> {
         accounts: &StorableAccountsWithHashesAndWriteVersions<'a, 'b, T, U, V>,
         skip: usize,
     ) -> TieredStorageResult<Vec<StoredAccountInfo>> {
-        let footer = TieredStorageFooter {
-            account_meta_format: self.format.account_meta_format,
-            owners_block_format: self.format.owners_block_format,
-            account_block_format: self.format.account_block_format,
-            account_index_format: self.format.account_index_format,
-            account_entry_count: accounts
-                .accounts
-                .len()
-                .saturating_sub(skip)
-                .try_into()
-                .expect("num accounts <= u32::MAX"),
-            ..TieredStorageFooter::default()
-        };
-
-        footer.write_footer_block(&self.storage)?;
-
-        Err(TieredStorageError::Unsupported())
+        match self.format.account_meta_format {
+            AccountMetaFormat::Hot => {
+                write_hot_accounts(&self.storage, accounts, skip, self.format)
+            }
+            AccountMetaFormat::Cold => {
+                write_cold_accounts(&self.storage, accounts, skip, self.format)
+            }
+        }
     }
 }
@@ -3,11 +3,15 @@ use {
         nonce_info::{NonceFull, NonceInfo, NoncePartial},
         rent_debits::RentDebits,
     },
-    solana_program_runtime::loaded_programs::LoadedProgramsForTxBatch,
+    solana_program_runtime::{
+        instruction_trace_recorder::InstructionExecutionRecord,
+        loaded_programs::LoadedProgramsForTxBatch,
+    },
     solana_sdk::{
-        instruction::{CompiledInstruction, TRANSACTION_LEVEL_STACK_HEIGHT},
+        instruction::{CompiledInstruction, InstructionError, TRANSACTION_LEVEL_STACK_HEIGHT},
+        pubkey::Pubkey,
         transaction::{self, TransactionError},
-        transaction_context::{TransactionContext, TransactionReturnData},
+        transaction_context::{InstructionTrace, TransactionContext, TransactionReturnData},
     },
 };
 
@@ -74,6 +78,7 @@ pub struct TransactionExecutionDetails {
     pub status: transaction::Result<()>,
     pub log_messages: Option<Vec<String>>,
     pub inner_instructions: Option<InnerInstructionsList>,
+    pub instruction_trace: Option<InstructionTraceList>,
     pub durable_nonce_fee: Option<DurableNonceFee>,
     pub return_data: Option<TransactionReturnData>,
     pub executed_units: u64,
@@ -174,6 +179,59 @@ pub fn inner_instructions_list_from_instruction_trace(
     outer_instructions
 }
 
+/// Every instruction executed in a transaction, in invocation order
+pub type InstructionTraceList = Vec<InstructionTrace>;
+
+/// Extract the InstructionTraceList from a TransactionContext, the records of
+/// an InstructionTraceRecorder and the untruncated log messages
+pub fn instruction_trace_list_from_instruction_trace(
+    transaction_context: &TransactionContext,
+    records: &[InstructionExecutionRecord],
+    log_messages: &[String],
+) -> Result<InstructionTraceList, InstructionError> {
+    let instruction_trace_length = transaction_context.get_instruction_trace_length();
+
+    // Attribute every log message to the innermost instruction which was
+    // executing when it was emitted.  Instructions are recorded in invocation
+    // order, so an inner instruction always comes after the instruction which
+    // invoked it and overrides its attribution.
+    let mut log_message_owners = vec![None; log_messages.len()];
+    for (index_in_trace, record) in records.iter().enumerate() {
+        for owner in log_message_owners
+            .iter_mut()
+            .take(record.log_messages.end)
+            .skip(record.log_messages.start)
+        {
+            *owner = Some(index_in_trace);
+        }
+    }
+    let mut instruction_log_messages = vec![Vec::new(); instruction_trace_length];
+    for (message, owner) in log_messages.iter().zip(log_message_owners) {
+        if let Some(messages) = owner.and_then(|owner| instruction_log_messages.get_mut(owner)) {
+            messages.push(message.clone());
+        }
+    }
+
+    let default_record = InstructionExecutionRecord::default();
+    let mut instruction_trace = Vec::with_capacity(instruction_trace_length);
+    for (index_in_trace, log_messages) in instruction_log_messages.into_iter().enumerate() {
+        let instruction_context =
+            transaction_context.get_instruction_context_at_index_in_trace(index_in_trace)?;
+        let record = records.get(index_in_trace).unwrap_or(&default_record);
+        instruction_trace.push(InstructionTrace {
+            program_id: instruction_context
+                .get_last_program_key(transaction_context)
+                .copied()
+                .unwrap_or_default(),
+            stack_height: u32::try_from(instruction_context.get_stack_height()).unwrap_or(u32::MAX),
+            compute_units_consumed: record.compute_units_consumed,
+            return_data: (!record.return_data.data.is_empty()).then(|| record.return_data.clone()),
+            log_messages,
+        });
+    }
+    Ok(instruction_trace)
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, solana_sdk::transaction_context::TransactionContext};
@@ -223,4 +281,75 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_instruction_trace_list_from_instruction_trace() {
+        let instruction_trace = [1, 2, 1];
+        let mut transaction_context =
+            TransactionContext::new(vec![], None, 3, instruction_trace.len());
+        for (index_in_trace, stack_height) in instruction_trace.into_iter().enumerate() {
+            while stack_height <= transaction_context.get_instruction_context_stack_height() {
+                transaction_context.pop().unwrap();
+            }
+            transaction_context
+                .get_next_instruction_context()
+                .unwrap()
+                .configure(&[], &[], &[index_in_trace as u8]);
+            transaction_context.push().unwrap();
+        }
+
+        let return_data = TransactionReturnData {
+            program_id: Pubkey::new_unique(),
+            data: vec![42],
+        };
+        let records = [
+            InstructionExecutionRecord {
+                compute_units_consumed: 300,
+                return_data: return_data.clone(),
+                log_messages: 0..4,
+            },
+            InstructionExecutionRecord {
+                compute_units_consumed: 100,
+                return_data: return_data.clone(),
+                log_messages: 1..3,
+            },
+            InstructionExecutionRecord {
+                compute_units_consumed: 50,
+                return_data: TransactionReturnData::default(),
+                log_messages: 4..5,
+            },
+        ];
+        let log_messages: Vec<_> = (0..5).map(|i| i.to_string()).collect();
+
+        assert_eq!(
+            instruction_trace_list_from_instruction_trace(
+                &transaction_context,
+                &records,
+                &log_messages
+            ),
+            Ok(vec![
+                InstructionTrace {
+                    program_id: Pubkey::default(),
+                    stack_height: 1,
+                    compute_units_consumed: 300,
+                    return_data: Some(return_data.clone()),
+                    log_messages: vec!["0".to_string(), "3".to_string()],
+                },
+                InstructionTrace {
+                    program_id: Pubkey::default(),
+                    stack_height: 2,
+                    compute_units_consumed: 100,
+                    return_data: Some(return_data),
+                    log_messages: vec!["1".to_string(), "2".to_string()],
+                },
+                InstructionTrace {
+                    program_id: Pubkey::default(),
+                    stack_height: 1,
+                    compute_units_consumed: 50,
+                    return_data: None,
+                    log_messages: vec!["4".to_string()],
+                },
+            ])
+        );
+    }
 }
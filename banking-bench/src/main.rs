@@ -9,6 +9,7 @@ use {
     solana_core::{
         banking_stage::BankingStage,
         banking_trace::{BankingPacketBatch, BankingTracer, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT},
+        validator::BlockProductionMethod,
     },
     solana_gossip::cluster_info::{ClusterInfo, Node},
     solana_ledger::{
@@ -304,8 +305,20 @@ fn main() {
                 .requires("simulate_mint")
                 .help("In simulating mint, number of mint transactions out of 100."),
         )
+        .arg(
+            Arg::new("block_production_method")
+                .long("block-production-method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .possible_values(BlockProductionMethod::cli_names())
+                .help(BlockProductionMethod::cli_message()),
+        )
         .get_matches();
 
+    let block_production_method = matches
+        .value_of_t::<BlockProductionMethod>("block_production_method")
+        .unwrap_or_default();
+
     let num_banking_threads = matches
         .value_of_t::<u32>("num_banking_threads")
         .unwrap_or_else(|_| BankingStage::num_threads());
@@ -451,6 +464,7 @@ fn main() {
             ),
         };
         let banking_stage = BankingStage::new_num_threads(
+            block_production_method,
             &cluster_info,
             &poh_recorder,
             non_vote_receiver,
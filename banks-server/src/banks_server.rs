@@ -194,7 +194,8 @@ fn simulate_transaction(
         post_simulation_accounts: _,
         units_consumed,
         return_data,
-    } = bank.simulate_transaction_unchecked(sanitized_transaction);
+        inner_instructions: _,
+    } = bank.simulate_transaction_unchecked(sanitized_transaction, false);
     let simulation_details = TransactionSimulationDetails {
         logs,
         units_consumed,
@@ -1612,6 +1612,8 @@ pub fn process_logs(config: &CliConfig, filter: &RpcTransactionLogsFilter) -> Pr
             RpcTransactionLogsFilter::AllWithVotes => " (including votes)".into(),
             RpcTransactionLogsFilter::Mentions(addresses) =>
                 format!(" mentioning {}", addresses.join(",")),
+            RpcTransactionLogsFilter::MentionsAll(addresses) =>
+                format!(" mentioning all of {}", addresses.join(",")),
         },
         config.commitment.commitment
     );
@@ -802,7 +802,7 @@ pub fn process_program_subcommand(
     }
 }
 
-fn get_default_program_keypair(program_location: &Option<String>) -> Keypair {
+pub(crate) fn get_default_program_keypair(program_location: &Option<String>) -> Keypair {
     let program_keypair = {
         if let Some(program_location) = program_location {
             let mut keypair_file = PathBuf::new();
@@ -1719,7 +1719,7 @@ fn process_close(
     }
 }
 
-fn calculate_max_chunk_size<F>(create_msg: &F) -> usize
+pub(crate) fn calculate_max_chunk_size<F>(create_msg: &F) -> usize
 where
     F: Fn(u32, Vec<u8>) -> Message,
 {
@@ -2093,7 +2093,7 @@ fn complete_partial_program_init(
     Ok((instructions, balance_needed))
 }
 
-fn check_payer(
+pub(crate) fn check_payer(
     rpc_client: &RpcClient,
     config: &CliConfig,
     balance_needed: u64,
@@ -2162,59 +2162,7 @@ fn send_deploy_messages(
     if !write_messages.is_empty() {
         if let Some(write_signer) = write_signer {
             trace!("Writing program data");
-            let connection_cache = if config.use_quic {
-                ConnectionCache::new_quic("connection_cache_cli_program_quic", 1)
-            } else {
-                ConnectionCache::with_udp("connection_cache_cli_program_udp", 1)
-            };
-            let transaction_errors = match connection_cache {
-                ConnectionCache::Udp(cache) => TpuClient::new_with_connection_cache(
-                    rpc_client.clone(),
-                    &config.websocket_url,
-                    TpuClientConfig::default(),
-                    cache,
-                )?
-                .send_and_confirm_messages_with_spinner(
-                    write_messages,
-                    &[payer_signer, write_signer],
-                ),
-                ConnectionCache::Quic(cache) => {
-                    let tpu_client_fut = solana_client::nonblocking::tpu_client::TpuClient::new_with_connection_cache(
-                        rpc_client.get_inner_client().clone(),
-                        config.websocket_url.as_str(),
-                        solana_client::tpu_client::TpuClientConfig::default(),
-                        cache,
-                    );
-                    let tpu_client = rpc_client
-                        .runtime()
-                        .block_on(tpu_client_fut)
-                        .expect("Should return a valid tpu client");
-
-                    send_and_confirm_transactions_in_parallel_blocking(
-                        rpc_client.clone(),
-                        Some(tpu_client),
-                        write_messages,
-                        &[payer_signer, write_signer],
-                        SendAndConfirmConfig {
-                            resign_txs_count: Some(5),
-                            with_spinner: true,
-                        },
-                    )
-                },
-            }
-            .map_err(|err| format!("Data writes to account failed: {err}"))?
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
-
-            if !transaction_errors.is_empty() {
-                for transaction_error in &transaction_errors {
-                    error!("{:?}", transaction_error);
-                }
-                return Err(
-                    format!("{} write transactions failed", transaction_errors.len()).into(),
-                );
-            }
+            send_write_messages(rpc_client.clone(), config, write_messages, write_signer)?;
         }
     }
 
@@ -2244,6 +2192,67 @@ fn send_deploy_messages(
     Ok(())
 }
 
+/// Sends the write messages to the TPU and waits for their confirmation.  The
+/// messages are sent in parallel when QUIC is enabled.
+pub(crate) fn send_write_messages(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    write_messages: &[Message],
+    write_signer: &dyn Signer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let payer_signer = config.signers[0];
+    let connection_cache = if config.use_quic {
+        ConnectionCache::new_quic("connection_cache_cli_program_quic", 1)
+    } else {
+        ConnectionCache::with_udp("connection_cache_cli_program_udp", 1)
+    };
+    let transaction_errors = match connection_cache {
+        ConnectionCache::Udp(cache) => TpuClient::new_with_connection_cache(
+            rpc_client.clone(),
+            &config.websocket_url,
+            TpuClientConfig::default(),
+            cache,
+        )?
+        .send_and_confirm_messages_with_spinner(write_messages, &[payer_signer, write_signer]),
+        ConnectionCache::Quic(cache) => {
+            let tpu_client_fut =
+                solana_client::nonblocking::tpu_client::TpuClient::new_with_connection_cache(
+                    rpc_client.get_inner_client().clone(),
+                    config.websocket_url.as_str(),
+                    solana_client::tpu_client::TpuClientConfig::default(),
+                    cache,
+                );
+            let tpu_client = rpc_client
+                .runtime()
+                .block_on(tpu_client_fut)
+                .expect("Should return a valid tpu client");
+
+            send_and_confirm_transactions_in_parallel_blocking(
+                rpc_client.clone(),
+                Some(tpu_client),
+                write_messages,
+                &[payer_signer, write_signer],
+                SendAndConfirmConfig {
+                    resign_txs_count: Some(5),
+                    with_spinner: true,
+                },
+            )
+        }
+    }
+    .map_err(|err| format!("Data writes to account failed: {err}"))?
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    if !transaction_errors.is_empty() {
+        for transaction_error in &transaction_errors {
+            error!("{:?}", transaction_error);
+        }
+        return Err(format!("{} write transactions failed", transaction_errors.len()).into());
+    }
+    Ok(())
+}
+
 fn create_ephemeral_keypair(
 ) -> Result<(usize, bip39::Mnemonic, Keypair), Box<dyn std::error::Error>> {
     const WORDS: usize = 12;
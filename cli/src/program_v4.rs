@@ -0,0 +1,1509 @@
+use {
+    crate::{
+        checks::*,
+        cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+        program::{
+            calculate_max_chunk_size, check_payer, get_default_program_keypair, send_write_messages,
+        },
+    },
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
+    log::*,
+    solana_account_decoder::UiAccountEncoding,
+    solana_clap_utils::{
+        self, hidden_unless_forced, input_parsers::*, input_validators::*, keypair::*,
+    },
+    solana_cli_output::{
+        display::new_spinner_progress_bar, CliProgramAccountType, CliProgramAuthority,
+        CliProgramId, CliProgramV4, CliProgramsV4, CliSignature,
+    },
+    solana_loader_v4_program::create_program_runtime_environment_v2,
+    solana_program_runtime::{compute_budget::ComputeBudget, invoke_context::InvokeContext},
+    solana_rbpf::{elf::Executable, verifier::RequisiteVerifier},
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig},
+        filter::{Memcmp, RpcFilterType},
+    },
+    solana_sdk::{
+        account::Account,
+        clock::DEFAULT_MS_PER_SLOT,
+        loader_v4::{self, LoaderV4State, LoaderV4Status, DEPLOYMENT_COOLDOWN_IN_SLOTS},
+        message::Message,
+        pubkey::Pubkey,
+        signature::{Signature, Signer},
+        system_instruction,
+        transaction::Transaction,
+    },
+    std::{
+        fs::File,
+        io::{Read, Write},
+        mem::size_of,
+        rc::Rc,
+        sync::Arc,
+        thread::sleep,
+        time::Duration,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProgramV4CliCommand {
+    Deploy {
+        program_location: String,
+        program_signer_index: Option<SignerIndex>,
+        authority_signer_index: SignerIndex,
+        skip_fee_check: bool,
+    },
+    Redeploy {
+        program_location: String,
+        program_address: Pubkey,
+        buffer_signer_index: Option<SignerIndex>,
+        authority_signer_index: SignerIndex,
+        skip_fee_check: bool,
+    },
+    Retract {
+        program_address: Pubkey,
+        authority_signer_index: SignerIndex,
+    },
+    TransferAuthority {
+        program_address: Pubkey,
+        authority_signer_index: SignerIndex,
+        new_authority_signer_index: Option<SignerIndex>,
+    },
+    Show {
+        account_pubkey: Option<Pubkey>,
+        authority_pubkey: Pubkey,
+        all: bool,
+        use_lamports_unit: bool,
+    },
+    Dump {
+        account_pubkey: Pubkey,
+        output_location: String,
+    },
+}
+
+pub trait ProgramV4SubCommands {
+    fn program_v4_subcommands(self) -> Self;
+}
+
+impl ProgramV4SubCommands for App<'_, '_> {
+    fn program_v4_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("program-v4")
+                .about("Program management for the v4 loader")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .arg(
+                    Arg::with_name("skip_fee_check")
+                        .long("skip-fee-check")
+                        .hidden(hidden_unless_forced())
+                        .takes_value(false)
+                        .global(true)
+                )
+                .subcommand(
+                    SubCommand::with_name("deploy")
+                        .about("Deploy a new program, or resume an interrupted deploy")
+                        .arg(
+                            Arg::with_name("program_location")
+                                .index(1)
+                                .value_name("PROGRAM_FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("/path/to/program.so"),
+                        )
+                        .arg(
+                            Arg::with_name("program_keypair")
+                                .long("program-keypair")
+                                .value_name("PROGRAM_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Program account signer \
+                                      [default: keypair at /path/to/program-keypair.json if present, otherwise a random address]")
+                        )
+                        .arg(
+                            Arg::with_name("authority")
+                                .long("authority")
+                                .value_name("AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Program authority [default: the default configured keypair]")
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("redeploy")
+                        .about("Redeploy an existing program")
+                        .arg(
+                            Arg::with_name("program_location")
+                                .index(1)
+                                .value_name("PROGRAM_FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("/path/to/program.so"),
+                        )
+                        .arg(
+                            Arg::with_name("program_id")
+                                .long("program-id")
+                                .value_name("PROGRAM_ID")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_valid_pubkey)
+                                .help("Address of the program to redeploy")
+                        )
+                        .arg(
+                            Arg::with_name("buffer")
+                                .long("buffer")
+                                .value_name("BUFFER_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Intermediate buffer account to write data to, so that the program \
+                                      keeps running until it is replaced in a single transaction. \
+                                      Can be used to resume a failed redeploy [default: write the program in place]")
+                        )
+                        .arg(
+                            Arg::with_name("authority")
+                                .long("authority")
+                                .value_name("AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Program authority [default: the default configured keypair]")
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("retract")
+                        .about("Retract a deployed program, so that it can no longer be invoked")
+                        .arg(
+                            Arg::with_name("program_id")
+                                .index(1)
+                                .value_name("PROGRAM_ADDRESS")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_valid_pubkey)
+                                .help("Address of the program to retract")
+                        )
+                        .arg(
+                            Arg::with_name("authority")
+                                .long("authority")
+                                .value_name("AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Program authority [default: the default configured keypair]")
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("transfer-authority")
+                        .about("Transfer the authority of a program, or finalize it")
+                        .arg(
+                            Arg::with_name("program_id")
+                                .index(1)
+                                .value_name("PROGRAM_ADDRESS")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_valid_pubkey)
+                                .help("Address of the program")
+                        )
+                        .arg(
+                            Arg::with_name("authority")
+                                .long("authority")
+                                .value_name("AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .validator(is_valid_signer)
+                                .help("Current program authority [default: the default configured keypair]")
+                        )
+                        .arg(
+                            Arg::with_name("new_authority")
+                                .long("new-authority")
+                                .value_name("NEW_AUTHORITY_SIGNER")
+                                .takes_value(true)
+                                .required_unless("final")
+                                .validator(is_valid_signer)
+                                .help("New program authority, which must sign the transaction")
+                        )
+                        .arg(
+                            Arg::with_name("final")
+                                .long("final")
+                                .conflicts_with("new_authority")
+                                .help("Finalize the program, which makes it immutable")
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Display information about a program")
+                        .arg(
+                            Arg::with_name("account")
+                                .index(1)
+                                .value_name("ACCOUNT_ADDRESS")
+                                .takes_value(true)
+                                .validator(is_valid_pubkey)
+                                .help("Address of the program to show \
+                                      [default: every program that matches the authority]")
+                        )
+                        .arg(
+                            Arg::with_name("all")
+                                .long("all")
+                                .conflicts_with("account")
+                                .conflicts_with("authority")
+                                .help("Show programs for all authorities")
+                        )
+                        .arg(
+                            pubkey!(Arg::with_name("authority")
+                                .long("authority")
+                                .value_name("AUTHORITY")
+                                .conflicts_with("all"),
+                                "Authority [default: the default configured keypair]"),
+                        )
+                        .arg(
+                            Arg::with_name("lamports")
+                                .long("lamports")
+                                .takes_value(false)
+                                .help("Display balance in lamports instead of SOL"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("dump")
+                        .about("Write the program data to a file")
+                        .arg(
+                            Arg::with_name("account")
+                                .index(1)
+                                .value_name("ACCOUNT_ADDRESS")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_valid_pubkey)
+                                .help("Address of the program or buffer")
+                        )
+                        .arg(
+                            Arg::with_name("output_location")
+                                .index(2)
+                                .value_name("OUTPUT_FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("/path/to/program.so"),
+                        ),
+                )
+        )
+    }
+}
+
+pub fn parse_program_v4_subcommand(
+    matches: &ArgMatches<'_>,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let (subcommand, sub_matches) = matches.subcommand();
+    let matches_skip_fee_check = matches.is_present("skip_fee_check");
+    let sub_matches_skip_fee_check = sub_matches
+        .map(|m| m.is_present("skip_fee_check"))
+        .unwrap_or(false);
+    let skip_fee_check = matches_skip_fee_check || sub_matches_skip_fee_check;
+
+    let response = match (subcommand, sub_matches) {
+        ("deploy", Some(matches)) => {
+            let mut bulk_signers = vec![Some(
+                default_signer.signer_from_path(matches, wallet_manager)?,
+            )];
+
+            let program_pubkey = if let Ok((program_signer, Some(program_pubkey))) =
+                signer_of(matches, "program_keypair", wallet_manager)
+            {
+                bulk_signers.push(program_signer);
+                Some(program_pubkey)
+            } else {
+                None
+            };
+
+            let (authority, authority_pubkey) = signer_of(matches, "authority", wallet_manager)?;
+            bulk_signers.push(authority);
+
+            let signer_info =
+                default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Deploy {
+                    program_location: matches.value_of("program_location").unwrap().to_string(),
+                    program_signer_index: signer_info.index_of_or_none(program_pubkey),
+                    authority_signer_index: signer_info.index_of(authority_pubkey).unwrap(),
+                    skip_fee_check,
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("redeploy", Some(matches)) => {
+            let mut bulk_signers = vec![Some(
+                default_signer.signer_from_path(matches, wallet_manager)?,
+            )];
+
+            let buffer_pubkey = if let Ok((buffer_signer, Some(buffer_pubkey))) =
+                signer_of(matches, "buffer", wallet_manager)
+            {
+                bulk_signers.push(buffer_signer);
+                Some(buffer_pubkey)
+            } else {
+                None
+            };
+
+            let (authority, authority_pubkey) = signer_of(matches, "authority", wallet_manager)?;
+            bulk_signers.push(authority);
+
+            let signer_info =
+                default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Redeploy {
+                    program_location: matches.value_of("program_location").unwrap().to_string(),
+                    program_address: pubkey_of(matches, "program_id").unwrap(),
+                    buffer_signer_index: signer_info.index_of_or_none(buffer_pubkey),
+                    authority_signer_index: signer_info.index_of(authority_pubkey).unwrap(),
+                    skip_fee_check,
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("retract", Some(matches)) => {
+            let (authority, authority_pubkey) = signer_of(matches, "authority", wallet_manager)?;
+
+            let signer_info = default_signer.generate_unique_signers(
+                vec![
+                    Some(default_signer.signer_from_path(matches, wallet_manager)?),
+                    authority,
+                ],
+                matches,
+                wallet_manager,
+            )?;
+
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Retract {
+                    program_address: pubkey_of(matches, "program_id").unwrap(),
+                    authority_signer_index: signer_info.index_of(authority_pubkey).unwrap(),
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("transfer-authority", Some(matches)) => {
+            let (authority, authority_pubkey) = signer_of(matches, "authority", wallet_manager)?;
+            let mut bulk_signers = vec![
+                Some(default_signer.signer_from_path(matches, wallet_manager)?),
+                authority,
+            ];
+
+            let new_authority_pubkey = if matches.is_present("final") {
+                None
+            } else {
+                let (new_authority, new_authority_pubkey) =
+                    signer_of(matches, "new_authority", wallet_manager)?;
+                bulk_signers.push(new_authority);
+                new_authority_pubkey
+            };
+
+            let signer_info =
+                default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::TransferAuthority {
+                    program_address: pubkey_of(matches, "program_id").unwrap(),
+                    authority_signer_index: signer_info.index_of(authority_pubkey).unwrap(),
+                    new_authority_signer_index: signer_info.index_of_or_none(new_authority_pubkey),
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("show", Some(matches)) => {
+            let authority_pubkey = if let Some(authority_pubkey) =
+                pubkey_of_signer(matches, "authority", wallet_manager)?
+            {
+                authority_pubkey
+            } else {
+                default_signer
+                    .signer_from_path(matches, wallet_manager)?
+                    .pubkey()
+            };
+
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Show {
+                    account_pubkey: pubkey_of(matches, "account"),
+                    authority_pubkey,
+                    all: matches.is_present("all"),
+                    use_lamports_unit: matches.is_present("lamports"),
+                }),
+                signers: vec![],
+            }
+        }
+        ("dump", Some(matches)) => CliCommandInfo {
+            command: CliCommand::ProgramV4(ProgramV4CliCommand::Dump {
+                account_pubkey: pubkey_of(matches, "account").unwrap(),
+                output_location: matches.value_of("output_location").unwrap().to_string(),
+            }),
+            signers: vec![],
+        },
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+pub fn process_program_v4_subcommand(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    program_subcommand: &ProgramV4CliCommand,
+) -> ProcessResult {
+    match program_subcommand {
+        ProgramV4CliCommand::Deploy {
+            program_location,
+            program_signer_index,
+            authority_signer_index,
+            skip_fee_check,
+        } => process_deploy(
+            rpc_client,
+            config,
+            program_location,
+            *program_signer_index,
+            *authority_signer_index,
+            *skip_fee_check,
+        ),
+        ProgramV4CliCommand::Redeploy {
+            program_location,
+            program_address,
+            buffer_signer_index,
+            authority_signer_index,
+            skip_fee_check,
+        } => process_redeploy(
+            rpc_client,
+            config,
+            program_location,
+            program_address,
+            *buffer_signer_index,
+            *authority_signer_index,
+            *skip_fee_check,
+        ),
+        ProgramV4CliCommand::Retract {
+            program_address,
+            authority_signer_index,
+        } => process_retract(
+            &rpc_client,
+            config,
+            program_address,
+            *authority_signer_index,
+        ),
+        ProgramV4CliCommand::TransferAuthority {
+            program_address,
+            authority_signer_index,
+            new_authority_signer_index,
+        } => process_transfer_authority(
+            &rpc_client,
+            config,
+            program_address,
+            *authority_signer_index,
+            *new_authority_signer_index,
+        ),
+        ProgramV4CliCommand::Show {
+            account_pubkey,
+            authority_pubkey,
+            all,
+            use_lamports_unit,
+        } => process_show(
+            &rpc_client,
+            config,
+            *account_pubkey,
+            authority_pubkey,
+            *all,
+            *use_lamports_unit,
+        ),
+        ProgramV4CliCommand::Dump {
+            account_pubkey,
+            output_location,
+        } => process_dump(&rpc_client, config, account_pubkey, output_location),
+    }
+}
+
+/// Deploy a new program, resuming the upload if the program account already
+/// exists in the retracted state
+fn process_deploy(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    program_location: &str,
+    program_signer_index: Option<SignerIndex>,
+    authority_signer_index: SignerIndex,
+    skip_fee_check: bool,
+) -> ProcessResult {
+    let program_data = read_and_verify_elf(program_location)?;
+    let default_program_keypair = get_default_program_keypair(&Some(program_location.to_string()));
+    let program_signer = if let Some(index) = program_signer_index {
+        config.signers[index]
+    } else {
+        &default_program_keypair
+    };
+    let program_address = program_signer.pubkey();
+
+    if let Some(account) = rpc_client
+        .get_account_with_commitment(&program_address, config.commitment)?
+        .value
+    {
+        if let Some(state) = get_program_state(&account) {
+            if state.status != LoaderV4Status::Retracted {
+                return Err(format!(
+                    "Program {program_address} is already deployed, \
+                    use `solana program-v4 redeploy` to replace it"
+                )
+                .into());
+            }
+        }
+    }
+
+    do_process_write_and_deploy(
+        rpc_client,
+        config,
+        &program_data,
+        &program_address,
+        &program_address,
+        Some(program_signer),
+        config.signers[authority_signer_index],
+        skip_fee_check,
+    )
+}
+
+/// Redeploy an existing program, either in place or via a buffer account
+fn process_redeploy(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    program_location: &str,
+    program_address: &Pubkey,
+    buffer_signer_index: Option<SignerIndex>,
+    authority_signer_index: SignerIndex,
+    skip_fee_check: bool,
+) -> ProcessResult {
+    let program_data = read_and_verify_elf(program_location)?;
+    if rpc_client
+        .get_account_with_commitment(program_address, config.commitment)?
+        .value
+        .is_none()
+    {
+        return Err(format!(
+            "Program {program_address} does not exist, use `solana program-v4 deploy` to create it"
+        )
+        .into());
+    }
+
+    let buffer_signer = buffer_signer_index.map(|index| config.signers[index]);
+    let write_address = buffer_signer
+        .map(|signer| signer.pubkey())
+        .unwrap_or(*program_address);
+
+    do_process_write_and_deploy(
+        rpc_client,
+        config,
+        &program_data,
+        &write_address,
+        program_address,
+        buffer_signer,
+        config.signers[authority_signer_index],
+        skip_fee_check,
+    )
+}
+
+/// Write the program data into the account at `write_address`, creating it
+/// if needed, and deploy it to `program_address`.
+///
+/// When the two addresses differ, the account at `write_address` is a buffer
+/// whose data replaces the program in the final transaction.  Chunks which
+/// already hold the expected data are not written again, which allows an
+/// interrupted upload to be resumed.
+#[allow(clippy::too_many_arguments)]
+fn do_process_write_and_deploy(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    program_data: &[u8],
+    write_address: &Pubkey,
+    program_address: &Pubkey,
+    write_signer: Option<&dyn Signer>,
+    authority_signer: &dyn Signer,
+    skip_fee_check: bool,
+) -> ProcessResult {
+    let payer_pubkey = config.signers[0].pubkey();
+    let authority_pubkey = authority_signer.pubkey();
+    let blockhash = rpc_client.get_latest_blockhash()?;
+
+    let program_data_len: u32 = program_data
+        .len()
+        .try_into()
+        .map_err(|_| "Program is too large")?;
+    let minimum_balance = rpc_client.get_minimum_balance_for_rent_exemption(
+        LoaderV4State::program_data_offset().saturating_add(program_data.len()),
+    )?;
+
+    // Create the account to write to, or resize an existing one
+    let mut balance_needed = 0;
+    let mut needs_retract = false;
+    let mut initial_instructions = vec![];
+    let write_account = rpc_client
+        .get_account_with_commitment(write_address, config.commitment)?
+        .value;
+    let existing_data: &[u8] = if let Some(account) = &write_account {
+        let state = check_program_account(account, write_address, &authority_pubkey)?;
+        if state.status != LoaderV4Status::Retracted {
+            if write_address != program_address {
+                return Err(format!("Buffer {write_address} is already deployed").into());
+            }
+            needs_retract = true;
+            initial_instructions.push(loader_v4::retract(write_address, &authority_pubkey));
+        }
+        if account.lamports < minimum_balance {
+            let lamports = minimum_balance.saturating_sub(account.lamports);
+            initial_instructions.push(system_instruction::transfer(
+                &payer_pubkey,
+                write_address,
+                lamports,
+            ));
+            balance_needed += lamports;
+        }
+        if account.data.len()
+            != LoaderV4State::program_data_offset().saturating_add(program_data.len())
+        {
+            initial_instructions.push(loader_v4::truncate(
+                write_address,
+                &authority_pubkey,
+                program_data_len,
+                &payer_pubkey,
+            ));
+        }
+        &account.data[LoaderV4State::program_data_offset()..]
+    } else {
+        if write_signer.is_none() {
+            return Err(
+                format!("Account {write_address} does not exist, must provide a keypair").into(),
+            );
+        }
+        initial_instructions.extend(loader_v4::create_buffer(
+            &payer_pubkey,
+            write_address,
+            minimum_balance,
+            &authority_pubkey,
+            program_data_len,
+            &payer_pubkey,
+        ));
+        balance_needed += minimum_balance;
+        &[]
+    };
+    let initial_message = (!initial_instructions.is_empty()).then(|| {
+        Message::new_with_blockhash(&initial_instructions, Some(&payer_pubkey), &blockhash)
+    });
+
+    // Create the write messages for the chunks that differ from the existing data
+    let create_msg = |offset: u32, bytes: Vec<u8>| {
+        let instruction = loader_v4::write(write_address, &authority_pubkey, offset, bytes);
+        Message::new_with_blockhash(&[instruction], Some(&payer_pubkey), &blockhash)
+    };
+    let write_messages = build_write_messages(program_data, existing_data, &create_msg);
+
+    // Create the final message, which deploys the program
+    let final_instructions = if write_address == program_address {
+        vec![loader_v4::deploy(program_address, &authority_pubkey)]
+    } else {
+        let program_account = rpc_client
+            .get_account_with_commitment(program_address, config.commitment)?
+            .value
+            .ok_or_else(|| format!("Program {program_address} does not exist"))?;
+        let state = check_program_account(&program_account, program_address, &authority_pubkey)?;
+        let mut instructions = vec![];
+        // the program takes on the size of the buffer, so it must be funded
+        // for it in advance
+        if program_account.lamports < minimum_balance {
+            let lamports = minimum_balance.saturating_sub(program_account.lamports);
+            instructions.push(system_instruction::transfer(
+                &payer_pubkey,
+                program_address,
+                lamports,
+            ));
+            balance_needed += lamports;
+        }
+        if state.status != LoaderV4Status::Retracted {
+            needs_retract = true;
+            instructions.push(loader_v4::retract(program_address, &authority_pubkey));
+        }
+        instructions.push(loader_v4::deploy_from_source(
+            program_address,
+            &authority_pubkey,
+            write_address,
+        ));
+        instructions
+    };
+    let final_message = Some(Message::new_with_blockhash(
+        &final_instructions,
+        Some(&payer_pubkey),
+        &blockhash,
+    ));
+
+    if !skip_fee_check {
+        check_payer(
+            &rpc_client,
+            config,
+            balance_needed,
+            &initial_message,
+            &write_messages,
+            &final_message,
+        )?;
+    }
+
+    if let Some(message) = initial_message {
+        trace!("Preparing the program account");
+        if needs_retract && write_address == program_address {
+            wait_for_deployment_cooldown(&rpc_client, config, program_address)?;
+        }
+        let mut signers = vec![config.signers[0], authority_signer];
+        signers.extend(write_signer);
+        sign_and_send_message(&rpc_client, config, &message, &signers)
+            .map_err(|err| format!("Preparing the program account failed: {err}"))?;
+    }
+
+    if !write_messages.is_empty() {
+        trace!("Writing program data");
+        send_write_messages(
+            rpc_client.clone(),
+            config,
+            &write_messages,
+            authority_signer,
+        )?;
+    }
+
+    if let Some(message) = final_message {
+        trace!("Deploying program");
+        // a newly created program has its deployment slot set at creation,
+        // so the cooldown applies to its first deployment as well
+        if needs_retract || write_address == program_address {
+            wait_for_deployment_cooldown(&rpc_client, config, program_address)?;
+        }
+        sign_and_send_message(
+            &rpc_client,
+            config,
+            &message,
+            &[config.signers[0], authority_signer],
+        )
+        .map_err(|err| format!("Deploying program failed: {err}"))?;
+    }
+
+    Ok(config.output_format.formatted_string(&CliProgramId {
+        program_id: program_address.to_string(),
+    }))
+}
+
+fn process_retract(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_address: &Pubkey,
+    authority_signer_index: SignerIndex,
+) -> ProcessResult {
+    let authority_signer = config.signers[authority_signer_index];
+    let program_account = rpc_client
+        .get_account_with_commitment(program_address, config.commitment)?
+        .value
+        .ok_or_else(|| format!("Program {program_address} does not exist"))?;
+    let state = check_program_account(
+        &program_account,
+        program_address,
+        &authority_signer.pubkey(),
+    )?;
+    if state.status == LoaderV4Status::Retracted {
+        return Err(format!("Program {program_address} is not deployed").into());
+    }
+
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let message = Message::new_with_blockhash(
+        &[loader_v4::retract(
+            program_address,
+            &authority_signer.pubkey(),
+        )],
+        Some(&config.signers[0].pubkey()),
+        &blockhash,
+    );
+    check_account_for_fee_with_commitment(
+        rpc_client,
+        &config.signers[0].pubkey(),
+        &message,
+        config.commitment,
+    )?;
+    wait_for_deployment_cooldown(rpc_client, config, program_address)?;
+    let signature = sign_and_send_message(
+        rpc_client,
+        config,
+        &message,
+        &[config.signers[0], authority_signer],
+    )
+    .map_err(|err| format!("Retracting program failed: {err}"))?;
+
+    Ok(config.output_format.formatted_string(&CliSignature {
+        signature: signature.to_string(),
+    }))
+}
+
+fn process_transfer_authority(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_address: &Pubkey,
+    authority_signer_index: SignerIndex,
+    new_authority_signer_index: Option<SignerIndex>,
+) -> ProcessResult {
+    let authority_signer = config.signers[authority_signer_index];
+    let new_authority_signer = new_authority_signer_index.map(|index| config.signers[index]);
+    let new_authority_pubkey = new_authority_signer.map(|signer| signer.pubkey());
+
+    let program_account = rpc_client
+        .get_account_with_commitment(program_address, config.commitment)?
+        .value
+        .ok_or_else(|| format!("Program {program_address} does not exist"))?;
+    let state = check_program_account(
+        &program_account,
+        program_address,
+        &authority_signer.pubkey(),
+    )?;
+    if new_authority_pubkey.is_none() && state.status != LoaderV4Status::Deployed {
+        return Err(format!("Program {program_address} must be deployed to be finalized").into());
+    }
+
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let message = Message::new_with_blockhash(
+        &[loader_v4::transfer_authority(
+            program_address,
+            &authority_signer.pubkey(),
+            new_authority_pubkey.as_ref(),
+        )],
+        Some(&config.signers[0].pubkey()),
+        &blockhash,
+    );
+    check_account_for_fee_with_commitment(
+        rpc_client,
+        &config.signers[0].pubkey(),
+        &message,
+        config.commitment,
+    )?;
+    let mut signers = vec![config.signers[0], authority_signer];
+    signers.extend(new_authority_signer);
+    sign_and_send_message(rpc_client, config, &message, &signers)
+        .map_err(|err| format!("Transferring the program authority failed: {err}"))?;
+
+    Ok(config.output_format.formatted_string(&CliProgramAuthority {
+        authority: new_authority_pubkey
+            .map(|pubkey| pubkey.to_string())
+            .unwrap_or_else(|| "none".to_string()),
+        account_type: CliProgramAccountType::Program,
+    }))
+}
+
+fn process_show(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    account_pubkey: Option<Pubkey>,
+    authority_pubkey: &Pubkey,
+    all: bool,
+    use_lamports_unit: bool,
+) -> ProcessResult {
+    if let Some(account_pubkey) = account_pubkey {
+        let account = rpc_client
+            .get_account_with_commitment(&account_pubkey, config.commitment)?
+            .value
+            .ok_or_else(|| format!("Unable to find the account {account_pubkey}"))?;
+        let program = build_cli_program(&account_pubkey, &account, use_lamports_unit)
+            .ok_or_else(|| format!("{account_pubkey} is not a loader-v4 program"))?;
+        Ok(config.output_format.formatted_string(&program))
+    } else {
+        let authority_pubkey = if all { None } else { Some(authority_pubkey) };
+        let programs = get_programs(rpc_client, config, authority_pubkey, use_lamports_unit)?;
+        Ok(config.output_format.formatted_string(&programs))
+    }
+}
+
+fn process_dump(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    account_pubkey: &Pubkey,
+    output_location: &str,
+) -> ProcessResult {
+    let account = rpc_client
+        .get_account_with_commitment(account_pubkey, config.commitment)?
+        .value
+        .ok_or_else(|| format!("Unable to find the account {account_pubkey}"))?;
+    if get_program_state(&account).is_none() {
+        return Err(format!("{account_pubkey} is not a loader-v4 program").into());
+    }
+    let program_data = &account.data[LoaderV4State::program_data_offset()..];
+    let mut f = File::create(output_location)?;
+    f.write_all(program_data)?;
+    Ok(format!("Wrote program to {output_location}"))
+}
+
+fn get_programs(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    authority_pubkey: Option<&Pubkey>,
+    use_lamports_unit: bool,
+) -> Result<CliProgramsV4, Box<dyn std::error::Error>> {
+    let filters = authority_pubkey
+        .map(|authority_pubkey| {
+            vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                size_of::<u64>(),
+                authority_pubkey.as_ref(),
+            ))]
+        })
+        .unwrap_or_default();
+    let results = rpc_client.get_program_accounts_with_config(
+        &loader_v4::id(),
+        RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(config.commitment),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?;
+
+    let programs = results
+        .iter()
+        .filter_map(|(address, account)| build_cli_program(address, account, use_lamports_unit))
+        .collect();
+    Ok(CliProgramsV4 {
+        programs,
+        use_lamports_unit,
+    })
+}
+
+fn build_cli_program(
+    address: &Pubkey,
+    account: &Account,
+    use_lamports_unit: bool,
+) -> Option<CliProgramV4> {
+    let state = get_program_state(account)?;
+    Some(CliProgramV4 {
+        program_id: address.to_string(),
+        owner: account.owner.to_string(),
+        authority: state.authority_address.to_string(),
+        last_deploy_slot: state.slot,
+        status: format!("{:?}", state.status),
+        data_len: account
+            .data
+            .len()
+            .saturating_sub(LoaderV4State::program_data_offset()),
+        lamports: account.lamports,
+        use_lamports_unit,
+    })
+}
+
+/// Returns the state of a loader-v4 program account, or None if the account
+/// is not an initialized loader-v4 program account.
+fn get_program_state(account: &Account) -> Option<LoaderV4State> {
+    if !loader_v4::check_id(&account.owner) {
+        return None;
+    }
+    let data = account.data.get(..LoaderV4State::program_data_offset())?;
+    let (slot, data) = data.split_at(size_of::<u64>());
+    let (authority_address, status) = data.split_at(size_of::<Pubkey>());
+    let status = match u64::from_le_bytes(status.try_into().ok()?) {
+        0 => LoaderV4Status::Retracted,
+        1 => LoaderV4Status::Deployed,
+        2 => LoaderV4Status::Finalized,
+        _ => return None,
+    };
+    Some(LoaderV4State {
+        slot: u64::from_le_bytes(slot.try_into().ok()?),
+        authority_address: Pubkey::try_from(authority_address).ok()?,
+        status,
+    })
+}
+
+/// Returns the state of a loader-v4 program account which is to be modified
+/// by the specified authority.
+fn check_program_account(
+    account: &Account,
+    address: &Pubkey,
+    authority_pubkey: &Pubkey,
+) -> Result<LoaderV4State, Box<dyn std::error::Error>> {
+    let state = get_program_state(account)
+        .ok_or_else(|| format!("{address} is not a loader-v4 program account"))?;
+    if state.authority_address != *authority_pubkey {
+        return Err(format!(
+            "{address} has authority {}, not {authority_pubkey}",
+            state.authority_address
+        )
+        .into());
+    }
+    if state.status == LoaderV4Status::Finalized {
+        return Err(format!("Program {address} is finalized").into());
+    }
+    Ok(state)
+}
+
+/// Split the program data into write messages, skipping the chunks which
+/// already match `existing_data`.
+fn build_write_messages<F>(
+    program_data: &[u8],
+    existing_data: &[u8],
+    create_msg: &F,
+) -> Vec<Message>
+where
+    F: Fn(u32, Vec<u8>) -> Message,
+{
+    let chunk_size = calculate_max_chunk_size(create_msg);
+    program_data
+        .chunks(chunk_size)
+        .zip(0..)
+        .filter_map(|(chunk, i)| {
+            let offset = i * chunk_size;
+            (existing_data.get(offset..offset + chunk.len()) != Some(chunk))
+                .then(|| create_msg(offset as u32, chunk.to_vec()))
+        })
+        .collect()
+}
+
+/// Loader-v4 rejects deploying or retracting a program within
+/// DEPLOYMENT_COOLDOWN_IN_SLOTS of its last deployment, so wait it out.
+fn wait_for_deployment_cooldown(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_address: &Pubkey,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(state) = rpc_client
+        .get_account_with_commitment(program_address, config.commitment)?
+        .value
+        .as_ref()
+        .and_then(get_program_state)
+    else {
+        return Ok(());
+    };
+    let cooldown_end = state.slot.saturating_add(DEPLOYMENT_COOLDOWN_IN_SLOTS);
+
+    let progress_bar = new_spinner_progress_bar();
+    loop {
+        let slot = rpc_client.get_slot_with_commitment(config.commitment)?;
+        if slot >= cooldown_end {
+            break;
+        }
+        progress_bar.set_message(format!(
+            "Waiting for the deployment cooldown of {program_address}: {} slots remaining",
+            cooldown_end - slot
+        ));
+        sleep(Duration::from_millis(DEFAULT_MS_PER_SLOT));
+    }
+    progress_bar.finish_and_clear();
+    Ok(())
+}
+
+fn sign_and_send_message(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    message: &Message,
+    signers: &[&dyn Signer],
+) -> Result<Signature, Box<dyn std::error::Error>> {
+    // only pass the signers required by the message, as signing fails on
+    // extraneous keypairs
+    let signer_keys = message.signer_keys();
+    let mut required_signers: Vec<&dyn Signer> = vec![];
+    for signer in signers {
+        let pubkey = signer.pubkey();
+        if signer_keys.contains(&&pubkey)
+            && !required_signers
+                .iter()
+                .any(|required| required.pubkey() == pubkey)
+        {
+            required_signers.push(*signer);
+        }
+    }
+
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let mut transaction = Transaction::new_unsigned(message.clone());
+    transaction.try_sign(&required_signers, blockhash)?;
+    let signature = rpc_client.send_and_confirm_transaction_with_spinner_and_config(
+        &transaction,
+        config.commitment,
+        RpcSendTransactionConfig {
+            preflight_commitment: Some(config.commitment.commitment),
+            ..RpcSendTransactionConfig::default()
+        },
+    )?;
+    Ok(signature)
+}
+
+fn read_and_verify_elf(program_location: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut file = File::open(program_location)
+        .map_err(|err| format!("Unable to open program file: {err}"))?;
+    let mut program_data = Vec::new();
+    file.read_to_end(&mut program_data)
+        .map_err(|err| format!("Unable to read program file: {err}"))?;
+
+    // Verify the program against the environment of the v4 loader
+    let program_runtime_environment =
+        create_program_runtime_environment_v2(&ComputeBudget::default(), false);
+    let executable =
+        Executable::<InvokeContext>::from_elf(&program_data, Arc::new(program_runtime_environment))
+            .map_err(|err| format!("ELF error: {err}"))?;
+
+    executable
+        .verify::<RequisiteVerifier>()
+        .map_err(|err| format!("ELF error: {err}"))?;
+
+    Ok(program_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{clap_app::get_clap_app, cli::parse_command},
+        solana_sdk::{
+            hash::Hash,
+            signature::{read_keypair_file, write_keypair_file, Keypair},
+        },
+    };
+
+    fn make_tmp_path(name: &str) -> String {
+        let out_dir = std::env::var("FARF_DIR").unwrap_or_else(|_| "farf".to_string());
+        let keypair = Keypair::new();
+
+        let path = format!("{}/tmp/{}-{}", out_dir, name, keypair.pubkey());
+
+        // whack any possible collision
+        let _ignored = std::fs::remove_dir_all(&path);
+        // whack any possible collision
+        let _ignored = std::fs::remove_file(&path);
+
+        path
+    }
+
+    #[test]
+    fn test_cli_parse_deploy() {
+        let test_commands = get_clap_app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let keypair_file = make_tmp_path("keypair_file");
+        write_keypair_file(&default_keypair, &keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", &keypair_file);
+
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "deploy",
+            "/Users/test/program.so",
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Deploy {
+                    program_location: "/Users/test/program.so".to_string(),
+                    program_signer_index: None,
+                    authority_signer_index: 0,
+                    skip_fee_check: false,
+                }),
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+
+        let program_keypair = Keypair::new();
+        let program_keypair_file = make_tmp_path("program_keypair_file");
+        write_keypair_file(&program_keypair, &program_keypair_file).unwrap();
+        let authority_keypair = Keypair::new();
+        let authority_keypair_file = make_tmp_path("authority_keypair_file");
+        write_keypair_file(&authority_keypair, &authority_keypair_file).unwrap();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "deploy",
+            "/Users/test/program.so",
+            "--program-keypair",
+            &program_keypair_file,
+            "--authority",
+            &authority_keypair_file,
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Deploy {
+                    program_location: "/Users/test/program.so".to_string(),
+                    program_signer_index: Some(1),
+                    authority_signer_index: 2,
+                    skip_fee_check: false,
+                }),
+                signers: vec![
+                    read_keypair_file(&keypair_file).unwrap().into(),
+                    read_keypair_file(&program_keypair_file).unwrap().into(),
+                    read_keypair_file(&authority_keypair_file).unwrap().into(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_redeploy() {
+        let test_commands = get_clap_app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let keypair_file = make_tmp_path("keypair_file");
+        write_keypair_file(&default_keypair, &keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", &keypair_file);
+
+        let program_address = Pubkey::new_unique();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "redeploy",
+            "/Users/test/program.so",
+            "--program-id",
+            &program_address.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Redeploy {
+                    program_location: "/Users/test/program.so".to_string(),
+                    program_address,
+                    buffer_signer_index: None,
+                    authority_signer_index: 0,
+                    skip_fee_check: false,
+                }),
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+
+        let buffer_keypair = Keypair::new();
+        let buffer_keypair_file = make_tmp_path("buffer_keypair_file");
+        write_keypair_file(&buffer_keypair, &buffer_keypair_file).unwrap();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "redeploy",
+            "/Users/test/program.so",
+            "--program-id",
+            &program_address.to_string(),
+            "--buffer",
+            &buffer_keypair_file,
+            "--skip-fee-check",
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Redeploy {
+                    program_location: "/Users/test/program.so".to_string(),
+                    program_address,
+                    buffer_signer_index: Some(1),
+                    authority_signer_index: 0,
+                    skip_fee_check: true,
+                }),
+                signers: vec![
+                    read_keypair_file(&keypair_file).unwrap().into(),
+                    read_keypair_file(&buffer_keypair_file).unwrap().into(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_retract_and_transfer_authority() {
+        let test_commands = get_clap_app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let keypair_file = make_tmp_path("keypair_file");
+        write_keypair_file(&default_keypair, &keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", &keypair_file);
+
+        let program_address = Pubkey::new_unique();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "retract",
+            &program_address.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Retract {
+                    program_address,
+                    authority_signer_index: 0,
+                }),
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+
+        let new_authority_keypair = Keypair::new();
+        let new_authority_keypair_file = make_tmp_path("new_authority_keypair_file");
+        write_keypair_file(&new_authority_keypair, &new_authority_keypair_file).unwrap();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "transfer-authority",
+            &program_address.to_string(),
+            "--new-authority",
+            &new_authority_keypair_file,
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::TransferAuthority {
+                    program_address,
+                    authority_signer_index: 0,
+                    new_authority_signer_index: Some(1),
+                }),
+                signers: vec![
+                    read_keypair_file(&keypair_file).unwrap().into(),
+                    read_keypair_file(&new_authority_keypair_file)
+                        .unwrap()
+                        .into(),
+                ],
+            }
+        );
+
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "transfer-authority",
+            &program_address.to_string(),
+            "--final",
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::TransferAuthority {
+                    program_address,
+                    authority_signer_index: 0,
+                    new_authority_signer_index: None,
+                }),
+                signers: vec![read_keypair_file(&keypair_file).unwrap().into()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_show_and_dump() {
+        let test_commands = get_clap_app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let keypair_file = make_tmp_path("keypair_file");
+        write_keypair_file(&default_keypair, &keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", &keypair_file);
+
+        let program_address = Pubkey::new_unique();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "show",
+            &program_address.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Show {
+                    account_pubkey: Some(program_address),
+                    authority_pubkey: default_keypair.pubkey(),
+                    all: false,
+                    use_lamports_unit: false,
+                }),
+                signers: vec![],
+            }
+        );
+
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "show",
+            "--all",
+            "--lamports",
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Show {
+                    account_pubkey: None,
+                    authority_pubkey: default_keypair.pubkey(),
+                    all: true,
+                    use_lamports_unit: true,
+                }),
+                signers: vec![],
+            }
+        );
+
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "dump",
+            &program_address.to_string(),
+            "/Users/test/program.so",
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::ProgramV4(ProgramV4CliCommand::Dump {
+                    account_pubkey: program_address,
+                    output_location: "/Users/test/program.so".to_string(),
+                }),
+                signers: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_program_state() {
+        let authority_address = Pubkey::new_unique();
+        let mut data = vec![];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(authority_address.as_ref());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        let mut account = Account {
+            lamports: 1,
+            data,
+            owner: loader_v4::id(),
+            executable: true,
+            rent_epoch: 0,
+        };
+        assert_eq!(
+            get_program_state(&account),
+            Some(LoaderV4State {
+                slot: 42,
+                authority_address,
+                status: LoaderV4Status::Deployed,
+            })
+        );
+
+        // uninitialized
+        account
+            .data
+            .truncate(LoaderV4State::program_data_offset() - 1);
+        assert_eq!(get_program_state(&account), None);
+
+        // wrong owner
+        account.owner = Pubkey::new_unique();
+        assert_eq!(get_program_state(&account), None);
+    }
+
+    #[test]
+    fn test_build_write_messages() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let create_msg = |offset: u32, bytes: Vec<u8>| {
+            let instruction = loader_v4::write(&program, &authority, offset, bytes);
+            Message::new_with_blockhash(&[instruction], Some(&payer), &Hash::default())
+        };
+        let chunk_size = calculate_max_chunk_size(&create_msg);
+        let program_data: Vec<u8> = (0..chunk_size * 3 + 10).map(|i| i as u8).collect();
+
+        // nothing has been written yet
+        assert_eq!(
+            build_write_messages(&program_data, &[], &create_msg).len(),
+            4
+        );
+
+        // resume after the first two chunks have been written
+        let mut existing_data = program_data[..chunk_size * 2].to_vec();
+        existing_data.resize(program_data.len(), 0);
+        let messages = build_write_messages(&program_data, &existing_data, &create_msg);
+        assert_eq!(
+            messages,
+            vec![
+                create_msg(
+                    (chunk_size * 2) as u32,
+                    program_data[chunk_size * 2..chunk_size * 3].to_vec()
+                ),
+                create_msg(
+                    (chunk_size * 3) as u32,
+                    program_data[chunk_size * 3..].to_vec()
+                ),
+            ]
+        );
+
+        // everything has been written
+        assert!(build_write_messages(&program_data, &program_data, &create_msg).is_empty());
+    }
+}
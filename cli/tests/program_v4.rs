@@ -0,0 +1,93 @@
+use {
+    solana_cli::{
+        cli::{process_command, CliCommand, CliConfig},
+        program_v4::ProgramV4CliCommand,
+    },
+    solana_faucet::faucet::run_local_faucet,
+    solana_loader_v4_program::get_state,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        instruction::{Instruction, InstructionError},
+        loader_v4::{self, LoaderV4Status},
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    solana_test_validator::TestValidator,
+    std::{env, path::PathBuf, thread::sleep, time::Duration},
+};
+
+#[test]
+fn test_cli_program_v4_deploy_and_invoke() {
+    solana_logger::setup();
+
+    // returns the custom error 42 when invoked
+    let mut program_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    program_path.push("..");
+    program_path.push("programs");
+    program_path.push("loader-v4");
+    program_path.push("test_elfs");
+    program_path.push("out");
+    program_path.push("rodata_section");
+    program_path.set_extension("so");
+
+    let mint_keypair = Keypair::new();
+    let mint_pubkey = mint_keypair.pubkey();
+    let faucet_addr = run_local_faucet(mint_keypair, None);
+    let test_validator =
+        TestValidator::with_no_fees(mint_pubkey, Some(faucet_addr), SocketAddrSpace::Unspecified);
+
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+
+    let mut config = CliConfig::recent_for_tests();
+    let keypair = Keypair::new();
+    let program_keypair = Keypair::new();
+    config.json_rpc_url = test_validator.rpc_url();
+    config.signers = vec![&keypair, &program_keypair];
+    config.command = CliCommand::Airdrop {
+        pubkey: None,
+        lamports: 100_000_000_000,
+    };
+    process_command(&config).unwrap();
+
+    config.command = CliCommand::ProgramV4(ProgramV4CliCommand::Deploy {
+        program_location: program_path.to_str().unwrap().to_string(),
+        program_signer_index: Some(1),
+        authority_signer_index: 0,
+        skip_fee_check: false,
+    });
+    process_command(&config).unwrap();
+
+    let program_id = program_keypair.pubkey();
+    let program_account = rpc_client.get_account(&program_id).unwrap();
+    assert_eq!(program_account.owner, loader_v4::id());
+    let state = get_state(&program_account.data).unwrap();
+    assert_eq!(state.status, LoaderV4Status::Deployed);
+    assert_eq!(state.authority_address, keypair.pubkey());
+
+    // a deployed program becomes visible in the slot after its deployment
+    let deployment_slot = state.slot;
+    while rpc_client.get_slot().unwrap() <= deployment_slot {
+        sleep(Duration::from_millis(100));
+    }
+
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(program_id, &[], vec![])],
+        Some(&keypair.pubkey()),
+        &[&keypair],
+        blockhash,
+    );
+    let err = rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .unwrap_err();
+    assert_eq!(
+        err.get_transaction_error(),
+        Some(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(42)
+        ))
+    );
+}
@@ -346,6 +346,13 @@ impl AccountsHashVerifier {
         if accounts_package.package_kind
             == AccountsPackageKind::Snapshot(SnapshotKind::FullSnapshot)
         {
+            accounts_package
+                .accounts
+                .accounts_db
+                .seal_persisted_secondary_indexes(
+                    accounts_package.slot,
+                    &accounts_hash_for_reserialize,
+                );
             accounts_package
                 .accounts
                 .accounts_db
@@ -1,4 +1,6 @@
 use {
+    crate::consensus::fork_choice_snapshot::ForkChoiceSnapshotRequest,
+    crossbeam_channel::Sender,
     solana_gossip::cluster_info::ClusterInfo,
     solana_runtime::bank_forks::BankForks,
     solana_sdk::pubkey::Pubkey,
@@ -14,4 +16,5 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub bank_forks: Arc<RwLock<BankForks>>,
     pub vote_account: Pubkey,
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>,
+    pub fork_choice_snapshot_sender: Sender<ForkChoiceSnapshotRequest>,
 }
@@ -0,0 +1,501 @@
+//! Offline replay of banking trace files.
+//!
+//! [`BankingTraceEvents`] loads the events recorded by [`BankingTracer`] and
+//! [`BankingSimulator`] feeds the recorded packets through a real
+//! [`BankingStage`] against a bank loaded for the parent of a traced leader
+//! slot. Packets are sent with the same relative timing as they were
+//! originally received, so that leader-side scheduling can be reproduced
+//! after the fact.
+use {
+    crate::{
+        banking_stage::BankingStage,
+        banking_trace::{
+            BankingPacketBatch, BankingTracer, ChannelLabel, TimedTracedEvent, TracedEvent,
+            BASENAME,
+        },
+        validator::BlockProductionMethod,
+    },
+    bincode::deserialize_from,
+    crossbeam_channel::{unbounded, RecvTimeoutError},
+    log::*,
+    solana_client::connection_cache::ConnectionCache,
+    solana_gossip::cluster_info::{ClusterInfo, Node},
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_poh::{
+        poh_recorder::PohRecorder,
+        poh_service::{PohService, DEFAULT_HASHES_PER_BATCH, DEFAULT_PINNED_CPU_CORE},
+    },
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache,
+    },
+    solana_sdk::{
+        clock::{Slot, DEFAULT_MS_PER_SLOT},
+        genesis_config::GenesisConfig,
+        hash::Hash,
+        signature::{Keypair, Signer},
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    solana_tpu_client::tpu_client::DEFAULT_TPU_CONNECTION_POOL_SIZE,
+    std::{
+        collections::BTreeMap,
+        fs::{read_dir, File},
+        io::{self, BufReader},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep},
+        time::{Duration, Instant, SystemTime},
+    },
+    thiserror::Error,
+};
+
+/// Packets received this long before the first simulated slot started are
+/// also replayed, so that the banking stage has buffered them by the time it
+/// becomes leader.
+const WARMUP_DURATION: Duration = Duration::from_millis(DEFAULT_MS_PER_SLOT);
+const ENTRY_RECV_TIMEOUT: Duration = Duration::from_millis(10);
+const SENDER_SLEEP_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Error, Debug)]
+pub enum SimulateError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] bincode::Error),
+
+    #[error("First simulated slot {0} must be after the parent slot {1}")]
+    InvalidFirstSimulatedSlot(Slot, Slot),
+
+    #[error("No leader found for slot {0}")]
+    MissingLeader(Slot),
+
+    #[error("No traced block start found for parent slot {0}")]
+    MissingBlockStart(Slot),
+}
+
+/// Recorded block hashes of a slot, along with the time its child leader slot started.
+#[derive(Debug, Clone, Copy)]
+struct TracedBlock {
+    child_start_time: SystemTime,
+    blockhash: Hash,
+    bank_hash: Hash,
+}
+
+/// All events from a banking trace directory, ordered by time.
+#[derive(Default)]
+pub struct BankingTraceEvents {
+    packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+    blocks_by_slot: BTreeMap<Slot, TracedBlock>,
+}
+
+impl BankingTraceEvents {
+    /// Load all event files (the current file and any rotated ones) found in `trace_dir`.
+    pub fn load(trace_dir: &Path) -> Result<Self, SimulateError> {
+        let mut event_file_paths = read_dir(trace_dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        event_file_paths.retain(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with(BASENAME))
+                .unwrap_or(false)
+        });
+        Self::load_files(&event_file_paths)
+    }
+
+    pub fn load_files(event_file_paths: &[PathBuf]) -> Result<Self, SimulateError> {
+        let mut events = Self::default();
+        for event_file_path in event_file_paths {
+            info!("loading banking trace events from {:?}", event_file_path);
+            events.read_event_file(event_file_path)?;
+        }
+        // rotated files are not necessarily listed in order
+        events
+            .packet_batches
+            .sort_by_key(|(event_time, ..)| *event_time);
+        Ok(events)
+    }
+
+    fn read_event_file(&mut self, event_file_path: &Path) -> Result<(), SimulateError> {
+        let mut reader = BufReader::new(File::open(event_file_path)?);
+        loop {
+            let TimedTracedEvent(event_time, event) = match deserialize_from(&mut reader) {
+                Ok(event) => event,
+                Err(err) => match *err {
+                    // a file ends at an event boundary, unless the tracer was
+                    // interrupted while writing; either way nothing follows.
+                    bincode::ErrorKind::Io(ref io_err)
+                        if io_err.kind() == io::ErrorKind::UnexpectedEof =>
+                    {
+                        break;
+                    }
+                    _ => return Err(err.into()),
+                },
+            };
+            match event {
+                TracedEvent::PacketBatch(label, batch) => {
+                    self.packet_batches.push((event_time, label, batch));
+                }
+                TracedEvent::BlockAndBankHash(slot, blockhash, bank_hash) => {
+                    self.blocks_by_slot.insert(
+                        slot,
+                        TracedBlock {
+                            child_start_time: event_time,
+                            blockhash,
+                            bank_hash,
+                        },
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn packet_batch_count(&self) -> usize {
+        self.packet_batches.len()
+    }
+
+    /// Slots whose final hashes were recorded, i.e. the parents of traced leader slots.
+    pub fn traced_slots(&self) -> impl Iterator<Item = &Slot> {
+        self.blocks_by_slot.keys()
+    }
+}
+
+/// Outcome of a single simulated leader slot.
+#[derive(Debug)]
+pub struct SimulatedSlot {
+    pub slot: Slot,
+    pub entry_count: usize,
+    pub tick_count: usize,
+    pub transaction_count: u64,
+    pub block_cost: u64,
+    pub block_cost_limit: u64,
+    pub blockhash: Hash,
+    pub bank_hash: Hash,
+    /// `(blockhash, bank_hash)` of the slot, as recorded in the trace.
+    pub traced_hashes: Option<(Hash, Hash)>,
+}
+
+impl SimulatedSlot {
+    /// Returns `None` if the trace does not contain the hashes for this slot.
+    pub fn is_diverged(&self) -> Option<bool> {
+        self.traced_hashes.map(|(blockhash, bank_hash)| {
+            (blockhash, bank_hash) != (self.blockhash, self.bank_hash)
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SimulationReport {
+    pub sent_packet_count: usize,
+    pub simulated_slots: Vec<SimulatedSlot>,
+}
+
+pub struct BankingSimulator {
+    banking_trace_events: BankingTraceEvents,
+    first_simulated_slot: Slot,
+}
+
+impl BankingSimulator {
+    pub fn new(banking_trace_events: BankingTraceEvents, first_simulated_slot: Slot) -> Self {
+        Self {
+            banking_trace_events,
+            first_simulated_slot,
+        }
+    }
+
+    /// Replay the traced packets through a `BankingStage`, producing blocks for the
+    /// consecutive leader slots starting at `first_simulated_slot`.
+    ///
+    /// The working bank of `bank_forks` is used as the parent of the first
+    /// simulated slot, so it must be the bank the traced leader built upon.
+    pub fn simulate(
+        self,
+        genesis_config: &GenesisConfig,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        block_production_method: BlockProductionMethod,
+    ) -> Result<SimulationReport, SimulateError> {
+        let Self {
+            banking_trace_events,
+            first_simulated_slot,
+        } = self;
+        let BankingTraceEvents {
+            packet_batches,
+            blocks_by_slot,
+        } = banking_trace_events;
+
+        let mut parent_bank = bank_forks.read().unwrap().working_bank();
+        if parent_bank.slot() >= first_simulated_slot {
+            return Err(SimulateError::InvalidFirstSimulatedSlot(
+                first_simulated_slot,
+                parent_bank.slot(),
+            ));
+        }
+        let first_block_start_time = blocks_by_slot
+            .get(&parent_bank.slot())
+            .ok_or(SimulateError::MissingBlockStart(parent_bank.slot()))?
+            .child_start_time;
+
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&parent_bank));
+        let leader = leader_schedule_cache
+            .slot_leader_at(first_simulated_slot, Some(&parent_bank))
+            .ok_or(SimulateError::MissingLeader(first_simulated_slot))?;
+        let last_simulated_slot = (first_simulated_slot..)
+            .take_while(|slot| {
+                leader_schedule_cache.slot_leader_at(*slot, Some(&parent_bank)) == Some(leader)
+            })
+            .last()
+            .unwrap_or(first_simulated_slot);
+        info!(
+            "simulating slots {}..={} of leader {} from parent slot {}",
+            first_simulated_slot,
+            last_simulated_slot,
+            leader,
+            parent_bank.slot(),
+        );
+
+        let exit = Arc::new(AtomicBool::default());
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            parent_bank.tick_height(),
+            parent_bank.last_blockhash(),
+            parent_bank.clone(),
+            Some((first_simulated_slot, last_simulated_slot)),
+            parent_bank.ticks_per_slot(),
+            &leader,
+            blockstore,
+            &leader_schedule_cache,
+            &genesis_config.poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            &genesis_config.poh_config,
+            exit.clone(),
+            parent_bank.ticks_per_slot(),
+            DEFAULT_PINNED_CPU_CORE,
+            DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+        );
+
+        // The traced leader's identity is not available, so use a throwaway one for gossip.
+        // Block production only depends on the leader pubkey given to PohRecorder.
+        let cluster_info = {
+            let keypair = Arc::new(Keypair::new());
+            let node = Node::new_localhost_with_pubkey(&keypair.pubkey());
+            Arc::new(ClusterInfo::new(
+                node.info,
+                keypair,
+                SocketAddrSpace::Unspecified,
+            ))
+        };
+        let banking_tracer = BankingTracer::new_disabled();
+        let (non_vote_sender, non_vote_receiver) = banking_tracer.create_channel_non_vote();
+        let (tpu_vote_sender, tpu_vote_receiver) = banking_tracer.create_channel_tpu_vote();
+        let (gossip_vote_sender, gossip_vote_receiver) =
+            banking_tracer.create_channel_gossip_vote();
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let banking_stage = BankingStage::new(
+            block_production_method,
+            &cluster_info,
+            &poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            None,
+            replay_vote_sender,
+            None,
+            Arc::new(ConnectionCache::new_quic(
+                "connection_cache_banking_simulation",
+                DEFAULT_TPU_CONNECTION_POOL_SIZE,
+            )),
+            bank_forks.clone(),
+            &Arc::new(PrioritizationFeeCache::new(0u64)),
+        );
+
+        let simulation_start = Instant::now();
+        let replay_start_time = first_block_start_time - WARMUP_DURATION;
+        let sender_thread = {
+            let exit = exit.clone();
+            thread::Builder::new()
+                .name("solSimSender".into())
+                .spawn(move || {
+                    let mut sent_packet_count = 0;
+                    'outer: for (event_time, label, batch) in packet_batches {
+                        let Ok(offset) = event_time.duration_since(replay_start_time) else {
+                            continue;
+                        };
+                        // the trace may extend far beyond the simulated slots, so keep
+                        // checking for exit while waiting for the next event.
+                        while let Some(duration) = offset.checked_sub(simulation_start.elapsed()) {
+                            if exit.load(Ordering::Relaxed) {
+                                break 'outer;
+                            }
+                            sleep(duration.min(SENDER_SLEEP_INTERVAL));
+                        }
+                        let packet_count = batch.0.iter().map(|batch| batch.len()).sum::<usize>();
+                        let sender = match label {
+                            ChannelLabel::NonVote => &non_vote_sender,
+                            ChannelLabel::TpuVote => &tpu_vote_sender,
+                            ChannelLabel::GossipVote => &gossip_vote_sender,
+                            ChannelLabel::Dummy => continue,
+                        };
+                        if sender.send(batch).is_err() {
+                            break 'outer;
+                        }
+                        sent_packet_count += packet_count;
+                    }
+                    sent_packet_count
+                })
+                .unwrap()
+        };
+
+        sleep(WARMUP_DURATION);
+        let mut simulated_slots = vec![];
+        for slot in first_simulated_slot..=last_simulated_slot {
+            let bank = bank_forks.write().unwrap().insert(Bank::new_from_parent(
+                parent_bank.clone(),
+                &leader,
+                slot,
+            ));
+            {
+                let mut poh_recorder = poh_recorder.write().unwrap();
+                poh_recorder.reset(parent_bank.clone(), Some((slot, last_simulated_slot)));
+                poh_recorder.set_bank(bank.clone(), false);
+            }
+
+            let mut entry_count = 0;
+            let mut tick_count = 0;
+            while !bank.is_complete() {
+                match entry_receiver.recv_timeout(ENTRY_RECV_TIMEOUT) {
+                    Ok((_bank, (entry, _tick_height))) => {
+                        if entry.is_tick() {
+                            tick_count += 1;
+                        } else {
+                            entry_count += 1;
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            bank.freeze();
+
+            let (block_cost, block_cost_limit) = {
+                let cost_tracker = bank.read_cost_tracker().unwrap();
+                (cost_tracker.block_cost(), cost_tracker.block_cost_limit())
+            };
+            let simulated_slot = SimulatedSlot {
+                slot,
+                entry_count,
+                tick_count,
+                transaction_count: bank.executed_transaction_count(),
+                block_cost,
+                block_cost_limit,
+                blockhash: bank.last_blockhash(),
+                bank_hash: bank.hash(),
+                traced_hashes: blocks_by_slot
+                    .get(&slot)
+                    .map(|block| (block.blockhash, block.bank_hash)),
+            };
+            info!("simulated slot: {:?}", simulated_slot);
+            simulated_slots.push(simulated_slot);
+            parent_bank = bank;
+        }
+
+        exit.store(true, Ordering::Relaxed);
+        let sent_packet_count = sender_thread.join().unwrap();
+        banking_stage.join().unwrap();
+        poh_service.join().unwrap();
+
+        Ok(SimulationReport {
+            sent_packet_count,
+            simulated_slots,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::{
+            for_test, receiving_loop_with_minimized_sender_overhead, DirByteLimit, TraceError,
+        },
+        std::str::FromStr,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_load_banking_trace_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), DirByteLimit::max_value()))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+
+        let dummy_main_thread = thread::spawn(move || {
+            receiving_loop_with_minimized_sender_overhead::<_, TraceError, 0>(
+                exit,
+                non_vote_receiver,
+                |_packet_batch| Ok(()),
+            )
+        });
+
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        let blockhash = Hash::from_str("B1ockhash1111111111111111111111111111111111").unwrap();
+        let bank_hash = Hash::from_str("BankHash11111111111111111111111111111111111").unwrap();
+        tracer.hash_event(4, &blockhash, &bank_hash);
+
+        for_test::terminate_tracer(
+            tracer,
+            tracer_thread,
+            dummy_main_thread,
+            non_vote_sender,
+            None,
+        );
+
+        let events = BankingTraceEvents::load(&path).unwrap();
+        assert_eq!(events.packet_batch_count(), 2);
+        assert_eq!(events.traced_slots().collect::<Vec<_>>(), vec![&4]);
+        let block = events.blocks_by_slot.get(&4).unwrap();
+        assert_eq!(block.blockhash, blockhash);
+        assert_eq!(block.bank_hash, bank_hash);
+        assert!(events
+            .packet_batches
+            .windows(2)
+            .all(|pair| pair[0].0 <= pair[1].0));
+    }
+
+    #[test]
+    fn test_simulated_slot_is_diverged() {
+        let blockhash = Hash::new_unique();
+        let bank_hash = Hash::new_unique();
+        let mut simulated_slot = SimulatedSlot {
+            slot: 5,
+            entry_count: 0,
+            tick_count: 64,
+            transaction_count: 0,
+            block_cost: 0,
+            block_cost_limit: 0,
+            blockhash,
+            bank_hash,
+            traced_hashes: None,
+        };
+        assert_eq!(simulated_slot.is_diverged(), None);
+        simulated_slot.traced_hashes = Some((blockhash, bank_hash));
+        assert_eq!(simulated_slot.is_diverged(), Some(false));
+        simulated_slot.traced_hashes = Some((blockhash, Hash::new_unique()));
+        assert_eq!(simulated_slot.is_diverged(), Some(true));
+    }
+}
@@ -5,18 +5,28 @@
 use {
     self::{
         committer::Committer,
+        consume_worker::ConsumeWorker,
         consumer::Consumer,
         decision_maker::{BufferedPacketsDecision, DecisionMaker},
+        forward_worker::ForwardWorker,
         forwarder::Forwarder,
         latest_unprocessed_votes::{LatestUnprocessedVotes, VoteSource},
         leader_slot_metrics::LeaderSlotMetricsTracker,
+        packet_deserializer::PacketDeserializer,
         packet_receiver::PacketReceiver,
         qos_service::QosService,
+        transaction_scheduler::{
+            prio_graph_scheduler::PrioGraphScheduler, scheduler_controller::SchedulerController,
+            scheduler_error::SchedulerError,
+        },
         unprocessed_packet_batches::*,
         unprocessed_transaction_storage::{ThreadType, UnprocessedTransactionStorage},
     },
-    crate::{banking_trace::BankingPacketReceiver, tracer_packet_stats::TracerPacketStats},
-    crossbeam_channel::RecvTimeoutError,
+    crate::{
+        banking_trace::BankingPacketReceiver, tracer_packet_stats::TracerPacketStats,
+        validator::BlockProductionMethod,
+    },
+    crossbeam_channel::{unbounded, RecvTimeoutError},
     histogram::Histogram,
     solana_client::connection_cache::ConnectionCache,
     solana_gossip::cluster_info::ClusterInfo,
@@ -307,6 +317,7 @@ impl BankingStage {
     /// Create the stage using `bank`. Exit when `verified_receiver` is dropped.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        block_production_method: BlockProductionMethod,
         cluster_info: &Arc<ClusterInfo>,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         non_vote_receiver: BankingPacketReceiver,
@@ -320,6 +331,7 @@ impl BankingStage {
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
     ) -> Self {
         Self::new_num_threads(
+            block_production_method,
             cluster_info,
             poh_recorder,
             non_vote_receiver,
@@ -337,6 +349,56 @@ impl BankingStage {
 
     #[allow(clippy::too_many_arguments)]
     pub fn new_num_threads(
+        block_production_method: BlockProductionMethod,
+        cluster_info: &Arc<ClusterInfo>,
+        poh_recorder: &Arc<RwLock<PohRecorder>>,
+        non_vote_receiver: BankingPacketReceiver,
+        tpu_vote_receiver: BankingPacketReceiver,
+        gossip_vote_receiver: BankingPacketReceiver,
+        num_threads: u32,
+        transaction_status_sender: Option<TransactionStatusSender>,
+        replay_vote_sender: ReplayVoteSender,
+        log_messages_bytes_limit: Option<usize>,
+        connection_cache: Arc<ConnectionCache>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+    ) -> Self {
+        match block_production_method {
+            BlockProductionMethod::ThreadLocalMultiIterator => {
+                Self::new_thread_local_multi_iterator(
+                    cluster_info,
+                    poh_recorder,
+                    non_vote_receiver,
+                    tpu_vote_receiver,
+                    gossip_vote_receiver,
+                    num_threads,
+                    transaction_status_sender,
+                    replay_vote_sender,
+                    log_messages_bytes_limit,
+                    connection_cache,
+                    bank_forks,
+                    prioritization_fee_cache,
+                )
+            }
+            BlockProductionMethod::CentralScheduler => Self::new_central_scheduler(
+                cluster_info,
+                poh_recorder,
+                non_vote_receiver,
+                tpu_vote_receiver,
+                gossip_vote_receiver,
+                num_threads,
+                transaction_status_sender,
+                replay_vote_sender,
+                log_messages_bytes_limit,
+                connection_cache,
+                bank_forks,
+                prioritization_fee_cache,
+            ),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_thread_local_multi_iterator(
         cluster_info: &Arc<ClusterInfo>,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         non_vote_receiver: BankingPacketReceiver,
@@ -386,48 +448,213 @@ impl BankingStage {
                     ),
                 };
 
-                let mut packet_receiver =
-                    PacketReceiver::new(id, packet_receiver, bank_forks.clone());
-                let poh_recorder = poh_recorder.clone();
-
-                let committer = Committer::new(
+                Self::spawn_thread_local_multi_iterator_thread(
+                    id,
+                    packet_receiver,
+                    cluster_info,
+                    poh_recorder,
                     transaction_status_sender.clone(),
                     replay_vote_sender.clone(),
-                    prioritization_fee_cache.clone(),
-                );
-                let decision_maker = DecisionMaker::new(cluster_info.id(), poh_recorder.clone());
-                let forwarder = Forwarder::new(
-                    poh_recorder.clone(),
-                    bank_forks.clone(),
-                    cluster_info.clone(),
+                    log_messages_bytes_limit,
                     connection_cache.clone(),
+                    bank_forks.clone(),
+                    prioritization_fee_cache,
                     data_budget.clone(),
-                );
-                let consumer = Consumer::new(
-                    committer,
+                    unprocessed_transaction_storage,
+                )
+            })
+            .collect();
+        Self { bank_thread_hdls }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_central_scheduler(
+        cluster_info: &Arc<ClusterInfo>,
+        poh_recorder: &Arc<RwLock<PohRecorder>>,
+        non_vote_receiver: BankingPacketReceiver,
+        tpu_vote_receiver: BankingPacketReceiver,
+        gossip_vote_receiver: BankingPacketReceiver,
+        num_threads: u32,
+        transaction_status_sender: Option<TransactionStatusSender>,
+        replay_vote_sender: ReplayVoteSender,
+        log_messages_bytes_limit: Option<usize>,
+        connection_cache: Arc<ConnectionCache>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+    ) -> Self {
+        assert!(num_threads >= MIN_TOTAL_THREADS);
+        let data_budget = Arc::new(DataBudget::default());
+        // Keeps track of extraneous vote transactions for the vote threads
+        let latest_unprocessed_votes = Arc::new(LatestUnprocessedVotes::new());
+
+        // + 1 for the forward worker, + 1 for the central scheduler thread
+        let mut bank_thread_hdls = Vec::with_capacity(num_threads as usize + 2);
+
+        // Spawn legacy voting threads first: 1 gossip, 1 tpu
+        for (id, packet_receiver, vote_source) in [
+            (0, gossip_vote_receiver, VoteSource::Gossip),
+            (1, tpu_vote_receiver, VoteSource::Tpu),
+        ] {
+            bank_thread_hdls.push(Self::spawn_thread_local_multi_iterator_thread(
+                id,
+                packet_receiver,
+                cluster_info,
+                poh_recorder,
+                transaction_status_sender.clone(),
+                replay_vote_sender.clone(),
+                log_messages_bytes_limit,
+                connection_cache.clone(),
+                bank_forks.clone(),
+                prioritization_fee_cache,
+                data_budget.clone(),
+                UnprocessedTransactionStorage::new_vote_storage(
+                    latest_unprocessed_votes.clone(),
+                    vote_source,
+                ),
+            ));
+        }
+
+        // Create channels for communication between scheduler and workers
+        let num_workers = num_threads - NUM_VOTE_PROCESSING_THREADS;
+        let (finished_work_sender, finished_work_receiver) = unbounded();
+
+        // Spawn the worker threads
+        let mut work_senders = Vec::with_capacity(num_workers as usize);
+        for index in 0..num_workers {
+            let id = index + NUM_VOTE_PROCESSING_THREADS;
+            let (work_sender, work_receiver) = unbounded();
+            work_senders.push(work_sender);
+            let consume_worker = ConsumeWorker::new(
+                work_receiver,
+                Consumer::new(
+                    Committer::new(
+                        transaction_status_sender.clone(),
+                        replay_vote_sender.clone(),
+                        prioritization_fee_cache.clone(),
+                    ),
                     poh_recorder.read().unwrap().new_recorder(),
                     QosService::new(id),
                     log_messages_bytes_limit,
-                );
+                ),
+                finished_work_sender.clone(),
+                poh_recorder.read().unwrap().new_leader_bank_notifier(),
+            );
 
+            bank_thread_hdls.push(
                 Builder::new()
-                    .name(format!("solBanknStgTx{id:02}"))
+                    .name(format!("solCoWorker{id:02}"))
                     .spawn(move || {
-                        Self::process_loop(
-                            &mut packet_receiver,
-                            &decision_maker,
-                            &forwarder,
-                            &consumer,
-                            id,
-                            unprocessed_transaction_storage,
-                        );
+                        let _ = consume_worker.run();
                     })
-                    .unwrap()
-            })
-            .collect();
+                    .unwrap(),
+            )
+        }
+
+        // Spawn the forward worker thread
+        let (forward_work_sender, forward_work_receiver) = unbounded();
+        let (finished_forward_work_sender, finished_forward_work_receiver) = unbounded();
+        let forward_worker = ForwardWorker::new(
+            forward_work_receiver,
+            ForwardOption::ForwardTransaction,
+            Forwarder::new(
+                poh_recorder.clone(),
+                bank_forks.clone(),
+                cluster_info.clone(),
+                connection_cache,
+                data_budget,
+            ),
+            finished_forward_work_sender,
+        );
+        bank_thread_hdls.push(
+            Builder::new()
+                .name("solFwWorker".to_string())
+                .spawn(move || {
+                    let _ = forward_worker.run();
+                })
+                .unwrap(),
+        );
+
+        // Spawn the central scheduler thread
+        bank_thread_hdls.push({
+            let packet_deserializer =
+                PacketDeserializer::new(non_vote_receiver, bank_forks.clone());
+            let scheduler = PrioGraphScheduler::new(work_senders, finished_work_receiver);
+            let scheduler_controller = SchedulerController::new(
+                DecisionMaker::new(cluster_info.id(), poh_recorder.clone()),
+                packet_deserializer,
+                bank_forks,
+                scheduler,
+                forward_work_sender,
+                finished_forward_work_receiver,
+            );
+            Builder::new()
+                .name("solBnkTxSched".to_string())
+                .spawn(move || match scheduler_controller.run() {
+                    Ok(_) => {}
+                    Err(SchedulerError::DisconnectedRecvChannel(_)) => {}
+                    Err(SchedulerError::DisconnectedSendChannel(_)) => {
+                        warn!("Unexpected worker disconnect from scheduler")
+                    }
+                })
+                .unwrap()
+        });
+
         Self { bank_thread_hdls }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_thread_local_multi_iterator_thread(
+        id: u32,
+        packet_receiver: BankingPacketReceiver,
+        cluster_info: &Arc<ClusterInfo>,
+        poh_recorder: &Arc<RwLock<PohRecorder>>,
+        transaction_status_sender: Option<TransactionStatusSender>,
+        replay_vote_sender: ReplayVoteSender,
+        log_messages_bytes_limit: Option<usize>,
+        connection_cache: Arc<ConnectionCache>,
+        bank_forks: Arc<RwLock<BankForks>>,
+        prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        data_budget: Arc<DataBudget>,
+        unprocessed_transaction_storage: UnprocessedTransactionStorage,
+    ) -> JoinHandle<()> {
+        let mut packet_receiver = PacketReceiver::new(id, packet_receiver, bank_forks.clone());
+        let poh_recorder = poh_recorder.clone();
+
+        let committer = Committer::new(
+            transaction_status_sender,
+            replay_vote_sender,
+            prioritization_fee_cache.clone(),
+        );
+        let decision_maker = DecisionMaker::new(cluster_info.id(), poh_recorder.clone());
+        let forwarder = Forwarder::new(
+            poh_recorder.clone(),
+            bank_forks,
+            cluster_info.clone(),
+            connection_cache,
+            data_budget,
+        );
+        let consumer = Consumer::new(
+            committer,
+            poh_recorder.read().unwrap().new_recorder(),
+            QosService::new(id),
+            log_messages_bytes_limit,
+        );
+
+        Builder::new()
+            .name(format!("solBanknStgTx{id:02}"))
+            .spawn(move || {
+                Self::process_loop(
+                    &mut packet_receiver,
+                    &decision_maker,
+                    &forwarder,
+                    &consumer,
+                    id,
+                    unprocessed_transaction_storage,
+                );
+            })
+            .unwrap()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn process_buffered_packets(
         decision_maker: &DecisionMaker,
@@ -604,6 +831,7 @@ mod tests {
             sync::atomic::{AtomicBool, Ordering},
             thread::sleep,
         },
+        test_case::test_case,
     };
 
     pub(crate) fn new_test_cluster_info(keypair: Option<Arc<Keypair>>) -> (Node, ClusterInfo) {
@@ -620,8 +848,9 @@ mod tests {
             .collect()
     }
 
-    #[test]
-    fn test_banking_stage_shutdown1() {
+    #[test_case(BlockProductionMethod::ThreadLocalMultiIterator; "thread_local_multi_iterator")]
+    #[test_case(BlockProductionMethod::CentralScheduler; "central_scheduler")]
+    fn test_banking_stage_shutdown1(block_production_method: BlockProductionMethod) {
         let genesis_config = create_genesis_config(2).genesis_config;
         let bank = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
         let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
@@ -644,6 +873,7 @@ mod tests {
             let (replay_vote_sender, _replay_vote_receiver) = unbounded();
 
             let banking_stage = BankingStage::new(
+                block_production_method,
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -700,6 +930,7 @@ mod tests {
             let (replay_vote_sender, _replay_vote_receiver) = unbounded();
 
             let banking_stage = BankingStage::new(
+                BlockProductionMethod::ThreadLocalMultiIterator,
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -745,8 +976,9 @@ mod tests {
         with_vers.into_iter().map(|(b, _)| b).collect()
     }
 
-    #[test]
-    fn test_banking_stage_entries_only() {
+    #[test_case(BlockProductionMethod::ThreadLocalMultiIterator; "thread_local_multi_iterator")]
+    #[test_case(BlockProductionMethod::CentralScheduler; "central_scheduler")]
+    fn test_banking_stage_entries_only(block_production_method: BlockProductionMethod) {
         solana_logger::setup();
         let GenesisConfigInfo {
             genesis_config,
@@ -781,6 +1013,7 @@ mod tests {
             let (replay_vote_sender, _replay_vote_receiver) = unbounded();
 
             let banking_stage = BankingStage::new(
+                block_production_method,
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -942,6 +1175,7 @@ mod tests {
                 let (_, cluster_info) = new_test_cluster_info(/*keypair:*/ None);
                 let cluster_info = Arc::new(cluster_info);
                 let _banking_stage = BankingStage::new_num_threads(
+                    BlockProductionMethod::ThreadLocalMultiIterator,
                     &cluster_info,
                     &poh_recorder,
                     non_vote_receiver,
@@ -1133,6 +1367,7 @@ mod tests {
             let (replay_vote_sender, _replay_vote_receiver) = unbounded();
 
             let banking_stage = BankingStage::new(
+                BlockProductionMethod::ThreadLocalMultiIterator,
                 &cluster_info,
                 &poh_recorder,
                 non_vote_receiver,
@@ -59,6 +59,12 @@ impl Committer {
         self.transaction_status_sender.is_some()
     }
 
+    pub(super) fn instruction_trace_recording_enabled(&self) -> bool {
+        self.transaction_status_sender
+            .as_ref()
+            .map_or(false, |sender| sender.enable_instruction_trace_recording)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(super) fn commit_transactions(
         &self,
@@ -26,7 +26,6 @@ pub(crate) struct ConsumeWorker {
     leader_bank_notifier: Arc<LeaderBankNotifier>,
 }
 
-#[allow(dead_code)]
 impl ConsumeWorker {
     pub fn new(
         consume_receiver: Receiver<ConsumeWork>,
@@ -552,6 +552,7 @@ impl Consumer {
                 transaction_status_sender_enabled,
                 transaction_status_sender_enabled,
                 transaction_status_sender_enabled,
+                self.committer.instruction_trace_recording_enabled(),
                 &mut execute_and_commit_timings.execute_timings,
                 None, // account_overrides
                 self.log_messages_bytes_limit
@@ -1648,6 +1649,7 @@ mod tests {
             let committer = Committer::new(
                 Some(TransactionStatusSender {
                     sender: transaction_status_sender,
+                    enable_instruction_trace_recording: false,
                 }),
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
@@ -1785,6 +1787,7 @@ mod tests {
             let committer = Committer::new(
                 Some(TransactionStatusSender {
                     sender: transaction_status_sender,
+                    enable_instruction_trace_recording: false,
                 }),
                 replay_vote_sender,
                 Arc::new(PrioritizationFeeCache::new(0u64)),
@@ -23,7 +23,6 @@ pub(crate) struct ForwardWorker {
     forwarded_sender: Sender<FinishedForwardWork>,
 }
 
-#[allow(dead_code)]
 impl ForwardWorker {
     pub fn new(
         forward_receiver: Receiver<ForwardWork>,
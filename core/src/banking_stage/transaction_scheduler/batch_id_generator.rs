@@ -0,0 +1,14 @@
+use crate::banking_stage::scheduler_messages::TransactionBatchId;
+
+#[derive(Default)]
+pub struct BatchIdGenerator {
+    next_id: u64,
+}
+
+impl BatchIdGenerator {
+    pub fn next(&mut self) -> TransactionBatchId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        TransactionBatchId::new(id)
+    }
+}
@@ -0,0 +1,240 @@
+use {
+    super::transaction_priority_id::TransactionPriorityId,
+    crate::banking_stage::scheduler_messages::TransactionId,
+    solana_sdk::pubkey::Pubkey,
+    std::collections::{BinaryHeap, HashMap},
+};
+
+/// A directed acyclic graph of transactions, ordered by priority.
+///
+/// Transactions are expected to be inserted in descending priority order.
+/// An edge is created from each inserted transaction to every previously
+/// inserted transaction that it conflicts with, i.e. a transaction is
+/// *blocked* until all higher-priority conflicting transactions have been
+/// unblocked.
+///
+/// Only unblocked transactions are available to `pop`, so the graph never
+/// yields a transaction ahead of a higher-priority transaction that it
+/// conflicts with. This allows the scheduler to look-ahead past conflicting
+/// transactions without reordering them.
+pub(crate) struct PrioGraph {
+    /// Nodes for all transactions that have not yet been unblocked.
+    nodes: HashMap<TransactionId, GraphNode>,
+    /// Transactions that are not blocked by any other transaction.
+    main_queue: BinaryHeap<TransactionPriorityId>,
+    /// Most recent accesses for each account. Used to create edges on insertion.
+    account_accesses: HashMap<Pubkey, AccountAccess>,
+}
+
+struct GraphNode {
+    /// Lower-priority transactions which are blocked by this transaction.
+    edges: Vec<TransactionPriorityId>,
+    /// Number of higher-priority transactions which block this transaction.
+    blocked_by_count: usize,
+}
+
+#[derive(Default)]
+struct AccountAccess {
+    /// Most recent transaction that write-locks the account.
+    last_write: Option<TransactionId>,
+    /// Transactions that read-lock the account since `last_write`.
+    reads_since_last_write: Vec<TransactionId>,
+}
+
+impl PrioGraph {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            nodes: HashMap::with_capacity(capacity),
+            main_queue: BinaryHeap::with_capacity(capacity),
+            account_accesses: HashMap::new(),
+        }
+    }
+
+    /// Returns true if there are no unblocked transactions to pop.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.main_queue.is_empty()
+    }
+
+    /// Insert a transaction into the graph, creating edges from all
+    /// conflicting transactions that are still in the graph.
+    pub(crate) fn insert_transaction<'a>(
+        &mut self,
+        id: TransactionPriorityId,
+        write_account_locks: impl Iterator<Item = &'a Pubkey>,
+        read_account_locks: impl Iterator<Item = &'a Pubkey>,
+    ) {
+        let mut blocking_ids = Vec::new();
+        for account in write_account_locks {
+            let access = self.account_accesses.entry(*account).or_default();
+            blocking_ids.extend(access.last_write.replace(id.id));
+            blocking_ids.append(&mut access.reads_since_last_write);
+        }
+        for account in read_account_locks {
+            let access = self.account_accesses.entry(*account).or_default();
+            blocking_ids.extend(access.last_write);
+            access.reads_since_last_write.push(id.id);
+        }
+
+        let mut blocked_by_count = 0;
+        for blocking_id in blocking_ids {
+            // Transactions which have already been unblocked do not block.
+            let Some(blocking_node) = self.nodes.get_mut(&blocking_id) else {
+                continue;
+            };
+            // All edges for `id` are added in this call, so duplicates are adjacent.
+            if blocking_node.edges.last() != Some(&id) {
+                blocking_node.edges.push(id);
+                blocked_by_count += 1;
+            }
+        }
+
+        if blocked_by_count == 0 {
+            self.main_queue.push(id);
+        }
+        self.nodes.insert(
+            id.id,
+            GraphNode {
+                edges: Vec::new(),
+                blocked_by_count,
+            },
+        );
+    }
+
+    /// Pop the highest priority unblocked transaction.
+    /// The transaction continues to block lower-priority conflicting
+    /// transactions until `unblock` is called.
+    pub(crate) fn pop(&mut self) -> Option<TransactionPriorityId> {
+        self.main_queue.pop()
+    }
+
+    /// Remove a popped transaction from the graph, and unblock any
+    /// transactions which are no longer blocked by anything.
+    pub(crate) fn unblock(&mut self, id: &TransactionPriorityId) {
+        let Some(node) = self.nodes.remove(&id.id) else {
+            return;
+        };
+        for blocked_id in node.edges {
+            let blocked_node = self
+                .nodes
+                .get_mut(&blocked_id.id)
+                .expect("blocked transaction must be in graph");
+            blocked_node.blocked_by_count -= 1;
+            if blocked_node.blocked_by_count == 0 {
+                self.main_queue.push(blocked_id);
+            }
+        }
+    }
+
+    /// Pop the highest priority unblocked transaction and immediately unblock it.
+    /// Repeated calls drain the graph in an order consistent with priority and conflicts.
+    pub(crate) fn pop_and_unblock(&mut self) -> Option<TransactionPriorityId> {
+        let id = self.pop()?;
+        self.unblock(&id);
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priority_id(priority: u64) -> TransactionPriorityId {
+        TransactionPriorityId::new(priority, TransactionId::new(priority))
+    }
+
+    #[test]
+    fn test_prio_graph_no_conflicts() {
+        let mut prio_graph = PrioGraph::with_capacity(3);
+        for priority in [3, 2, 1] {
+            let account = Pubkey::new_unique();
+            prio_graph.insert_transaction(
+                priority_id(priority),
+                [account].iter(),
+                std::iter::empty(),
+            );
+        }
+
+        // all transactions are immediately available, in priority order
+        assert_eq!(prio_graph.pop(), Some(priority_id(3)));
+        assert_eq!(prio_graph.pop(), Some(priority_id(2)));
+        assert_eq!(prio_graph.pop(), Some(priority_id(1)));
+        assert_eq!(prio_graph.pop(), None);
+        assert!(prio_graph.is_empty());
+    }
+
+    #[test]
+    fn test_prio_graph_write_conflicts() {
+        let mut prio_graph = PrioGraph::with_capacity(3);
+        let account = Pubkey::new_unique();
+        let other_account = Pubkey::new_unique();
+        prio_graph.insert_transaction(priority_id(3), [account].iter(), std::iter::empty());
+        prio_graph.insert_transaction(priority_id(2), [account].iter(), std::iter::empty());
+        prio_graph.insert_transaction(priority_id(1), [other_account].iter(), std::iter::empty());
+
+        // 2 is blocked by 3, but 1 is not blocked
+        assert_eq!(prio_graph.pop(), Some(priority_id(3)));
+        assert_eq!(prio_graph.pop(), Some(priority_id(1)));
+        assert_eq!(prio_graph.pop(), None);
+        assert!(prio_graph.is_empty());
+
+        prio_graph.unblock(&priority_id(3));
+        assert_eq!(prio_graph.pop(), Some(priority_id(2)));
+    }
+
+    #[test]
+    fn test_prio_graph_read_conflicts() {
+        let mut prio_graph = PrioGraph::with_capacity(4);
+        let account = Pubkey::new_unique();
+        prio_graph.insert_transaction(priority_id(4), std::iter::empty(), [account].iter());
+        prio_graph.insert_transaction(priority_id(3), std::iter::empty(), [account].iter());
+        prio_graph.insert_transaction(priority_id(2), [account].iter(), std::iter::empty());
+        prio_graph.insert_transaction(priority_id(1), std::iter::empty(), [account].iter());
+
+        // reads do not block each other
+        assert_eq!(prio_graph.pop(), Some(priority_id(4)));
+        assert_eq!(prio_graph.pop(), Some(priority_id(3)));
+        assert_eq!(prio_graph.pop(), None);
+
+        // write is blocked by both reads
+        prio_graph.unblock(&priority_id(4));
+        assert_eq!(prio_graph.pop(), None);
+        prio_graph.unblock(&priority_id(3));
+        assert_eq!(prio_graph.pop(), Some(priority_id(2)));
+        assert_eq!(prio_graph.pop(), None);
+
+        // read is blocked by the write
+        prio_graph.unblock(&priority_id(2));
+        assert_eq!(prio_graph.pop(), Some(priority_id(1)));
+    }
+
+    #[test]
+    fn test_prio_graph_pop_and_unblock() {
+        let mut prio_graph = PrioGraph::with_capacity(3);
+        let account = Pubkey::new_unique();
+        for priority in [3, 2, 1] {
+            prio_graph.insert_transaction(
+                priority_id(priority),
+                [account].iter(),
+                std::iter::empty(),
+            );
+        }
+
+        let drained: Vec<_> = std::iter::from_fn(|| prio_graph.pop_and_unblock()).collect();
+        assert_eq!(
+            drained,
+            vec![priority_id(3), priority_id(2), priority_id(1)]
+        );
+    }
+
+    #[test]
+    fn test_prio_graph_insert_after_unblock() {
+        let mut prio_graph = PrioGraph::with_capacity(2);
+        let account = Pubkey::new_unique();
+        prio_graph.insert_transaction(priority_id(2), [account].iter(), std::iter::empty());
+        assert_eq!(prio_graph.pop_and_unblock(), Some(priority_id(2)));
+
+        // unblocked transactions no longer block newly inserted transactions
+        prio_graph.insert_transaction(priority_id(1), [account].iter(), std::iter::empty());
+        assert_eq!(prio_graph.pop(), Some(priority_id(1)));
+    }
+}
@@ -0,0 +1,691 @@
+use {
+    super::{
+        in_flight_tracker::InFlightTracker,
+        prio_graph::PrioGraph,
+        scheduler_error::SchedulerError,
+        thread_aware_account_locks::{ThreadAwareAccountLocks, ThreadId, ThreadSet},
+        transaction_priority_id::TransactionPriorityId,
+        transaction_state::SanitizedTransactionTTL,
+        transaction_state_container::TransactionStateContainer,
+    },
+    crate::banking_stage::{
+        consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
+        read_write_account_set::ReadWriteAccountSet,
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId, TransactionId},
+    },
+    crossbeam_channel::{Receiver, Sender, TryRecvError},
+    itertools::izip,
+    solana_sdk::{clock::Slot, saturating_add_assign, transaction::SanitizedTransaction},
+};
+
+/// Number of transactions to keep in the look-ahead window of the `PrioGraph`.
+/// This only needs to be large enough to give the scheduler a reasonable idea
+/// of whether a transaction will conflict with an upcoming transaction.
+const LOOK_AHEAD_WINDOW_SIZE: usize = 2048;
+
+/// Maximum number of transactions to schedule in a single call to `schedule`.
+const MAX_TRANSACTIONS_PER_SCHEDULING_PASS: usize = 100_000;
+
+/// Maximum number of transactions that may be in-flight on a single thread.
+/// Threads at this limit are not scheduled to until some work completes.
+const QUEUED_TRANSACTION_LIMIT: usize = 64 * 100;
+
+pub(crate) struct PrioGraphScheduler {
+    in_flight_tracker: InFlightTracker,
+    account_locks: ThreadAwareAccountLocks,
+    consume_work_senders: Vec<Sender<ConsumeWork>>,
+    finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+}
+
+impl PrioGraphScheduler {
+    pub(crate) fn new(
+        consume_work_senders: Vec<Sender<ConsumeWork>>,
+        finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+    ) -> Self {
+        let num_threads = consume_work_senders.len();
+        Self {
+            in_flight_tracker: InFlightTracker::new(num_threads),
+            account_locks: ThreadAwareAccountLocks::new(num_threads),
+            consume_work_senders,
+            finished_consume_work_receiver,
+        }
+    }
+
+    /// Schedule transactions from the given `TransactionStateContainer` to be consumed by the
+    /// worker threads. Returns summary of scheduling, or an error.
+    ///
+    /// Uses a `PrioGraph` to perform look-ahead during the scheduling of transactions.
+    /// This, combined with internal tracking of threads' in-flight transactions, allows
+    /// for load-balancing while prioritizing scheduling transactions onto threads that will
+    /// not cause conflicts in the near future.
+    pub(crate) fn schedule(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<SchedulingSummary, SchedulerError> {
+        let num_threads = self.consume_work_senders.len();
+        let mut batches = Batches::new(num_threads);
+
+        // Threads with too much outstanding work are not schedulable.
+        let mut schedulable_threads = ThreadSet::any(num_threads);
+        for (thread_id, num_in_flight) in self
+            .in_flight_tracker
+            .num_in_flight_per_thread()
+            .iter()
+            .enumerate()
+        {
+            if *num_in_flight >= QUEUED_TRANSACTION_LIMIT {
+                schedulable_threads.remove(thread_id);
+            }
+        }
+        if schedulable_threads.is_empty() {
+            return Ok(SchedulingSummary::default());
+        }
+
+        // Some transactions may be unschedulable due to multi-thread conflicts.
+        // These transactions cannot be scheduled until some conflicting work is completed.
+        // However, the scheduler should not allow other transactions that conflict with
+        // these transactions to be scheduled before them.
+        let mut unschedulable_ids = Vec::new();
+        let mut blocking_locks = ReadWriteAccountSet::default();
+
+        // Create the initial look-ahead window.
+        let mut prio_graph = PrioGraph::with_capacity(LOOK_AHEAD_WINDOW_SIZE);
+        for _ in 0..LOOK_AHEAD_WINDOW_SIZE {
+            if !Self::insert_next_into_graph(container, &mut prio_graph) {
+                break;
+            }
+        }
+
+        let mut unblock_this_batch =
+            Vec::with_capacity(num_threads * TARGET_NUM_TRANSACTIONS_PER_BATCH);
+        let mut num_scheduled: usize = 0;
+        let mut num_sent: usize = 0;
+        while num_scheduled < MAX_TRANSACTIONS_PER_SCHEDULING_PASS {
+            // If nothing is unblocked in the `PrioGraph`, there is nothing left to schedule.
+            if prio_graph.is_empty() {
+                break;
+            }
+
+            while let Some(id) = prio_graph.pop() {
+                unblock_this_batch.push(id);
+
+                // Keep the look-ahead window full.
+                Self::insert_next_into_graph(container, &mut prio_graph);
+
+                // Should always be in the container, but can just skip if it is not for some reason.
+                let Some(transaction_state) = container.get_mut_transaction_state(&id.id) else {
+                    continue;
+                };
+
+                let transaction = &transaction_state.transaction_ttl().transaction;
+
+                // Check if this transaction conflicts with any blocked transactions.
+                if !blocking_locks.check_sanitized_message_account_locks(transaction.message()) {
+                    blocking_locks.add_sanitized_message_account_locks(transaction.message());
+                    unschedulable_ids.push(id);
+                    continue;
+                }
+
+                // Schedule the transaction if it can be.
+                let transaction_locks = transaction.get_account_locks_unchecked();
+                let Some(thread_id) = self.account_locks.try_lock_accounts(
+                    transaction_locks.writable.into_iter(),
+                    transaction_locks.readonly.into_iter(),
+                    schedulable_threads,
+                    |thread_set| {
+                        Self::select_thread(
+                            thread_set,
+                            &batches.transactions,
+                            self.in_flight_tracker.num_in_flight_per_thread(),
+                        )
+                    },
+                ) else {
+                    blocking_locks.add_sanitized_message_account_locks(transaction.message());
+                    unschedulable_ids.push(id);
+                    continue;
+                };
+
+                saturating_add_assign!(num_scheduled, 1);
+
+                let SanitizedTransactionTTL {
+                    transaction,
+                    max_age_slot,
+                } = transaction_state.transition_to_pending();
+
+                batches.transactions[thread_id].push(transaction);
+                batches.ids[thread_id].push(id.id);
+                batches.max_age_slots[thread_id].push(max_age_slot);
+
+                // If target batch size is reached, send only this batch.
+                if batches.ids[thread_id].len() >= TARGET_NUM_TRANSACTIONS_PER_BATCH {
+                    saturating_add_assign!(num_sent, self.send_batch(&mut batches, thread_id)?);
+                    if self.in_flight_tracker.num_in_flight_per_thread()[thread_id]
+                        >= QUEUED_TRANSACTION_LIMIT
+                    {
+                        schedulable_threads.remove(thread_id);
+                        if schedulable_threads.is_empty() {
+                            break;
+                        }
+                    }
+                }
+
+                if num_scheduled >= MAX_TRANSACTIONS_PER_SCHEDULING_PASS {
+                    break;
+                }
+            }
+
+            // Send all non-empty batches
+            saturating_add_assign!(num_sent, self.send_batches(&mut batches)?);
+
+            // Unblock all transactions that were blocked by the transactions that were just sent.
+            for id in unblock_this_batch.drain(..) {
+                prio_graph.unblock(&id);
+            }
+
+            if schedulable_threads.is_empty() {
+                break;
+            }
+        }
+
+        // Send batches for any remaining transactions
+        saturating_add_assign!(num_sent, self.send_batches(&mut batches)?);
+
+        // Push unschedulable ids back into the container
+        let num_unschedulable = unschedulable_ids.len();
+        for id in unschedulable_ids {
+            container.push_id_into_queue(id);
+        }
+
+        // Push remaining transactions back into the container
+        while let Some(id) = prio_graph.pop_and_unblock() {
+            container.push_id_into_queue(id);
+        }
+
+        assert_eq!(
+            num_scheduled, num_sent,
+            "number of scheduled and sent transactions must match"
+        );
+
+        Ok(SchedulingSummary {
+            num_scheduled,
+            num_unschedulable,
+        })
+    }
+
+    /// Receive completed batches of transactions without blocking.
+    /// Returns (num_transactions, num_retryable_transactions) on success.
+    pub(crate) fn receive_completed(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<(usize, usize), SchedulerError> {
+        let mut total_num_transactions: usize = 0;
+        let mut total_num_retryable: usize = 0;
+        loop {
+            let (num_transactions, num_retryable) = self.try_receive_completed(container)?;
+            if num_transactions == 0 {
+                break;
+            }
+            saturating_add_assign!(total_num_transactions, num_transactions);
+            saturating_add_assign!(total_num_retryable, num_retryable);
+        }
+        Ok((total_num_transactions, total_num_retryable))
+    }
+
+    /// Receive completed batches of transactions.
+    /// Returns `Ok((num_transactions, num_retryable))` if a batch was received, `Ok((0, 0))` if no batch was received.
+    fn try_receive_completed(
+        &mut self,
+        container: &mut TransactionStateContainer,
+    ) -> Result<(usize, usize), SchedulerError> {
+        match self.finished_consume_work_receiver.try_recv() {
+            Ok(FinishedConsumeWork {
+                work:
+                    ConsumeWork {
+                        batch_id,
+                        ids,
+                        transactions,
+                        max_age_slots,
+                    },
+                retryable_indexes,
+            }) => {
+                let num_transactions = ids.len();
+                let num_retryable = retryable_indexes.len();
+
+                // Free the locks
+                self.complete_batch(batch_id, &transactions);
+
+                // Retryable transactions should be inserted back into the container
+                let mut retryable_iter = retryable_indexes.into_iter().peekable();
+                for (index, (id, transaction, max_age_slot)) in
+                    izip!(ids, transactions, max_age_slots).enumerate()
+                {
+                    if let Some(retryable_index) = retryable_iter.peek() {
+                        if *retryable_index == index {
+                            container.retry_transaction(
+                                id,
+                                SanitizedTransactionTTL {
+                                    transaction,
+                                    max_age_slot,
+                                },
+                            );
+                            retryable_iter.next();
+                            continue;
+                        }
+                    }
+                    container.remove_by_id(&id);
+                }
+
+                Ok((num_transactions, num_retryable))
+            }
+            Err(TryRecvError::Empty) => Ok((0, 0)),
+            Err(TryRecvError::Disconnected) => Err(SchedulerError::DisconnectedRecvChannel(
+                "finished consume work",
+            )),
+        }
+    }
+
+    /// Mark a given `TransactionBatchId` as completed.
+    /// This will update the internal tracking, including account locks.
+    fn complete_batch(
+        &mut self,
+        batch_id: TransactionBatchId,
+        transactions: &[SanitizedTransaction],
+    ) {
+        let thread_id = self.in_flight_tracker.complete_batch(batch_id);
+        for transaction in transactions {
+            let account_locks = transaction.get_account_locks_unchecked();
+            self.account_locks.unlock_accounts(
+                account_locks.writable.into_iter(),
+                account_locks.readonly.into_iter(),
+                thread_id,
+            );
+        }
+    }
+
+    /// Send all batches of transactions to the worker threads.
+    /// Returns the number of transactions sent.
+    fn send_batches(&mut self, batches: &mut Batches) -> Result<usize, SchedulerError> {
+        (0..self.consume_work_senders.len())
+            .map(|thread_index| self.send_batch(batches, thread_index))
+            .sum()
+    }
+
+    /// Send a batch of transactions to the given thread's `ConsumeWork` channel.
+    /// Returns the number of transactions sent.
+    fn send_batch(
+        &mut self,
+        batches: &mut Batches,
+        thread_index: usize,
+    ) -> Result<usize, SchedulerError> {
+        if batches.ids[thread_index].is_empty() {
+            return Ok(0);
+        }
+
+        let (ids, transactions, max_age_slots) = batches.take_batch(thread_index);
+
+        let batch_id = self.in_flight_tracker.track_batch(ids.len(), thread_index);
+
+        let num_scheduled = ids.len();
+        let work = ConsumeWork {
+            batch_id,
+            ids,
+            transactions,
+            max_age_slots,
+        };
+        self.consume_work_senders[thread_index]
+            .send(work)
+            .map_err(|_| SchedulerError::DisconnectedSendChannel("consume work sender"))?;
+
+        Ok(num_scheduled)
+    }
+
+    /// Pop the next transaction from the container and insert it into the graph.
+    /// Returns false if the container is empty.
+    fn insert_next_into_graph(
+        container: &mut TransactionStateContainer,
+        prio_graph: &mut PrioGraph,
+    ) -> bool {
+        let Some(id) = container.pop() else {
+            return false;
+        };
+        let Some(transaction_ttl) = container.get_transaction_ttl(&id.id) else {
+            return true;
+        };
+        let account_locks = transaction_ttl.transaction.get_account_locks_unchecked();
+        prio_graph.insert_transaction(
+            id,
+            account_locks.writable.into_iter(),
+            account_locks.readonly.into_iter(),
+        );
+        true
+    }
+
+    /// Given the schedulable `thread_set`, select the thread with the least amount
+    /// of work queued up.
+    /// Currently, "work" is just defined as the number of transactions.
+    fn select_thread(
+        thread_set: ThreadSet,
+        batches_per_thread: &[Vec<SanitizedTransaction>],
+        in_flight_per_thread: &[usize],
+    ) -> ThreadId {
+        thread_set
+            .contained_threads_iter()
+            .map(|thread_id| {
+                (
+                    thread_id,
+                    batches_per_thread[thread_id].len() + in_flight_per_thread[thread_id],
+                )
+            })
+            .min_by(|a, b| a.1.cmp(&b.1))
+            .map(|(thread_id, _)| thread_id)
+            .unwrap()
+    }
+}
+
+/// Metrics from scheduling transactions.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct SchedulingSummary {
+    /// Number of transactions scheduled.
+    pub num_scheduled: usize,
+    /// Number of transactions that were not scheduled due to conflicts.
+    pub num_unschedulable: usize,
+}
+
+struct Batches {
+    ids: Vec<Vec<TransactionId>>,
+    transactions: Vec<Vec<SanitizedTransaction>>,
+    max_age_slots: Vec<Vec<Slot>>,
+}
+
+impl Batches {
+    fn new(num_threads: usize) -> Self {
+        Self {
+            ids: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
+            transactions: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
+            max_age_slots: vec![Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH); num_threads],
+        }
+    }
+
+    fn take_batch(
+        &mut self,
+        thread_id: ThreadId,
+    ) -> (Vec<TransactionId>, Vec<SanitizedTransaction>, Vec<Slot>) {
+        (
+            core::mem::replace(
+                &mut self.ids[thread_id],
+                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
+            ),
+            core::mem::replace(
+                &mut self.transactions[thread_id],
+                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
+            ),
+            core::mem::replace(
+                &mut self.max_age_slots[thread_id],
+                Vec::with_capacity(TARGET_NUM_TRANSACTIONS_PER_BATCH),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_stage::immutable_deserialized_packet::ImmutableDeserializedPacket,
+        crossbeam_channel::{unbounded, Receiver},
+        itertools::Itertools,
+        solana_perf::packet::Packet,
+        solana_runtime::transaction_priority_details::TransactionPriorityDetails,
+        solana_sdk::{
+            compute_budget::ComputeBudgetInstruction, hash::Hash, message::Message, pubkey::Pubkey,
+            signature::Keypair, signer::Signer, system_instruction, transaction::Transaction,
+        },
+        std::sync::Arc,
+    };
+
+    macro_rules! txid {
+        ($value:expr) => {
+            TransactionId::new($value)
+        };
+    }
+
+    macro_rules! txids {
+        ([$($element:expr),*]) => {
+            vec![ $(txid!($element)),* ]
+        };
+    }
+
+    fn create_test_frame(
+        num_threads: usize,
+    ) -> (
+        PrioGraphScheduler,
+        Vec<Receiver<ConsumeWork>>,
+        Sender<FinishedConsumeWork>,
+    ) {
+        let (consume_work_senders, consume_work_receivers) =
+            (0..num_threads).map(|_| unbounded()).unzip();
+        let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
+        let scheduler =
+            PrioGraphScheduler::new(consume_work_senders, finished_consume_work_receiver);
+        (
+            scheduler,
+            consume_work_receivers,
+            finished_consume_work_sender,
+        )
+    }
+
+    fn prioritized_transfers(
+        from_keypair: &Keypair,
+        to_pubkeys: &[Pubkey],
+        lamports: u64,
+        priority: u64,
+    ) -> Transaction {
+        let to_pubkeys_lamports = to_pubkeys
+            .iter()
+            .map(|pubkey| (*pubkey, lamports))
+            .collect_vec();
+        let mut ixs =
+            system_instruction::transfer_many(&from_keypair.pubkey(), &to_pubkeys_lamports);
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority));
+        let message = Message::new(&ixs, Some(&from_keypair.pubkey()));
+        Transaction::new(&[from_keypair], message, Hash::default())
+    }
+
+    fn create_container<'a>(
+        tx_infos: impl IntoIterator<Item = (&'a Keypair, &'a [Pubkey], u64, u64)>,
+    ) -> TransactionStateContainer {
+        let mut container = TransactionStateContainer::with_capacity(10 * 1024);
+        for (index, (from_keypair, to_pubkeys, lamports, priority)) in
+            tx_infos.into_iter().enumerate()
+        {
+            let id = TransactionId::new(index as u64);
+            let transaction = prioritized_transfers(from_keypair, to_pubkeys, lamports, priority);
+            let packet = Arc::new(
+                ImmutableDeserializedPacket::new(Packet::from_data(None, &transaction).unwrap())
+                    .unwrap(),
+            );
+            let transaction_ttl = SanitizedTransactionTTL {
+                transaction: SanitizedTransaction::from_transaction_for_tests(transaction),
+                max_age_slot: Slot::MAX,
+            };
+            container.insert_new_transaction(
+                id,
+                transaction_ttl,
+                packet,
+                TransactionPriorityDetails {
+                    priority,
+                    compute_unit_limit: 0,
+                },
+            );
+        }
+
+        container
+    }
+
+    fn collect_work(
+        receiver: &Receiver<ConsumeWork>,
+    ) -> (Vec<ConsumeWork>, Vec<Vec<TransactionId>>) {
+        receiver
+            .try_iter()
+            .map(|work| {
+                let ids = work.ids.clone();
+                (work, ids)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_schedule_disconnected_channel() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let keypair = Keypair::new();
+        let mut container = create_container([(&keypair, &[Pubkey::new_unique()][..], 1, 1)]);
+
+        drop(work_receivers); // explicitly drop receivers
+        assert_matches!(
+            scheduler.schedule(&mut container),
+            Err(SchedulerError::DisconnectedSendChannel(_))
+        );
+    }
+
+    #[test]
+    fn test_schedule_single_threaded_no_conflicts() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let (keypair_a, keypair_b) = (Keypair::new(), Keypair::new());
+        let mut container = create_container([
+            (&keypair_a, &[Pubkey::new_unique()][..], 1, 1),
+            (&keypair_b, &[Pubkey::new_unique()][..], 2, 2),
+        ]);
+
+        let scheduling_summary = scheduler.schedule(&mut container).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(collect_work(&work_receivers[0]).1, vec![txids!([1, 0])]);
+    }
+
+    #[test]
+    fn test_schedule_single_threaded_conflict() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let (keypair_a, keypair_b) = (Keypair::new(), Keypair::new());
+        let pubkey = Pubkey::new_unique();
+        let mut container = create_container([
+            (&keypair_a, &[pubkey][..], 1, 1),
+            (&keypair_b, &[pubkey][..], 1, 2),
+        ]);
+
+        // conflicting transactions are never in the same batch
+        let scheduling_summary = scheduler.schedule(&mut container).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(
+            collect_work(&work_receivers[0]).1,
+            vec![txids!([1]), txids!([0])]
+        );
+    }
+
+    #[test]
+    fn test_schedule_consume_single_threaded_multi_batch() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let keypairs = (0..4 * TARGET_NUM_TRANSACTIONS_PER_BATCH)
+            .map(|_| Keypair::new())
+            .collect_vec();
+        let to_pubkeys = (0..4 * TARGET_NUM_TRANSACTIONS_PER_BATCH)
+            .map(|_| [Pubkey::new_unique()])
+            .collect_vec();
+        let mut container = create_container(
+            keypairs
+                .iter()
+                .zip(to_pubkeys.iter())
+                .map(|(keypair, to_pubkeys)| (keypair, &to_pubkeys[..], 1, 1)),
+        );
+
+        // expect 4 full batches to be scheduled
+        let scheduling_summary = scheduler.schedule(&mut container).unwrap();
+        assert_eq!(
+            scheduling_summary.num_scheduled,
+            4 * TARGET_NUM_TRANSACTIONS_PER_BATCH
+        );
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+
+        let thread0_work_counts: Vec<_> = work_receivers[0]
+            .try_iter()
+            .map(|work| work.ids.len())
+            .collect();
+        assert_eq!(thread0_work_counts, [TARGET_NUM_TRANSACTIONS_PER_BATCH; 4]);
+    }
+
+    #[test]
+    fn test_schedule_simple_thread_selection() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(2);
+        let keypairs = (0..4).map(|_| Keypair::new()).collect_vec();
+        let to_pubkeys = (0..4).map(|_| [Pubkey::new_unique()]).collect_vec();
+        let mut container =
+            create_container(
+                keypairs.iter().zip(to_pubkeys.iter()).enumerate().map(
+                    |(index, (keypair, to_pubkeys))| (keypair, &to_pubkeys[..], 1, index as u64),
+                ),
+            );
+
+        let scheduling_summary = scheduler.schedule(&mut container).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 4);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(collect_work(&work_receivers[0]).1, [txids!([3, 1])]);
+        assert_eq!(collect_work(&work_receivers[1]).1, [txids!([2, 0])]);
+    }
+
+    #[test]
+    fn test_schedule_priority_guard() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(2);
+        let accounts = (0..5).map(|_| Keypair::new()).collect_vec();
+        let pubkeys = accounts
+            .iter()
+            .map(|keypair| keypair.pubkey())
+            .collect_vec();
+        let mut container = create_container([
+            (&accounts[0], &pubkeys[1..2], 1, 4),
+            (&accounts[2], &pubkeys[3..4], 1, 3),
+            (&accounts[1], &pubkeys[2..3], 1, 2),
+            (&accounts[4], &pubkeys[2..3], 1, 1),
+        ]);
+
+        // The first two transactions are scheduled on separate threads.
+        // The third conflicts with both threads and cannot be scheduled.
+        // The fourth could be scheduled on thread 1, but would then be processed
+        // ahead of the higher priority third transaction.
+        let scheduling_summary = scheduler.schedule(&mut container).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_unschedulable, 2);
+        assert_eq!(collect_work(&work_receivers[0]).1, [txids!([0])]);
+        assert_eq!(collect_work(&work_receivers[1]).1, [txids!([1])]);
+        assert_eq!(container.len(), 2);
+    }
+
+    #[test]
+    fn test_receive_completed() {
+        let (mut scheduler, work_receivers, finished_work_sender) = create_test_frame(1);
+        let (keypair_a, keypair_b) = (Keypair::new(), Keypair::new());
+        let mut container = create_container([
+            (&keypair_a, &[Pubkey::new_unique()][..], 1, 1),
+            (&keypair_b, &[Pubkey::new_unique()][..], 2, 2),
+        ]);
+
+        let scheduling_summary = scheduler.schedule(&mut container).unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert!(container.is_empty());
+
+        // mark the second transaction in the batch as retryable
+        let (mut work, _ids) = collect_work(&work_receivers[0]);
+        finished_work_sender
+            .send(FinishedConsumeWork {
+                work: work.remove(0),
+                retryable_indexes: vec![1],
+            })
+            .unwrap();
+        assert_eq!(scheduler.receive_completed(&mut container).unwrap(), (2, 1));
+        assert_eq!(container.len(), 1);
+        assert_eq!(
+            container.pop().map(|priority_id| priority_id.id),
+            Some(txid!(0))
+        );
+        assert_eq!(scheduler.in_flight_tracker.num_in_flight_per_thread(), &[0]);
+    }
+}
@@ -0,0 +1,828 @@
+//! Control flow for BankingStage's transaction scheduler.
+
+use {
+    super::{
+        prio_graph_scheduler::PrioGraphScheduler, scheduler_error::SchedulerError,
+        transaction_id_generator::TransactionIdGenerator,
+        transaction_state::SanitizedTransactionTTL,
+        transaction_state_container::TransactionStateContainer,
+    },
+    crate::banking_stage::{
+        decision_maker::{BufferedPacketsDecision, DecisionMaker},
+        immutable_deserialized_packet::ImmutableDeserializedPacket,
+        packet_deserializer::PacketDeserializer,
+        scheduler_messages::{FinishedForwardWork, ForwardWork, TransactionId},
+        TOTAL_BUFFERED_PACKETS,
+    },
+    crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TryRecvError},
+    solana_accounts_db::transaction_error_metrics::TransactionErrorMetrics,
+    solana_measure::measure_us,
+    solana_runtime::{
+        bank_forks::BankForks, transaction_priority_details::TransactionPriorityDetails,
+    },
+    solana_sdk::{clock::MAX_PROCESSING_AGE, saturating_add_assign, timing::AtomicInterval},
+    std::{
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+};
+
+/// Maximum number of packets sent to the forward worker in a single batch.
+const FORWARD_BATCH_SIZE: usize = 128;
+/// Number of transactions checked against the bank at once when cleaning the queue.
+const CLEAN_BATCH_SIZE: usize = 128;
+
+/// Controls packet and transaction flow into scheduler, and scheduling execution.
+pub(crate) struct SchedulerController {
+    /// Decision maker for determining what should be done with transactions.
+    decision_maker: DecisionMaker,
+    /// Packet/Transaction ingress.
+    packet_receiver: PacketDeserializer,
+    bank_forks: Arc<RwLock<BankForks>>,
+    /// Generates unique IDs for incoming transactions.
+    transaction_id_generator: TransactionIdGenerator,
+    /// Container for transaction state.
+    /// Shared resource between `packet_receiver` and `scheduler`.
+    container: TransactionStateContainer,
+    /// State for scheduling and communicating with consume worker threads.
+    scheduler: PrioGraphScheduler,
+    /// Channel for sending packets to the forward worker.
+    forward_work_sender: Sender<ForwardWork>,
+    /// Channel for receiving completed forwarding work.
+    finished_forward_work_receiver: Receiver<FinishedForwardWork>,
+    /// Metrics tracking counts on transactions in different states.
+    count_metrics: SchedulerCountMetrics,
+    /// Metrics tracking time spent in different code sections.
+    timing_metrics: SchedulerTimingMetrics,
+}
+
+impl SchedulerController {
+    pub(crate) fn new(
+        decision_maker: DecisionMaker,
+        packet_deserializer: PacketDeserializer,
+        bank_forks: Arc<RwLock<BankForks>>,
+        scheduler: PrioGraphScheduler,
+        forward_work_sender: Sender<ForwardWork>,
+        finished_forward_work_receiver: Receiver<FinishedForwardWork>,
+    ) -> Self {
+        Self {
+            decision_maker,
+            packet_receiver: packet_deserializer,
+            bank_forks,
+            transaction_id_generator: TransactionIdGenerator::default(),
+            container: TransactionStateContainer::with_capacity(TOTAL_BUFFERED_PACKETS),
+            scheduler,
+            forward_work_sender,
+            finished_forward_work_receiver,
+            count_metrics: SchedulerCountMetrics::default(),
+            timing_metrics: SchedulerTimingMetrics::default(),
+        }
+    }
+
+    pub(crate) fn run(mut self) -> Result<(), SchedulerError> {
+        loop {
+            let (decision, decision_time_us) =
+                measure_us!(self.decision_maker.make_consume_or_forward_decision());
+            saturating_add_assign!(self.timing_metrics.decision_time_us, decision_time_us);
+
+            self.process_transactions(&decision)?;
+            self.receive_completed()?;
+            if !self.receive_and_buffer_packets() {
+                break;
+            }
+
+            // Report metrics only if there is data.
+            // Reset intervals when appropriate, regardless of report.
+            let should_report = self.count_metrics.has_data();
+            self.count_metrics.maybe_report_and_reset(should_report);
+            self.timing_metrics.maybe_report_and_reset(should_report);
+        }
+
+        Ok(())
+    }
+
+    /// Process packets based on decision.
+    fn process_transactions(
+        &mut self,
+        decision: &BufferedPacketsDecision,
+    ) -> Result<(), SchedulerError> {
+        match decision {
+            BufferedPacketsDecision::Consume(_bank_start) => {
+                let (scheduling_summary, schedule_time_us) =
+                    measure_us!(self.scheduler.schedule(&mut self.container)?);
+                saturating_add_assign!(
+                    self.count_metrics.num_scheduled,
+                    scheduling_summary.num_scheduled
+                );
+                saturating_add_assign!(
+                    self.count_metrics.num_unschedulable,
+                    scheduling_summary.num_unschedulable
+                );
+                saturating_add_assign!(self.timing_metrics.schedule_time_us, schedule_time_us);
+            }
+            BufferedPacketsDecision::Forward => {
+                let (_, forward_time_us) = measure_us!(self.forward_packets(false)?);
+                saturating_add_assign!(self.timing_metrics.forward_time_us, forward_time_us);
+            }
+            BufferedPacketsDecision::ForwardAndHold => {
+                let (_, clean_time_us) = measure_us!(self.clean_queue());
+                saturating_add_assign!(self.timing_metrics.clean_time_us, clean_time_us);
+                let (_, forward_time_us) = measure_us!(self.forward_packets(true)?);
+                saturating_add_assign!(self.timing_metrics.forward_time_us, forward_time_us);
+            }
+            BufferedPacketsDecision::Hold => {}
+        }
+
+        Ok(())
+    }
+
+    /// Send all unforwarded packets in the container to the forward worker.
+    /// If `hold` is false, all transactions in the queue are removed from the container.
+    fn forward_packets(&mut self, hold: bool) -> Result<(), SchedulerError> {
+        let mut ids = Vec::with_capacity(FORWARD_BATCH_SIZE);
+        let mut packets = Vec::with_capacity(FORWARD_BATCH_SIZE);
+        for priority_id in self.container.priority_ordered_ids(hold) {
+            let Some(transaction_state) = self.container.get_mut_transaction_state(&priority_id.id)
+            else {
+                continue;
+            };
+
+            if !transaction_state.forwarded() {
+                transaction_state.set_forwarded();
+                ids.push(priority_id.id);
+                packets.push(transaction_state.packet().clone());
+            }
+            if !hold {
+                self.container.remove_by_id(&priority_id.id);
+                saturating_add_assign!(self.count_metrics.num_dropped_on_forward, 1);
+            }
+
+            if ids.len() >= FORWARD_BATCH_SIZE {
+                self.send_forward_work(
+                    core::mem::replace(&mut ids, Vec::with_capacity(FORWARD_BATCH_SIZE)),
+                    core::mem::replace(&mut packets, Vec::with_capacity(FORWARD_BATCH_SIZE)),
+                )?;
+            }
+        }
+
+        if !ids.is_empty() {
+            self.send_forward_work(ids, packets)?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove transactions which can no longer be processed from the queue, i.e. ones which
+    /// are expired or were already processed. In-flight transactions are not affected.
+    fn clean_queue(&mut self) {
+        let bank = self.bank_forks.read().unwrap().working_bank();
+        let mut error_counters = TransactionErrorMetrics::default();
+        for chunk in self
+            .container
+            .priority_ordered_ids(false)
+            .chunks(CLEAN_BATCH_SIZE)
+        {
+            let (priority_ids, transactions): (Vec<_>, Vec<_>) = chunk
+                .iter()
+                .filter_map(|priority_id| {
+                    let transaction_ttl = self.container.get_transaction_ttl(&priority_id.id)?;
+                    if transaction_ttl.max_age_slot < bank.slot() {
+                        self.container.remove_by_id(&priority_id.id);
+                        saturating_add_assign!(self.count_metrics.num_dropped_on_age_and_status, 1);
+                        return None;
+                    }
+                    Some((*priority_id, transaction_ttl.transaction.clone()))
+                })
+                .unzip();
+            let lock_results = vec![Ok(()); transactions.len()];
+            let check_results = bank.check_transactions(
+                &transactions,
+                &lock_results,
+                MAX_PROCESSING_AGE,
+                &mut error_counters,
+            );
+            for (priority_id, (result, _nonce)) in priority_ids.into_iter().zip(check_results) {
+                if result.is_ok() {
+                    self.container.push_id_into_queue(priority_id);
+                } else {
+                    self.container.remove_by_id(&priority_id.id);
+                    saturating_add_assign!(self.count_metrics.num_dropped_on_age_and_status, 1);
+                }
+            }
+        }
+    }
+
+    fn send_forward_work(
+        &mut self,
+        ids: Vec<TransactionId>,
+        packets: Vec<Arc<ImmutableDeserializedPacket>>,
+    ) -> Result<(), SchedulerError> {
+        saturating_add_assign!(self.count_metrics.num_forward_attempted, packets.len());
+        self.forward_work_sender
+            .send(ForwardWork { ids, packets })
+            .map_err(|_| SchedulerError::DisconnectedSendChannel("forward work sender"))
+    }
+
+    /// Receives completed transactions from the workers and updates metrics.
+    fn receive_completed(&mut self) -> Result<(), SchedulerError> {
+        let ((num_transactions, num_retryable), receive_completed_time_us) =
+            measure_us!(self.scheduler.receive_completed(&mut self.container)?);
+        saturating_add_assign!(self.count_metrics.num_finished, num_transactions);
+        saturating_add_assign!(self.count_metrics.num_retryable, num_retryable);
+        saturating_add_assign!(
+            self.timing_metrics.receive_completed_time_us,
+            receive_completed_time_us
+        );
+
+        loop {
+            match self.finished_forward_work_receiver.try_recv() {
+                Ok(FinishedForwardWork { work, successful }) => {
+                    if successful {
+                        saturating_add_assign!(
+                            self.count_metrics.num_forwarded,
+                            work.packets.len()
+                        );
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    return Err(SchedulerError::DisconnectedRecvChannel(
+                        "finished forward work",
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the packet receiver is still connected.
+    fn receive_and_buffer_packets(&mut self) -> bool {
+        let remaining_queue_capacity = self.container.remaining_queue_capacity();
+
+        const MAX_PACKET_RECEIVE_TIME: Duration = Duration::from_millis(100);
+        let recv_timeout = if !self.container.is_empty() {
+            Duration::from_millis(0)
+        } else {
+            MAX_PACKET_RECEIVE_TIME
+        };
+
+        let (received_packet_results, receive_time_us) = measure_us!(self
+            .packet_receiver
+            .receive_packets(recv_timeout, remaining_queue_capacity));
+        saturating_add_assign!(self.timing_metrics.receive_time_us, receive_time_us);
+
+        match received_packet_results {
+            Ok(receive_packet_results) => {
+                let num_received_packets = receive_packet_results.deserialized_packets.len();
+                saturating_add_assign!(self.count_metrics.num_received, num_received_packets);
+
+                let (_, buffer_time_us) =
+                    measure_us!(self.buffer_packets(receive_packet_results.deserialized_packets));
+                saturating_add_assign!(self.timing_metrics.buffer_time_us, buffer_time_us);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return false,
+        }
+
+        true
+    }
+
+    fn buffer_packets(&mut self, packets: Vec<ImmutableDeserializedPacket>) {
+        // Sanitize packets, generate IDs, and insert into the container.
+        let bank = self.bank_forks.read().unwrap().working_bank();
+        let last_slot_in_epoch = bank.epoch_schedule().get_last_slot_in_epoch(bank.epoch());
+        let feature_set = &bank.feature_set;
+        let vote_only = bank.vote_only_bank();
+        for packet in packets {
+            let Some(transaction) =
+                packet.build_sanitized_transaction(feature_set, vote_only, bank.as_ref())
+            else {
+                saturating_add_assign!(self.count_metrics.num_dropped_on_sanitization, 1);
+                continue;
+            };
+
+            let transaction_id = self.transaction_id_generator.next();
+            let transaction_ttl = SanitizedTransactionTTL {
+                transaction,
+                max_age_slot: last_slot_in_epoch,
+            };
+            let transaction_priority_details = TransactionPriorityDetails {
+                priority: packet.priority(),
+                compute_unit_limit: packet.compute_unit_limit(),
+            };
+            if self.container.insert_new_transaction(
+                transaction_id,
+                transaction_ttl,
+                Arc::new(packet),
+                transaction_priority_details,
+            ) {
+                saturating_add_assign!(self.count_metrics.num_dropped_on_capacity, 1);
+            }
+            // At capacity, the lowest priority transaction is dropped, which may be this one.
+            if self
+                .container
+                .get_transaction_ttl(&transaction_id)
+                .is_some()
+            {
+                saturating_add_assign!(self.count_metrics.num_buffered, 1);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct SchedulerCountMetrics {
+    interval: AtomicInterval,
+
+    /// Number of packets received.
+    num_received: usize,
+    /// Number of packets buffered.
+    num_buffered: usize,
+
+    /// Number of transactions scheduled.
+    num_scheduled: usize,
+    /// Number of transactions that were unschedulable.
+    num_unschedulable: usize,
+    /// Number of completed transactions received from workers.
+    num_finished: usize,
+    /// Number of transactions that were retryable.
+    num_retryable: usize,
+
+    /// Number of packets sent to the forward worker.
+    num_forward_attempted: usize,
+    /// Number of packets successfully forwarded.
+    num_forwarded: usize,
+
+    /// Number of transactions that were immediately dropped on receive.
+    num_dropped_on_sanitization: usize,
+    /// Number of transactions that were dropped due to capacity limits.
+    num_dropped_on_capacity: usize,
+    /// Number of transactions that were removed from the container after forwarding.
+    num_dropped_on_forward: usize,
+    /// Number of transactions that were dropped because they were expired or already processed.
+    num_dropped_on_age_and_status: usize,
+}
+
+impl SchedulerCountMetrics {
+    fn maybe_report_and_reset(&mut self, should_report: bool) {
+        const REPORT_INTERVAL_MS: u64 = 1000;
+        if self.interval.should_update(REPORT_INTERVAL_MS) {
+            if should_report {
+                self.report();
+            }
+            self.reset();
+        }
+    }
+
+    fn report(&self) {
+        datapoint_info!(
+            "banking_stage_scheduler_counts",
+            ("num_received", self.num_received, i64),
+            ("num_buffered", self.num_buffered, i64),
+            ("num_scheduled", self.num_scheduled, i64),
+            ("num_unschedulable", self.num_unschedulable, i64),
+            ("num_finished", self.num_finished, i64),
+            ("num_retryable", self.num_retryable, i64),
+            ("num_forward_attempted", self.num_forward_attempted, i64),
+            ("num_forwarded", self.num_forwarded, i64),
+            (
+                "num_dropped_on_sanitization",
+                self.num_dropped_on_sanitization,
+                i64
+            ),
+            ("num_dropped_on_capacity", self.num_dropped_on_capacity, i64),
+            ("num_dropped_on_forward", self.num_dropped_on_forward, i64),
+            (
+                "num_dropped_on_age_and_status",
+                self.num_dropped_on_age_and_status,
+                i64
+            ),
+        );
+    }
+
+    fn has_data(&self) -> bool {
+        self.num_received != 0
+            || self.num_buffered != 0
+            || self.num_scheduled != 0
+            || self.num_unschedulable != 0
+            || self.num_finished != 0
+            || self.num_retryable != 0
+            || self.num_forward_attempted != 0
+            || self.num_forwarded != 0
+            || self.num_dropped_on_sanitization != 0
+            || self.num_dropped_on_capacity != 0
+            || self.num_dropped_on_forward != 0
+            || self.num_dropped_on_age_and_status != 0
+    }
+
+    fn reset(&mut self) {
+        self.num_received = 0;
+        self.num_buffered = 0;
+        self.num_scheduled = 0;
+        self.num_unschedulable = 0;
+        self.num_finished = 0;
+        self.num_retryable = 0;
+        self.num_forward_attempted = 0;
+        self.num_forwarded = 0;
+        self.num_dropped_on_sanitization = 0;
+        self.num_dropped_on_capacity = 0;
+        self.num_dropped_on_forward = 0;
+        self.num_dropped_on_age_and_status = 0;
+    }
+}
+
+#[derive(Default)]
+struct SchedulerTimingMetrics {
+    interval: AtomicInterval,
+    /// Time spent making processing decisions.
+    decision_time_us: u64,
+    /// Time spent receiving packets.
+    receive_time_us: u64,
+    /// Time spent buffering packets.
+    buffer_time_us: u64,
+    /// Time spent scheduling transactions.
+    schedule_time_us: u64,
+    /// Time spent removing expired or processed transactions from the queue.
+    clean_time_us: u64,
+    /// Time spent sending packets to the forward worker.
+    forward_time_us: u64,
+    /// Time spent receiving completed transactions.
+    receive_completed_time_us: u64,
+}
+
+impl SchedulerTimingMetrics {
+    fn maybe_report_and_reset(&mut self, should_report: bool) {
+        const REPORT_INTERVAL_MS: u64 = 1000;
+        if self.interval.should_update(REPORT_INTERVAL_MS) {
+            if should_report {
+                self.report();
+            }
+            self.reset();
+        }
+    }
+
+    fn report(&self) {
+        datapoint_info!(
+            "banking_stage_scheduler_timing",
+            ("decision_time_us", self.decision_time_us, i64),
+            ("receive_time_us", self.receive_time_us, i64),
+            ("buffer_time_us", self.buffer_time_us, i64),
+            ("schedule_time_us", self.schedule_time_us, i64),
+            ("clean_time_us", self.clean_time_us, i64),
+            ("forward_time_us", self.forward_time_us, i64),
+            (
+                "receive_completed_time_us",
+                self.receive_completed_time_us,
+                i64
+            ),
+        );
+    }
+
+    fn reset(&mut self) {
+        self.decision_time_us = 0;
+        self.receive_time_us = 0;
+        self.buffer_time_us = 0;
+        self.schedule_time_us = 0;
+        self.clean_time_us = 0;
+        self.forward_time_us = 0;
+        self.receive_completed_time_us = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            banking_stage::{
+                scheduler_messages::{ConsumeWork, FinishedConsumeWork},
+                tests::create_slow_genesis_config,
+            },
+            banking_trace::BankingPacketBatch,
+        },
+        crossbeam_channel::unbounded,
+        itertools::Itertools,
+        solana_ledger::{
+            blockstore::Blockstore, genesis_utils::GenesisConfigInfo,
+            get_tmp_ledger_path_auto_delete, leader_schedule_cache::LeaderScheduleCache,
+        },
+        solana_perf::packet::{to_packet_batches, NUM_PACKETS},
+        solana_poh::poh_recorder::{BankStart, PohRecorder, Record, WorkingBankEntry},
+        solana_runtime::bank::Bank,
+        solana_sdk::{
+            compute_budget::ComputeBudgetInstruction, hash::Hash, message::Message,
+            poh_config::PohConfig, pubkey::Pubkey, signature::Keypair, signer::Signer,
+            system_instruction, transaction::Transaction,
+        },
+        std::{sync::atomic::AtomicBool, time::Instant},
+        tempfile::TempDir,
+    };
+
+    // Helper struct to hold the channels, files, etc. needed by the scheduler controller.
+    struct TestFrame {
+        bank: Arc<Bank>,
+        _ledger_path: TempDir,
+        _entry_receiver: Receiver<WorkingBankEntry>,
+        _record_receiver: Receiver<Record>,
+        banking_packet_sender: Sender<BankingPacketBatch>,
+
+        consume_work_receivers: Vec<Receiver<ConsumeWork>>,
+        finished_consume_work_sender: Sender<FinishedConsumeWork>,
+        forward_work_receiver: Receiver<ForwardWork>,
+        _finished_forward_work_sender: Sender<FinishedForwardWork>,
+    }
+
+    fn create_test_frame(num_threads: usize) -> (TestFrame, SchedulerController) {
+        let GenesisConfigInfo { genesis_config, .. } = create_slow_genesis_config(10_000);
+        let bank = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let bank = bank_forks.read().unwrap().working_bank();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path())
+            .expect("Expected to be able to open database ledger");
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            bank.tick_height(),
+            bank.last_blockhash(),
+            bank.clone(),
+            Some((4, 4)),
+            bank.ticks_per_slot(),
+            &Pubkey::new_unique(),
+            Arc::new(blockstore),
+            &Arc::new(LeaderScheduleCache::new_from_bank(&bank)),
+            &PohConfig::default(),
+            Arc::new(AtomicBool::default()),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let decision_maker = DecisionMaker::new(Pubkey::new_unique(), poh_recorder);
+
+        let (banking_packet_sender, banking_packet_receiver) = unbounded();
+        let packet_deserializer =
+            PacketDeserializer::new(banking_packet_receiver, bank_forks.clone());
+
+        let (consume_work_senders, consume_work_receivers) =
+            (0..num_threads).map(|_| unbounded()).unzip();
+        let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
+        let scheduler =
+            PrioGraphScheduler::new(consume_work_senders, finished_consume_work_receiver);
+        let (forward_work_sender, forward_work_receiver) = unbounded();
+        let (finished_forward_work_sender, finished_forward_work_receiver) = unbounded();
+
+        let scheduler_controller = SchedulerController::new(
+            decision_maker,
+            packet_deserializer,
+            bank_forks,
+            scheduler,
+            forward_work_sender,
+            finished_forward_work_receiver,
+        );
+
+        (
+            TestFrame {
+                bank,
+                _ledger_path: ledger_path,
+                _entry_receiver: entry_receiver,
+                _record_receiver: record_receiver,
+                banking_packet_sender,
+                consume_work_receivers,
+                finished_consume_work_sender,
+                forward_work_receiver,
+                _finished_forward_work_sender: finished_forward_work_sender,
+            },
+            scheduler_controller,
+        )
+    }
+
+    fn prioritized_transfer(
+        from_keypair: &Keypair,
+        to_pubkey: &Pubkey,
+        lamports: u64,
+        priority: u64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let ixs = vec![
+            system_instruction::transfer(&from_keypair.pubkey(), to_pubkey, lamports),
+            ComputeBudgetInstruction::set_compute_unit_price(priority),
+        ];
+        let message = Message::new(&ixs, Some(&from_keypair.pubkey()));
+        Transaction::new(&[from_keypair], message, recent_blockhash)
+    }
+
+    fn to_banking_packet_batch(txs: &[Transaction]) -> BankingPacketBatch {
+        Arc::new((to_packet_batches(txs, NUM_PACKETS), None))
+    }
+
+    fn receive_and_buffer(
+        test_frame: &TestFrame,
+        scheduler_controller: &mut SchedulerController,
+        txs: &[Transaction],
+    ) {
+        test_frame
+            .banking_packet_sender
+            .send(to_banking_packet_batch(txs))
+            .unwrap();
+        assert!(scheduler_controller.receive_and_buffer_packets());
+    }
+
+    fn consume_decision(bank: &Arc<Bank>) -> BufferedPacketsDecision {
+        BufferedPacketsDecision::Consume(BankStart {
+            working_bank: bank.clone(),
+            bank_creation_time: Arc::new(Instant::now()),
+        })
+    }
+
+    #[test]
+    fn test_schedule_consume_by_priority() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(1);
+        let TestFrame {
+            bank,
+            consume_work_receivers,
+            finished_consume_work_sender,
+            ..
+        } = &test_frame;
+
+        let txs = [
+            prioritized_transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                1,
+                bank.last_blockhash(),
+            ),
+            prioritized_transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                2,
+                bank.last_blockhash(),
+            ),
+        ];
+        receive_and_buffer(&test_frame, &mut scheduler_controller, &txs);
+        assert_eq!(scheduler_controller.count_metrics.num_buffered, 2);
+
+        scheduler_controller
+            .process_transactions(&consume_decision(bank))
+            .unwrap();
+        let consume_work = consume_work_receivers[0].try_iter().collect_vec();
+        assert_eq!(consume_work.len(), 1);
+        assert_eq!(
+            consume_work[0]
+                .transactions
+                .iter()
+                .map(|tx| *tx.message_hash())
+                .collect_vec(),
+            vec![txs[1].message.hash(), txs[0].message.hash()]
+        );
+
+        // Processed transactions are removed from the container.
+        for work in consume_work {
+            finished_consume_work_sender
+                .send(FinishedConsumeWork {
+                    work,
+                    retryable_indexes: vec![],
+                })
+                .unwrap();
+        }
+        scheduler_controller.receive_completed().unwrap();
+        assert_eq!(scheduler_controller.count_metrics.num_finished, 2);
+        assert!(scheduler_controller.container.is_empty());
+    }
+
+    #[test]
+    fn test_schedule_consume_retryable() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(1);
+        let TestFrame {
+            bank,
+            consume_work_receivers,
+            finished_consume_work_sender,
+            ..
+        } = &test_frame;
+
+        let txs = [prioritized_transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            1,
+            bank.last_blockhash(),
+        )];
+        receive_and_buffer(&test_frame, &mut scheduler_controller, &txs);
+        scheduler_controller
+            .process_transactions(&consume_decision(bank))
+            .unwrap();
+        let work = consume_work_receivers[0].try_recv().unwrap();
+        finished_consume_work_sender
+            .send(FinishedConsumeWork {
+                work,
+                retryable_indexes: vec![0],
+            })
+            .unwrap();
+        scheduler_controller.receive_completed().unwrap();
+        assert_eq!(scheduler_controller.count_metrics.num_retryable, 1);
+
+        // The retryable transaction is scheduled again.
+        scheduler_controller
+            .process_transactions(&consume_decision(bank))
+            .unwrap();
+        let work = consume_work_receivers[0].try_recv().unwrap();
+        assert_eq!(work.transactions[0].message_hash(), &txs[0].message.hash());
+    }
+
+    #[test]
+    fn test_forward_and_hold() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(1);
+        let TestFrame {
+            bank,
+            forward_work_receiver,
+            ..
+        } = &test_frame;
+
+        let txs = [
+            prioritized_transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                1,
+                bank.last_blockhash(),
+            ),
+            prioritized_transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                2,
+                bank.last_blockhash(),
+            ),
+        ];
+        receive_and_buffer(&test_frame, &mut scheduler_controller, &txs);
+
+        // Held transactions stay in the container, and are forwarded only once.
+        scheduler_controller
+            .process_transactions(&BufferedPacketsDecision::ForwardAndHold)
+            .unwrap();
+        let forward_work = forward_work_receiver.try_iter().collect_vec();
+        assert_eq!(forward_work.len(), 1);
+        assert_eq!(
+            forward_work[0].ids,
+            vec![TransactionId::new(1), TransactionId::new(0)]
+        );
+        assert_eq!(scheduler_controller.container.len(), 2);
+
+        scheduler_controller
+            .process_transactions(&BufferedPacketsDecision::ForwardAndHold)
+            .unwrap();
+        assert!(forward_work_receiver.try_recv().is_err());
+        assert_eq!(scheduler_controller.container.len(), 2);
+
+        // Forwarding without holding drains the container.
+        scheduler_controller
+            .process_transactions(&BufferedPacketsDecision::Forward)
+            .unwrap();
+        assert!(forward_work_receiver.try_recv().is_err());
+        assert!(scheduler_controller.container.is_empty());
+        assert_eq!(scheduler_controller.count_metrics.num_dropped_on_forward, 2);
+    }
+
+    #[test]
+    fn test_drop_expired_transactions() {
+        let (test_frame, mut scheduler_controller) = create_test_frame(1);
+        let TestFrame {
+            bank,
+            forward_work_receiver,
+            ..
+        } = &test_frame;
+
+        let txs = [
+            prioritized_transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                1,
+                bank.last_blockhash(),
+            ),
+            prioritized_transfer(
+                &Keypair::new(),
+                &Pubkey::new_unique(),
+                1,
+                2,
+                Hash::new_unique(),
+            ),
+        ];
+        receive_and_buffer(&test_frame, &mut scheduler_controller, &txs);
+        assert_eq!(scheduler_controller.container.len(), 2);
+
+        // The transaction with an unknown blockhash is dropped instead of forwarded.
+        scheduler_controller
+            .process_transactions(&BufferedPacketsDecision::ForwardAndHold)
+            .unwrap();
+        let forward_work = forward_work_receiver.try_iter().collect_vec();
+        assert_eq!(forward_work.len(), 1);
+        assert_eq!(forward_work[0].ids, vec![TransactionId::new(0)]);
+        assert_eq!(scheduler_controller.container.len(), 1);
+        assert_eq!(
+            scheduler_controller
+                .count_metrics
+                .num_dropped_on_age_and_status,
+            1
+        );
+    }
+}
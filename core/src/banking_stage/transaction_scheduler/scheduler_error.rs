@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SchedulerError {
+    #[error("Sending channel disconnected: {0}")]
+    DisconnectedSendChannel(&'static str),
+    #[error("Recv channel disconnected: {0}")]
+    DisconnectedRecvChannel(&'static str),
+}
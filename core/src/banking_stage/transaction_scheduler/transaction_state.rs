@@ -1,6 +1,8 @@
 use {
+    crate::banking_stage::immutable_deserialized_packet::ImmutableDeserializedPacket,
     solana_runtime::transaction_priority_details::TransactionPriorityDetails,
     solana_sdk::{slot_history::Slot, transaction::SanitizedTransaction},
+    std::sync::Arc,
 };
 
 /// Simple wrapper type to tie a sanitized transaction to max age slot.
@@ -28,16 +30,21 @@ pub(crate) struct SanitizedTransactionTTL {
 ///   internal `SanitizedTransaction` is moved out of the `TransactionState` and sent
 ///   to the appropriate thread for processing. This is done to avoid cloning the
 ///  `SanitizedTransaction`.
+///
+/// The original packet is kept in both states, so that it can be forwarded
+///   regardless of whether the transaction is currently being processed.
 #[allow(clippy::large_enum_variant)]
 pub(crate) enum TransactionState {
     /// The transaction is available for scheduling.
     Unprocessed {
         transaction_ttl: SanitizedTransactionTTL,
+        packet: Arc<ImmutableDeserializedPacket>,
         transaction_priority_details: TransactionPriorityDetails,
         forwarded: bool,
     },
     /// The transaction is currently scheduled or being processed.
     Pending {
+        packet: Arc<ImmutableDeserializedPacket>,
         transaction_priority_details: TransactionPriorityDetails,
         forwarded: bool,
     },
@@ -47,15 +54,25 @@ impl TransactionState {
     /// Creates a new `TransactionState` in the `Unprocessed` state.
     pub(crate) fn new(
         transaction_ttl: SanitizedTransactionTTL,
+        packet: Arc<ImmutableDeserializedPacket>,
         transaction_priority_details: TransactionPriorityDetails,
     ) -> Self {
         Self::Unprocessed {
             transaction_ttl,
+            packet,
             transaction_priority_details,
             forwarded: false,
         }
     }
 
+    /// Returns a reference to the original packet of the transaction.
+    pub(crate) fn packet(&self) -> &Arc<ImmutableDeserializedPacket> {
+        match self {
+            Self::Unprocessed { packet, .. } => packet,
+            Self::Pending { packet, .. } => packet,
+        }
+    }
+
     /// Returns a reference to the priority details of the transaction.
     pub(crate) fn transaction_priority_details(&self) -> &TransactionPriorityDetails {
         match self {
@@ -102,10 +119,12 @@ impl TransactionState {
         match self.take() {
             TransactionState::Unprocessed {
                 transaction_ttl,
+                packet,
                 transaction_priority_details,
                 forwarded,
             } => {
                 *self = TransactionState::Pending {
+                    packet,
                     transaction_priority_details,
                     forwarded,
                 };
@@ -127,11 +146,13 @@ impl TransactionState {
         match self.take() {
             TransactionState::Unprocessed { .. } => panic!("already unprocessed"),
             TransactionState::Pending {
+                packet,
                 transaction_priority_details,
                 forwarded,
             } => {
                 *self = Self::Unprocessed {
                     transaction_ttl,
+                    packet,
                     transaction_priority_details,
                     forwarded,
                 }
@@ -155,9 +176,11 @@ impl TransactionState {
     /// Internal helper to transitioning between states.
     /// Replaces `self` with a dummy state that will immediately be overwritten in transition.
     fn take(&mut self) -> Self {
+        let packet = self.packet().clone();
         core::mem::replace(
             self,
             Self::Pending {
+                packet,
                 transaction_priority_details: TransactionPriorityDetails {
                     priority: 0,
                     compute_unit_limit: 0,
@@ -172,6 +195,7 @@ impl TransactionState {
 mod tests {
     use {
         super::*,
+        solana_perf::packet::Packet,
         solana_sdk::{
             compute_budget::ComputeBudgetInstruction, hash::Hash, message::Message,
             signature::Keypair, signer::Signer, system_instruction, transaction::Transaction,
@@ -191,6 +215,9 @@ mod tests {
         let message = Message::new(&ixs, Some(&from_keypair.pubkey()));
         let tx = Transaction::new(&[&from_keypair], message, Hash::default());
 
+        let packet = Arc::new(
+            ImmutableDeserializedPacket::new(Packet::from_data(None, tx.clone()).unwrap()).unwrap(),
+        );
         let transaction_ttl = SanitizedTransactionTTL {
             transaction: SanitizedTransaction::from_transaction_for_tests(tx),
             max_age_slot: Slot::MAX,
@@ -198,6 +225,7 @@ mod tests {
 
         TransactionState::new(
             transaction_ttl,
+            packet,
             TransactionPriorityDetails {
                 priority,
                 compute_unit_limit: 0,
@@ -3,10 +3,13 @@ use {
         transaction_priority_id::TransactionPriorityId,
         transaction_state::{SanitizedTransactionTTL, TransactionState},
     },
-    crate::banking_stage::scheduler_messages::TransactionId,
+    crate::banking_stage::{
+        immutable_deserialized_packet::ImmutableDeserializedPacket,
+        scheduler_messages::TransactionId,
+    },
     min_max_heap::MinMaxHeap,
     solana_runtime::transaction_priority_details::TransactionPriorityDetails,
-    std::collections::HashMap,
+    std::{collections::HashMap, sync::Arc},
 };
 
 /// This structure will hold `TransactionState` for the entirety of a
@@ -57,9 +60,21 @@ impl TransactionStateContainer {
         self.priority_queue.capacity() - self.priority_queue.len()
     }
 
+    /// Returns the number of transactions in the queue.
+    pub(crate) fn len(&self) -> usize {
+        self.priority_queue.len()
+    }
+
+    /// Pop the highest priority transaction id from the queue.
+    /// The transaction state is not removed from the map.
+    pub(crate) fn pop(&mut self) -> Option<TransactionPriorityId> {
+        self.priority_queue.pop_max()
+    }
+
     /// Get an iterator of the top `n` transaction ids in the priority queue.
     /// This will remove the ids from the queue, but not drain the remainder
     /// of the queue.
+    #[cfg(test)]
     pub(crate) fn take_top_n(
         &mut self,
         n: usize,
@@ -93,7 +108,7 @@ impl TransactionStateContainer {
     }
 
     /// Get reference to `SanitizedTransactionTTL` by id.
-    /// Panics if the transaction does not exist.
+    /// Returns `None` if the transaction does not exist.
     pub(crate) fn get_transaction_ttl(
         &self,
         id: &TransactionId,
@@ -103,28 +118,20 @@ impl TransactionStateContainer {
             .map(|state| state.transaction_ttl())
     }
 
-    /// Take `SanitizedTransactionTTL` by id.
-    /// This transitions the transaction to `Pending` state.
-    /// Panics if the transaction does not exist.
-    pub(crate) fn take_transaction(&mut self, id: &TransactionId) -> SanitizedTransactionTTL {
-        self.id_to_transaction_state
-            .get_mut(id)
-            .expect("transaction must exist")
-            .transition_to_pending()
-    }
-
     /// Insert a new transaction into the container's queues and maps.
+    /// Returns `true` if a packet was dropped due to capacity limits.
     pub(crate) fn insert_new_transaction(
         &mut self,
         transaction_id: TransactionId,
         transaction_ttl: SanitizedTransactionTTL,
+        packet: Arc<ImmutableDeserializedPacket>,
         transaction_priority_details: TransactionPriorityDetails,
-    ) {
+    ) -> bool {
         let priority_id =
             TransactionPriorityId::new(transaction_priority_details.priority, transaction_id);
         self.id_to_transaction_state.insert(
             transaction_id,
-            TransactionState::new(transaction_ttl, transaction_priority_details),
+            TransactionState::new(transaction_ttl, packet, transaction_priority_details),
         );
         self.push_id_into_queue(priority_id)
     }
@@ -146,12 +153,15 @@ impl TransactionStateContainer {
 
     /// Pushes a transaction id into the priority queue. If the queue is full, the lowest priority
     /// transaction will be dropped (removed from the queue and map).
-    pub(crate) fn push_id_into_queue(&mut self, priority_id: TransactionPriorityId) {
+    /// Returns `true` if a packet was dropped due to capacity limits.
+    pub(crate) fn push_id_into_queue(&mut self, priority_id: TransactionPriorityId) -> bool {
         if self.remaining_queue_capacity() == 0 {
             let popped_id = self.priority_queue.push_pop_min(priority_id);
             self.remove_by_id(&popped_id.id);
+            true
         } else {
             self.priority_queue.push(priority_id);
+            false
         }
     }
 
@@ -167,6 +177,7 @@ impl TransactionStateContainer {
 mod tests {
     use {
         super::*,
+        solana_perf::packet::Packet,
         solana_sdk::{
             compute_budget::ComputeBudgetInstruction,
             hash::Hash,
@@ -179,7 +190,13 @@ mod tests {
         },
     };
 
-    fn test_transaction(priority: u64) -> (SanitizedTransactionTTL, TransactionPriorityDetails) {
+    fn test_transaction(
+        priority: u64,
+    ) -> (
+        SanitizedTransactionTTL,
+        Arc<ImmutableDeserializedPacket>,
+        TransactionPriorityDetails,
+    ) {
         let from_keypair = Keypair::new();
         let ixs = vec![
             system_instruction::transfer(
@@ -192,12 +209,16 @@ mod tests {
         let message = Message::new(&ixs, Some(&from_keypair.pubkey()));
         let tx = Transaction::new(&[&from_keypair], message, Hash::default());
 
+        let packet = Arc::new(
+            ImmutableDeserializedPacket::new(Packet::from_data(None, tx.clone()).unwrap()).unwrap(),
+        );
         let transaction_ttl = SanitizedTransactionTTL {
             transaction: SanitizedTransaction::from_transaction_for_tests(tx),
             max_age_slot: Slot::MAX,
         };
         (
             transaction_ttl,
+            packet,
             TransactionPriorityDetails {
                 priority,
                 compute_unit_limit: 0,
@@ -208,10 +229,12 @@ mod tests {
     fn push_to_container(container: &mut TransactionStateContainer, num: usize) {
         for id in 0..num as u64 {
             let priority = id;
-            let (transaction_ttl, transaction_priority_details) = test_transaction(priority);
+            let (transaction_ttl, packet, transaction_priority_details) =
+                test_transaction(priority);
             container.insert_new_transaction(
                 TransactionId::new(id),
                 transaction_ttl,
+                packet,
                 transaction_priority_details,
             );
         }
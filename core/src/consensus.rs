@@ -1,4 +1,5 @@
 pub mod fork_choice;
+pub mod fork_choice_snapshot;
 pub mod heaviest_subtree_fork_choice;
 pub(crate) mod latest_validator_votes_for_frozen_banks;
 pub mod progress_map;
@@ -1373,6 +1374,82 @@ impl Tower {
     pub fn restore(tower_storage: &dyn TowerStorage, node_pubkey: &Pubkey) -> Result<Self> {
         tower_storage.load(node_pubkey)
     }
+
+    /// Returns the signed tower, to be handed over to another validator running with the same
+    /// identity
+    pub fn export(&self, node_keypair: &Keypair) -> Result<Vec<u8>> {
+        let saved_tower = SavedTowerVersions::from(SavedTower::new(self, node_keypair)?);
+        Ok(bincode::serialize(&saved_tower)?)
+    }
+
+    /// Returns the tower exported by another validator running as `node_pubkey`
+    pub fn import(exported_tower: &[u8], node_pubkey: &Pubkey) -> Result<Self> {
+        bincode::deserialize::<SavedTowerVersions>(exported_tower)?.try_into_tower(node_pubkey)
+    }
+
+    /// Checks that this tower, handed over by another validator running with the same identity,
+    /// is at least as recent as `local_tower`, the tower this validator has for that identity,
+    /// and that voting on its last voted slot does not violate the lockouts of `local_tower`
+    pub fn check_handover(&self, local_tower: &Tower, bank_forks: &BankForks) -> Result<()> {
+        let Some(local_last_voted_slot) = local_tower.last_voted_slot() else {
+            return Ok(());
+        };
+        let Some(last_voted_slot) = self.last_voted_slot() else {
+            return Err(TowerError::HandoverRejected(format!(
+                "the tower has no votes but the local tower voted on slot {local_last_voted_slot}"
+            )));
+        };
+        if last_voted_slot < local_last_voted_slot {
+            return Err(TowerError::HandoverRejected(format!(
+                "the tower last voted on slot {last_voted_slot}, \
+                 before the last vote of the local tower on slot {local_last_voted_slot}"
+            )));
+        }
+        if last_voted_slot == local_last_voted_slot {
+            if self.last_vote.hash() != local_tower.last_vote.hash() {
+                return Err(TowerError::HandoverRejected(format!(
+                    "the tower and the local tower voted on different versions of slot \
+                     {last_voted_slot}"
+                )));
+            }
+            return Ok(());
+        }
+
+        let root = bank_forks.root();
+        let slot_history = bank_forks.root_bank().get_slot_history();
+        let ancestors = bank_forks
+            .get(last_voted_slot)
+            .map(|bank| bank.proper_ancestors_set())
+            .ok_or_else(|| {
+                TowerError::HandoverRejected(format!(
+                    "slot {last_voted_slot} has not been replayed yet, retry later"
+                ))
+            })?;
+        // Same as is_locked_out(), except that the local tower may be rooted before the bank
+        // forks root, so its older votes are checked against the history of rooted slots
+        let mut vote_state = local_tower.vote_state.clone();
+        process_slot_vote_unchecked(&mut vote_state, last_voted_slot);
+        let locked_out_slot = vote_state
+            .votes
+            .iter()
+            .map(|vote| vote.slot())
+            .chain(vote_state.root_slot)
+            .filter(|slot| *slot != last_voted_slot)
+            .find(|slot| {
+                if *slot <= root {
+                    slot_history.check(*slot) == Check::NotFound
+                } else {
+                    !ancestors.contains(slot)
+                }
+            });
+        if let Some(locked_out_slot) = locked_out_slot {
+            return Err(TowerError::HandoverRejected(format!(
+                "voting on slot {last_voted_slot} would violate the lockout of the vote of the \
+                 local tower on slot {locked_out_slot}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Error, Debug)]
@@ -1400,6 +1477,12 @@ pub enum TowerError {
 
     #[error("The tower is useless because of new hard fork: {0}")]
     HardFork(Slot),
+
+    #[error("The tower cannot be handed over: {0}")]
+    HandoverRejected(String),
+
+    #[error("Lost etcd instance lock for {0}")]
+    LostEtcdInstanceLock(Pubkey),
 }
 
 // Tower1_14_11 is the persisted data format for the Tower, decoupling it from VoteState::Current
@@ -2422,6 +2505,71 @@ pub mod test {
         assert_eq!(tower.vote_state.votes[1].confirmation_count(), 1);
     }
 
+    #[test]
+    fn test_check_handover() {
+        let mut vote_simulator = VoteSimulator::new(1);
+        let forks = tr(0) / (tr(1) / (tr(2) / tr(3)) / tr(4));
+        vote_simulator.fill_bank_forks(forks, &HashMap::new(), true);
+        let bank_forks = vote_simulator.bank_forks.read().unwrap();
+        let tower_with_votes = |slots: &[Slot]| {
+            let mut tower = Tower::new_for_tests(0, 0.67);
+            for slot in slots {
+                tower.record_vote(*slot, Hash::default());
+            }
+            tower
+        };
+        let local_tower = tower_with_votes(&[1, 2]);
+
+        assert!(tower_with_votes(&[1, 2, 3])
+            .check_handover(&local_tower, &bank_forks)
+            .is_ok());
+        assert!(tower_with_votes(&[1, 2])
+            .check_handover(&local_tower, &bank_forks)
+            .is_ok());
+        assert!(tower_with_votes(&[1])
+            .check_handover(&Tower::new_for_tests(0, 0.67), &bank_forks)
+            .is_ok());
+
+        // older than the local tower
+        assert_matches!(
+            tower_with_votes(&[1]).check_handover(&local_tower, &bank_forks),
+            Err(TowerError::HandoverRejected(_))
+        );
+        assert_matches!(
+            Tower::new_for_tests(0, 0.67).check_handover(&local_tower, &bank_forks),
+            Err(TowerError::HandoverRejected(_))
+        );
+        // slot 2 of the local tower is still locked out when voting on slot 4
+        assert_matches!(
+            tower_with_votes(&[1, 4]).check_handover(&local_tower, &bank_forks),
+            Err(TowerError::HandoverRejected(_))
+        );
+        // slot 9 was not replayed
+        assert_matches!(
+            tower_with_votes(&[1, 2, 9]).check_handover(&local_tower, &bank_forks),
+            Err(TowerError::HandoverRejected(_))
+        );
+    }
+
+    #[test]
+    fn test_export_import_tower() {
+        let identity_keypair = Keypair::new();
+        let mut tower = Tower::new_for_tests(10, 0.9);
+        tower.node_pubkey = identity_keypair.pubkey();
+        tower.record_vote(1, Hash::default());
+
+        let exported_tower = tower.export(&identity_keypair).unwrap();
+        let imported_tower = Tower::import(&exported_tower, &identity_keypair.pubkey()).unwrap();
+        assert_eq!(imported_tower.last_voted_slot(), Some(1));
+        assert_eq!(imported_tower.node_pubkey, identity_keypair.pubkey());
+
+        assert_matches!(
+            Tower::import(&exported_tower, &Pubkey::new_unique()),
+            Err(TowerError::InvalidSignature)
+        );
+        assert!(Tower::export(&tower, &Keypair::new()).is_err());
+    }
+
     #[test]
     fn test_check_vote_threshold_below_threshold() {
         let mut tower = Tower::new_for_tests(1, 0.67);
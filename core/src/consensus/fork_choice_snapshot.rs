@@ -0,0 +1,185 @@
+//! Snapshot of the fork choice state of the replay stage, returned by the admin RPC to
+//! inspect the forks of a running validator
+
+use {
+    crate::consensus::{
+        heaviest_subtree_fork_choice::HeaviestSubtreeForkChoice, progress_map::ProgressMap, Tower,
+    },
+    solana_sdk::clock::Slot,
+    tokio::sync::oneshot,
+};
+
+/// Request of a snapshot of the fork choice state, answered by the replay stage
+pub struct ForkChoiceSnapshotRequest {
+    pub response_sender: oneshot::Sender<ForkChoiceSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkChoiceSnapshot {
+    pub root: Slot,
+    /// Slot of the heaviest fork, which is voted on if the tower allows it
+    pub heaviest_slot: Slot,
+    /// The frozen slots descending from the root, in slot order
+    pub slots: Vec<ForkChoiceSlot>,
+    pub tower: TowerSnapshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkChoiceSlot {
+    pub slot: Slot,
+    pub bank_hash: String,
+    pub parent: Option<Slot>,
+    /// Stake of the validators whose latest vote is for this slot
+    pub stake_voted_at: u64,
+    /// Stake of the validators whose latest vote is for this slot or one of its descendants
+    pub stake_voted_subtree: u64,
+    pub total_stake: u64,
+    /// False if this slot or one of its ancestors is a duplicate slot that is not confirmed
+    pub is_candidate: bool,
+    pub is_duplicate_confirmed: bool,
+    pub is_unconfirmed_duplicate: bool,
+    pub is_supermajority_confirmed: bool,
+    pub is_propagated: bool,
+    pub has_voted: bool,
+    pub is_locked_out: bool,
+    pub vote_threshold_passed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TowerSnapshot {
+    pub node_pubkey: String,
+    pub root: Slot,
+    pub last_voted_slot: Option<Slot>,
+    pub votes: Vec<TowerVote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TowerVote {
+    pub slot: Slot,
+    pub confirmation_count: u32,
+}
+
+impl ForkChoiceSnapshot {
+    pub fn new(
+        tower: &Tower,
+        progress: &ProgressMap,
+        fork_choice: &HeaviestSubtreeForkChoice,
+    ) -> Self {
+        let mut slots: Vec<_> = fork_choice
+            .all_slots_stake_voted_subtree()
+            .map(|(slot_hash_key, stake_voted_subtree)| {
+                let (slot, bank_hash) = *slot_hash_key;
+                let fork_stats = progress.get_fork_stats(slot);
+                ForkChoiceSlot {
+                    slot,
+                    bank_hash: bank_hash.to_string(),
+                    parent: fork_choice.parent(slot_hash_key).map(|(parent, _)| parent),
+                    stake_voted_at: fork_choice.stake_voted_at(slot_hash_key).unwrap_or(0),
+                    stake_voted_subtree,
+                    total_stake: fork_stats.map_or(0, |fork_stats| fork_stats.total_stake),
+                    is_candidate: fork_choice.is_candidate(slot_hash_key).unwrap_or(false),
+                    is_duplicate_confirmed: fork_choice
+                        .is_duplicate_confirmed(slot_hash_key)
+                        .unwrap_or(false),
+                    is_unconfirmed_duplicate: fork_choice
+                        .is_unconfirmed_duplicate(slot_hash_key)
+                        .unwrap_or(false),
+                    is_supermajority_confirmed: fork_stats
+                        .map_or(false, |fork_stats| fork_stats.is_supermajority_confirmed),
+                    is_propagated: progress.is_propagated(slot).unwrap_or(false),
+                    has_voted: fork_stats.map_or(false, |fork_stats| fork_stats.has_voted),
+                    is_locked_out: fork_stats.map_or(false, |fork_stats| fork_stats.is_locked_out),
+                    vote_threshold_passed: fork_stats
+                        .map_or(false, |fork_stats| fork_stats.vote_threshold.passed()),
+                }
+            })
+            .collect();
+        slots.sort_unstable_by_key(|slot| slot.slot);
+
+        Self {
+            root: fork_choice.tree_root().0,
+            heaviest_slot: fork_choice.best_overall_slot().0,
+            slots,
+            tower: TowerSnapshot {
+                node_pubkey: tower.node_pubkey.to_string(),
+                root: tower.root(),
+                last_voted_slot: tower.last_voted_slot(),
+                votes: tower
+                    .vote_state
+                    .votes
+                    .iter()
+                    .map(|vote| TowerVote {
+                        slot: vote.slot(),
+                        confirmation_count: vote.confirmation_count(),
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::vote_simulator::VoteSimulator, solana_sdk::hash::Hash,
+        std::collections::HashMap, trees::tr,
+    };
+
+    #[test]
+    fn test_fork_choice_snapshot() {
+        let mut vote_simulator = VoteSimulator::new(1);
+        let forks = tr(0) / (tr(1) / (tr(2) / tr(3)) / tr(4));
+        vote_simulator.fill_bank_forks(forks, &HashMap::new(), true);
+        let mut tower = Tower::new_for_tests(0, 0.67);
+        tower.record_vote(1, Hash::default());
+        tower.record_vote(2, Hash::default());
+
+        let snapshot = ForkChoiceSnapshot::new(
+            &tower,
+            &vote_simulator.progress,
+            &vote_simulator.heaviest_subtree_fork_choice,
+        );
+        assert_eq!(snapshot.root, 0);
+        assert_eq!(
+            snapshot
+                .slots
+                .iter()
+                .map(|slot| (slot.slot, slot.parent))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, None),
+                (1, Some(0)),
+                (2, Some(1)),
+                (3, Some(2)),
+                (4, Some(1))
+            ]
+        );
+        assert!(snapshot.slots.iter().all(|slot| slot.is_candidate));
+        assert_eq!(snapshot.tower.last_voted_slot, Some(2));
+        assert_eq!(
+            snapshot.tower.votes,
+            vec![
+                TowerVote {
+                    slot: 1,
+                    confirmation_count: 2
+                },
+                TowerVote {
+                    slot: 2,
+                    confirmation_count: 1
+                },
+            ]
+        );
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["heaviestSlot"], snapshot.heaviest_slot);
+        assert_eq!(json["slots"][1]["parent"], 0);
+        assert_eq!(
+            serde_json::from_value::<ForkChoiceSnapshot>(json).unwrap(),
+            snapshot
+        );
+    }
+}
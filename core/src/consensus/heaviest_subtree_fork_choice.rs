@@ -994,7 +994,7 @@ impl HeaviestSubtreeForkChoice {
         }
     }
 
-    fn parent(&self, slot_hash_key: &SlotHashKey) -> Option<SlotHashKey> {
+    pub fn parent(&self, slot_hash_key: &SlotHashKey) -> Option<SlotHashKey> {
         self.fork_infos
             .get(slot_hash_key)
             .map(|fork_info| fork_info.parent)
@@ -3,6 +3,7 @@ use {
         tower1_14_11::Tower1_14_11, tower1_7_14::SavedTower1_7_14, Result, Tower, TowerError,
         TowerVersions,
     },
+    crossbeam_channel::{unbounded, Sender},
     solana_sdk::{
         pubkey::Pubkey,
         signature::{Signature, Signer},
@@ -11,6 +12,12 @@ use {
         fs::{self, File},
         io::{self, BufReader},
         path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
     },
 };
 
@@ -21,7 +28,7 @@ pub enum SavedTowerVersions {
 }
 
 impl SavedTowerVersions {
-    fn try_into_tower(&self, node_pubkey: &Pubkey) -> Result<Tower> {
+    pub(crate) fn try_into_tower(&self, node_pubkey: &Pubkey) -> Result<Tower> {
         // This method assumes that `self` was just deserialized
         assert_eq!(self.pubkey(), Pubkey::default());
 
@@ -309,10 +316,7 @@ impl TowerStorage for EtcdTowerStorage {
             })?;
 
         if !response.succeeded() {
-            return Err(TowerError::IoError(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Lost etcd instance lock for {node_pubkey}"),
-            )));
+            return Err(TowerError::LostEtcdInstanceLock(*node_pubkey));
         }
 
         for op_response in response.op_responses() {
@@ -357,9 +361,179 @@ impl TowerStorage for EtcdTowerStorage {
             .map_err(Self::etdc_to_tower_error)?;
 
         if !response.succeeded() {
+            return Err(TowerError::LostEtcdInstanceLock(saved_tower.pubkey()));
+        }
+        Ok(())
+    }
+}
+
+/// Default time a `QuorumTowerStorage` waits for each of its storages to store a tower
+pub const DEFAULT_TOWER_STORE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Stores the tower in several storages, e.g. a local file and one or more remote stores, so
+/// that a hot spare taking over the identity restores the last tower even if one of them was
+/// lost.
+///
+/// A tower is stored once it has been written to `write_quorum` of the storages, otherwise
+/// `store()` fails and the vote is not sent. A storage which has not stored the tower within
+/// `store_timeout` is not waited for, and is skipped by the next stores until it completes. A
+/// lost etcd instance lock fails `store()` regardless of the quorum, as another validator is
+/// then voting with the same identity. `load()` returns the newest of the valid towers found in
+/// the storages.
+///
+/// Each storage stores the towers in its own thread, which exits once the `QuorumTowerStorage`
+/// is dropped and its last store completed.
+pub struct QuorumTowerStorage {
+    storages: Vec<Arc<dyn TowerStorage>>,
+    write_quorum: usize,
+    store_timeout: Duration,
+    store_workers: Vec<TowerStoreWorker>,
+}
+
+/// Tower to store, and the sender of the result along with the index of the storage
+type TowerStoreRequest = (Arc<SavedTowerVersions>, Sender<(usize, Result<()>)>);
+
+struct TowerStoreWorker {
+    sender: Sender<TowerStoreRequest>,
+    // whether the storage is still storing a previous tower
+    storing: Arc<AtomicBool>,
+}
+
+impl TowerStoreWorker {
+    fn new(i: usize, storage: Arc<dyn TowerStorage>) -> Self {
+        let (sender, receiver) = unbounded::<TowerStoreRequest>();
+        let storing = Arc::new(AtomicBool::default());
+        let worker_storing = storing.clone();
+        thread::Builder::new()
+            .name(format!("solTowerStore{i:02}"))
+            .spawn(move || {
+                for (saved_tower, result_sender) in receiver {
+                    let result = storage.store(&saved_tower);
+                    worker_storing.store(false, Ordering::Release);
+                    // the result is dropped if the tower store stopped waiting for it
+                    let _ = result_sender.send((i, result));
+                }
+            })
+            .unwrap();
+        Self { sender, storing }
+    }
+}
+
+impl QuorumTowerStorage {
+    pub fn new(storages: Vec<Arc<dyn TowerStorage>>, write_quorum: usize) -> Self {
+        Self::new_with_store_timeout(storages, write_quorum, DEFAULT_TOWER_STORE_TIMEOUT)
+    }
+
+    pub fn new_with_store_timeout(
+        storages: Vec<Arc<dyn TowerStorage>>,
+        write_quorum: usize,
+        store_timeout: Duration,
+    ) -> Self {
+        assert!(
+            write_quorum > 0 && write_quorum <= storages.len(),
+            "invalid write quorum {write_quorum} of {} tower storages",
+            storages.len()
+        );
+        let store_workers = storages
+            .iter()
+            .enumerate()
+            .map(|(i, storage)| TowerStoreWorker::new(i, storage.clone()))
+            .collect();
+        Self {
+            storages,
+            write_quorum,
+            store_timeout,
+            store_workers,
+        }
+    }
+}
+
+impl TowerStorage for QuorumTowerStorage {
+    fn load(&self, node_pubkey: &Pubkey) -> Result<Tower> {
+        let mut newest_tower: Option<Tower> = None;
+        let mut load_error = None;
+        for (i, storage) in self.storages.iter().enumerate() {
+            match storage.load(node_pubkey) {
+                Ok(tower) => {
+                    let is_newer = newest_tower.as_ref().map_or(true, |newest_tower| {
+                        (tower.last_voted_slot(), tower.root())
+                            > (newest_tower.last_voted_slot(), newest_tower.root())
+                    });
+                    if is_newer {
+                        newest_tower = Some(tower);
+                    }
+                }
+                Err(err @ TowerError::LostEtcdInstanceLock(_)) => return Err(err),
+                Err(err) => {
+                    if !err.is_file_missing() {
+                        warn!("Unable to load tower from tower storage {}: {}", i, err);
+                    }
+                    // report a missing tower only if it is missing from all the storages
+                    if load_error
+                        .as_ref()
+                        .map_or(true, TowerError::is_file_missing)
+                    {
+                        load_error = Some(err);
+                    }
+                }
+            }
+        }
+        newest_tower.ok_or_else(|| load_error.unwrap())
+    }
+
+    fn store(&self, saved_tower: &SavedTowerVersions) -> Result<()> {
+        let saved_tower = Arc::new(saved_tower.clone());
+        let (sender, receiver) = unbounded();
+        let mut num_pending = 0;
+        for (i, worker) in self.store_workers.iter().enumerate() {
+            if worker.storing.swap(true, Ordering::AcqRel) {
+                error!(
+                    "Unable to save tower to tower storage {}: still saving a previous tower",
+                    i
+                );
+                continue;
+            }
+            if worker
+                .sender
+                .send((saved_tower.clone(), sender.clone()))
+                .is_err()
+            {
+                worker.storing.store(false, Ordering::Release);
+                error!(
+                    "Unable to save tower to tower storage {}: store thread exited",
+                    i
+                );
+                continue;
+            }
+            num_pending += 1;
+        }
+        drop(sender);
+
+        let deadline = Instant::now() + self.store_timeout;
+        let mut num_stored = 0;
+        while num_pending > 0 {
+            let Ok((i, result)) = receiver.recv_deadline(deadline) else {
+                error!(
+                    "Unable to save tower to {} tower storages within {:?}",
+                    num_pending, self.store_timeout
+                );
+                break;
+            };
+            num_pending -= 1;
+            match result {
+                Ok(()) => num_stored += 1,
+                Err(err @ TowerError::LostEtcdInstanceLock(_)) => return Err(err),
+                Err(err) => error!("Unable to save tower to tower storage {}: {}", i, err),
+            }
+        }
+        if num_stored < self.write_quorum {
             return Err(TowerError::IoError(io::Error::new(
                 io::ErrorKind::Other,
-                format!("Lost etcd instance lock for {}", saved_tower.pubkey()),
+                format!(
+                    "Saved tower to {num_stored} of {} tower storages, {} required",
+                    self.storages.len(),
+                    self.write_quorum
+                ),
             )));
         }
         Ok(())
@@ -419,4 +593,194 @@ pub mod test {
         assert_eq!(loaded.vote_state.root_slot, Some(1));
         assert_eq!(loaded.stray_restored_slot(), None);
     }
+
+    struct FailingTowerStorage;
+
+    impl TowerStorage for FailingTowerStorage {
+        fn load(&self, _node_pubkey: &Pubkey) -> Result<Tower> {
+            Err(TowerError::IoError(io::Error::new(
+                io::ErrorKind::Other,
+                "FailingTowerStorage::load()",
+            )))
+        }
+
+        fn store(&self, _saved_tower: &SavedTowerVersions) -> Result<()> {
+            Err(TowerError::IoError(io::Error::new(
+                io::ErrorKind::Other,
+                "FailingTowerStorage::store()",
+            )))
+        }
+    }
+
+    struct SlowTowerStorage(Duration);
+
+    impl TowerStorage for SlowTowerStorage {
+        fn load(&self, _node_pubkey: &Pubkey) -> Result<Tower> {
+            Err(TowerError::IoError(io::ErrorKind::NotFound.into()))
+        }
+
+        fn store(&self, _saved_tower: &SavedTowerVersions) -> Result<()> {
+            thread::sleep(self.0);
+            Ok(())
+        }
+    }
+
+    struct LostLockTowerStorage;
+
+    impl TowerStorage for LostLockTowerStorage {
+        fn load(&self, node_pubkey: &Pubkey) -> Result<Tower> {
+            Err(TowerError::LostEtcdInstanceLock(*node_pubkey))
+        }
+
+        fn store(&self, saved_tower: &SavedTowerVersions) -> Result<()> {
+            Err(TowerError::LostEtcdInstanceLock(saved_tower.pubkey()))
+        }
+    }
+
+    #[test]
+    fn test_quorum_tower_storage() {
+        let tower_paths = [TempDir::new().unwrap(), TempDir::new().unwrap()];
+        let file_storages: Vec<_> = tower_paths
+            .iter()
+            .map(|tower_path| Arc::new(FileTowerStorage::new(tower_path.path().to_path_buf())))
+            .collect();
+        let identity_keypair = Keypair::new();
+        let node_pubkey = identity_keypair.pubkey();
+        let mut tower = Tower::new_for_tests(10, 0.9);
+        tower.node_pubkey = node_pubkey;
+
+        let storages: Vec<Arc<dyn TowerStorage>> =
+            vec![file_storages[0].clone(), file_storages[1].clone()];
+        assert!(
+            Tower::restore(&QuorumTowerStorage::new(storages.clone(), 2), &node_pubkey)
+                .unwrap_err()
+                .is_file_missing()
+        );
+        // a storage failing to load may hold the tower, which is then not reported as missing
+        let tower_storage = QuorumTowerStorage::new(
+            storages
+                .into_iter()
+                .chain([Arc::new(FailingTowerStorage) as Arc<dyn TowerStorage>])
+                .collect(),
+            2,
+        );
+        assert!(!Tower::restore(&tower_storage, &node_pubkey)
+            .unwrap_err()
+            .is_file_missing());
+
+        tower.record_vote(1, Hash::default());
+        tower.save(&tower_storage, &identity_keypair).unwrap();
+        assert_eq!(
+            Tower::restore(&tower_storage, &node_pubkey)
+                .unwrap()
+                .last_voted_slot(),
+            Some(1)
+        );
+
+        // the newest tower is loaded, whichever storage holds it
+        tower.record_vote(2, Hash::default());
+        tower
+            .save(file_storages[1].as_ref(), &identity_keypair)
+            .unwrap();
+        assert_eq!(
+            Tower::restore(&tower_storage, &node_pubkey)
+                .unwrap()
+                .last_voted_slot(),
+            Some(2)
+        );
+
+        // a corrupted tower is ignored
+        fs::write(file_storages[1].filename(&node_pubkey), [0; 128]).unwrap();
+        assert_eq!(
+            Tower::restore(&tower_storage, &node_pubkey)
+                .unwrap()
+                .last_voted_slot(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_quorum_tower_storage_write_quorum() {
+        let tower_path = TempDir::new().unwrap();
+        let file_storage = Arc::new(FileTowerStorage::new(tower_path.path().to_path_buf()));
+        let identity_keypair = Keypair::new();
+        let mut tower = Tower::new_for_tests(10, 0.9);
+        tower.node_pubkey = identity_keypair.pubkey();
+
+        let storages: Vec<Arc<dyn TowerStorage>> = vec![
+            file_storage.clone(),
+            Arc::new(FailingTowerStorage),
+            Arc::new(FailingTowerStorage),
+        ];
+        let tower_storage = QuorumTowerStorage::new(storages.clone(), 2);
+        assert!(tower.save(&tower_storage, &identity_keypair).is_err());
+        let tower_storage = QuorumTowerStorage::new(storages, 1);
+        tower.save(&tower_storage, &identity_keypair).unwrap();
+
+        // the storages failing to load are ignored, unless no tower is found
+        assert_eq!(
+            Tower::restore(&tower_storage, &identity_keypair.pubkey())
+                .unwrap()
+                .node_pubkey,
+            identity_keypair.pubkey()
+        );
+        fs::remove_file(file_storage.filename(&identity_keypair.pubkey())).unwrap();
+        assert!(!Tower::restore(&tower_storage, &identity_keypair.pubkey())
+            .unwrap_err()
+            .is_file_missing());
+    }
+
+    #[test]
+    fn test_quorum_tower_storage_slow_storage() {
+        let tower_path = TempDir::new().unwrap();
+        let file_storage = Arc::new(FileTowerStorage::new(tower_path.path().to_path_buf()));
+        let identity_keypair = Keypair::new();
+        let mut tower = Tower::new_for_tests(10, 0.9);
+        tower.node_pubkey = identity_keypair.pubkey();
+
+        let storages: Vec<Arc<dyn TowerStorage>> = vec![
+            file_storage,
+            Arc::new(SlowTowerStorage(Duration::from_secs(5))),
+        ];
+        let tower_storage = QuorumTowerStorage::new_with_store_timeout(
+            storages.clone(),
+            1,
+            Duration::from_millis(100),
+        );
+        // the slow storage is not waited for once the timeout elapsed
+        let start = Instant::now();
+        tower.save(&tower_storage, &identity_keypair).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // the slow storage is skipped while still storing
+        tower.save(&tower_storage, &identity_keypair).unwrap();
+        // a storage not storing the tower in time fails a quorum requiring it
+        let tower_storage =
+            QuorumTowerStorage::new_with_store_timeout(storages, 2, Duration::from_millis(100));
+        assert!(tower.save(&tower_storage, &identity_keypair).is_err());
+    }
+
+    #[test]
+    fn test_quorum_tower_storage_lost_etcd_instance_lock() {
+        let tower_path = TempDir::new().unwrap();
+        let file_storage = Arc::new(FileTowerStorage::new(tower_path.path().to_path_buf()));
+        let identity_keypair = Keypair::new();
+        let mut tower = Tower::new_for_tests(10, 0.9);
+        tower.node_pubkey = identity_keypair.pubkey();
+        tower
+            .save(file_storage.as_ref(), &identity_keypair)
+            .unwrap();
+
+        // a lost instance lock is fatal even with the write quorum reached
+        let tower_storage =
+            QuorumTowerStorage::new(vec![file_storage, Arc::new(LostLockTowerStorage)], 1);
+        assert_matches!(
+            tower.save(&tower_storage, &identity_keypair),
+            Err(TowerError::LostEtcdInstanceLock(_))
+        );
+        assert_matches!(
+            Tower::restore(&tower_storage, &identity_keypair.pubkey()),
+            Err(TowerError::LostEtcdInstanceLock(_))
+        );
+    }
 }
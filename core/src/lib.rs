@@ -10,6 +10,7 @@
 
 pub mod accounts_hash_verifier;
 pub mod admin_rpc_post_init;
+pub mod banking_simulation;
 pub mod banking_stage;
 pub mod banking_trace;
 pub mod cache_block_meta_service;
@@ -11,6 +11,7 @@ use {
         commitment_service::{AggregateCommitmentService, CommitmentAggregationData},
         consensus::{
             fork_choice::{ForkChoice, SelectVoteAndResetForkResult},
+            fork_choice_snapshot::{ForkChoiceSnapshot, ForkChoiceSnapshotRequest},
             heaviest_subtree_fork_choice::HeaviestSubtreeForkChoice,
             latest_validator_votes_for_frozen_banks::LatestValidatorVotesForFrozenBanks,
             progress_map::{ForkProgress, ProgressMap, PropagatedStats, ReplaySlotStats},
@@ -248,6 +249,7 @@ pub struct ReplayStageConfig {
     // duplicate voting which can lead to slashing.
     pub wait_to_vote_slot: Option<Slot>,
     pub replay_slots_concurrently: bool,
+    pub fork_choice_snapshot_receiver: Receiver<ForkChoiceSnapshotRequest>,
 }
 
 #[derive(Default)]
@@ -530,6 +532,7 @@ impl ReplayStage {
             tower_storage,
             wait_to_vote_slot,
             replay_slots_concurrently,
+            fork_choice_snapshot_receiver,
         } = config;
 
         trace!("replay stage");
@@ -1068,6 +1071,13 @@ impl ReplayStage {
                 }
                 start_leader_time.stop();
 
+                for request in fork_choice_snapshot_receiver.try_iter() {
+                    let snapshot =
+                        ForkChoiceSnapshot::new(&tower, &progress, &heaviest_subtree_fork_choice);
+                    // the requester may have timed out and dropped the receiver
+                    let _ = request.response_sender.send(snapshot);
+                }
+
                 let mut wait_receive_time = Measure::start("wait_receive_time");
                 if !did_complete_bank {
                     // only wait for the signal if we did not just process a bank; maybe there are more slots available
@@ -11,7 +11,7 @@ use {
         },
         cluster_slots_service::{cluster_slots::ClusterSlots, ClusterSlotsService},
         completed_data_sets_service::CompletedDataSetsSender,
-        consensus::tower_storage::TowerStorage,
+        consensus::{fork_choice_snapshot::ForkChoiceSnapshotRequest, tower_storage::TowerStorage},
         cost_update_service::CostUpdateService,
         drop_bank_service::DropBankService,
         ledger_cleanup_service::LedgerCleanupService,
@@ -139,6 +139,7 @@ impl Tvu {
         turbine_quic_endpoint_sender: AsyncSender<(SocketAddr, Bytes)>,
         turbine_quic_endpoint_receiver: Receiver<(Pubkey, SocketAddr, Bytes)>,
         repair_quic_endpoint_sender: AsyncSender<LocalRequest>,
+        fork_choice_snapshot_receiver: Receiver<ForkChoiceSnapshotRequest>,
     ) -> Result<Self, String> {
         let TvuSockets {
             repair: repair_socket,
@@ -257,6 +258,7 @@ impl Tvu {
             tower_storage: tower_storage.clone(),
             wait_to_vote_slot,
             replay_slots_concurrently: tvu_config.replay_slots_concurrently,
+            fork_choice_snapshot_receiver,
         };
 
         let (voting_sender, voting_receiver) = unbounded();
@@ -493,6 +495,7 @@ pub mod tests {
             turbine_quic_endpoint_sender,
             turbine_quic_endpoint_receiver,
             repair_quic_endpoint_sender,
+            unbounded().1,
         )
         .expect("assume success");
         exit.store(true, Ordering::Relaxed);
@@ -93,6 +93,7 @@ use {
         },
         bank::Bank,
         bank_forks::BankForks,
+        builtins::BuiltinPrototype,
         commitment::BlockCommitmentCache,
         prioritization_fee_cache::PrioritizationFeeCache,
         runtime_config::RuntimeConfig,
@@ -167,6 +168,7 @@ impl BlockVerificationMethod {
 pub enum BlockProductionMethod {
     #[default]
     ThreadLocalMultiIterator,
+    CentralScheduler,
 }
 
 impl BlockProductionMethod {
@@ -259,6 +261,8 @@ pub struct ValidatorConfig {
     pub block_production_method: BlockProductionMethod,
     pub generator_config: Option<GeneratorConfig>,
     pub use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup,
+    /// Builtin programs added to the bank in addition to the ones of the runtime.
+    pub additional_builtins: Option<Vec<BuiltinPrototype>>,
 }
 
 impl Default for ValidatorConfig {
@@ -326,6 +330,7 @@ impl Default for ValidatorConfig {
             block_production_method: BlockProductionMethod::default(),
             generator_config: None,
             use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup::default(),
+            additional_builtins: None,
         }
     }
 }
@@ -633,6 +638,11 @@ impl Validator {
         let transaction_notifier = geyser_plugin_service
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_transaction_notifier());
+        let transaction_trace_notifications_enabled = geyser_plugin_service
+            .as_ref()
+            .map_or(false, |geyser_plugin_service| {
+                geyser_plugin_service.transaction_trace_notifications_enabled()
+            });
 
         let entry_notifier = geyser_plugin_service
             .as_ref()
@@ -695,6 +705,7 @@ impl Validator {
             &start_progress,
             accounts_update_notifier,
             transaction_notifier,
+            transaction_trace_notifications_enabled,
             entry_notifier,
             Some(poh_timing_point_sender.clone()),
         )?;
@@ -1074,11 +1085,13 @@ impl Validator {
             exit.clone(),
         );
 
+        let (fork_choice_snapshot_sender, fork_choice_snapshot_receiver) = unbounded();
         *admin_rpc_service_post_init.write().unwrap() = Some(AdminRpcRequestMetadataPostInit {
             bank_forks: bank_forks.clone(),
             cluster_info: cluster_info.clone(),
             vote_account: *vote_account,
             repair_whitelist: config.repair_whitelist.clone(),
+            fork_choice_snapshot_sender,
         });
 
         let waited_for_supermajority = wait_for_supermajority(
@@ -1255,6 +1268,7 @@ impl Validator {
             turbine_quic_endpoint_sender.clone(),
             turbine_quic_endpoint_receiver,
             repair_quic_endpoint_sender,
+            fork_choice_snapshot_receiver,
         )?;
 
         let tpu = Tpu::new(
@@ -1296,6 +1310,7 @@ impl Validator {
             tracer_thread,
             tpu_enable_udp,
             &prioritization_fee_cache,
+            config.block_production_method.clone(),
             config.generator_config.clone(),
         );
 
@@ -1649,6 +1664,7 @@ fn load_blockstore(
     start_progress: &Arc<RwLock<ValidatorStartProgress>>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     transaction_notifier: Option<TransactionNotifierLock>,
+    transaction_trace_notifications_enabled: bool,
     entry_notifier: Option<EntryNotifierLock>,
     poh_timing_point_sender: Option<PohTimingSender>,
 ) -> Result<
@@ -1725,6 +1741,7 @@ fn load_blockstore(
         accounts_db_skip_shrink: config.accounts_db_skip_shrink,
         runtime_config: config.runtime_config.clone(),
         use_snapshot_archives_at_startup: config.use_snapshot_archives_at_startup,
+        additional_builtins: config.additional_builtins.clone(),
         ..blockstore_processor::ProcessOptions::default()
     };
 
@@ -1739,6 +1756,7 @@ fn load_blockstore(
                 enable_rpc_transaction_history,
                 config.rpc_config.enable_extended_tx_metadata_storage,
                 transaction_notifier,
+                transaction_trace_notifications_enabled,
             )
         } else {
             TransactionHistoryServices::default()
@@ -2125,11 +2143,13 @@ fn initialize_rpc_transaction_history_services(
     enable_rpc_transaction_history: bool,
     enable_extended_tx_metadata_storage: bool,
     transaction_notifier: Option<TransactionNotifierLock>,
+    transaction_trace_notifications_enabled: bool,
 ) -> TransactionHistoryServices {
     let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
     let (transaction_status_sender, transaction_status_receiver) = unbounded();
     let transaction_status_sender = Some(TransactionStatusSender {
         sender: transaction_status_sender,
+        enable_instruction_trace_recording: transaction_trace_notifications_enabled,
     });
     let transaction_status_service = Some(TransactionStatusService::new(
         transaction_status_receiver,
@@ -7,7 +7,7 @@ use {
     solana_gossip::cluster_info::ClusterInfo,
     solana_measure::measure::Measure,
     solana_poh::poh_recorder::PohRecorder,
-    solana_sdk::{clock::Slot, transaction::Transaction},
+    solana_sdk::{clock::Slot, signature::Signer, transaction::Transaction},
     std::{
         sync::{Arc, RwLock},
         thread::{self, Builder, JoinHandle},
@@ -68,14 +68,27 @@ impl VotingService {
         tower_storage: &dyn TowerStorage,
         vote_op: VoteOp,
     ) {
-        if let VoteOp::PushVote { saved_tower, .. } = &vote_op {
-            let mut measure = Measure::start("tower_save-ms");
-            if let Err(err) = tower_storage.store(saved_tower) {
-                error!("Unable to save tower to storage: {:?}", err);
-                std::process::exit(1);
+        {
+            // Holding the identity keypair until the tower is saved makes an identity change wait
+            // for the tower of the vote in flight, and drops the votes of the previous identity
+            // still queued once the identity changed
+            let identity_keypair = cluster_info.keypair();
+            if vote_op.tx().message.account_keys.first() != Some(&identity_keypair.pubkey()) {
+                warn!(
+                    "Dropping vote of a previous identity, the identity is now {}",
+                    identity_keypair.pubkey()
+                );
+                return;
+            }
+            if let VoteOp::PushVote { saved_tower, .. } = &vote_op {
+                let mut measure = Measure::start("tower_save-ms");
+                if let Err(err) = tower_storage.store(saved_tower) {
+                    error!("Unable to save tower to storage: {:?}", err);
+                    std::process::exit(1);
+                }
+                measure.stop();
+                inc_new_counter_info!("tower_save-ms", measure.as_ms() as usize);
             }
-            measure.stop();
-            inc_new_counter_info!("tower_save-ms", measure.as_ms() as usize);
         }
 
         let _ = cluster_info.send_transaction(
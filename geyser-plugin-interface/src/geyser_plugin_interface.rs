@@ -8,7 +8,7 @@ use {
         signature::Signature,
         transaction::SanitizedTransaction,
     },
-    solana_transaction_status::{Reward, TransactionStatusMeta},
+    solana_transaction_status::{InstructionTrace, Reward, TransactionStatusMeta},
     std::{any::Any, error, io},
     thiserror::Error,
 };
@@ -162,6 +162,34 @@ pub enum ReplicaTransactionInfoVersions<'a> {
     V0_0_2(&'a ReplicaTransactionInfoV2<'a>),
 }
 
+/// The execution trace of a transaction
+#[derive(Clone, Debug)]
+pub struct ReplicaTransactionTraceInfo<'a> {
+    /// The first signature of the transaction, used for identifying the transaction.
+    pub signature: &'a Signature,
+
+    /// Indicates if the transaction is a simple vote transaction.
+    pub is_vote: bool,
+
+    /// The sanitized transaction.
+    pub transaction: &'a SanitizedTransaction,
+
+    /// The transaction's index in the block
+    pub index: usize,
+
+    /// Every instruction executed by the transaction, including the inner
+    /// instructions invoked via CPI, in invocation order. The trace of a
+    /// failed transaction ends with the instruction which failed.
+    pub instructions: &'a [InstructionTrace],
+}
+
+/// A wrapper to future-proof ReplicaTransactionTraceInfo handling. To make a change to the
+/// structure of ReplicaTransactionTraceInfo, add an new enum variant wrapping a newer version,
+/// which will force plugin implementations to handle the change.
+pub enum ReplicaTransactionTraceInfoVersions<'a> {
+    V0_0_1(&'a ReplicaTransactionTraceInfo<'a>),
+}
+
 #[derive(Clone, Debug)]
 pub struct ReplicaEntryInfo<'a> {
     /// The slot number of the block containing this Entry
@@ -272,7 +300,7 @@ pub type Result<T> = std::result::Result<T, GeyserPluginError>;
 /// Geyser plugins must describe desired behavior for load and unload,
 /// as well as how they will handle streamed data.
 pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
     /// The callback called when a plugin is loaded by the system,
     /// used for doing whatever initialization is required by the plugin.
@@ -328,6 +356,16 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called with the execution trace of a transaction processed in a slot.
+    #[allow(unused_variables)]
+    fn notify_transaction_trace(
+        &self,
+        transaction_trace: ReplicaTransactionTraceInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Called when an entry is executed.
     #[allow(unused_variables)]
     fn notify_entry(&self, entry: ReplicaEntryInfoVersions) -> Result<()> {
@@ -354,6 +392,13 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         false
     }
 
+    /// Check if the plugin is interested in transaction execution traces
+    /// Default is false -- if the plugin is interested in
+    /// transaction execution traces, return true.
+    fn transaction_trace_notifications_enabled(&self) -> bool {
+        false
+    }
+
     /// Check if the plugin is interested in entry data
     /// Default is false -- if the plugin is interested in
     /// entry data, return true.
@@ -0,0 +1,181 @@
+/// Module implementing the account filter of a plugin config file, evaluated
+/// by the plugin manager before notifying the plugin of an account update
+use {
+    crate::geyser_plugin_manager::GeyserPluginManagerError,
+    serde_derive::Deserialize,
+    solana_rpc_client_api::filter::RpcFilterType,
+    solana_sdk::{account::ReadableAccount, pubkey::Pubkey},
+    std::{collections::HashSet, str::FromStr},
+};
+
+/// The `account_filter` section of a plugin config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountFilterConfig {
+    #[serde(default)]
+    accounts: Vec<String>,
+    #[serde(default)]
+    owners: Vec<String>,
+    /// Filters on the account, in the format of the `getProgramAccounts`
+    /// filters
+    #[serde(default)]
+    filters: Vec<RpcFilterType>,
+}
+
+/// Selects the account updates a plugin is notified of. An account update is
+/// selected if its account is one of `accounts` or is owned by one of
+/// `owners`, and passes all of `filters`. Empty `accounts` and `owners`
+/// select all the accounts.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountFilter {
+    accounts: HashSet<Pubkey>,
+    owners: HashSet<Pubkey>,
+    filters: Vec<RpcFilterType>,
+}
+
+impl AccountFilter {
+    /// Returns the account filter of a plugin config file, if any
+    pub fn from_plugin_config(
+        config: &serde_json::Value,
+    ) -> Result<Option<Self>, GeyserPluginManagerError> {
+        let config: AccountFilterConfig = match config.get("account_filter") {
+            None | Some(serde_json::Value::Null) => return Ok(None),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|err| {
+                GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                    "Invalid account_filter section in the plugin config file, error: {err:?}"
+                ))
+            })?,
+        };
+        Self::new(config).map(Some)
+    }
+
+    fn new(config: AccountFilterConfig) -> Result<Self, GeyserPluginManagerError> {
+        let parse_pubkeys = |keys: Vec<String>| {
+            keys.into_iter()
+                .map(|key| {
+                    Pubkey::from_str(&key).map_err(|err| {
+                        GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                            "Invalid pubkey {key} in the account_filter section, error: {err:?}"
+                        ))
+                    })
+                })
+                .collect::<Result<HashSet<_>, _>>()
+        };
+        let filters = config
+            .filters
+            .into_iter()
+            .map(|mut filter| {
+                filter.verify().map_err(|err| {
+                    GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                        "Invalid filter {filter:?} in the account_filter section, error: {err}"
+                    ))
+                })?;
+                // decode the memcmp bytes once rather than on every update
+                if let RpcFilterType::Memcmp(memcmp) = &mut filter {
+                    memcmp.convert_to_raw_bytes().map_err(|err| {
+                        GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                            "Invalid memcmp filter in the account_filter section, error: {err}"
+                        ))
+                    })?;
+                }
+                Ok(filter)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            accounts: parse_pubkeys(config.accounts)?,
+            owners: parse_pubkeys(config.owners)?,
+            filters,
+        })
+    }
+
+    /// Whether an update of `account` at `pubkey` is selected
+    pub fn allows(&self, pubkey: &Pubkey, account: &impl ReadableAccount) -> bool {
+        let selected = (self.accounts.is_empty() && self.owners.is_empty())
+            || self.accounts.contains(pubkey)
+            || self.owners.contains(account.owner());
+        selected && self.filters.iter().all(|filter| filter.allows(account))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_rpc_client_api::filter::{Memcmp, MemcmpEncodedBytes},
+        solana_sdk::account::AccountSharedData,
+    };
+
+    #[test]
+    fn test_account_filter_config() {
+        let config = serde_json::json!({"libpath": "libplugin.so"});
+        assert_eq!(AccountFilter::from_plugin_config(&config).unwrap(), None);
+
+        let owner = Pubkey::new_unique();
+        let config = serde_json::json!({
+            "libpath": "libplugin.so",
+            "account_filter": {
+                "owners": [owner.to_string()],
+                "filters": [
+                    {"dataSize": 8},
+                    {"memcmp": {"offset": 4, "bytes": bs58::encode([1, 2]).into_string()}},
+                ],
+            },
+        });
+        assert_eq!(
+            AccountFilter::from_plugin_config(&config).unwrap(),
+            Some(AccountFilter {
+                accounts: HashSet::default(),
+                owners: HashSet::from([owner]),
+                filters: vec![
+                    RpcFilterType::DataSize(8),
+                    RpcFilterType::Memcmp(Memcmp::new(4, MemcmpEncodedBytes::Bytes(vec![1, 2]))),
+                ],
+            })
+        );
+
+        let config = serde_json::json!({"account_filter": {"owners": ["not a pubkey"]}});
+        assert!(AccountFilter::from_plugin_config(&config).is_err());
+        let config = serde_json::json!({"account_filter": {"program": []}});
+        assert!(AccountFilter::from_plugin_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_account_filter_allows() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut account = AccountSharedData::new(1, 8, &owner);
+        account.data_as_mut_slice()[4..6].copy_from_slice(&[1, 2]);
+
+        // an empty filter selects every account
+        assert!(AccountFilter::default().allows(&pubkey, &account));
+
+        let by_account = AccountFilter {
+            accounts: HashSet::from([pubkey]),
+            ..AccountFilter::default()
+        };
+        assert!(by_account.allows(&pubkey, &account));
+        assert!(!by_account.allows(&Pubkey::new_unique(), &account));
+
+        let by_owner = AccountFilter {
+            owners: HashSet::from([owner]),
+            ..AccountFilter::default()
+        };
+        assert!(by_owner.allows(&Pubkey::new_unique(), &account));
+        assert!(!by_owner.allows(
+            &pubkey,
+            &AccountSharedData::new(1, 8, &Pubkey::new_unique())
+        ));
+
+        let by_data = AccountFilter {
+            owners: HashSet::from([owner]),
+            filters: vec![
+                RpcFilterType::DataSize(8),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(4, vec![1, 2])),
+            ],
+            ..AccountFilter::default()
+        };
+        assert!(by_data.allows(&pubkey, &account));
+        assert!(!by_data.allows(&pubkey, &AccountSharedData::new(1, 8, &owner)));
+        assert!(!by_data.allows(&pubkey, &AccountSharedData::new(1, 9, &owner)));
+    }
+}
@@ -36,7 +36,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         if let Some(account_info) =
             self.accountinfo_from_shared_account_data(account, txn, pubkey, write_version)
         {
-            self.notify_plugins_of_account_update(account_info, slot, false);
+            self.notify_plugins_of_account_update(pubkey, account, account_info, slot, false);
         }
     }
 
@@ -44,7 +44,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         let mut measure_all = Measure::start("geyser-plugin-notify-account-restore-all");
         let mut measure_copy = Measure::start("geyser-plugin-copy-stored-account-info");
 
-        let account = self.accountinfo_from_stored_account_meta(account);
+        let account_info = self.accountinfo_from_stored_account_meta(account);
         measure_copy.stop();
 
         inc_new_counter_debug!(
@@ -54,8 +54,14 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
             100000
         );
 
-        if let Some(account_info) = account {
-            self.notify_plugins_of_account_update(account_info, slot, true);
+        if let Some(account_info) = account_info {
+            self.notify_plugins_of_account_update(
+                account.pubkey(),
+                account,
+                account_info,
+                slot,
+                true,
+            );
         }
         measure_all.stop();
 
@@ -139,8 +145,12 @@ impl AccountsUpdateNotifierImpl {
         })
     }
 
+    /// Notifies the plugins whose account filter selects the update of
+    /// `stored_account` at `pubkey`, described by `account`
     fn notify_plugins_of_account_update(
         &self,
+        pubkey: &Pubkey,
+        stored_account: &impl ReadableAccount,
         account: ReplicaAccountInfoV3,
         slot: Slot,
         is_startup: bool,
@@ -152,6 +162,9 @@ impl AccountsUpdateNotifierImpl {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
+            if !plugin.account_update_selected(pubkey, stored_account) {
+                continue;
+            }
             let mut measure = Measure::start("geyser-plugin-update-account");
             match plugin.update_account(
                 ReplicaAccountInfoVersions::V0_0_3(&account),
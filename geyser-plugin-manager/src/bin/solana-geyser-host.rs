@@ -0,0 +1,37 @@
+use {
+    clap::{crate_description, crate_name, App, Arg},
+    log::*,
+    solana_geyser_plugin_manager::plugin_host::run_plugin_host,
+    std::{path::PathBuf, process::exit},
+};
+
+fn main() {
+    solana_logger::setup_with_default("solana=info");
+    let matches = App::new(crate_name!())
+        .about(crate_description!())
+        .version(solana_version::version!())
+        .arg(
+            Arg::with_name("shm_path")
+                .long("shm-path")
+                .value_name("PATH")
+                .takes_value(true)
+                .required(true)
+                .help("Shared memory ring buffer created by the validator"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+                .help("Geyser plugin config file"),
+        )
+        .get_matches();
+
+    let shm_path = PathBuf::from(matches.value_of("shm_path").unwrap());
+    let config_file = PathBuf::from(matches.value_of("config").unwrap());
+    if let Err(err) = run_plugin_host(&shm_path, &config_file) {
+        error!("{err}");
+        exit(1);
+    }
+}
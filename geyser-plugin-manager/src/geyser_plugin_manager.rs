@@ -1,16 +1,62 @@
 use {
+    crate::account_filter::AccountFilter,
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     jsonrpc_server_utils::tokio::sync::oneshot::Sender as OneShotSender,
     libloading::Library,
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
-    std::path::Path,
+    solana_sdk::{account::ReadableAccount, pubkey::Pubkey},
+    std::{
+        ops::{Deref, DerefMut},
+        path::Path,
+    },
 };
 
+/// A plugin loaded by the manager, along with the account filter of its
+/// config file
+#[derive(Debug)]
+pub struct LoadedGeyserPlugin {
+    plugin: Box<dyn GeyserPlugin>,
+    account_filter: Option<AccountFilter>,
+}
+
+impl LoadedGeyserPlugin {
+    pub fn new(plugin: Box<dyn GeyserPlugin>, account_filter: Option<AccountFilter>) -> Self {
+        Self {
+            plugin,
+            account_filter,
+        }
+    }
+
+    /// Whether the plugin is to be notified of an update of `account` at
+    /// `pubkey`
+    pub fn account_update_selected(&self, pubkey: &Pubkey, account: &impl ReadableAccount) -> bool {
+        self.account_filter
+            .as_ref()
+            .map(|account_filter| account_filter.allows(pubkey, account))
+            .unwrap_or(true)
+    }
+}
+
+impl Deref for LoadedGeyserPlugin {
+    type Target = Box<dyn GeyserPlugin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.plugin
+    }
+}
+
+impl DerefMut for LoadedGeyserPlugin {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.plugin
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct GeyserPluginManager {
-    pub plugins: Vec<Box<dyn GeyserPlugin>>,
-    libs: Vec<Library>,
+    pub plugins: Vec<LoadedGeyserPlugin>,
+    /// `None` for the plugins running out of process
+    libs: Vec<Option<Library>>,
 }
 
 impl GeyserPluginManager {
@@ -54,6 +100,16 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in transaction execution traces
+    pub fn transaction_trace_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.transaction_trace_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Check if there is any plugin interested in entry data
     pub fn entry_notifications_enabled(&self) -> bool {
         for plugin in &self.plugins {
@@ -252,6 +308,9 @@ pub enum GeyserPluginManagerError {
 
     #[error("The GeyserPlugin on_load method failed")]
     PluginStartError(String),
+
+    #[error("Cannot start the out-of-process plugin host: {0}")]
+    PluginHostStartError(String),
 }
 
 /// # Safety
@@ -261,13 +320,44 @@ pub enum GeyserPluginManagerError {
 ///
 /// This returns the geyser plugin, the dynamic library, and the parsed config file as a &str.
 /// (The geyser plugin interface requires a &str for the on_load method).
+///
+/// Plugins configured to run out of process are loaded by a `solana-geyser-host` process
+/// instead, and have no dynamic library.
 #[cfg(not(test))]
 pub(crate) fn load_plugin_from_config(
     geyser_plugin_config_file: &Path,
-) -> Result<(Box<dyn GeyserPlugin>, Library, &str), GeyserPluginManagerError> {
-    use std::{fs::File, io::Read, path::PathBuf};
-    type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
-    use libloading::Symbol;
+) -> Result<(LoadedGeyserPlugin, Option<Library>, &str), GeyserPluginManagerError> {
+    use crate::remote_plugin::{PluginHostConfig, RemoteGeyserPlugin};
+
+    let config = read_plugin_config(geyser_plugin_config_file)?;
+    let config_file = geyser_plugin_config_file
+        .as_os_str()
+        .to_str()
+        .ok_or(GeyserPluginManagerError::InvalidPluginPath)?;
+    let account_filter = AccountFilter::from_plugin_config(&config)?;
+
+    if let Some(host_config) = PluginHostConfig::from_plugin_config(&config)? {
+        let plugin = RemoteGeyserPlugin::start(geyser_plugin_config_file, host_config)?;
+        return Ok((
+            LoadedGeyserPlugin::new(Box::new(plugin), account_filter),
+            None,
+            config_file,
+        ));
+    }
+
+    let (plugin, lib) = load_plugin_library(geyser_plugin_config_file, &config)?;
+    Ok((
+        LoadedGeyserPlugin::new(plugin, account_filter),
+        Some(lib),
+        config_file,
+    ))
+}
+
+/// Reads and parses the Json5 plugin config file
+pub(crate) fn read_plugin_config(
+    geyser_plugin_config_file: &Path,
+) -> Result<serde_json::Value, GeyserPluginManagerError> {
+    use std::{fs::File, io::Read};
 
     let mut file = match File::open(geyser_plugin_config_file) {
         Ok(file) => file,
@@ -285,16 +375,25 @@ pub(crate) fn load_plugin_from_config(
         )));
     }
 
-    let result: serde_json::Value = match json5::from_str(&contents) {
-        Ok(value) => value,
-        Err(err) => {
-            return Err(GeyserPluginManagerError::InvalidConfigFileFormat(format!(
-                "The config file {geyser_plugin_config_file:?} is not in a valid Json5 format, error: {err:?}"
-            )));
-        }
-    };
+    json5::from_str(&contents).map_err(|err| {
+        GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+            "The config file {geyser_plugin_config_file:?} is not in a valid Json5 format, error: {err:?}"
+        ))
+    })
+}
 
-    let libpath = result["libpath"]
+/// # Safety
+///
+/// This function loads the dynamically linked library specified by the `libpath` of the
+/// parsed config file. The library must do necessary initializations.
+pub(crate) fn load_plugin_library(
+    geyser_plugin_config_file: &Path,
+    config: &serde_json::Value,
+) -> Result<(Box<dyn GeyserPlugin>, Library), GeyserPluginManagerError> {
+    use {libloading::Symbol, std::path::PathBuf};
+    type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
+
+    let libpath = config["libpath"]
         .as_str()
         .ok_or(GeyserPluginManagerError::LibPathNotSet)?;
     let mut libpath = PathBuf::from(libpath);
@@ -307,11 +406,6 @@ pub(crate) fn load_plugin_from_config(
         libpath = config_dir.join(libpath);
     }
 
-    let config_file = geyser_plugin_config_file
-        .as_os_str()
-        .to_str()
-        .ok_or(GeyserPluginManagerError::InvalidPluginPath)?;
-
     let (plugin, lib) = unsafe {
         let lib = Library::new(libpath)
             .map_err(|e| GeyserPluginManagerError::PluginLoadError(e.to_string()))?;
@@ -321,7 +415,7 @@ pub(crate) fn load_plugin_from_config(
         let plugin_raw = constructor();
         (Box::from_raw(plugin_raw), lib)
     };
-    Ok((plugin, lib, config_file))
+    Ok((plugin, lib))
 }
 
 #[cfg(test)]
@@ -337,7 +431,7 @@ const TESTPLUGIN2_CONFIG: &str = "TESTPLUGIN2_CONFIG";
 #[cfg(test)]
 pub(crate) fn load_plugin_from_config(
     geyser_plugin_config_file: &Path,
-) -> Result<(Box<dyn GeyserPlugin>, Library, &str), GeyserPluginManagerError> {
+) -> Result<(LoadedGeyserPlugin, Option<Library>, &str), GeyserPluginManagerError> {
     if geyser_plugin_config_file.ends_with(TESTPLUGIN_CONFIG) {
         Ok(tests::dummy_plugin_and_library(
             tests::TestPlugin,
@@ -359,7 +453,7 @@ pub(crate) fn load_plugin_from_config(
 mod tests {
     use {
         crate::geyser_plugin_manager::{
-            GeyserPluginManager, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
         },
         libloading::Library,
         solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
@@ -369,10 +463,10 @@ mod tests {
     pub(super) fn dummy_plugin_and_library<P: GeyserPlugin>(
         plugin: P,
         config_path: &'static str,
-    ) -> (Box<dyn GeyserPlugin>, Library, &'static str) {
+    ) -> (LoadedGeyserPlugin, Option<Library>, &'static str) {
         (
-            Box::new(plugin),
-            Library::from(libloading::os::unix::Library::this()),
+            LoadedGeyserPlugin::new(Box::new(plugin), None),
+            Some(Library::from(libloading::os::unix::Library::this())),
             config_path,
         )
     }
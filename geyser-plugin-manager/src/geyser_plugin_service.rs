@@ -37,6 +37,7 @@ pub struct GeyserPluginService {
     transaction_notifier: Option<TransactionNotifierLock>,
     entry_notifier: Option<EntryNotifierLock>,
     block_metadata_notifier: Option<BlockMetadataNotifierLock>,
+    transaction_trace_notifications_enabled: bool,
 }
 
 impl GeyserPluginService {
@@ -79,7 +80,10 @@ impl GeyserPluginService {
 
         let account_data_notifications_enabled =
             plugin_manager.account_data_notifications_enabled();
-        let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
+        let transaction_trace_notifications_enabled =
+            plugin_manager.transaction_trace_notifications_enabled();
+        let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled()
+            || transaction_trace_notifications_enabled;
         let entry_notifications_enabled = plugin_manager.entry_notifications_enabled();
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
@@ -143,6 +147,7 @@ impl GeyserPluginService {
             transaction_notifier,
             entry_notifier,
             block_metadata_notifier,
+            transaction_trace_notifications_enabled,
         })
     }
 
@@ -164,6 +169,12 @@ impl GeyserPluginService {
         self.transaction_notifier.clone()
     }
 
+    /// Whether any plugin is interested in transaction traces, which are only
+    /// recorded when this is the case
+    pub fn transaction_trace_notifications_enabled(&self) -> bool {
+        self.transaction_trace_notifications_enabled
+    }
+
     pub fn get_entry_notifier(&self) -> Option<EntryNotifierLock> {
         self.entry_notifier.clone()
     }
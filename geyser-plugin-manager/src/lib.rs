@@ -1,9 +1,13 @@
+pub mod account_filter;
 pub mod accounts_update_notifier;
 pub mod block_metadata_notifier;
 pub mod block_metadata_notifier_interface;
 pub mod entry_notifier;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
+pub mod plugin_host;
+pub mod remote_plugin;
+pub mod shm_ring_buffer;
 pub mod slot_status_notifier;
 pub mod slot_status_observer;
 pub mod transaction_notifier;
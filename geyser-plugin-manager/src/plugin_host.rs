@@ -0,0 +1,427 @@
+/// Module implementing the `solana-geyser-host` process, which loads a plugin
+/// and delivers to it the notifications forwarded by a `RemoteGeyserPlugin`
+use {
+    crate::{
+        geyser_plugin_manager::{load_plugin_library, read_plugin_config},
+        remote_plugin::{plugin_flags, HostMessage, HostTransaction},
+        shm_ring_buffer::{ConsumerState, ProducerState, ShmRingBuffer},
+    },
+    log::*,
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoV2, ReplicaAccountInfoVersions,
+        ReplicaBlockInfo, ReplicaBlockInfoV2, ReplicaBlockInfoVersions, ReplicaEntryInfo,
+        ReplicaEntryInfoVersions, ReplicaTransactionInfo, ReplicaTransactionInfoV2,
+        ReplicaTransactionInfoVersions, ReplicaTransactionTraceInfo,
+        ReplicaTransactionTraceInfoVersions, Result,
+    },
+    solana_sdk::{
+        message::SimpleAddressLoader,
+        transaction::{SanitizedTransaction, SanitizedVersionedTransaction},
+    },
+    solana_transaction_status::TransactionStatusMeta,
+    std::{os::unix::process::parent_id, path::Path, thread, time::Duration},
+};
+
+const IDLE_SLEEP: Duration = Duration::from_millis(1);
+
+/// Loads the plugin configured in `config_file` and delivers to it the
+/// notifications read from the ring buffer at `shm_path`, until the
+/// validator closes the ring buffer or exits
+pub fn run_plugin_host(shm_path: &Path, config_file: &Path) -> std::result::Result<(), String> {
+    let buffer = ShmRingBuffer::open(shm_path)
+        .map_err(|err| format!("Failed to open the ring buffer {shm_path:?}: {err}"))?;
+    buffer.heartbeat();
+
+    let (mut plugin, lib) = match load_plugin(config_file) {
+        Ok(plugin) => plugin,
+        Err(err) => {
+            buffer.set_plugin_info(0, &err);
+            buffer.set_consumer_state(ConsumerState::Failed);
+            return Err(err);
+        }
+    };
+    buffer.set_plugin_info(plugin_flags(plugin.as_ref()), plugin.name());
+    buffer.set_consumer_state(ConsumerState::Ready);
+    info!("Loaded plugin {} out of process", plugin.name());
+
+    let validator_pid = parent_id();
+    let mut frame = Vec::new();
+    loop {
+        buffer.heartbeat();
+        if buffer.try_pop(&mut frame) {
+            let result = bincode::deserialize(&frame)
+                .map_err(|err| GeyserPluginError::Custom(err))
+                .and_then(|message| dispatch(plugin.as_ref(), message));
+            if let Err(err) = result {
+                error!("Failed to notify plugin {}: {err}", plugin.name());
+            }
+            continue;
+        }
+        if buffer.producer_state() == ProducerState::Closed {
+            break;
+        }
+        if parent_id() != validator_pid {
+            warn!("The validator exited, unloading plugin {}", plugin.name());
+            break;
+        }
+        thread::sleep(IDLE_SLEEP);
+    }
+
+    info!("Unloading plugin {}", plugin.name());
+    plugin.on_unload();
+    // the plugin must be dropped before the library implementing it
+    drop(plugin);
+    drop(lib);
+    buffer.set_consumer_state(ConsumerState::Exited);
+    Ok(())
+}
+
+fn load_plugin(
+    config_file: &Path,
+) -> std::result::Result<(Box<dyn GeyserPlugin>, libloading::Library), String> {
+    let config = read_plugin_config(config_file).map_err(|err| format!("{err}: {err:?}"))?;
+    let (mut plugin, lib) =
+        load_plugin_library(config_file, &config).map_err(|err| format!("{err}: {err:?}"))?;
+    let config_file = config_file
+        .to_str()
+        .ok_or_else(|| format!("Invalid config file path {config_file:?}"))?;
+    plugin
+        .on_load(config_file)
+        .map_err(|err| format!("on_load method of plugin {} failed: {err}", plugin.name()))?;
+    Ok((plugin, lib))
+}
+
+fn sanitize_transaction(transaction: HostTransaction) -> Result<SanitizedTransaction> {
+    let HostTransaction {
+        is_vote,
+        transaction,
+        message_hash,
+        loaded_addresses,
+        ..
+    } = transaction;
+    SanitizedVersionedTransaction::try_from(transaction)
+        .and_then(|transaction| {
+            SanitizedTransaction::try_new(
+                transaction,
+                message_hash,
+                is_vote,
+                SimpleAddressLoader::Enabled(loaded_addresses),
+            )
+        })
+        .map_err(|err| GeyserPluginError::TransactionUpdateError {
+            msg: format!("Failed to sanitize the transaction: {err}"),
+        })
+}
+
+/// Delivers a notification to the plugin, rebuilding the `Replica*` structs
+/// from the forwarded message
+fn dispatch(plugin: &dyn GeyserPlugin, message: HostMessage) -> Result<()> {
+    match message {
+        HostMessage::UpdateAccount {
+            account,
+            slot,
+            is_startup,
+        } => {
+            let account_info = ReplicaAccountInfoV2 {
+                pubkey: &account.pubkey,
+                lamports: account.lamports,
+                owner: &account.owner,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: &account.data,
+                write_version: account.write_version,
+                txn_signature: account.txn_signature.as_ref(),
+            };
+            plugin.update_account(
+                ReplicaAccountInfoVersions::V0_0_2(&account_info),
+                slot,
+                is_startup,
+            )
+        }
+        HostMessage::EndOfStartup => plugin.notify_end_of_startup(),
+        HostMessage::UpdateSlotStatus {
+            slot,
+            parent,
+            status,
+        } => plugin.update_slot_status(slot, parent, status.into()),
+        HostMessage::Transaction {
+            transaction,
+            transaction_status_meta,
+            index,
+            slot,
+        } => {
+            let signature = transaction.signature;
+            let is_vote = transaction.is_vote;
+            let loaded_addresses = transaction.loaded_addresses.clone();
+            let transaction = sanitize_transaction(transaction)?;
+            let mut transaction_status_meta = TransactionStatusMeta::from(transaction_status_meta);
+            transaction_status_meta.loaded_addresses = loaded_addresses;
+            match index {
+                Some(index) => {
+                    let transaction_info = ReplicaTransactionInfoV2 {
+                        signature: &signature,
+                        is_vote,
+                        transaction: &transaction,
+                        transaction_status_meta: &transaction_status_meta,
+                        index,
+                    };
+                    plugin.notify_transaction(
+                        ReplicaTransactionInfoVersions::V0_0_2(&transaction_info),
+                        slot,
+                    )
+                }
+                None => {
+                    let transaction_info = ReplicaTransactionInfo {
+                        signature: &signature,
+                        is_vote,
+                        transaction: &transaction,
+                        transaction_status_meta: &transaction_status_meta,
+                    };
+                    plugin.notify_transaction(
+                        ReplicaTransactionInfoVersions::V0_0_1(&transaction_info),
+                        slot,
+                    )
+                }
+            }
+        }
+        HostMessage::TransactionTrace {
+            transaction,
+            index,
+            instructions,
+            slot,
+        } => {
+            let signature = transaction.signature;
+            let is_vote = transaction.is_vote;
+            let transaction = sanitize_transaction(transaction)?;
+            let transaction_trace_info = ReplicaTransactionTraceInfo {
+                signature: &signature,
+                is_vote,
+                transaction: &transaction,
+                index,
+                instructions: &instructions,
+            };
+            plugin.notify_transaction_trace(
+                ReplicaTransactionTraceInfoVersions::V0_0_1(&transaction_trace_info),
+                slot,
+            )
+        }
+        HostMessage::Entry {
+            slot,
+            index,
+            num_hashes,
+            hash,
+            executed_transaction_count,
+        } => {
+            let entry_info = ReplicaEntryInfo {
+                slot,
+                index,
+                num_hashes,
+                hash: &hash,
+                executed_transaction_count,
+            };
+            plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_1(&entry_info))
+        }
+        HostMessage::BlockMetadata(block) => match (
+            block.parent_slot,
+            block.parent_blockhash.as_deref(),
+            block.executed_transaction_count,
+        ) {
+            (Some(parent_slot), Some(parent_blockhash), Some(executed_transaction_count)) => {
+                let block_info = ReplicaBlockInfoV2 {
+                    parent_slot,
+                    parent_blockhash,
+                    slot: block.slot,
+                    blockhash: &block.blockhash,
+                    rewards: &block.rewards,
+                    block_time: block.block_time,
+                    block_height: block.block_height,
+                    executed_transaction_count,
+                };
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_2(&block_info))
+            }
+            _ => {
+                let block_info = ReplicaBlockInfo {
+                    slot: block.slot,
+                    blockhash: &block.blockhash,
+                    rewards: &block.rewards,
+                    block_time: block.block_time,
+                    block_height: block.block_height,
+                };
+                plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_1(&block_info))
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::remote_plugin::{HostAccountInfo, HostSlotStatus},
+        solana_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
+        solana_sdk::{
+            clock::Slot, hash::Hash, message::v0::LoadedAddresses, pubkey::Pubkey,
+            signature::Keypair, system_transaction,
+        },
+        solana_storage_proto::StoredTransactionStatusMeta,
+        std::sync::Mutex,
+        tempfile::TempDir,
+    };
+
+    /// Records a description of every notification it receives
+    #[derive(Debug, Default)]
+    struct RecordingPlugin {
+        notifications: Mutex<Vec<String>>,
+    }
+
+    impl RecordingPlugin {
+        fn record(&self, notification: String) -> Result<()> {
+            self.notifications.lock().unwrap().push(notification);
+            Ok(())
+        }
+    }
+
+    impl GeyserPlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn update_account(
+            &self,
+            account: ReplicaAccountInfoVersions,
+            slot: Slot,
+            is_startup: bool,
+        ) -> Result<()> {
+            let ReplicaAccountInfoVersions::V0_0_2(account) = account else {
+                panic!("unexpected account info version");
+            };
+            self.record(format!(
+                "account {:?} {} {:?} {slot} {is_startup} {:?}",
+                account.pubkey, account.lamports, account.data, account.txn_signature
+            ))
+        }
+
+        fn update_slot_status(
+            &self,
+            slot: Slot,
+            parent: Option<u64>,
+            status: SlotStatus,
+        ) -> Result<()> {
+            self.record(format!("slot {slot} {parent:?} {status:?}"))
+        }
+
+        fn notify_transaction(
+            &self,
+            transaction: ReplicaTransactionInfoVersions,
+            slot: Slot,
+        ) -> Result<()> {
+            let ReplicaTransactionInfoVersions::V0_0_2(transaction) = transaction else {
+                panic!("unexpected transaction info version");
+            };
+            self.record(format!(
+                "transaction {} {} {} {} {slot}",
+                transaction.signature,
+                transaction.transaction.signature(),
+                transaction.index,
+                transaction.transaction_status_meta.fee
+            ))
+        }
+
+        fn notify_entry(&self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+            let ReplicaEntryInfoVersions::V0_0_1(entry) = entry;
+            self.record(format!(
+                "entry {} {} {} {:?} {}",
+                entry.slot,
+                entry.index,
+                entry.num_hashes,
+                entry.hash,
+                entry.executed_transaction_count
+            ))
+        }
+    }
+
+    #[test]
+    fn test_host_message_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let producer = ShmRingBuffer::create(&path, 4096).unwrap();
+        let consumer = ShmRingBuffer::open(&path).unwrap();
+        let plugin = RecordingPlugin::default();
+
+        let pubkey = Pubkey::new_unique();
+        let transaction = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&Keypair::new(), &pubkey, 1, Hash::default()),
+        );
+        let signature = *transaction.signature();
+        let transaction_status_meta = TransactionStatusMeta {
+            fee: 5000,
+            ..TransactionStatusMeta::default()
+        };
+        let messages = vec![
+            HostMessage::UpdateAccount {
+                account: HostAccountInfo {
+                    pubkey: pubkey.to_bytes().to_vec(),
+                    lamports: 42,
+                    owner: Pubkey::default().to_bytes().to_vec(),
+                    executable: false,
+                    rent_epoch: 0,
+                    data: vec![1, 2, 3],
+                    write_version: 7,
+                    txn_signature: Some(signature),
+                },
+                slot: 5,
+                is_startup: true,
+            },
+            HostMessage::UpdateSlotStatus {
+                slot: 5,
+                parent: Some(4),
+                status: HostSlotStatus::Rooted,
+            },
+            HostMessage::Transaction {
+                transaction: HostTransaction {
+                    signature,
+                    is_vote: false,
+                    transaction: transaction.to_versioned_transaction(),
+                    message_hash: *transaction.message_hash(),
+                    loaded_addresses: LoadedAddresses::default(),
+                },
+                transaction_status_meta: StoredTransactionStatusMeta::try_from(
+                    transaction_status_meta,
+                )
+                .unwrap(),
+                index: Some(3),
+                slot: 5,
+            },
+            HostMessage::Entry {
+                slot: 5,
+                index: 1,
+                num_hashes: 12,
+                hash: vec![9; 32],
+                executed_transaction_count: 2,
+            },
+        ];
+
+        let mut frame = Vec::new();
+        for message in messages {
+            producer
+                .try_push(&bincode::serialize(&message).unwrap())
+                .unwrap();
+            assert!(consumer.try_pop(&mut frame));
+            dispatch(&plugin, bincode::deserialize(&frame).unwrap()).unwrap();
+        }
+
+        assert_eq!(
+            plugin.notifications.into_inner().unwrap(),
+            vec![
+                format!(
+                    "account {:?} 42 [1, 2, 3] 5 true {:?}",
+                    pubkey.to_bytes(),
+                    Some(signature)
+                ),
+                "slot 5 Some(4) Rooted".to_string(),
+                format!("transaction {signature} {signature} 3 5000 5"),
+                format!("entry 5 1 12 {:?} 2", [9u8; 32]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,843 @@
+/// Module responsible for forwarding the notifications of a plugin running
+/// out of process, in a `solana-geyser-host` process, through a shared memory
+/// ring buffer
+use {
+    crate::{
+        geyser_plugin_manager::GeyserPluginManagerError,
+        shm_ring_buffer::{ConsumerState, PushError, ShmRingBuffer},
+    },
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_geyser_plugin_interface::geyser_plugin_interface::{
+        GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, ReplicaBlockInfoVersions,
+        ReplicaEntryInfoVersions, ReplicaTransactionInfoVersions,
+        ReplicaTransactionTraceInfoVersions, Result, SlotStatus,
+    },
+    solana_metrics::datapoint_info,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        hash::Hash,
+        message::v0::LoadedAddresses,
+        signature::Signature,
+        timing::{timestamp, AtomicInterval},
+        transaction::{SanitizedTransaction, VersionedTransaction},
+    },
+    solana_storage_proto::StoredTransactionStatusMeta,
+    solana_transaction_status::{InstructionTrace, Reward, TransactionStatusMeta},
+    std::{
+        path::{Path, PathBuf},
+        process::{Child, Command},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Mutex,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+pub const ACCOUNT_DATA_NOTIFICATIONS: u64 = 1;
+pub const TRANSACTION_NOTIFICATIONS: u64 = 1 << 1;
+pub const TRANSACTION_TRACE_NOTIFICATIONS: u64 = 1 << 2;
+pub const ENTRY_NOTIFICATIONS: u64 = 1 << 3;
+
+const DEFAULT_HOST_BINARY: &str = "solana-geyser-host";
+const DEFAULT_BUFFER_SIZE_MB: usize = 256;
+const DEFAULT_CONSUMER_TIMEOUT_SECS: u64 = 30;
+const BLOCKED_SLEEP: Duration = Duration::from_micros(100);
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const METRICS_REPORT_INTERVAL_MS: u64 = 1000;
+
+/// What to do with a notification when the ring buffer is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackpressurePolicy {
+    /// Wait for the plugin host to catch up, disconnecting it if it stops
+    /// making progress for longer than the consumer timeout
+    #[default]
+    Block,
+    /// Drop the notification
+    Drop,
+    /// Stop forwarding notifications to the plugin host
+    Disconnect,
+}
+
+/// The `out_of_process` section of a plugin config file
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginHostConfig {
+    /// Path of the `solana-geyser-host` binary, defaults to the one next to
+    /// the validator binary
+    #[serde(default)]
+    pub host_path: Option<PathBuf>,
+    /// Path of the file backing the ring buffer, defaults to a file in
+    /// `/dev/shm`
+    #[serde(default)]
+    pub shm_path: Option<PathBuf>,
+    #[serde(default = "default_buffer_size_mb")]
+    pub buffer_size_mb: usize,
+    #[serde(default)]
+    pub backpressure: BackpressurePolicy,
+    /// How long the plugin host may go without making progress, or take to
+    /// load the plugin, before it is considered dead
+    #[serde(default = "default_consumer_timeout_secs")]
+    pub consumer_timeout_secs: u64,
+}
+
+fn default_buffer_size_mb() -> usize {
+    DEFAULT_BUFFER_SIZE_MB
+}
+
+fn default_consumer_timeout_secs() -> u64 {
+    DEFAULT_CONSUMER_TIMEOUT_SECS
+}
+
+impl PluginHostConfig {
+    /// Returns the out-of-process configuration of a plugin config file, if
+    /// the plugin is to be run out of process
+    pub fn from_plugin_config(
+        config: &serde_json::Value,
+    ) -> std::result::Result<Option<Self>, GeyserPluginManagerError> {
+        match config.get("out_of_process") {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(value) => serde_json::from_value(value.clone())
+                .map(Some)
+                .map_err(|err| {
+                    GeyserPluginManagerError::InvalidConfigFileFormat(format!(
+                        "Invalid out_of_process section in the plugin config file, error: {err:?}"
+                    ))
+                }),
+        }
+    }
+}
+
+/// A notification forwarded to the plugin host
+#[derive(Serialize, Deserialize)]
+pub enum HostMessage {
+    UpdateAccount {
+        account: HostAccountInfo,
+        slot: Slot,
+        is_startup: bool,
+    },
+    EndOfStartup,
+    UpdateSlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: HostSlotStatus,
+    },
+    Transaction {
+        transaction: HostTransaction,
+        transaction_status_meta: StoredTransactionStatusMeta,
+        index: Option<usize>,
+        slot: Slot,
+    },
+    TransactionTrace {
+        transaction: HostTransaction,
+        index: usize,
+        instructions: Vec<InstructionTrace>,
+        slot: Slot,
+    },
+    Entry {
+        slot: Slot,
+        index: usize,
+        num_hashes: u64,
+        hash: Vec<u8>,
+        executed_transaction_count: u64,
+    },
+    BlockMetadata(HostBlockInfo),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostAccountInfo {
+    pub pubkey: Vec<u8>,
+    pub lamports: u64,
+    pub owner: Vec<u8>,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: Vec<u8>,
+    pub write_version: u64,
+    pub txn_signature: Option<Signature>,
+}
+
+impl<'a> From<ReplicaAccountInfoVersions<'a>> for HostAccountInfo {
+    fn from(account: ReplicaAccountInfoVersions<'a>) -> Self {
+        match account {
+            ReplicaAccountInfoVersions::V0_0_1(account) => Self {
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                txn_signature: None,
+            },
+            ReplicaAccountInfoVersions::V0_0_2(account) => Self {
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                txn_signature: account.txn_signature.copied(),
+            },
+            ReplicaAccountInfoVersions::V0_0_3(account) => Self {
+                pubkey: account.pubkey.to_vec(),
+                lamports: account.lamports,
+                owner: account.owner.to_vec(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                data: account.data.to_vec(),
+                write_version: account.write_version,
+                txn_signature: account.txn.map(|txn| *txn.signature()),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostSlotStatus {
+    Processed,
+    Rooted,
+    Confirmed,
+}
+
+impl From<SlotStatus> for HostSlotStatus {
+    fn from(status: SlotStatus) -> Self {
+        match status {
+            SlotStatus::Processed => Self::Processed,
+            SlotStatus::Rooted => Self::Rooted,
+            SlotStatus::Confirmed => Self::Confirmed,
+        }
+    }
+}
+
+impl From<HostSlotStatus> for SlotStatus {
+    fn from(status: HostSlotStatus) -> Self {
+        match status {
+            HostSlotStatus::Processed => Self::Processed,
+            HostSlotStatus::Rooted => Self::Rooted,
+            HostSlotStatus::Confirmed => Self::Confirmed,
+        }
+    }
+}
+
+/// The parts of a `SanitizedTransaction` needed to sanitize it again in the
+/// plugin host
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostTransaction {
+    pub signature: Signature,
+    pub is_vote: bool,
+    pub transaction: VersionedTransaction,
+    pub message_hash: Hash,
+    pub loaded_addresses: LoadedAddresses,
+}
+
+impl HostTransaction {
+    fn new(signature: &Signature, is_vote: bool, transaction: &SanitizedTransaction) -> Self {
+        Self {
+            signature: *signature,
+            is_vote,
+            transaction: transaction.to_versioned_transaction(),
+            message_hash: *transaction.message_hash(),
+            loaded_addresses: transaction.get_loaded_addresses(),
+        }
+    }
+}
+
+/// The loaded addresses are carried by the transaction, the deprecated
+/// bincode representation of the status meta does not support them
+fn stored_transaction_status_meta(
+    transaction_status_meta: &TransactionStatusMeta,
+) -> Result<StoredTransactionStatusMeta> {
+    let mut transaction_status_meta = transaction_status_meta.clone();
+    transaction_status_meta.loaded_addresses = LoadedAddresses::default();
+    StoredTransactionStatusMeta::try_from(transaction_status_meta)
+        .map_err(|err| GeyserPluginError::Custom(err))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostBlockInfo {
+    pub parent_slot: Option<Slot>,
+    pub parent_blockhash: Option<String>,
+    pub slot: Slot,
+    pub blockhash: String,
+    pub rewards: Vec<Reward>,
+    pub block_time: Option<UnixTimestamp>,
+    pub block_height: Option<u64>,
+    pub executed_transaction_count: Option<u64>,
+}
+
+impl<'a> From<ReplicaBlockInfoVersions<'a>> for HostBlockInfo {
+    fn from(block: ReplicaBlockInfoVersions<'a>) -> Self {
+        match block {
+            ReplicaBlockInfoVersions::V0_0_1(block) => Self {
+                parent_slot: None,
+                parent_blockhash: None,
+                slot: block.slot,
+                blockhash: block.blockhash.to_string(),
+                rewards: block.rewards.to_vec(),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: None,
+            },
+            ReplicaBlockInfoVersions::V0_0_2(block) => Self {
+                parent_slot: Some(block.parent_slot),
+                parent_blockhash: Some(block.parent_blockhash.to_string()),
+                slot: block.slot,
+                blockhash: block.blockhash.to_string(),
+                rewards: block.rewards.to_vec(),
+                block_time: block.block_time,
+                block_height: block.block_height,
+                executed_transaction_count: Some(block.executed_transaction_count),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RemotePluginStats {
+    sent: AtomicU64,
+    dropped: AtomicU64,
+    blocked_us: AtomicU64,
+    last_report: AtomicInterval,
+}
+
+/// A plugin running in a `solana-geyser-host` process. The notifications are
+/// serialized into a shared memory ring buffer read by the plugin host,
+/// which delivers them to the actual plugin.
+#[derive(Debug)]
+pub struct RemoteGeyserPlugin {
+    name: String,
+    flags: u64,
+    /// Serializes the writers, the ring buffer only supports one producer
+    buffer: Mutex<ShmRingBuffer>,
+    shm_path: PathBuf,
+    backpressure: BackpressurePolicy,
+    consumer_timeout: Duration,
+    host: Mutex<Option<Child>>,
+    disconnected: AtomicBool,
+    stats: RemotePluginStats,
+}
+
+impl RemoteGeyserPlugin {
+    /// Spawns the plugin host of the plugin configured in `config_file`, and
+    /// waits for it to load the plugin
+    pub fn start(
+        config_file: &Path,
+        config: PluginHostConfig,
+    ) -> std::result::Result<Self, GeyserPluginManagerError> {
+        let shm_path = config.shm_path.clone().unwrap_or_else(|| {
+            let stem = config_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            PathBuf::from(format!(
+                "/dev/shm/solana-geyser-{}-{stem}",
+                std::process::id()
+            ))
+        });
+        let host_path = config.host_path.clone().unwrap_or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|dir| dir.join(DEFAULT_HOST_BINARY)))
+                .filter(|path| path.exists())
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_HOST_BINARY))
+        });
+        let consumer_timeout = Duration::from_secs(config.consumer_timeout_secs);
+
+        let buffer = ShmRingBuffer::create(&shm_path, config.buffer_size_mb * 1024 * 1024)
+            .map_err(|err| {
+                GeyserPluginManagerError::PluginHostStartError(format!(
+                    "Failed to create the ring buffer {shm_path:?}, error: {err:?}"
+                ))
+            })?;
+        let mut host = Command::new(&host_path)
+            .arg("--shm-path")
+            .arg(&shm_path)
+            .arg("--config")
+            .arg(config_file)
+            .spawn()
+            .map_err(|err| {
+                let _ = std::fs::remove_file(&shm_path);
+                GeyserPluginManagerError::PluginHostStartError(format!(
+                    "Failed to spawn {host_path:?}, error: {err:?}"
+                ))
+            })?;
+
+        let start = Instant::now();
+        let startup_error = loop {
+            match buffer.consumer_state() {
+                ConsumerState::Ready => break None,
+                ConsumerState::Failed => break Some(buffer.plugin_info().1),
+                ConsumerState::Exited => break Some("the plugin host exited".to_string()),
+                ConsumerState::Starting => {}
+            }
+            if let Ok(Some(status)) = host.try_wait() {
+                break Some(format!("the plugin host exited with {status}"));
+            }
+            if start.elapsed() > consumer_timeout {
+                break Some(format!(
+                    "the plugin was not loaded after {consumer_timeout:?}"
+                ));
+            }
+            thread::sleep(STARTUP_POLL_INTERVAL);
+        };
+        if let Some(err) = startup_error {
+            let _ = host.kill();
+            let _ = host.wait();
+            let _ = std::fs::remove_file(&shm_path);
+            return Err(GeyserPluginManagerError::PluginHostStartError(format!(
+                "Failed to load the plugin of {config_file:?} out of process: {err}"
+            )));
+        }
+
+        let (flags, name) = buffer.plugin_info();
+        info!(
+            "Started plugin host {} for plugin {name}, ring buffer {shm_path:?}",
+            host.id()
+        );
+        Ok(Self {
+            name,
+            flags,
+            buffer: Mutex::new(buffer),
+            shm_path,
+            backpressure: config.backpressure,
+            consumer_timeout,
+            host: Mutex::new(Some(host)),
+            disconnected: AtomicBool::default(),
+            stats: RemotePluginStats::default(),
+        })
+    }
+
+    fn send(&self, message: &HostMessage) -> Result<()> {
+        if self.disconnected.load(Ordering::Relaxed) {
+            self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        let frame = bincode::serialize(message).map_err(|err| GeyserPluginError::Custom(err))?;
+
+        let buffer = self.buffer.lock().unwrap();
+        let mut blocked_since = None;
+        let result = loop {
+            match buffer.try_push(&frame) {
+                Ok(()) => {
+                    self.stats.sent.fetch_add(1, Ordering::Relaxed);
+                    break Ok(());
+                }
+                Err(PushError::TooLarge) => {
+                    self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    break Err(GeyserPluginError::Custom(
+                        format!(
+                            "Notification of {} bytes does not fit in the ring buffer of the \
+                             plugin host",
+                            frame.len()
+                        )
+                        .into(),
+                    ));
+                }
+                Err(PushError::Corrupted) => {
+                    self.disconnect(&buffer, "the plugin host corrupted the ring buffer");
+                    break Ok(());
+                }
+                Err(PushError::Full) => match self.backpressure {
+                    BackpressurePolicy::Drop => {
+                        self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                        break Ok(());
+                    }
+                    BackpressurePolicy::Disconnect => {
+                        self.disconnect(&buffer, "the ring buffer is full");
+                        break Ok(());
+                    }
+                    BackpressurePolicy::Block => {
+                        if let Err(reason) = self.check_consumer(&buffer) {
+                            self.disconnect(&buffer, &reason);
+                            break Ok(());
+                        }
+                        blocked_since.get_or_insert_with(Instant::now);
+                        thread::sleep(BLOCKED_SLEEP);
+                    }
+                },
+            }
+        };
+        if let Some(blocked_since) = blocked_since {
+            self.stats.blocked_us.fetch_add(
+                blocked_since.elapsed().as_micros() as u64,
+                Ordering::Relaxed,
+            );
+        }
+        self.maybe_report_metrics(&buffer);
+        result
+    }
+
+    /// Returns an error if the plugin host is no longer making progress
+    fn check_consumer(&self, buffer: &ShmRingBuffer) -> std::result::Result<(), String> {
+        match buffer.consumer_state() {
+            ConsumerState::Ready => {}
+            state => return Err(format!("the plugin host is {state:?}")),
+        }
+        let since_heartbeat =
+            Duration::from_millis(timestamp().saturating_sub(buffer.consumer_heartbeat()));
+        if since_heartbeat > self.consumer_timeout {
+            return Err(format!(
+                "the plugin host made no progress for {since_heartbeat:?}"
+            ));
+        }
+        Ok(())
+    }
+
+    fn disconnect(&self, buffer: &ShmRingBuffer, reason: &str) {
+        self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+        if !self.disconnected.swap(true, Ordering::Relaxed) {
+            error!(
+                "Disconnecting the plugin host of {}, {reason}. No more notifications will be \
+                 sent to the plugin",
+                self.name
+            );
+            buffer.close_producer();
+            datapoint_info!(
+                "geyser-plugin-host-disconnected",
+                "plugin" => self.name,
+                ("reason", reason, String),
+            );
+        }
+    }
+
+    fn maybe_report_metrics(&self, buffer: &ShmRingBuffer) {
+        if self
+            .stats
+            .last_report
+            .should_update(METRICS_REPORT_INTERVAL_MS)
+        {
+            datapoint_info!(
+                "geyser-plugin-host",
+                "plugin" => self.name,
+                ("lag_bytes", buffer.pending_bytes(), i64),
+                ("capacity_bytes", buffer.capacity(), i64),
+                ("sent", self.stats.sent.swap(0, Ordering::Relaxed), i64),
+                ("dropped", self.stats.dropped.swap(0, Ordering::Relaxed), i64),
+                ("blocked_us", self.stats.blocked_us.swap(0, Ordering::Relaxed), i64),
+                ("disconnected", self.disconnected.load(Ordering::Relaxed), bool),
+            );
+        }
+    }
+
+    /// Lets the plugin host drain the ring buffer and unload the plugin,
+    /// killing it if it does not exit within the consumer timeout
+    fn shutdown(&self) {
+        let Some(mut host) = self.host.lock().unwrap().take() else {
+            return;
+        };
+        self.buffer.lock().unwrap().close_producer();
+        let start = Instant::now();
+        loop {
+            match host.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if start.elapsed() < self.consumer_timeout => {
+                    thread::sleep(STARTUP_POLL_INTERVAL)
+                }
+                _ => {
+                    warn!(
+                        "The plugin host of {} did not exit in time, killing it",
+                        self.name
+                    );
+                    let _ = host.kill();
+                    let _ = host.wait();
+                    break;
+                }
+            }
+        }
+        if let Err(err) = std::fs::remove_file(&self.shm_path) {
+            warn!(
+                "Failed to remove the ring buffer {:?}: {err}",
+                self.shm_path
+            );
+        }
+    }
+}
+
+impl Drop for RemoteGeyserPlugin {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl GeyserPlugin for RemoteGeyserPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The plugin host already loaded the plugin with the config file
+    fn on_load(&mut self, _config_file: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        self.shutdown();
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<()> {
+        self.send(&HostMessage::UpdateAccount {
+            account: account.into(),
+            slot,
+            is_startup,
+        })
+    }
+
+    fn notify_end_of_startup(&self) -> Result<()> {
+        self.send(&HostMessage::EndOfStartup)
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: Slot,
+        parent: Option<u64>,
+        status: SlotStatus,
+    ) -> Result<()> {
+        self.send(&HostMessage::UpdateSlotStatus {
+            slot,
+            parent,
+            status: status.into(),
+        })
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        let message = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(info) => HostMessage::Transaction {
+                transaction: HostTransaction::new(info.signature, info.is_vote, info.transaction),
+                transaction_status_meta: stored_transaction_status_meta(
+                    info.transaction_status_meta,
+                )?,
+                index: None,
+                slot,
+            },
+            ReplicaTransactionInfoVersions::V0_0_2(info) => HostMessage::Transaction {
+                transaction: HostTransaction::new(info.signature, info.is_vote, info.transaction),
+                transaction_status_meta: stored_transaction_status_meta(
+                    info.transaction_status_meta,
+                )?,
+                index: Some(info.index),
+                slot,
+            },
+        };
+        self.send(&message)
+    }
+
+    fn notify_transaction_trace(
+        &self,
+        transaction: ReplicaTransactionTraceInfoVersions,
+        slot: Slot,
+    ) -> Result<()> {
+        let ReplicaTransactionTraceInfoVersions::V0_0_1(info) = transaction;
+        self.send(&HostMessage::TransactionTrace {
+            transaction: HostTransaction::new(info.signature, info.is_vote, info.transaction),
+            index: info.index,
+            instructions: info.instructions.to_vec(),
+            slot,
+        })
+    }
+
+    fn notify_entry(&self, entry: ReplicaEntryInfoVersions) -> Result<()> {
+        let ReplicaEntryInfoVersions::V0_0_1(entry) = entry;
+        self.send(&HostMessage::Entry {
+            slot: entry.slot,
+            index: entry.index,
+            num_hashes: entry.num_hashes,
+            hash: entry.hash.to_vec(),
+            executed_transaction_count: entry.executed_transaction_count,
+        })
+    }
+
+    fn notify_block_metadata(&self, blockinfo: ReplicaBlockInfoVersions) -> Result<()> {
+        self.send(&HostMessage::BlockMetadata(blockinfo.into()))
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        self.flags & ACCOUNT_DATA_NOTIFICATIONS != 0
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        self.flags & TRANSACTION_NOTIFICATIONS != 0
+    }
+
+    fn transaction_trace_notifications_enabled(&self) -> bool {
+        self.flags & TRANSACTION_TRACE_NOTIFICATIONS != 0
+    }
+
+    fn entry_notifications_enabled(&self) -> bool {
+        self.flags & ENTRY_NOTIFICATIONS != 0
+    }
+}
+
+/// The notification flags of `plugin`, published by the plugin host
+pub fn plugin_flags(plugin: &dyn GeyserPlugin) -> u64 {
+    let mut flags = 0;
+    if plugin.account_data_notifications_enabled() {
+        flags |= ACCOUNT_DATA_NOTIFICATIONS;
+    }
+    if plugin.transaction_notifications_enabled() {
+        flags |= TRANSACTION_NOTIFICATIONS;
+    }
+    if plugin.transaction_trace_notifications_enabled() {
+        flags |= TRANSACTION_TRACE_NOTIFICATIONS;
+    }
+    if plugin.entry_notifications_enabled() {
+        flags |= ENTRY_NOTIFICATIONS;
+    }
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::shm_ring_buffer::ProducerState, std::sync::Arc, tempfile::TempDir};
+
+    fn remote_plugin(
+        buffer: ShmRingBuffer,
+        backpressure: BackpressurePolicy,
+    ) -> RemoteGeyserPlugin {
+        RemoteGeyserPlugin {
+            name: "remote".to_string(),
+            flags: ACCOUNT_DATA_NOTIFICATIONS | ENTRY_NOTIFICATIONS,
+            buffer: Mutex::new(buffer),
+            shm_path: PathBuf::default(),
+            backpressure,
+            consumer_timeout: Duration::from_secs(DEFAULT_CONSUMER_TIMEOUT_SECS),
+            host: Mutex::default(),
+            disconnected: AtomicBool::default(),
+            stats: RemotePluginStats::default(),
+        }
+    }
+
+    /// Fills a ring buffer with end of startup notifications, returning the
+    /// number of notifications it holds
+    fn fill(plugin: &RemoteGeyserPlugin) -> u64 {
+        let buffer = plugin.buffer.lock().unwrap();
+        let frame = bincode::serialize(&HostMessage::EndOfStartup).unwrap();
+        let mut count = 0;
+        while buffer.try_push(&frame).is_ok() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_plugin_host_config() {
+        let config = serde_json::json!({"libpath": "libplugin.so"});
+        assert_eq!(PluginHostConfig::from_plugin_config(&config).unwrap(), None);
+
+        let config = serde_json::json!({
+            "libpath": "libplugin.so",
+            "out_of_process": {"buffer_size_mb": 16, "backpressure": "drop"},
+        });
+        assert_eq!(
+            PluginHostConfig::from_plugin_config(&config).unwrap(),
+            Some(PluginHostConfig {
+                host_path: None,
+                shm_path: None,
+                buffer_size_mb: 16,
+                backpressure: BackpressurePolicy::Drop,
+                consumer_timeout_secs: DEFAULT_CONSUMER_TIMEOUT_SECS,
+            })
+        );
+
+        let config = serde_json::json!({
+            "libpath": "libplugin.so",
+            "out_of_process": {"backpressure": "wait"},
+        });
+        assert!(PluginHostConfig::from_plugin_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_backpressure_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let plugin = remote_plugin(
+            ShmRingBuffer::create(&path, 1024).unwrap(),
+            BackpressurePolicy::Drop,
+        );
+        let consumer = ShmRingBuffer::open(&path).unwrap();
+        let count = fill(&plugin);
+
+        plugin.notify_end_of_startup().unwrap();
+        assert_eq!(plugin.stats.dropped.load(Ordering::Relaxed), 1);
+        assert!(!plugin.disconnected.load(Ordering::Relaxed));
+
+        // once the plugin host caught up, notifications are sent again
+        let mut frame = Vec::new();
+        assert!(consumer.try_pop(&mut frame));
+        plugin.notify_end_of_startup().unwrap();
+        assert_eq!(plugin.stats.sent.load(Ordering::Relaxed), 1);
+        let mut received = 0;
+        while consumer.try_pop(&mut frame) {
+            received += 1;
+        }
+        assert_eq!(received, count);
+    }
+
+    #[test]
+    fn test_backpressure_disconnect() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let plugin = remote_plugin(
+            ShmRingBuffer::create(&path, 1024).unwrap(),
+            BackpressurePolicy::Disconnect,
+        );
+        let consumer = ShmRingBuffer::open(&path).unwrap();
+        fill(&plugin);
+
+        plugin.notify_end_of_startup().unwrap();
+        assert!(plugin.disconnected.load(Ordering::Relaxed));
+        assert_eq!(consumer.producer_state(), ProducerState::Closed);
+
+        // nothing is sent once disconnected, even with free space
+        let mut frame = Vec::new();
+        while consumer.try_pop(&mut frame) {}
+        plugin.notify_end_of_startup().unwrap();
+        assert!(!consumer.try_pop(&mut frame));
+        assert_eq!(plugin.stats.dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_backpressure_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let plugin = remote_plugin(
+            ShmRingBuffer::create(&path, 1024).unwrap(),
+            BackpressurePolicy::Block,
+        );
+        let consumer = Arc::new(ShmRingBuffer::open(&path).unwrap());
+        consumer.heartbeat();
+        consumer.set_consumer_state(ConsumerState::Ready);
+        fill(&plugin);
+
+        // the notification is sent once the plugin host catches up
+        let reader = {
+            let consumer = consumer.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                let mut frame = Vec::new();
+                assert!(consumer.try_pop(&mut frame));
+            })
+        };
+        plugin.notify_end_of_startup().unwrap();
+        reader.join().unwrap();
+        assert_eq!(plugin.stats.sent.load(Ordering::Relaxed), 1);
+        assert!(plugin.stats.blocked_us.load(Ordering::Relaxed) > 0);
+        assert!(!plugin.disconnected.load(Ordering::Relaxed));
+
+        // a plugin host which exited is disconnected instead of blocking
+        consumer.set_consumer_state(ConsumerState::Exited);
+        plugin.notify_end_of_startup().unwrap();
+        assert!(plugin.disconnected.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,472 @@
+//! A single-producer single-consumer ring buffer of length prefixed frames,
+//! backed by a memory mapped file which is shared between the validator and
+//! an out-of-process plugin host.
+//!
+//! The first page of the file is a header holding the read and write
+//! positions and the state of both ends of the buffer, the rest of the file
+//! holds the frames. Positions only ever increase; the offset of a position
+//! inside the buffer is the position modulo the capacity.
+use {
+    memmap2::MmapMut,
+    solana_sdk::timing::timestamp,
+    std::{
+        fs::OpenOptions,
+        io,
+        path::Path,
+        ptr,
+        sync::atomic::{AtomicU64, Ordering},
+    },
+};
+
+const MAGIC: u64 = 0x5253_5945_5347_4c53;
+const VERSION: u64 = 1;
+const HEADER_SIZE: usize = 4096;
+const FRAME_LEN_SIZE: usize = std::mem::size_of::<u32>();
+const MAX_PLUGIN_INFO_LEN: usize = 1024;
+
+/// State of the validator end of the buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProducerState {
+    Running,
+    /// No more frames will be written, the consumer exits once it has
+    /// drained the buffer
+    Closed,
+}
+
+/// State of the plugin host end of the buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsumerState {
+    Starting,
+    /// The plugin is loaded and the consumer is reading frames
+    Ready,
+    /// The plugin could not be loaded, the plugin info holds the error
+    Failed,
+    Exited,
+}
+
+impl ConsumerState {
+    fn from_u64(value: u64) -> Self {
+        match value {
+            0 => Self::Starting,
+            1 => Self::Ready,
+            2 => Self::Failed,
+            _ => Self::Exited,
+        }
+    }
+
+    fn to_u64(self) -> u64 {
+        match self {
+            Self::Starting => 0,
+            Self::Ready => 1,
+            Self::Failed => 2,
+            Self::Exited => 3,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushError {
+    /// There is not enough free space in the buffer for the frame
+    Full,
+    /// The frame can never fit in the buffer
+    TooLarge,
+    /// The read position in the header is not consistent with the write
+    /// position, the consumer wrote garbage into the shared header
+    Corrupted,
+}
+
+#[repr(C)]
+struct Header {
+    magic: AtomicU64,
+    version: AtomicU64,
+    capacity: AtomicU64,
+    /// Total number of bytes written, only advanced by the producer
+    write_pos: AtomicU64,
+    /// Total number of bytes read, only advanced by the consumer
+    read_pos: AtomicU64,
+    producer_state: AtomicU64,
+    consumer_state: AtomicU64,
+    /// Milliseconds since the unix epoch at which the consumer was last alive
+    consumer_heartbeat: AtomicU64,
+    /// Published by the consumer before it becomes ready
+    plugin_flags: AtomicU64,
+    plugin_info_len: AtomicU64,
+    plugin_info: [u8; MAX_PLUGIN_INFO_LEN],
+}
+
+const _: () = assert!(std::mem::size_of::<Header>() <= HEADER_SIZE);
+
+pub struct ShmRingBuffer {
+    _mmap: MmapMut,
+    header: *mut Header,
+    data: *mut u8,
+    capacity: u64,
+}
+
+// SAFETY: The header is only accessed through atomics, except for the plugin
+// info which is written by the consumer before it publishes its state with
+// release ordering. Frame bytes are only written by the single producer
+// before it advances `write_pos`, and only read by the single consumer
+// before it advances `read_pos`.
+unsafe impl Send for ShmRingBuffer {}
+unsafe impl Sync for ShmRingBuffer {}
+
+impl std::fmt::Debug for ShmRingBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShmRingBuffer")
+            .field("capacity", &self.capacity)
+            .field("pending_bytes", &self.pending_bytes())
+            .finish()
+    }
+}
+
+impl ShmRingBuffer {
+    /// Creates the file backing a buffer of `capacity` bytes, overwriting
+    /// any existing file at `path`. Called by the producer.
+    pub fn create(path: &Path, capacity: usize) -> io::Result<Self> {
+        if capacity <= FRAME_LEN_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("ring buffer capacity {capacity} is too small"),
+            ));
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((HEADER_SIZE + capacity) as u64)?;
+        let buffer = Self::map(unsafe { MmapMut::map_mut(&file)? }, capacity as u64);
+        let header = buffer.header();
+        header.capacity.store(capacity as u64, Ordering::Relaxed);
+        header.version.store(VERSION, Ordering::Relaxed);
+        header.magic.store(MAGIC, Ordering::Release);
+        Ok(buffer)
+    }
+
+    /// Maps the buffer created by the producer at `path`. Called by the
+    /// consumer.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        if mmap.len() < HEADER_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("ring buffer file {path:?} is too small"),
+            ));
+        }
+        let header = unsafe { &*(mmap.as_ptr() as *const Header) };
+        if header.magic.load(Ordering::Acquire) != MAGIC
+            || header.version.load(Ordering::Relaxed) != VERSION
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{path:?} is not a geyser ring buffer"),
+            ));
+        }
+        let capacity = header.capacity.load(Ordering::Relaxed);
+        if capacity <= FRAME_LEN_SIZE as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("ring buffer file {path:?} has a capacity {capacity} that is too small"),
+            ));
+        }
+        if mmap.len() as u64 != HEADER_SIZE as u64 + capacity {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("ring buffer file {path:?} does not match its capacity {capacity}"),
+            ));
+        }
+        Ok(Self::map(mmap, capacity))
+    }
+
+    fn map(mut mmap: MmapMut, capacity: u64) -> Self {
+        let header = mmap.as_mut_ptr() as *mut Header;
+        let data = unsafe { mmap.as_mut_ptr().add(HEADER_SIZE) };
+        Self {
+            _mmap: mmap,
+            header,
+            data,
+            capacity,
+        }
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*self.header }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Number of bytes written by the producer and not yet read by the
+    /// consumer
+    pub fn pending_bytes(&self) -> u64 {
+        let header = self.header();
+        let read_pos = header.read_pos.load(Ordering::Acquire);
+        let write_pos = header.write_pos.load(Ordering::Acquire);
+        write_pos.saturating_sub(read_pos)
+    }
+
+    /// Appends a frame to the buffer. Must only be called by the producer.
+    ///
+    /// The header is shared with the consumer, so the positions are checked
+    /// before they are used: a read position ahead of the write position, or
+    /// more pending bytes than the capacity, can only be written by a
+    /// misbehaving consumer.
+    pub fn try_push(&self, frame: &[u8]) -> Result<(), PushError> {
+        let frame_len = u32::try_from(frame.len()).map_err(|_| PushError::TooLarge)?;
+        let frame_size = FRAME_LEN_SIZE as u64 + u64::from(frame_len);
+        if frame_size > self.capacity {
+            return Err(PushError::TooLarge);
+        }
+        let header = self.header();
+        let write_pos = header.write_pos.load(Ordering::Relaxed);
+        let read_pos = header.read_pos.load(Ordering::Acquire);
+        let pending_bytes = write_pos
+            .checked_sub(read_pos)
+            .filter(|pending_bytes| *pending_bytes <= self.capacity)
+            .ok_or(PushError::Corrupted)?;
+        if self.capacity - pending_bytes < frame_size {
+            return Err(PushError::Full);
+        }
+        let next_write_pos = write_pos
+            .checked_add(frame_size)
+            .ok_or(PushError::Corrupted)?;
+        self.copy_in(write_pos, &frame_len.to_le_bytes());
+        self.copy_in(write_pos + FRAME_LEN_SIZE as u64, frame);
+        header.write_pos.store(next_write_pos, Ordering::Release);
+        Ok(())
+    }
+
+    /// Removes the oldest frame from the buffer into `frame`, returning
+    /// false if the buffer is empty. Must only be called by the consumer.
+    pub fn try_pop(&self, frame: &mut Vec<u8>) -> bool {
+        let header = self.header();
+        let read_pos = header.read_pos.load(Ordering::Relaxed);
+        let write_pos = header.write_pos.load(Ordering::Acquire);
+        if write_pos == read_pos {
+            return false;
+        }
+        let mut frame_len = [0u8; FRAME_LEN_SIZE];
+        self.copy_out(read_pos, &mut frame_len);
+        frame.resize(u32::from_le_bytes(frame_len) as usize, 0);
+        self.copy_out(read_pos + FRAME_LEN_SIZE as u64, frame);
+        header.read_pos.store(
+            read_pos + (FRAME_LEN_SIZE + frame.len()) as u64,
+            Ordering::Release,
+        );
+        true
+    }
+
+    fn copy_in(&self, pos: u64, bytes: &[u8]) {
+        let offset = (pos % self.capacity) as usize;
+        let first = bytes.len().min(self.capacity as usize - offset);
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), self.data.add(offset), first);
+            ptr::copy_nonoverlapping(bytes[first..].as_ptr(), self.data, bytes.len() - first);
+        }
+    }
+
+    fn copy_out(&self, pos: u64, bytes: &mut [u8]) {
+        let offset = (pos % self.capacity) as usize;
+        let first = bytes.len().min(self.capacity as usize - offset);
+        unsafe {
+            ptr::copy_nonoverlapping(self.data.add(offset), bytes.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(self.data, bytes[first..].as_mut_ptr(), bytes.len() - first);
+        }
+    }
+
+    pub fn producer_state(&self) -> ProducerState {
+        match self.header().producer_state.load(Ordering::Acquire) {
+            0 => ProducerState::Running,
+            _ => ProducerState::Closed,
+        }
+    }
+
+    pub fn close_producer(&self) {
+        self.header().producer_state.store(1, Ordering::Release);
+    }
+
+    pub fn consumer_state(&self) -> ConsumerState {
+        ConsumerState::from_u64(self.header().consumer_state.load(Ordering::Acquire))
+    }
+
+    pub fn set_consumer_state(&self, state: ConsumerState) {
+        self.header()
+            .consumer_state
+            .store(state.to_u64(), Ordering::Release);
+    }
+
+    /// Milliseconds since the unix epoch at which the consumer last called
+    /// `heartbeat()`
+    pub fn consumer_heartbeat(&self) -> u64 {
+        self.header().consumer_heartbeat.load(Ordering::Relaxed)
+    }
+
+    pub fn heartbeat(&self) {
+        self.header()
+            .consumer_heartbeat
+            .store(timestamp(), Ordering::Relaxed);
+    }
+
+    /// Publishes the plugin flags and the plugin name, or the load error if
+    /// the plugin failed to load. Must be called by the consumer before it
+    /// sets its state.
+    pub fn set_plugin_info(&self, flags: u64, info: &str) {
+        let mut len = info.len().min(MAX_PLUGIN_INFO_LEN);
+        while !info.is_char_boundary(len) {
+            len -= 1;
+        }
+        let header = self.header();
+        unsafe {
+            ptr::copy_nonoverlapping(
+                info.as_ptr(),
+                ptr::addr_of_mut!((*self.header).plugin_info) as *mut u8,
+                len,
+            );
+        }
+        header.plugin_info_len.store(len as u64, Ordering::Relaxed);
+        header.plugin_flags.store(flags, Ordering::Relaxed);
+    }
+
+    /// Returns the plugin flags and info published by the consumer, only
+    /// meaningful once the consumer is ready or failed
+    pub fn plugin_info(&self) -> (u64, String) {
+        let header = self.header();
+        let len =
+            (header.plugin_info_len.load(Ordering::Relaxed) as usize).min(MAX_PLUGIN_INFO_LEN);
+        let mut info = vec![0u8; len];
+        unsafe {
+            ptr::copy_nonoverlapping(
+                ptr::addr_of!((*self.header).plugin_info) as *const u8,
+                info.as_mut_ptr(),
+                len,
+            );
+        }
+        let info = String::from_utf8_lossy(&info).into_owned();
+        (header.plugin_flags.load(Ordering::Relaxed), info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_push_pop_wraparound() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let producer = ShmRingBuffer::create(&path, 64).unwrap();
+        let consumer = ShmRingBuffer::open(&path).unwrap();
+        assert_eq!(consumer.capacity(), 64);
+
+        let mut frame = Vec::new();
+        assert!(!consumer.try_pop(&mut frame));
+        // frames of 4 + 20 bytes wrap around the 64 bytes of data many times
+        for i in 0..100u8 {
+            let sent = vec![i; 20];
+            producer.try_push(&sent).unwrap();
+            assert_eq!(producer.pending_bytes(), 24);
+            assert!(consumer.try_pop(&mut frame));
+            assert_eq!(frame, sent);
+            assert_eq!(producer.pending_bytes(), 0);
+        }
+    }
+
+    #[test]
+    fn test_push_full() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let producer = ShmRingBuffer::create(&path, 64).unwrap();
+        let consumer = ShmRingBuffer::open(&path).unwrap();
+
+        assert_eq!(producer.try_push(&[0; 61]), Err(PushError::TooLarge));
+        producer.try_push(&[1; 28]).unwrap();
+        producer.try_push(&[2; 28]).unwrap();
+        assert_eq!(producer.try_push(&[3; 1]), Err(PushError::Full));
+
+        let mut frame = Vec::new();
+        assert!(consumer.try_pop(&mut frame));
+        assert_eq!(frame, vec![1; 28]);
+        producer.try_push(&[3; 1]).unwrap();
+        assert!(consumer.try_pop(&mut frame));
+        assert_eq!(frame, vec![2; 28]);
+        assert!(consumer.try_pop(&mut frame));
+        assert_eq!(frame, vec![3; 1]);
+        assert!(!consumer.try_pop(&mut frame));
+    }
+
+    #[test]
+    fn test_push_corrupted() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let producer = ShmRingBuffer::create(&path, 64).unwrap();
+        producer.try_push(&[1; 28]).unwrap();
+
+        // a read position ahead of the write position
+        producer.header().read_pos.store(64, Ordering::Release);
+        assert_eq!(producer.try_push(&[2; 1]), Err(PushError::Corrupted));
+
+        // more pending bytes than the capacity
+        producer.header().read_pos.store(0, Ordering::Release);
+        producer.header().write_pos.store(65, Ordering::Release);
+        assert_eq!(producer.try_push(&[2; 1]), Err(PushError::Corrupted));
+
+        // a write position about to overflow
+        producer
+            .header()
+            .read_pos
+            .store(u64::MAX - 4, Ordering::Release);
+        producer
+            .header()
+            .write_pos
+            .store(u64::MAX - 4, Ordering::Release);
+        assert_eq!(producer.try_push(&[2; 1]), Err(PushError::Corrupted));
+    }
+
+    #[test]
+    fn test_open_too_small() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        assert!(ShmRingBuffer::create(&path, FRAME_LEN_SIZE).is_err());
+
+        for capacity in [0, FRAME_LEN_SIZE] {
+            let producer = ShmRingBuffer::create(&path, 64).unwrap();
+            producer
+                .header()
+                .capacity
+                .store(capacity as u64, Ordering::Release);
+            drop(producer);
+            OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_len((HEADER_SIZE + capacity) as u64)
+                .unwrap();
+            let err = ShmRingBuffer::open(&path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_states_and_plugin_info() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ring");
+        let producer = ShmRingBuffer::create(&path, 64).unwrap();
+        let consumer = ShmRingBuffer::open(&path).unwrap();
+        assert_eq!(producer.producer_state(), ProducerState::Running);
+        assert_eq!(producer.consumer_state(), ConsumerState::Starting);
+
+        consumer.set_plugin_info(0b101, "my_plugin");
+        consumer.heartbeat();
+        consumer.set_consumer_state(ConsumerState::Ready);
+        assert_eq!(producer.consumer_state(), ConsumerState::Ready);
+        assert_eq!(producer.plugin_info(), (0b101, "my_plugin".to_string()));
+        assert!(producer.consumer_heartbeat() > 0);
+
+        producer.close_producer();
+        assert_eq!(consumer.producer_state(), ProducerState::Closed);
+    }
+}
@@ -3,13 +3,14 @@ use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     log::*,
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
+        ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions, ReplicaTransactionTraceInfo,
+        ReplicaTransactionTraceInfoVersions,
     },
     solana_measure::measure::Measure,
     solana_metrics::*,
     solana_rpc::transaction_notifier_interface::TransactionNotifier,
     solana_sdk::{clock::Slot, signature::Signature, transaction::SanitizedTransaction},
-    solana_transaction_status::TransactionStatusMeta,
+    solana_transaction_status::{InstructionTrace, TransactionStatusMeta},
     std::sync::{Arc, RwLock},
 };
 
@@ -75,6 +76,61 @@ impl TransactionNotifier for TransactionNotifierImpl {
             10000
         );
     }
+
+    fn notify_transaction_trace(
+        &self,
+        slot: Slot,
+        index: usize,
+        signature: &Signature,
+        transaction: &SanitizedTransaction,
+        instruction_trace: &[InstructionTrace],
+    ) {
+        let mut measure = Measure::start("geyser-plugin-notify_plugins_of_transaction_trace");
+        let plugin_manager = self.plugin_manager.read().unwrap();
+
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let transaction_trace_info = ReplicaTransactionTraceInfo {
+            signature,
+            is_vote: transaction.is_simple_vote_transaction(),
+            transaction,
+            index,
+            instructions: instruction_trace,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.transaction_trace_notifications_enabled() {
+                continue;
+            }
+            match plugin.notify_transaction_trace(
+                ReplicaTransactionTraceInfoVersions::V0_0_1(&transaction_trace_info),
+                slot,
+            ) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify transaction trace, error: ({}) to plugin {}",
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified transaction trace to plugin {}",
+                        plugin.name()
+                    );
+                }
+            }
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_transaction_trace-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+    }
 }
 
 impl TransactionNotifierImpl {
@@ -26,11 +26,13 @@ use {
         },
         contact_info::{self, ContactInfo, Error as ContactInfoError, LegacyContactInfo},
         crds::{Crds, Cursor, GossipRoute},
+        crds_dump::CrdsDump,
         crds_gossip::CrdsGossip,
         crds_gossip_error::CrdsGossipError,
         crds_gossip_pull::{
             CrdsFilter, CrdsTimeouts, ProcessPullStats, CRDS_GOSSIP_PULL_CRDS_TIMEOUT_MS,
         },
+        crds_gossip_push::CrdsGossipPush,
         crds_value::{
             self, AccountsHashes, CrdsData, CrdsValue, CrdsValueLabel, EpochSlotsIndex,
             LegacySnapshotHashes, LowestSlot, NodeInstance, SnapshotHashes, Version, Vote,
@@ -40,6 +42,7 @@ use {
         epoch_slots::EpochSlots,
         gossip_error::GossipError,
         ping_pong::{self, PingCache, Pong},
+        push_policy::PushPolicy,
         socketaddr, socketaddr_any,
         weighted_shuffle::WeightedShuffle,
     },
@@ -111,7 +114,7 @@ const MAX_GOSSIP_TRAFFIC: usize = 128_000_000 / PACKET_DATA_SIZE;
 /// Max size of serialized crds-values in a Protocol::PushMessage packet. This
 /// is equal to PACKET_DATA_SIZE minus serialized size of an empty push
 /// message: Protocol::PushMessage(Pubkey::default(), Vec::default())
-const PUSH_MESSAGE_MAX_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE - 44;
+pub(crate) const PUSH_MESSAGE_MAX_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE - 44;
 pub(crate) const DUPLICATE_SHRED_MAX_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE - 115;
 /// Maximum number of hashes in AccountsHashes a node publishes
 /// such that the serialized size of the push/pull message stays below
@@ -442,6 +445,12 @@ impl ClusterInfo {
         self.contact_debug_interval = new;
     }
 
+    /// Replaces the policy of the push overlay; this resets the push active set and so
+    /// should be called before gossip is started.
+    pub fn set_push_policy(&mut self, policy: Arc<dyn PushPolicy>) {
+        self.gossip.push = CrdsGossipPush::new(policy);
+    }
+
     pub fn socket_addr_space(&self) -> &SocketAddrSpace {
         &self.socket_addr_space
     }
@@ -1292,6 +1301,12 @@ impl ClusterInfo {
             .collect()
     }
 
+    /// Copy of all the values in gossip, to be written to a file and analyzed offline
+    pub fn crds_dump(&self) -> CrdsDump {
+        let gossip_crds = self.gossip.crds.read().unwrap();
+        CrdsDump::new(self.id(), &gossip_crds, timestamp())
+    }
+
     pub fn gossip_peers(&self) -> Vec<LegacyContactInfo> {
         let me = self.id();
         let gossip_crds = self.gossip.crds.read().unwrap();
@@ -121,7 +121,7 @@ pub(crate) struct CrdsStats {
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct VersionedCrdsValue {
     /// Ordinal index indicating insert order.
-    ordinal: u64,
+    pub(crate) ordinal: u64,
     pub value: CrdsValue,
     /// local time when updated
     pub(crate) local_timestamp: u64,
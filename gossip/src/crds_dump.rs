@@ -0,0 +1,299 @@
+//! Dump of the crds table of a node, written to a file to analyze offline how values
+//! propagate through gossip: what each node holds, from which origins, and how stale.
+
+use {
+    crate::{
+        cluster_info::PUSH_MESSAGE_MAX_PAYLOAD_SIZE,
+        crds::Crds,
+        crds_value::{CrdsData, CrdsValue, CrdsValueLabel},
+    },
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fs::File,
+        io::{self, BufReader, BufWriter, Write},
+        path::Path,
+    },
+};
+
+/// Values larger than this do not fit in a push message, and only propagate through pull
+/// responses.
+pub const MAX_PUSHED_VALUE_SIZE: usize = PUSH_MESSAGE_MAX_PAYLOAD_SIZE;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrdsDumpEntry {
+    /// Insertion order of the value in the crds table
+    pub ordinal: u64,
+    /// Local time the value was inserted or last updated, in milliseconds
+    pub local_timestamp: u64,
+    pub value: CrdsValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrdsDump {
+    /// Pubkey of the node the crds table was dumped from
+    pub node: Pubkey,
+    /// Local time of the dump, in milliseconds
+    pub timestamp: u64,
+    /// Entries of the crds table, in insertion order
+    pub entries: Vec<CrdsDumpEntry>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct OriginStats {
+    pub num_values: usize,
+    pub num_bytes: u64,
+    pub num_values_by_kind: BTreeMap<&'static str, usize>,
+}
+
+/// Differences between the crds tables of two nodes, keyed by crds value label
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CrdsDumpDiff {
+    pub only_in_left: Vec<CrdsValueLabel>,
+    pub only_in_right: Vec<CrdsValueLabel>,
+    /// Labels whose values differ, with the wallclocks of the left and right values
+    pub mismatched: Vec<(CrdsValueLabel, /*left:*/ u64, /*right:*/ u64)>,
+}
+
+impl CrdsDump {
+    pub(crate) fn new(node: Pubkey, crds: &Crds, timestamp: u64) -> Self {
+        let mut entries: Vec<_> = crds
+            .values()
+            .map(|entry| CrdsDumpEntry {
+                ordinal: entry.ordinal,
+                local_timestamp: entry.local_timestamp,
+                value: entry.value.clone(),
+            })
+            .collect();
+        entries.sort_unstable_by_key(|entry| entry.ordinal);
+        Self {
+            node,
+            timestamp,
+            entries,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(&mut writer, self)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        writer.flush()
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        bincode::deserialize_from(reader)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Number and serialized size of the values of each origin
+    pub fn origin_stats(&self) -> HashMap<Pubkey, OriginStats> {
+        let mut stats = HashMap::<Pubkey, OriginStats>::new();
+        for entry in &self.entries {
+            let origin_stats = stats.entry(entry.value.pubkey()).or_default();
+            origin_stats.num_values += 1;
+            origin_stats.num_bytes += bincode::serialized_size(&entry.value).unwrap();
+            *origin_stats
+                .num_values_by_kind
+                .entry(kind(&entry.value.data))
+                .or_default() += 1;
+        }
+        stats
+    }
+
+    /// Entries whose wallclock is more than `max_age` milliseconds older than the dump
+    pub fn stale_entries(&self, max_age: u64) -> impl Iterator<Item = &CrdsDumpEntry> {
+        let min_wallclock = self.timestamp.saturating_sub(max_age);
+        self.entries
+            .iter()
+            .filter(move |entry| entry.value.wallclock() < min_wallclock)
+    }
+
+    /// Entries whose serialized value is larger than `max_size` bytes, with that size
+    pub fn oversized_entries(
+        &self,
+        max_size: usize,
+    ) -> impl Iterator<Item = (&CrdsDumpEntry, usize)> {
+        self.entries.iter().filter_map(move |entry| {
+            let size = bincode::serialized_size(&entry.value).unwrap() as usize;
+            (size > max_size).then_some((entry, size))
+        })
+    }
+
+    /// Compares the values of this dump (left) to the values of `other` (right)
+    pub fn diff(&self, other: &CrdsDump) -> CrdsDumpDiff {
+        let values: HashMap<_, _> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.value.label(), &entry.value))
+            .collect();
+        let other_values: HashMap<_, _> = other
+            .entries
+            .iter()
+            .map(|entry| (entry.value.label(), &entry.value))
+            .collect();
+        let mut diff = CrdsDumpDiff::default();
+        for (label, value) in &values {
+            match other_values.get(label) {
+                None => diff.only_in_left.push(label.clone()),
+                Some(other_value) if value != other_value => diff.mismatched.push((
+                    label.clone(),
+                    value.wallclock(),
+                    other_value.wallclock(),
+                )),
+                Some(_) => (),
+            }
+        }
+        diff.only_in_right.extend(
+            other_values
+                .keys()
+                .filter(|label| !values.contains_key(label))
+                .cloned(),
+        );
+        diff.only_in_left.sort_unstable_by_key(ToString::to_string);
+        diff.only_in_right.sort_unstable_by_key(ToString::to_string);
+        diff.mismatched
+            .sort_unstable_by_key(|(label, _, _)| label.to_string());
+        diff
+    }
+}
+
+/// Name of the crds data variant
+pub fn kind(data: &CrdsData) -> &'static str {
+    match data {
+        CrdsData::LegacyContactInfo(_) => "LegacyContactInfo",
+        CrdsData::Vote(_, _) => "Vote",
+        CrdsData::LowestSlot(_, _) => "LowestSlot",
+        CrdsData::LegacySnapshotHashes(_) => "LegacySnapshotHashes",
+        CrdsData::AccountsHashes(_) => "AccountsHashes",
+        CrdsData::EpochSlots(_, _) => "EpochSlots",
+        CrdsData::LegacyVersion(_) => "LegacyVersion",
+        CrdsData::Version(_) => "Version",
+        CrdsData::NodeInstance(_) => "NodeInstance",
+        CrdsData::DuplicateShred(_, _) => "DuplicateShred",
+        CrdsData::SnapshotHashes(_) => "SnapshotHashes",
+        CrdsData::ContactInfo(_) => "ContactInfo",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            crds::GossipRoute, crds_value::LowestSlot,
+            legacy_contact_info::LegacyContactInfo as ContactInfo,
+        },
+    };
+
+    fn new_contact_info(pubkey: &Pubkey, wallclock: u64) -> CrdsValue {
+        CrdsValue::new_unsigned(CrdsData::LegacyContactInfo(ContactInfo::new_localhost(
+            pubkey, wallclock,
+        )))
+    }
+
+    fn new_dump(node: Pubkey, values: Vec<CrdsValue>, timestamp: u64) -> CrdsDump {
+        let mut crds = Crds::default();
+        for value in values {
+            crds.insert(value, timestamp, GossipRoute::LocalMessage)
+                .unwrap();
+        }
+        CrdsDump::new(node, &crds, timestamp)
+    }
+
+    #[test]
+    fn test_crds_dump() {
+        let node = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let lowest_slot = CrdsValue::new_unsigned(CrdsData::LowestSlot(
+            0,
+            LowestSlot::new(node, /*lowest:*/ 5, /*wallclock:*/ 100),
+        ));
+        let dump = new_dump(
+            node,
+            vec![
+                new_contact_info(&node, 20_000),
+                lowest_slot.clone(),
+                new_contact_info(&other, 19_000),
+            ],
+            20_000,
+        );
+        assert_eq!(dump.entries.len(), 3);
+        assert!(dump
+            .entries
+            .windows(2)
+            .all(|entries| entries[0].ordinal < entries[1].ordinal));
+        assert_eq!(
+            bincode::deserialize::<CrdsDump>(&bincode::serialize(&dump).unwrap()).unwrap(),
+            dump
+        );
+
+        let origin_stats = dump.origin_stats();
+        assert_eq!(origin_stats[&node].num_values, 2);
+        assert_eq!(
+            origin_stats[&node].num_values_by_kind,
+            BTreeMap::from([("LegacyContactInfo", 1), ("LowestSlot", 1)])
+        );
+        assert_eq!(origin_stats[&other].num_values, 1);
+        assert_eq!(
+            origin_stats
+                .values()
+                .map(|stats| stats.num_bytes)
+                .sum::<u64>(),
+            dump.entries
+                .iter()
+                .map(|entry| bincode::serialized_size(&entry.value).unwrap())
+                .sum::<u64>()
+        );
+
+        let stale: Vec<_> = dump
+            .stale_entries(10_000)
+            .map(|entry| &entry.value)
+            .collect();
+        assert_eq!(stale, vec![&lowest_slot]);
+        assert_eq!(dump.stale_entries(500).count(), 2);
+
+        assert_eq!(dump.oversized_entries(MAX_PUSHED_VALUE_SIZE).count(), 0);
+        assert_eq!(dump.oversized_entries(0).count(), 3);
+    }
+
+    #[test]
+    fn test_crds_dump_diff() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let left = new_dump(
+            a,
+            vec![
+                new_contact_info(&a, 10),
+                new_contact_info(&b, 10),
+                new_contact_info(&c, 10),
+            ],
+            10,
+        );
+        let right = new_dump(
+            b,
+            vec![new_contact_info(&b, 12), new_contact_info(&c, 10)],
+            12,
+        );
+        assert_eq!(
+            left.diff(&right),
+            CrdsDumpDiff {
+                only_in_left: vec![CrdsValueLabel::LegacyContactInfo(a)],
+                only_in_right: vec![],
+                mismatched: vec![(CrdsValueLabel::LegacyContactInfo(b), 10, 12)],
+            }
+        );
+        assert_eq!(
+            right.diff(&left),
+            CrdsDumpDiff {
+                only_in_left: vec![],
+                only_in_right: vec![CrdsValueLabel::LegacyContactInfo(a)],
+                mismatched: vec![(CrdsValueLabel::LegacyContactInfo(b), 12, 10)],
+            }
+        );
+        assert_eq!(left.diff(&left), CrdsDumpDiff::default());
+    }
+}
@@ -19,12 +19,14 @@ use {
         crds_value::{CrdsData, CrdsValue},
         ping_pong::PingCache,
         push_active_set::PushActiveSet,
+        push_policy::{DefaultPushPolicy, PushPolicy},
         received_cache::ReceivedCache,
     },
     bincode::serialized_size,
     itertools::Itertools,
+    rand::SeedableRng,
+    rand_chacha::ChaChaRng,
     solana_sdk::{
-        packet::PACKET_DATA_SIZE,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
         timing::timestamp,
@@ -37,24 +39,20 @@ use {
         ops::{DerefMut, RangeBounds},
         sync::{
             atomic::{AtomicUsize, Ordering},
-            Mutex, RwLock,
+            Arc, Mutex, RwLock,
         },
     },
 };
 
-const CRDS_GOSSIP_PUSH_FANOUT: usize = 9;
 // With a fanout of 9, a 2000 node cluster should only take ~3.5 hops to converge.
 // However since pushes are stake weighed, some trailing nodes
 // might need more time to receive values. 30 seconds should be plenty.
 pub const CRDS_GOSSIP_PUSH_MSG_TIMEOUT_MS: u64 = 30000;
 const CRDS_GOSSIP_PRUNE_MSG_TIMEOUT_MS: u64 = 500;
-const CRDS_GOSSIP_PRUNE_STAKE_THRESHOLD_PCT: f64 = 0.15;
-const CRDS_GOSSIP_PRUNE_MIN_INGRESS_NODES: usize = 2;
-const CRDS_GOSSIP_PUSH_ACTIVE_SET_SIZE: usize = CRDS_GOSSIP_PUSH_FANOUT + 3;
 
 pub struct CrdsGossipPush {
-    /// Max bytes per message
-    max_bytes: usize,
+    /// Fanout, active set sampling and prune thresholds
+    policy: Arc<dyn PushPolicy>,
     /// Active set of validators for push
     active_set: RwLock<PushActiveSet>,
     /// Cursor into the crds table for values to push.
@@ -63,7 +61,6 @@ pub struct CrdsGossipPush {
     /// This cache represents a lagging view of which validators
     /// currently have this node in their `active_set`
     received_cache: Mutex<ReceivedCache>,
-    push_fanout: usize,
     pub(crate) msg_timeout: u64,
     pub prune_timeout: u64,
     pub num_total: AtomicUsize,
@@ -73,13 +70,17 @@ pub struct CrdsGossipPush {
 
 impl Default for CrdsGossipPush {
     fn default() -> Self {
+        Self::new(Arc::new(DefaultPushPolicy))
+    }
+}
+
+impl CrdsGossipPush {
+    pub fn new(policy: Arc<dyn PushPolicy>) -> Self {
         Self {
-            // Allow upto 64 Crds Values per PUSH
-            max_bytes: PACKET_DATA_SIZE * 64,
+            policy,
             active_set: RwLock::default(),
             crds_cursor: Mutex::default(),
             received_cache: Mutex::new(ReceivedCache::new(2 * CRDS_UNIQUE_PUBKEY_CAPACITY)),
-            push_fanout: CRDS_GOSSIP_PUSH_FANOUT,
             msg_timeout: CRDS_GOSSIP_PUSH_MSG_TIMEOUT_MS,
             prune_timeout: CRDS_GOSSIP_PRUNE_MSG_TIMEOUT_MS,
             num_total: AtomicUsize::default(),
@@ -87,8 +88,7 @@ impl Default for CrdsGossipPush {
             num_pushes: AtomicUsize::default(),
         }
     }
-}
-impl CrdsGossipPush {
+
     pub fn num_pending(&self, crds: &RwLock<Crds>) -> usize {
         let mut cursor: Cursor = *self.crds_cursor.lock().unwrap();
         crds.read().unwrap().get_entries(&mut cursor).count()
@@ -111,8 +111,8 @@ impl CrdsGossipPush {
                     .prune(
                         self_pubkey,
                         origin,
-                        CRDS_GOSSIP_PRUNE_STAKE_THRESHOLD_PCT,
-                        CRDS_GOSSIP_PRUNE_MIN_INGRESS_NODES,
+                        self.policy.prune_stake_threshold(),
+                        self.policy.prune_min_ingress_nodes(),
                         stakes,
                     )
                     .zip(repeat(origin))
@@ -201,7 +201,7 @@ impl CrdsGossipPush {
         for value in entries {
             let serialized_size = serialized_size(&value).unwrap();
             total_bytes = total_bytes.saturating_add(serialized_size as usize);
-            if total_bytes > self.max_bytes {
+            if total_bytes > self.policy.max_push_bytes() {
                 break;
             }
             num_values += 1;
@@ -212,7 +212,7 @@ impl CrdsGossipPush {
                 |node| value.should_force_push(node),
                 stakes,
             );
-            for node in nodes.take(self.push_fanout) {
+            for node in nodes.take(self.policy.push_fanout()) {
                 push_messages.entry(*node).or_default().push(value.clone());
                 num_pushes += 1;
             }
@@ -249,7 +249,10 @@ impl CrdsGossipPush {
         pings: &mut Vec<(SocketAddr, Ping)>,
         socket_addr_space: &SocketAddrSpace,
     ) {
-        let mut rng = rand::thread_rng();
+        let mut rng = match self.policy.active_set_seed(&self_keypair.pubkey()) {
+            Some(seed) => ChaChaRng::from_seed(seed),
+            None => ChaChaRng::from_rng(rand::thread_rng()).unwrap(),
+        };
         // Active and valid gossip nodes with matching shred-version.
         let nodes = crds_gossip::get_gossip_nodes(
             &mut rng,
@@ -281,10 +284,11 @@ impl CrdsGossipPush {
         let mut active_set = self.active_set.write().unwrap();
         active_set.rotate(
             &mut rng,
-            CRDS_GOSSIP_PUSH_ACTIVE_SET_SIZE,
+            self.policy.active_set_size(),
             cluster_size,
             &nodes,
             stakes,
+            self.policy.as_ref(),
         )
     }
 }
@@ -623,4 +627,79 @@ mod tests {
             .process_push_message(&crds, vec![(Pubkey::default(), vec![value])], 0)
             .is_empty());
     }
+
+    #[test]
+    fn test_push_policy() {
+        // Pushes each value to 2 peers, sampling the active set deterministically.
+        struct TestPushPolicy;
+        impl PushPolicy for TestPushPolicy {
+            fn push_fanout(&self) -> usize {
+                2
+            }
+            fn active_set_seed(&self, _pubkey: &Pubkey) -> Option<[u8; 32]> {
+                Some([7u8; 32])
+            }
+        }
+        let now = timestamp();
+        let mut rng = rand::thread_rng();
+        let mut crds = Crds::default();
+        let mut ping_cache = new_ping_cache();
+        for _ in 0..20 {
+            let peer = ContactInfo::new_rand(&mut rng, /*pubkey=*/ None);
+            ping_cache.mock_pong(*peer.pubkey(), peer.gossip().unwrap(), Instant::now());
+            let peer = CrdsValue::new_unsigned(CrdsData::LegacyContactInfo(peer));
+            assert_eq!(crds.insert(peer, now, GossipRoute::LocalMessage), Ok(()));
+        }
+        let crds = RwLock::new(crds);
+        let ping_cache = Mutex::new(ping_cache);
+        let keypair = Keypair::new();
+        let policy: Arc<dyn PushPolicy> = Arc::new(TestPushPolicy);
+        let pushes: Vec<_> = std::iter::repeat_with(|| {
+            let push = CrdsGossipPush::new(policy.clone());
+            push.refresh_push_active_set(
+                &crds,
+                &HashMap::new(), // stakes
+                None,            // gossip_validators
+                &keypair,
+                0, // self_shred_version
+                &ping_cache,
+                &mut Vec::new(), // pings
+                &SocketAddrSpace::Unspecified,
+            );
+            push
+        })
+        .take(2)
+        .collect();
+
+        let new_msg = CrdsValue::new_unsigned(CrdsData::LegacyContactInfo(
+            ContactInfo::new_localhost(&solana_sdk::pubkey::new_rand(), now),
+        ));
+        assert_eq!(
+            crds.write()
+                .unwrap()
+                .insert(new_msg.clone(), now, GossipRoute::LocalMessage),
+            Ok(())
+        );
+        let push_messages: Vec<_> = pushes
+            .iter()
+            .map(|push| {
+                push.new_push_messages(
+                    &keypair.pubkey(),
+                    &crds,
+                    now,
+                    &HashMap::<Pubkey, u64>::default(), // stakes
+                )
+                .0
+            })
+            .collect();
+        // Same seed, same push targets.
+        assert_eq!(push_messages[0], push_messages[1]);
+        assert_eq!(
+            push_messages[0]
+                .values()
+                .filter(|values| values.contains(&new_msg))
+                .count(),
+            2
+        );
+    }
 }
@@ -136,6 +136,37 @@ pub fn discover(
 ) -> std::io::Result<(
     Vec<ContactInfo>, // all gossip peers
     Vec<ContactInfo>, // tvu peers (validators)
+)> {
+    let (all_peers, tvu_peers, _spy_ref) = discover_with_cluster_info(
+        keypair,
+        entrypoint,
+        num_nodes,
+        timeout,
+        find_nodes_by_pubkey,
+        find_node_by_gossip_addr,
+        my_gossip_addr,
+        my_shred_version,
+        socket_addr_space,
+    )?;
+    Ok((all_peers, tvu_peers))
+}
+
+/// Same as `discover`, also returning the ClusterInfo of the spy node, e.g. to dump the
+/// gossip values it received
+pub fn discover_with_cluster_info(
+    keypair: Option<Keypair>,
+    entrypoint: Option<&SocketAddr>,
+    num_nodes: Option<usize>, // num_nodes only counts validators, excludes spy nodes
+    timeout: Duration,
+    find_nodes_by_pubkey: Option<&[Pubkey]>,
+    find_node_by_gossip_addr: Option<&SocketAddr>,
+    my_gossip_addr: Option<&SocketAddr>,
+    my_shred_version: u16,
+    socket_addr_space: SocketAddrSpace,
+) -> std::io::Result<(
+    Vec<ContactInfo>, // all gossip peers
+    Vec<ContactInfo>, // tvu peers (validators)
+    Arc<ClusterInfo>,
 )> {
     let keypair = keypair.unwrap_or_else(Keypair::new);
     let exit = Arc::new(AtomicBool::new(false));
@@ -174,7 +205,7 @@ pub fn discover(
             elapsed.as_secs(),
             spy_ref.contact_info_trace()
         );
-        return Ok((all_peers, tvu_peers));
+        return Ok((all_peers, tvu_peers, spy_ref));
     }
 
     if !tvu_peers.is_empty() {
@@ -182,7 +213,7 @@ pub fn discover(
             "discover failed to match criteria by timeout...\n{}",
             spy_ref.contact_info_trace()
         );
-        return Ok((all_peers, tvu_peers));
+        return Ok((all_peers, tvu_peers, spy_ref));
     }
 
     info!("discover failed...\n{}", spy_ref.contact_info_trace());
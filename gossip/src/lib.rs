@@ -5,6 +5,7 @@ pub mod cluster_info;
 pub mod cluster_info_metrics;
 pub mod contact_info;
 pub mod crds;
+pub mod crds_dump;
 pub mod crds_entry;
 pub mod crds_gossip;
 pub mod crds_gossip_error;
@@ -23,6 +24,7 @@ pub mod gossip_service;
 pub mod legacy_contact_info;
 pub mod ping_pong;
 mod push_active_set;
+pub mod push_policy;
 mod received_cache;
 pub mod weighted_shuffle;
 
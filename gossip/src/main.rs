@@ -11,13 +11,17 @@ use {
         input_validators::{is_keypair_or_ask_keyword, is_port, is_pubkey},
     },
     solana_gossip::{
-        gossip_service::discover, legacy_contact_info::LegacyContactInfo as ContactInfo,
+        crds_dump::{self, CrdsDump, OriginStats, MAX_PUSHED_VALUE_SIZE},
+        gossip_service::{discover, discover_with_cluster_info},
+        legacy_contact_info::LegacyContactInfo as ContactInfo,
     },
     solana_sdk::pubkey::Pubkey,
     solana_streamer::socket::SocketAddrSpace,
     std::{
+        collections::BTreeMap,
         error,
         net::{IpAddr, Ipv4Addr, SocketAddr},
+        path::Path,
         process::exit,
         time::Duration,
     },
@@ -152,6 +156,73 @@ fn parse_matches() -> ArgMatches<'static> {
                         .value_name("SECONDS")
                         .takes_value(true)
                         .help("Maximum time to wait in seconds [default: wait forever]"),
+                )
+                .arg(
+                    Arg::with_name("dump_crds")
+                        .long("dump-crds")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Once done, dump all the gossip values received to FILE, \
+                               to be inspected with `analyze-crds` or `diff-crds`"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("analyze-crds")
+                .about("Display statistics of a dump of gossip values")
+                .setting(AppSettings::DisableVersion)
+                .arg(
+                    Arg::with_name("dump")
+                        .index(1)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Dump of gossip values, from `solana-validator dump-crds` \
+                               or `spy --dump-crds`"),
+                )
+                .arg(
+                    Arg::with_name("max_age")
+                        .long("max-age")
+                        .value_name("SECONDS")
+                        .takes_value(true)
+                        .default_value("60")
+                        .help("Report values whose wallclock is older than this at the time of the dump"),
+                )
+                .arg(
+                    Arg::with_name("max_size")
+                        .long("max-size")
+                        .value_name("BYTES")
+                        .takes_value(true)
+                        .help("Report values larger than this \
+                               [default: the largest value that fits in a push message]"),
+                )
+                .arg(
+                    Arg::with_name("num_origins")
+                        .long("num-origins")
+                        .value_name("NUM")
+                        .takes_value(true)
+                        .default_value("20")
+                        .help("Number of origins to display, by decreasing size of their values"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff-crds")
+                .about("Compare two dumps of gossip values")
+                .setting(AppSettings::DisableVersion)
+                .arg(
+                    Arg::with_name("left")
+                        .index(1)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Dump of gossip values"),
+                )
+                .arg(
+                    Arg::with_name("right")
+                        .index(2)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Dump of gossip values to compare to"),
                 ),
         )
         .get_matches()
@@ -244,7 +315,7 @@ fn process_spy(matches: &ArgMatches, socket_addr_space: SocketAddrSpace) -> std:
         }),
     );
     let discover_timeout = Duration::from_secs(timeout.unwrap_or(u64::MAX));
-    let (_all_peers, validators) = discover(
+    let (_all_peers, validators, spy_ref) = discover_with_cluster_info(
         identity_keypair,
         entrypoint_addr.as_ref(),
         num_nodes,
@@ -256,6 +327,15 @@ fn process_spy(matches: &ArgMatches, socket_addr_space: SocketAddrSpace) -> std:
         socket_addr_space,
     )?;
 
+    if let Some(dump_path) = matches.value_of("dump_crds") {
+        let crds_dump = spy_ref.crds_dump();
+        crds_dump.write(Path::new(dump_path))?;
+        println!(
+            "Wrote {} gossip values to {dump_path}",
+            crds_dump.entries.len()
+        );
+    }
+
     process_spy_results(
         timeout,
         validators,
@@ -267,6 +347,114 @@ fn process_spy(matches: &ArgMatches, socket_addr_space: SocketAddrSpace) -> std:
     Ok(())
 }
 
+fn read_crds_dump(path: &str) -> CrdsDump {
+    CrdsDump::read(Path::new(path)).unwrap_or_else(|err| {
+        eprintln!("Unable to read gossip values dump {path}: {err}");
+        exit(1);
+    })
+}
+
+fn process_analyze_crds(matches: &ArgMatches) {
+    let crds_dump = read_crds_dump(matches.value_of("dump").unwrap());
+    let max_age = value_t_or_exit!(matches, "max_age", u64);
+    let max_size = value_t!(matches, "max_size", usize).unwrap_or(MAX_PUSHED_VALUE_SIZE);
+    let num_origins = value_t_or_exit!(matches, "num_origins", usize);
+
+    println!(
+        "Node {}: {} gossip values at timestamp {}",
+        crds_dump.node,
+        crds_dump.entries.len(),
+        crds_dump.timestamp
+    );
+
+    let mut num_values_by_kind = BTreeMap::<_, usize>::new();
+    for entry in &crds_dump.entries {
+        *num_values_by_kind
+            .entry(crds_dump::kind(&entry.value.data))
+            .or_default() += 1;
+    }
+    println!("\nValues by kind:");
+    for (kind, num_values) in num_values_by_kind {
+        println!("  {kind:<20} {num_values:>8}");
+    }
+
+    let origin_stats = crds_dump.origin_stats();
+    let mut origin_stats: Vec<(Pubkey, OriginStats)> = origin_stats.into_iter().collect();
+    origin_stats.sort_unstable_by(|(_, a), (_, b)| b.num_bytes.cmp(&a.num_bytes));
+    println!(
+        "\nValues by origin ({} origins, top {}):",
+        origin_stats.len(),
+        num_origins.min(origin_stats.len())
+    );
+    for (origin, stats) in origin_stats.iter().take(num_origins) {
+        println!(
+            "  {origin:<44} {:>8} values {:>10} bytes  {}",
+            stats.num_values,
+            stats.num_bytes,
+            stats
+                .num_values_by_kind
+                .iter()
+                .map(|(kind, num_values)| format!("{kind}={num_values}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+
+    let stale_entries: Vec<_> = crds_dump.stale_entries(max_age * 1000).collect();
+    println!(
+        "\n{} values with a wallclock older than {max_age}s:",
+        stale_entries.len()
+    );
+    for entry in stale_entries {
+        println!(
+            "  {} age: {}ms",
+            entry.value.label(),
+            crds_dump.timestamp.saturating_sub(entry.value.wallclock())
+        );
+    }
+
+    let oversized_entries: Vec<_> = crds_dump.oversized_entries(max_size).collect();
+    println!(
+        "\n{} values larger than {max_size} bytes:",
+        oversized_entries.len()
+    );
+    for (entry, size) in oversized_entries {
+        println!("  {} size: {size} bytes", entry.value.label());
+    }
+}
+
+fn process_diff_crds(matches: &ArgMatches) {
+    let left = read_crds_dump(matches.value_of("left").unwrap());
+    let right = read_crds_dump(matches.value_of("right").unwrap());
+    let diff = left.diff(&right);
+
+    println!(
+        "Left: node {}, {} gossip values at timestamp {}",
+        left.node,
+        left.entries.len(),
+        left.timestamp
+    );
+    println!(
+        "Right: node {}, {} gossip values at timestamp {}",
+        right.node,
+        right.entries.len(),
+        right.timestamp
+    );
+
+    println!("\n{} values only in left:", diff.only_in_left.len());
+    for label in &diff.only_in_left {
+        println!("  {label}");
+    }
+    println!("\n{} values only in right:", diff.only_in_right.len());
+    for label in &diff.only_in_right {
+        println!("  {label}");
+    }
+    println!("\n{} values that differ:", diff.mismatched.len());
+    for (label, left_wallclock, right_wallclock) in &diff.mismatched {
+        println!("  {label} left wallclock: {left_wallclock}, right wallclock: {right_wallclock}");
+    }
+}
+
 fn parse_entrypoint(matches: &ArgMatches) -> Option<SocketAddr> {
     matches.value_of("entrypoint").map(|entrypoint| {
         solana_net_utils::parse_host_port(entrypoint).unwrap_or_else(|e| {
@@ -337,6 +525,12 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         ("rpc-url", Some(matches)) => {
             process_rpc_url(matches, socket_addr_space)?;
         }
+        ("analyze-crds", Some(matches)) => {
+            process_analyze_crds(matches);
+        }
+        ("diff-crds", Some(matches)) => {
+            process_diff_crds(matches);
+        }
         _ => unreachable!(),
     }
 
@@ -1,5 +1,5 @@
 use {
-    crate::weighted_shuffle::WeightedShuffle,
+    crate::{push_policy::PushPolicy, weighted_shuffle::WeightedShuffle},
     indexmap::IndexMap,
     rand::Rng,
     solana_bloom::bloom::{AtomicBloom, Bloom},
@@ -66,6 +66,7 @@ impl PushActiveSet {
         // Gossip nodes to be sampled for each push active set.
         nodes: &[Pubkey],
         stakes: &HashMap<Pubkey, u64>,
+        policy: &dyn PushPolicy,
     ) {
         let num_bloom_filter_items = cluster_size.max(Self::MIN_NUM_BLOOM_ITEMS);
         // Active set of nodes to push to are sampled from these gossip nodes,
@@ -82,18 +83,7 @@ impl PushActiveSet {
         for (k, entry) in self.0.iter_mut().enumerate() {
             let weights: Vec<u64> = buckets
                 .iter()
-                .map(|&bucket| {
-                    // bucket <- get_stake_bucket(min stake of {
-                    //  this node, crds value owner and gossip peer
-                    // })
-                    // weight <- (bucket + 1)^2
-                    // min stake of {...} is a proxy for how much we care about
-                    // the link, and tries to mirror similar logic on the
-                    // receiving end when pruning incoming links:
-                    // https://github.com/solana-labs/solana/blob/81394cf92/gossip/src/received_cache.rs#L100-L105
-                    let bucket = bucket.min(k) as u64;
-                    bucket.saturating_add(1).saturating_pow(2)
-                })
+                .map(|&bucket| policy.active_set_weight(k, bucket))
                 .collect();
             entry.rotate(rng, size, num_bloom_filter_items, nodes, &weights);
         }
@@ -175,7 +165,10 @@ fn get_stake_bucket(stake: Option<&u64>) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, rand::SeedableRng, rand_chacha::ChaChaRng, std::iter::repeat_with};
+    use {
+        super::*, crate::push_policy::DefaultPushPolicy, rand::SeedableRng, rand_chacha::ChaChaRng,
+        std::iter::repeat_with,
+    };
 
     #[test]
     fn test_get_stake_bucket() {
@@ -212,7 +205,14 @@ mod tests {
         stakes.insert(pubkey, rng.gen_range(1..MAX_STAKE));
         let mut active_set = PushActiveSet::default();
         assert!(active_set.0.iter().all(|entry| entry.0.is_empty()));
-        active_set.rotate(&mut rng, 5, CLUSTER_SIZE, &nodes, &stakes);
+        active_set.rotate(
+            &mut rng,
+            5,
+            CLUSTER_SIZE,
+            &nodes,
+            &stakes,
+            &DefaultPushPolicy,
+        );
         assert!(active_set.0.iter().all(|entry| entry.0.len() == 5));
         // Assert that for all entries, each filter already prunes the key.
         for entry in &active_set.0 {
@@ -237,7 +237,14 @@ mod tests {
         assert!(active_set
             .get_nodes(&pubkey, other, |_| false, &stakes)
             .eq([13, 18, 16, 0].into_iter().map(|k| &nodes[k])));
-        active_set.rotate(&mut rng, 7, CLUSTER_SIZE, &nodes, &stakes);
+        active_set.rotate(
+            &mut rng,
+            7,
+            CLUSTER_SIZE,
+            &nodes,
+            &stakes,
+            &DefaultPushPolicy,
+        );
         assert!(active_set.0.iter().all(|entry| entry.0.len() == 7));
         assert!(active_set
             .get_nodes(&pubkey, origin, |_| false, &stakes)
@@ -0,0 +1,77 @@
+//! Policy of the gossip push overlay.
+//!
+//! The policy decides which gossip peers are sampled into the push active set, to how many
+//! of them each value is pushed, how many bytes are pushed each round, and when redundant
+//! peers pushing values of an origin are pruned. `DefaultPushPolicy` is the one used in
+//! the cluster; other policies allow experimenting with gossip bandwidth and propagation,
+//! e.g. in simulations.
+
+use solana_sdk::{packet::PACKET_DATA_SIZE, pubkey::Pubkey};
+
+const CRDS_GOSSIP_PUSH_FANOUT: usize = 9;
+const CRDS_GOSSIP_PUSH_ACTIVE_SET_SIZE: usize = CRDS_GOSSIP_PUSH_FANOUT + 3;
+// Allow upto 64 Crds Values per PUSH
+const CRDS_GOSSIP_PUSH_MAX_BYTES: usize = PACKET_DATA_SIZE * 64;
+const CRDS_GOSSIP_PRUNE_STAKE_THRESHOLD_PCT: f64 = 0.15;
+const CRDS_GOSSIP_PRUNE_MIN_INGRESS_NODES: usize = 2;
+
+/// The provided methods implement the default policy, so that a policy only overrides what
+/// it changes.
+pub trait PushPolicy: Send + Sync {
+    /// Maximum number of peers of the active set each value is pushed to
+    fn push_fanout(&self) -> usize {
+        CRDS_GOSSIP_PUSH_FANOUT
+    }
+
+    /// Number of peers retained in each entry of the active set when it is rotated
+    fn active_set_size(&self) -> usize {
+        CRDS_GOSSIP_PUSH_ACTIVE_SET_SIZE
+    }
+
+    /// Weight of a gossip peer when sampling the active set entry for `bucket`.
+    ///
+    /// Each active set entry is used for the values whose stake bucket of
+    ///     min stake of { this node, crds value owner }
+    /// is equal to `bucket`, and `peer_bucket` is the stake bucket of the peer. Stake
+    /// buckets are the number of bits of the stake in SOL. Weights must be non-zero.
+    fn active_set_weight(&self, bucket: usize, peer_bucket: usize) -> u64 {
+        // bucket <- get_stake_bucket(min stake of {
+        //  this node, crds value owner and gossip peer
+        // })
+        // weight <- (bucket + 1)^2
+        // min stake of {...} is a proxy for how much we care about
+        // the link, and tries to mirror similar logic on the
+        // receiving end when pruning incoming links:
+        // https://github.com/solana-labs/solana/blob/81394cf92/gossip/src/received_cache.rs#L100-L105
+        let bucket = bucket.min(peer_bucket) as u64;
+        bucket.saturating_add(1).saturating_pow(2)
+    }
+
+    /// Seed of the rng sampling the active set of the node, to make the active set
+    /// deterministic. If None, the active set is sampled randomly.
+    fn active_set_seed(&self, _pubkey: &Pubkey) -> Option<[u8; 32]> {
+        None
+    }
+
+    /// Maximum number of bytes of crds values pushed each round
+    fn max_push_bytes(&self) -> usize {
+        CRDS_GOSSIP_PUSH_MAX_BYTES
+    }
+
+    /// Fraction of min stake of { this node, crds value owner } that the peers which timely
+    /// delivered values of an origin should aggregate before the other peers are pruned.
+    fn prune_stake_threshold(&self) -> f64 {
+        CRDS_GOSSIP_PRUNE_STAKE_THRESHOLD_PCT
+    }
+
+    /// Minimum number of peers which are never pruned for an origin
+    fn prune_min_ingress_nodes(&self) -> usize {
+        CRDS_GOSSIP_PRUNE_MIN_INGRESS_NODES
+    }
+}
+
+/// Policy used in the cluster
+#[derive(Debug, Default)]
+pub struct DefaultPushPolicy;
+
+impl PushPolicy for DefaultPushPolicy {}
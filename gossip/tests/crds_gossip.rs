@@ -11,14 +11,17 @@ use {
         crds_gossip::*,
         crds_gossip_error::CrdsGossipError,
         crds_gossip_pull::{CrdsTimeouts, ProcessPullStats, CRDS_GOSSIP_PULL_CRDS_TIMEOUT_MS},
-        crds_gossip_push::CRDS_GOSSIP_PUSH_MSG_TIMEOUT_MS,
-        crds_value::{CrdsData, CrdsValue, CrdsValueLabel},
+        crds_gossip_push::{CrdsGossipPush, CRDS_GOSSIP_PUSH_MSG_TIMEOUT_MS},
+        crds_value::{CrdsData, CrdsValue, CrdsValueLabel, LowestSlot},
         legacy_contact_info::LegacyContactInfo as ContactInfo,
         ping_pong::PingCache,
+        push_policy::{DefaultPushPolicy, PushPolicy},
     },
     solana_rayon_threadlimit::get_thread_count,
     solana_sdk::{
         hash::hash,
+        native_token::LAMPORTS_PER_SOL,
+        packet::PACKET_DATA_SIZE,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
         timing::timestamp,
@@ -26,6 +29,7 @@ use {
     solana_streamer::socket::SocketAddrSpace,
     std::{
         collections::{HashMap, HashSet},
+        net::Ipv4Addr,
         ops::Deref,
         sync::{Arc, Mutex},
         time::{Duration, Instant},
@@ -244,6 +248,231 @@ fn connected_staked_network_create(stakes: &[u64]) -> Network {
     Network::new(network)
 }
 
+// Fully connected network where each node has the given stake and pushes
+// with the given policy. Each node holds the contact-info of all nodes and
+// has a pong from each, so that the push active sets are sampled from the
+// whole network.
+fn staked_network_create(stakes: &[u64], policy: Arc<dyn PushPolicy>) -> Network {
+    let now = timestamp();
+    let nodes: Vec<_> = stakes
+        .iter()
+        .enumerate()
+        .map(|(k, &stake)| {
+            let node_keypair = Arc::new(Keypair::new());
+            let mut contact_info = ContactInfo::new_localhost(&node_keypair.pubkey(), now);
+            // Distinct gossip addresses, otherwise the nodes are deduped when
+            // sampling the push active set.
+            contact_info
+                .set_gossip((Ipv4Addr::LOCALHOST, 1024 + k as u16))
+                .unwrap();
+            let node = CrdsGossip {
+                push: CrdsGossipPush::new(policy.clone()),
+                ..CrdsGossip::default()
+            };
+            Node::staked(node_keypair, contact_info, Arc::new(node), stake)
+        })
+        .collect();
+    let entries: Vec<_> = nodes
+        .iter()
+        .map(|node| CrdsValue::new_unsigned(CrdsData::LegacyContactInfo(node.contact_info.clone())))
+        .collect();
+    for node in &nodes {
+        let mut node_crds = node.gossip.crds.write().unwrap();
+        let mut ping_cache = node.ping_cache.lock().unwrap();
+        for (other, entry) in nodes.iter().zip(&entries) {
+            node_crds
+                .insert(entry.clone(), now, GossipRoute::LocalMessage)
+                .unwrap();
+            ping_cache.mock_pong(
+                other.keypair.pubkey(),
+                other.contact_info.gossip().unwrap(),
+                Instant::now(),
+            );
+        }
+    }
+    Network::new(
+        nodes
+            .into_iter()
+            .map(|node| (node.keypair.pubkey(), node))
+            .collect(),
+    )
+}
+
+// Zipf-like stake distribution: the k-th node has 1/k of the largest stake.
+fn zipf_stakes(num: usize) -> Vec<u64> {
+    (1..=num as u64)
+        .map(|k| 1_000_000 * LAMPORTS_PER_SOL / k)
+        .collect()
+}
+
+// Fractions of the total stake for which propagation latency is measured.
+const STAKE_THRESHOLDS: [f64; 3] = [0.5, 0.9, 1.0];
+
+struct PushPropagation {
+    // Number of push rounds until the value reached each of STAKE_THRESHOLDS.
+    rounds: [Option<usize>; 3],
+    // Fraction of the total stake the value reached.
+    stake_reached: f64,
+    bytes: usize,
+    num_msgs: usize,
+}
+
+// Pushes a new value from the origin and measures, round by round, how much
+// of the stake it reaches through push alone.
+fn network_run_push_propagation(
+    network: &Network,
+    origin: &Pubkey,
+    max_rounds: usize,
+) -> PushPropagation {
+    let network_values: Vec<Node> = network.values().cloned().collect();
+    let stakes = stakes(network);
+    let total_stake: u64 = stakes.values().sum();
+    network_values.par_iter().for_each(|node| {
+        node.gossip.refresh_push_active_set(
+            &node.keypair,
+            0, // shred version
+            &stakes,
+            None, // gossip validators
+            &node.ping_cache,
+            &mut Vec::new(), // pings
+            &SocketAddrSpace::Unspecified,
+        );
+    });
+    let start = timestamp();
+    // Advance the push cursors past the contact-infos, so that only the new
+    // value is pushed.
+    network_values.par_iter().for_each(|node| {
+        node.gossip
+            .new_push_messages(&node.keypair.pubkey(), vec![], start, &stakes);
+    });
+    let value = CrdsValue::new_unsigned(CrdsData::LowestSlot(
+        0,
+        LowestSlot::new(*origin, /*lowest:*/ 0, /*wallclock:*/ start),
+    ));
+    let label = value.label();
+    network[origin]
+        .gossip
+        .crds
+        .write()
+        .unwrap()
+        .insert(value, start, GossipRoute::LocalMessage)
+        .unwrap();
+    let mut propagation = PushPropagation {
+        rounds: [None; 3],
+        stake_reached: 0.0,
+        bytes: 0,
+        num_msgs: 0,
+    };
+    for round in 1..=max_rounds {
+        let now = start + round as u64 * 100;
+        let requests: Vec<_> = network_values
+            .par_iter()
+            .map(|node| {
+                let node_pubkey = node.keypair.pubkey();
+                let (push_messages, _, _) =
+                    node.gossip
+                        .new_push_messages(&node_pubkey, vec![], now, &stakes);
+                (node_pubkey, push_messages)
+            })
+            .collect();
+        let transfered: Vec<(usize, usize)> = requests
+            .into_par_iter()
+            .map(|(from, push_messages)| {
+                let mut bytes: usize = 0;
+                let mut num_msgs: usize = 0;
+                for (to, msgs) in push_messages {
+                    bytes += serialized_size(&msgs).unwrap() as usize;
+                    num_msgs += 1;
+                    let node = &network[&to];
+                    let origins = node.gossip.process_push_message(vec![(from, msgs)], now);
+                    let prunes = node.gossip.prune_received_cache(&to, origins, &stakes);
+                    for (peer, prune_keys) in prunes {
+                        bytes += serialized_size(&prune_keys).unwrap() as usize;
+                        network[&peer]
+                            .gossip
+                            .process_prune_msg(&peer, &to, &peer, &prune_keys, now, now, &stakes)
+                            .unwrap();
+                    }
+                }
+                (bytes, num_msgs)
+            })
+            .collect();
+        for (bytes, num_msgs) in transfered {
+            propagation.bytes += bytes;
+            propagation.num_msgs += num_msgs;
+        }
+        let stake_reached: u64 = network_values
+            .par_iter()
+            .filter(|node| {
+                let node_crds = node.gossip.crds.read().unwrap();
+                node_crds.get::<&CrdsValue>(&label).is_some()
+            })
+            .map(|node| node.stake)
+            .sum();
+        propagation.stake_reached = stake_reached as f64 / total_stake as f64;
+        for (rounds, threshold) in propagation.rounds.iter_mut().zip(STAKE_THRESHOLDS) {
+            if rounds.is_none() && propagation.stake_reached >= threshold {
+                *rounds = Some(round);
+            }
+        }
+        trace!(
+            "network_run_push_propagation_{}: round: {} stake_reached: {} bytes: {} num_msgs: {}",
+            network.len(),
+            round,
+            propagation.stake_reached,
+            propagation.bytes,
+            propagation.num_msgs,
+        );
+        if stake_reached == total_stake {
+            break;
+        }
+    }
+    propagation
+}
+
+fn push_propagation_simulator(num: usize, policy: Arc<dyn PushPolicy>) -> PushPropagation {
+    let stakes = zipf_stakes(num);
+    let network = staked_network_create(&stakes, policy);
+    let origin = network
+        .iter()
+        .max_by_key(|(_, node)| node.stake)
+        .map(|(pubkey, _)| *pubkey)
+        .unwrap();
+    let propagation = network_run_push_propagation(&network, &origin, /*max_rounds:*/ 20);
+    info!(
+        "push_propagation_{}: rounds to 50%, 90%, 100% of stake: {:?}, stake reached: {}, \
+         bytes: {}, num_msgs: {}",
+        num, propagation.rounds, propagation.stake_reached, propagation.bytes, propagation.num_msgs,
+    );
+    propagation
+}
+
+// Samples the push active set with an rng seeded by the node's pubkey.
+struct DeterministicPushPolicy;
+
+impl PushPolicy for DeterministicPushPolicy {
+    fn active_set_seed(&self, pubkey: &Pubkey) -> Option<[u8; 32]> {
+        Some(pubkey.to_bytes())
+    }
+}
+
+// Pushes to fewer peers and caps the bytes pushed each round.
+struct BandwidthCappedPushPolicy;
+
+impl PushPolicy for BandwidthCappedPushPolicy {
+    fn push_fanout(&self) -> usize {
+        4
+    }
+
+    fn active_set_size(&self) -> usize {
+        6
+    }
+
+    fn max_push_bytes(&self) -> usize {
+        PACKET_DATA_SIZE * 8
+    }
+}
+
 fn network_simulator_pull_only(thread_pool: &ThreadPool, network: &Network) {
     let num = network.len();
     let (converged, bytes_tx) = network_run_pull(thread_pool, network, 0, num * 2, 0.9);
@@ -733,6 +962,34 @@ fn test_star_network_large_push() {
     network_simulator(&thread_pool, &mut network, 0.9);
 }
 #[test]
+#[serial]
+fn test_push_propagation_default_policy() {
+    solana_logger::setup();
+    let propagation = push_propagation_simulator(200, Arc::new(DefaultPushPolicy));
+    assert!(propagation.rounds[1].is_some());
+}
+#[test]
+#[serial]
+fn test_push_propagation_deterministic_policy() {
+    solana_logger::setup();
+    let propagation = push_propagation_simulator(200, Arc::new(DeterministicPushPolicy));
+    assert!(propagation.rounds[1].is_some());
+}
+#[test]
+#[serial]
+fn test_push_propagation_bandwidth_capped_policy() {
+    solana_logger::setup();
+    let propagation = push_propagation_simulator(200, Arc::new(BandwidthCappedPushPolicy));
+    assert!(propagation.rounds[0].is_some());
+}
+#[test]
+#[ignore]
+fn test_push_propagation_large() {
+    solana_logger::setup();
+    push_propagation_simulator(2000, Arc::new(DefaultPushPolicy));
+    push_propagation_simulator(2000, Arc::new(BandwidthCappedPushPolicy));
+}
+#[test]
 fn test_prune_errors() {
     let crds_gossip = CrdsGossip::default();
     let keypair = Keypair::new();
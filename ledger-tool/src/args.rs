@@ -3,6 +3,7 @@ use {
     clap::{value_t, values_t_or_exit, ArgMatches},
     solana_accounts_db::{
         accounts_db::{AccountsDb, AccountsDbConfig, FillerAccountsConfig},
+        accounts_file::AccountsFileProvider,
         accounts_index::{AccountsIndexConfig, IndexLimitMb},
         partitioned_rewards::TestPartitionedEpochRewards,
     },
@@ -83,6 +84,12 @@ pub fn get_accounts_db_config(
         exhaustively_verify_refcounts: arg_matches.is_present("accounts_db_verify_refcounts"),
         skip_initial_hash_calc: arg_matches.is_present("accounts_db_skip_initial_hash_calculation"),
         test_partitioned_epoch_rewards,
+        accounts_file_provider: value_t!(
+            arg_matches,
+            "accounts_db_accounts_file_provider",
+            AccountsFileProvider
+        )
+        .unwrap_or_default(),
         ..AccountsDbConfig::default()
     }
 }
@@ -29,7 +29,7 @@ use {
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -585,6 +585,7 @@ async fn get_bigtable(
                 credential_type: CredentialType::Filepath(Some(args.crediential_path.unwrap())),
                 instance_name: args.instance_name,
                 app_profile_id: args.app_profile_id,
+                file_system_path: None,
             },
         )
         .await
@@ -620,6 +621,15 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests")
                 )
+                .arg(
+                    Arg::with_name("file_system_path")
+                        .global(true)
+                        .long("file-system-path")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .help("Use the ledger data stored as files under this directory \
+                               instead of a Bigtable instance")
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -1005,6 +1015,11 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         "rpc_bigtable_app_profile_id",
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
+    // without a default value, the global arg is propagated to the (sub)subcommand args
+    let file_system_path = sub_matches
+        .and_then(|sub_matches| sub_matches.value_of("file_system_path"))
+        .or_else(|| matches.value_of("file_system_path"))
+        .map(PathBuf::from);
 
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
@@ -1022,6 +1037,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(upload(
@@ -1038,6 +1054,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: !arg_matches.is_present("force"),
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(delete_slots(slots, config))
@@ -1047,6 +1064,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(first_available_block(config))
@@ -1057,6 +1075,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(block(slot, output_format, config))
@@ -1068,6 +1087,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1080,6 +1100,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1113,6 +1134,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1133,6 +1155,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                file_system_path,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
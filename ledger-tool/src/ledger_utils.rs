@@ -28,6 +28,7 @@ use {
             PrunedBanksRequestHandler, SnapshotRequestHandler,
         },
         bank_forks::BankForks,
+        snapshot_archive_info::SnapshotArchiveInfoGetter,
         snapshot_config::SnapshotConfig,
         snapshot_hash::StartingSnapshotHashes,
         snapshot_utils::{
@@ -89,7 +90,27 @@ pub fn load_and_process_ledger(
             snapshot_archive_path.unwrap_or_else(|| blockstore.ledger_path().to_path_buf());
         let incremental_snapshot_archives_dir =
             incremental_snapshot_archive_path.unwrap_or_else(|| full_snapshot_archives_dir.clone());
-        if let Some(full_snapshot_slot) =
+        if let Some(max_slot) = process_options.max_snapshot_archive_slot {
+            if let Some(full_snapshot_archive_info) =
+                snapshot_utils::get_highest_full_snapshot_archive_info_at_or_before(
+                    &full_snapshot_archives_dir,
+                    max_slot,
+                )
+            {
+                let incremental_snapshot_slot =
+                    snapshot_utils::get_highest_incremental_snapshot_archive_info_at_or_before(
+                        &incremental_snapshot_archives_dir,
+                        full_snapshot_archive_info.slot(),
+                        max_slot,
+                    )
+                    .map(|incremental_snapshot_archive_info| {
+                        incremental_snapshot_archive_info.slot()
+                    })
+                    .unwrap_or_default();
+                starting_slot =
+                    std::cmp::max(full_snapshot_archive_info.slot(), incremental_snapshot_slot);
+            }
+        } else if let Some(full_snapshot_slot) =
             snapshot_utils::get_highest_full_snapshot_archive_slot(&full_snapshot_archives_dir)
         {
             let incremental_snapshot_slot =
@@ -209,28 +230,30 @@ pub fn load_and_process_ledger(
     }
 
     let geyser_plugin_active = arg_matches.is_present("geyser_plugin_config");
-    let (accounts_update_notifier, transaction_notifier) = if geyser_plugin_active {
-        let geyser_config_files = values_t_or_exit!(arg_matches, "geyser_plugin_config", String)
-            .into_iter()
-            .map(PathBuf::from)
-            .collect::<Vec<_>>();
+    let (accounts_update_notifier, transaction_notifier, transaction_trace_notifications_enabled) =
+        if geyser_plugin_active {
+            let geyser_config_files =
+                values_t_or_exit!(arg_matches, "geyser_plugin_config", String)
+                    .into_iter()
+                    .map(PathBuf::from)
+                    .collect::<Vec<_>>();
 
-        let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
-        drop(confirmed_bank_sender);
-        let geyser_service =
-            GeyserPluginService::new(confirmed_bank_receiver, &geyser_config_files).unwrap_or_else(
-                |err| {
-                    eprintln!("Failed to setup Geyser service: {err}");
-                    exit(1);
-                },
-            );
-        (
-            geyser_service.get_accounts_update_notifier(),
-            geyser_service.get_transaction_notifier(),
-        )
-    } else {
-        (None, None)
-    };
+            let (confirmed_bank_sender, confirmed_bank_receiver) = unbounded();
+            drop(confirmed_bank_sender);
+            let geyser_service =
+                GeyserPluginService::new(confirmed_bank_receiver, &geyser_config_files)
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to setup Geyser service: {err}");
+                        exit(1);
+                    });
+            (
+                geyser_service.get_accounts_update_notifier(),
+                geyser_service.get_transaction_notifier(),
+                geyser_service.transaction_trace_notifications_enabled(),
+            )
+        } else {
+            (None, None, false)
+        };
 
     let exit = Arc::new(AtomicBool::new(false));
     let (bank_forks, leader_schedule_cache, starting_snapshot_hashes, ..) =
@@ -328,6 +351,7 @@ pub fn load_and_process_ledger(
             (
                 Some(TransactionStatusSender {
                     sender: transaction_status_sender,
+                    enable_instruction_trace_recording: transaction_trace_notifications_enabled,
                 }),
                 Some(transaction_status_service),
             )
@@ -17,7 +17,8 @@ use {
     serde_json::json,
     solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding},
     solana_accounts_db::{
-        accounts::Accounts, accounts_db::CalcAccountsHashDataSource, accounts_index::ScanConfig,
+        accounts::Accounts, accounts_db::CalcAccountsHashDataSource,
+        accounts_file::AccountsFileProvider, accounts_index::ScanConfig,
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
     },
     solana_clap_utils::{
@@ -31,8 +32,10 @@ use {
     },
     solana_cli_output::{CliAccount, CliAccountNewConfig, OutputFormat},
     solana_core::{
+        banking_simulation::{BankingSimulator, BankingTraceEvents},
+        consensus::fork_choice_snapshot::ForkChoiceSnapshot,
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
-        validator::BlockVerificationMethod,
+        validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
     solana_entry::entry::Entry,
@@ -88,7 +91,7 @@ use {
     std::{
         collections::{BTreeMap, BTreeSet, HashMap, HashSet},
         ffi::OsStr,
-        fs::File,
+        fs::{self, File},
         io::{self, stdout, BufRead, BufReader, Write},
         num::NonZeroUsize,
         path::{Path, PathBuf},
@@ -428,6 +431,18 @@ fn render_dot(dot: String, output_file: &str, output_format: &str) -> io::Result
     Ok(())
 }
 
+/// Writes `dot` to `output_file`, rendered according to its pdf or png extension
+fn write_dot(dot: String, output_file: &str) -> io::Result<()> {
+    let extension = Path::new(output_file).extension();
+    if extension == Some(OsStr::new("pdf")) {
+        render_dot(dot, output_file, "pdf")
+    } else if extension == Some(OsStr::new("png")) {
+        render_dot(dot, output_file, "png")
+    } else {
+        File::create(output_file).and_then(|mut file| file.write_all(&dot.into_bytes()))
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 enum GraphVoteAccountMode {
     Disabled,
@@ -737,6 +752,123 @@ fn graph_forks(bank_forks: &BankForks, config: &GraphConfig) -> String {
     dot.join("\n")
 }
 
+fn graph_fork_choice(snapshot: &ForkChoiceSnapshot) -> String {
+    let stake_percent = |stake: u64, total_stake: u64| {
+        if total_stake == 0 {
+            0.
+        } else {
+            stake as f64 / total_stake as f64 * 100.
+        }
+    };
+    let tower_votes: HashMap<_, _> = snapshot
+        .tower
+        .votes
+        .iter()
+        .map(|vote| (vote.slot, vote.confirmation_count))
+        .collect();
+
+    let mut dot = vec!["digraph {".to_string()];
+
+    for slot in &snapshot.slots {
+        let mut status = vec![];
+        if slot.is_duplicate_confirmed {
+            status.push("duplicate confirmed");
+        } else if slot.is_supermajority_confirmed {
+            status.push("supermajority confirmed");
+        }
+        if slot.is_unconfirmed_duplicate {
+            status.push("unconfirmed duplicate");
+        } else if !slot.is_candidate {
+            status.push("invalid ancestor");
+        }
+        if !slot.is_propagated {
+            status.push("not propagated");
+        }
+        if slot.is_locked_out {
+            status.push("locked out");
+        }
+        if !slot.vote_threshold_passed {
+            status.push("vote threshold failed");
+        }
+
+        dot.push(format!(
+            r#"  "{}"[label="{}\nhash: {}\nstake: {:.1} SOL ({:.1}%)\nsubtree stake: {:.1} SOL ({:.1}%){}{}",style="{}",color="{}"];"#,
+            slot.slot,
+            slot.slot,
+            slot.bank_hash,
+            lamports_to_sol(slot.stake_voted_at),
+            stake_percent(slot.stake_voted_at, slot.total_stake),
+            lamports_to_sol(slot.stake_voted_subtree),
+            stake_percent(slot.stake_voted_subtree, slot.total_stake),
+            if let Some(confirmation_count) = tower_votes.get(&slot.slot) {
+                format!("\ntower vote (conf={confirmation_count})")
+            } else {
+                "".to_string()
+            },
+            if status.is_empty() {
+                "".to_string()
+            } else {
+                format!("\n{}", status.join(", "))
+            },
+            if slot.slot == snapshot.heaviest_slot {
+                "filled"
+            } else if slot.has_voted {
+                "bold"
+            } else {
+                ""
+            },
+            if slot.is_unconfirmed_duplicate || !slot.is_candidate {
+                "red"
+            } else if slot.is_duplicate_confirmed || slot.is_supermajority_confirmed {
+                "green"
+            } else {
+                "black"
+            },
+        ));
+
+        if let Some(parent) = slot.parent {
+            let slot_distance = slot.slot - parent;
+            let link_label = if slot_distance > 1 {
+                format!("label=\"{} slots\",color=red", slot_distance - 1)
+            } else {
+                "color=blue".to_string()
+            };
+            dot.push(format!(
+                r#"  "{}" -> "{}"[{},dir=back];"#,
+                slot.slot, parent, link_label,
+            ));
+        }
+    }
+
+    // Link the local tower to the slot it last voted on
+    dot.push(format!(
+        r#"  "tower"[shape=box,label="Tower of {}\nroot slot: {}\nvote history:\n{}"];"#,
+        snapshot.tower.node_pubkey,
+        snapshot.tower.root,
+        snapshot
+            .tower
+            .votes
+            .iter()
+            .map(|vote| format!("slot {} (conf={})", vote.slot, vote.confirmation_count))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    ));
+    if let Some(last_voted_slot) = snapshot.tower.last_voted_slot {
+        if snapshot
+            .slots
+            .iter()
+            .any(|slot| slot.slot == last_voted_slot)
+        {
+            dot.push(format!(
+                r#"  "tower" -> "{last_voted_slot}" [style=dashed,label="last vote"];"#
+            ));
+        }
+    }
+
+    dot.push("}".to_string());
+    dot.join("\n")
+}
+
 fn analyze_column<
     C: solana_ledger::blockstore_db::Column + solana_ledger::blockstore_db::ColumnName,
 >(
@@ -1187,6 +1319,13 @@ fn main() {
             "AppendVecs that are older than (slots_per_epoch - SLOT-OFFSET) are squashed together.",
         )
         .hidden(hidden_unless_forced());
+    let accounts_file_provider_arg = Arg::with_name("accounts_db_accounts_file_provider")
+        .long("accounts-db-accounts-file-provider")
+        .value_name("FORMAT")
+        .takes_value(true)
+        .possible_values(AccountsFileProvider::cli_names())
+        .help(AccountsFileProvider::cli_message())
+        .hidden(hidden_unless_forced());
     let halt_at_slot_store_hash_raw_data = Arg::with_name("halt_at_slot_store_hash_raw_data")
             .long("halt-at-slot-store-hash-raw-data")
             .help("After halting at slot, run an accounts hash calculation and store the raw hash data for debugging.")
@@ -1612,6 +1751,7 @@ fn main() {
             .arg(&verify_index_arg)
             .arg(&accounts_db_skip_initial_hash_calc_arg)
             .arg(&ancient_append_vecs)
+            .arg(&accounts_file_provider_arg)
             .arg(&halt_at_slot_store_hash_raw_data)
             .arg(&hard_forks_arg)
             .arg(&accounts_db_test_hash_calculation_arg)
@@ -1713,6 +1853,26 @@ fn main() {
                     .possible_values(GraphVoteAccountMode::ALL_MODE_STRINGS)
                     .help("Specify if and how to graph vote accounts. Enabling will incur significant rendering overhead, especially `with-history`")
             )
+        ).subcommand(
+            SubCommand::with_name("fork-choice-graph")
+            .about("Create a Graphviz rendering of a validator's fork choice snapshot")
+            .after_help("The snapshot is the JSON output of `solana-validator fork-choice`")
+            .arg(
+                Arg::with_name("snapshot_filename")
+                    .index(1)
+                    .value_name("SNAPSHOT_FILENAME")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Fork choice snapshot file"),
+            )
+            .arg(
+                Arg::with_name("graph_filename")
+                    .index(2)
+                    .value_name("FILENAME")
+                    .takes_value(true)
+                    .required(true)
+                    .help("Output file"),
+            )
         ).subcommand(
             SubCommand::with_name("create-snapshot")
             .about("Create a new ledger snapshot")
@@ -1726,6 +1886,7 @@ fn main() {
             .arg(&accounts_db_skip_initial_hash_calc_arg)
             .arg(&accountsdb_skip_shrink)
             .arg(&ancient_append_vecs)
+            .arg(&accounts_file_provider_arg)
             .arg(&hard_forks_arg)
             .arg(&max_genesis_archive_unpacked_size_arg)
             .arg(&snapshot_version_arg)
@@ -1941,6 +2102,46 @@ fn main() {
                 .help("Do not print account data when printing account contents."),
             )
             .arg(&max_genesis_archive_unpacked_size_arg)
+        ).subcommand(
+            SubCommand::with_name("accounts-at-slot")
+            .about(
+                "Print the contents of accounts at a past slot, loading the highest snapshot \
+                archives at or before that slot and replaying the ledger up to it",
+            )
+            .arg(&account_paths_arg)
+            .arg(&accounts_hash_cache_path_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&disable_disk_index)
+            .arg(&accountsdb_verify_refcounts)
+            .arg(&accounts_db_skip_initial_hash_calc_arg)
+            .arg(&hard_forks_arg)
+            .arg(&accounts_data_encoding_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("slot")
+                    .index(1)
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Slot to print the accounts at"),
+            )
+            .arg(
+                Arg::with_name("addresses")
+                    .index(2)
+                    .value_name("ADDRESS")
+                    .validator(is_pubkey)
+                    .takes_value(true)
+                    .multiple(true)
+                    .required(true)
+                    .help("Addresses of the accounts to print"),
+            )
+            .arg(Arg::with_name("no_account_data")
+                .long("no-account-data")
+                .takes_value(false)
+                .help("Do not print account data when printing account contents."),
+            )
         ).subcommand(
             SubCommand::with_name("capitalization")
             .about("Print capitalization (aka, total supply) while checksumming it")
@@ -2154,6 +2355,47 @@ fn main() {
                     .help("Slots that their blocks are computed for cost, default to all slots in ledger"),
             )
         )
+        .subcommand(
+            SubCommand::with_name("simulate-block-production")
+            .about("Simulate producing blocks with banking trace event files in the ledger")
+            .arg(&account_paths_arg)
+            .arg(&accounts_hash_cache_path_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&disable_disk_index)
+            .arg(&accountsdb_skip_shrink)
+            .arg(&accounts_db_skip_initial_hash_calc_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(&use_snapshot_archives_at_startup)
+            .arg(
+                Arg::with_name("first_simulated_slot")
+                    .long("first-simulated-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Start simulation at the given slot, which must be a leader slot \
+                           of the node which recorded the banking trace. The ledger is \
+                           replayed up to the preceding slot to obtain the parent bank"),
+            )
+            .arg(
+                Arg::with_name("banking_trace_dir")
+                    .long("banking-trace-dir")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .help("Read banking trace event files from DIR \
+                           [default: <LEDGER_DIR>/banking_trace]"),
+            )
+            .arg(
+                Arg::with_name("block_production_method")
+                    .long("block-production-method")
+                    .value_name("METHOD")
+                    .takes_value(true)
+                    .possible_values(BlockProductionMethod::cli_names())
+                    .help(BlockProductionMethod::cli_message()),
+            )
+        )
         .subcommand(
             SubCommand::with_name("print-file-metadata")
             .about("Print the metadata of the specified ledger-store file. \
@@ -2192,6 +2434,21 @@ fn main() {
         bigtable_process_command(&ledger_path, arg_matches)
     } else if let ("program", Some(arg_matches)) = matches.subcommand() {
         program(&ledger_path, arg_matches)
+    } else if let ("fork-choice-graph", Some(arg_matches)) = matches.subcommand() {
+        let snapshot_file = value_t_or_exit!(arg_matches, "snapshot_filename", String);
+        let output_file = value_t_or_exit!(arg_matches, "graph_filename", String);
+
+        let snapshot: ForkChoiceSnapshot = fs::read(&snapshot_file)
+            .map_err(|err| err.to_string())
+            .and_then(|data| serde_json::from_slice(&data).map_err(|err| err.to_string()))
+            .unwrap_or_else(|err| {
+                eprintln!("Unable to read fork choice snapshot {snapshot_file}: {err}");
+                exit(1);
+            });
+        match write_dot(graph_fork_choice(&snapshot), &output_file) {
+            Ok(_) => println!("Wrote {output_file}"),
+            Err(err) => eprintln!("Unable to write {output_file}: {err}"),
+        }
     } else {
         let ledger_path = canonicalize_ledger_path(&ledger_path);
 
@@ -2738,18 +2995,7 @@ fn main() {
                 ) {
                     Ok((bank_forks, ..)) => {
                         let dot = graph_forks(&bank_forks.read().unwrap(), &graph_config);
-
-                        let extension = Path::new(&output_file).extension();
-                        let result = if extension == Some(OsStr::new("pdf")) {
-                            render_dot(dot, &output_file, "pdf")
-                        } else if extension == Some(OsStr::new("png")) {
-                            render_dot(dot, &output_file, "png")
-                        } else {
-                            File::create(&output_file)
-                                .and_then(|mut file| file.write_all(&dot.into_bytes()))
-                        };
-
-                        match result {
+                        match write_dot(dot, &output_file) {
                             Ok(_) => println!("Wrote {output_file}"),
                             Err(err) => eprintln!("Unable to write {output_file}: {err}"),
                         }
@@ -3355,6 +3601,92 @@ fn main() {
                     println!("\n{total_accounts_stats:#?}");
                 }
             }
+            ("accounts-at-slot", Some(arg_matches)) => {
+                let slot = value_t_or_exit!(arg_matches, "slot", Slot);
+                let addresses = values_t_or_exit!(arg_matches, "addresses", Pubkey);
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot: Some(slot),
+                    run_verification: false,
+                    accounts_db_config: Some(get_accounts_db_config(&ledger_path, arg_matches)),
+                    // The bank snapshots are usually more recent than the slot, only the
+                    // snapshot archives are retained for older slots
+                    use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup::Always,
+                    max_snapshot_archive_slot: Some(slot),
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    get_access_type(&process_options),
+                    wal_recovery_mode,
+                    force_update_to_open,
+                    enforce_ulimit_nofile,
+                );
+                let (bank_forks, ..) = load_and_process_ledger(
+                    arg_matches,
+                    &genesis_config,
+                    Arc::new(blockstore),
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Failed to load ledger: {err:?}");
+                    exit(1);
+                });
+
+                // A skipped slot has the state of the last slot processed before it
+                let bank = bank_forks.read().unwrap().working_bank();
+                if bank.slot() != slot {
+                    eprintln!(
+                        "Slot {slot} was not processed, printing the accounts at slot {}",
+                        bank.slot()
+                    );
+                }
+                let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+                match output_format {
+                    OutputFormat::Json | OutputFormat::JsonCompact => {
+                        let cli_account_new_config = CliAccountNewConfig {
+                            data_encoding: parse_encoding_format(arg_matches),
+                            ..CliAccountNewConfig::default()
+                        };
+                        let cli_accounts = addresses
+                            .iter()
+                            .map(|address| {
+                                bank.get_account(address).map(|account| {
+                                    CliAccount::new_with_config(
+                                        address,
+                                        &account,
+                                        &cli_account_new_config,
+                                    )
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        let json = if output_format == OutputFormat::Json {
+                            serde_json::to_string_pretty(&cli_accounts)
+                        } else {
+                            serde_json::to_string(&cli_accounts)
+                        };
+                        println!("{}", json.unwrap());
+                    }
+                    _ => {
+                        println!("Accounts at slot {}:", bank.slot());
+                        for address in &addresses {
+                            match bank.get_account(address) {
+                                Some(account) => output_account(
+                                    address,
+                                    &account,
+                                    None,
+                                    !arg_matches.is_present("no_account_data"),
+                                    parse_encoding_format(arg_matches),
+                                ),
+                                None => println!("{address}: not found"),
+                            }
+                        }
+                    }
+                }
+            }
             ("capitalization", Some(arg_matches)) => {
                 let halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
                 let process_options = ProcessOptions {
@@ -4210,6 +4542,104 @@ fn main() {
                     }
                 }
             }
+            ("simulate-block-production", Some(arg_matches)) => {
+                let first_simulated_slot =
+                    value_t_or_exit!(arg_matches, "first_simulated_slot", Slot);
+                let block_production_method = value_t!(
+                    arg_matches,
+                    "block_production_method",
+                    BlockProductionMethod
+                )
+                .unwrap_or_default();
+
+                let process_options = ProcessOptions {
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    halt_at_slot: Some(first_simulated_slot.saturating_sub(1)),
+                    run_verification: false,
+                    accounts_db_config: Some(get_accounts_db_config(&ledger_path, arg_matches)),
+                    accounts_db_skip_shrink: arg_matches.is_present("accounts_db_skip_shrink"),
+                    use_snapshot_archives_at_startup: value_t_or_exit!(
+                        arg_matches,
+                        use_snapshot_archives_at_startup::cli::NAME,
+                        UseSnapshotArchivesAtStartup
+                    ),
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let blockstore = Arc::new(open_blockstore(
+                    &ledger_path,
+                    get_access_type(&process_options),
+                    wal_recovery_mode,
+                    force_update_to_open,
+                    enforce_ulimit_nofile,
+                ));
+                let banking_trace_dir = value_t!(arg_matches, "banking_trace_dir", String)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| blockstore.banking_trace_path());
+                let banking_trace_events = BankingTraceEvents::load(&banking_trace_dir)
+                    .unwrap_or_else(|err| {
+                        eprintln!(
+                            "Failed to load banking trace events from {banking_trace_dir:?}: {err}"
+                        );
+                        exit(1);
+                    });
+
+                let (bank_forks, ..) = load_and_process_ledger(
+                    arg_matches,
+                    &genesis_config,
+                    blockstore.clone(),
+                    process_options,
+                    snapshot_archive_path,
+                    incremental_snapshot_archive_path,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Ledger loading failed: {err:?}");
+                    exit(1);
+                });
+
+                let simulator = BankingSimulator::new(banking_trace_events, first_simulated_slot);
+                let report = simulator
+                    .simulate(
+                        &genesis_config,
+                        bank_forks,
+                        blockstore,
+                        block_production_method,
+                    )
+                    .unwrap_or_else(|err| {
+                        eprintln!("Simulation failed: {err}");
+                        exit(1);
+                    });
+
+                println!("Sent packets: {}", report.sent_packet_count);
+                for simulated_slot in &report.simulated_slots {
+                    println!(
+                        "Slot {}: {} transactions, {} entries, {} ticks, {}/{} CUs ({:.1}%)",
+                        simulated_slot.slot,
+                        simulated_slot.transaction_count,
+                        simulated_slot.entry_count,
+                        simulated_slot.tick_count,
+                        simulated_slot.block_cost,
+                        simulated_slot.block_cost_limit,
+                        100.0 * simulated_slot.block_cost as f64
+                            / simulated_slot.block_cost_limit.max(1) as f64,
+                    );
+                    println!(
+                        "  simulated: blockhash {} bank hash {}",
+                        simulated_slot.blockhash, simulated_slot.bank_hash,
+                    );
+                    match simulated_slot.traced_hashes {
+                        Some((blockhash, bank_hash)) => println!(
+                            "  traced:    blockhash {blockhash} bank hash {bank_hash}{}",
+                            if simulated_slot.is_diverged() == Some(true) {
+                                " (diverged)"
+                            } else {
+                                ""
+                            },
+                        ),
+                        None => println!("  traced:    not recorded"),
+                    }
+                }
+            }
             ("print-file-metadata", Some(arg_matches)) => {
                 let blockstore = open_blockstore(
                     &ledger_path,
@@ -4238,6 +4668,7 @@ fn main() {
 pub mod tests {
     use {
         super::*,
+        solana_core::consensus::fork_choice_snapshot::{ForkChoiceSlot, TowerSnapshot, TowerVote},
         solana_ledger::{blockstore::make_many_slot_entries, get_tmp_ledger_path_auto_delete},
     };
 
@@ -4272,4 +4703,55 @@ pub mod tests {
         let expected: Vec<_> = (start_slot..num_slots).rev().collect();
         assert_eq!(optimistic_slots, expected);
     }
+
+    #[test]
+    fn test_graph_fork_choice() {
+        let fork_choice_slot = |slot, parent| ForkChoiceSlot {
+            slot,
+            bank_hash: Hash::default().to_string(),
+            parent,
+            stake_voted_at: 0,
+            stake_voted_subtree: 0,
+            total_stake: 100,
+            is_candidate: true,
+            is_duplicate_confirmed: false,
+            is_unconfirmed_duplicate: false,
+            is_supermajority_confirmed: false,
+            is_propagated: true,
+            has_voted: false,
+            is_locked_out: false,
+            vote_threshold_passed: true,
+        };
+        let snapshot = ForkChoiceSnapshot {
+            root: 0,
+            heaviest_slot: 3,
+            slots: vec![
+                fork_choice_slot(0, None),
+                fork_choice_slot(1, Some(0)),
+                ForkChoiceSlot {
+                    is_unconfirmed_duplicate: true,
+                    is_candidate: false,
+                    ..fork_choice_slot(2, Some(1))
+                },
+                fork_choice_slot(3, Some(1)),
+            ],
+            tower: TowerSnapshot {
+                node_pubkey: Pubkey::default().to_string(),
+                root: 0,
+                last_voted_slot: Some(1),
+                votes: vec![TowerVote {
+                    slot: 1,
+                    confirmation_count: 1,
+                }],
+            },
+        };
+
+        let dot = graph_fork_choice(&snapshot);
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.ends_with('}'));
+        assert!(dot.contains(r#""2" -> "1"[color=blue,dir=back];"#));
+        assert!(dot.contains(r#""3" -> "1"[label="1 slots",color=red,dir=back];"#));
+        assert!(dot.contains("unconfirmed duplicate"));
+        assert!(dot.contains(r#""tower" -> "1" [style=dashed,label="last vote"];"#));
+    }
 }
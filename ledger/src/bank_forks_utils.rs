@@ -22,7 +22,7 @@ use {
         snapshot_hash::{FullSnapshotHash, IncrementalSnapshotHash, StartingSnapshotHashes},
         snapshot_utils,
     },
-    solana_sdk::genesis_config::GenesisConfig,
+    solana_sdk::{clock::Slot, genesis_config::GenesisConfig},
     std::{
         path::PathBuf,
         process, result,
@@ -102,6 +102,7 @@ pub fn load_bank_forks(
 ) {
     fn get_snapshots_to_load(
         snapshot_config: Option<&SnapshotConfig>,
+        max_snapshot_archive_slot: Option<Slot>,
     ) -> Option<(
         FullSnapshotArchiveInfo,
         Option<IncrementalSnapshotArchiveInfo>,
@@ -111,11 +112,16 @@ pub fn load_bank_forks(
             return None;
         };
 
-        let Some(full_snapshot_archive_info) =
-            snapshot_utils::get_highest_full_snapshot_archive_info(
+        let full_snapshot_archive_info = match max_snapshot_archive_slot {
+            Some(max_slot) => snapshot_utils::get_highest_full_snapshot_archive_info_at_or_before(
                 &snapshot_config.full_snapshot_archives_dir,
-            )
-        else {
+                max_slot,
+            ),
+            None => snapshot_utils::get_highest_full_snapshot_archive_info(
+                &snapshot_config.full_snapshot_archives_dir,
+            ),
+        };
+        let Some(full_snapshot_archive_info) = full_snapshot_archive_info else {
             warn!(
                 "No snapshot package found in directory: {}; will load from genesis",
                 snapshot_config.full_snapshot_archives_dir.display()
@@ -123,11 +129,19 @@ pub fn load_bank_forks(
             return None;
         };
 
-        let incremental_snapshot_archive_info =
-            snapshot_utils::get_highest_incremental_snapshot_archive_info(
+        let incremental_snapshot_archive_info = match max_snapshot_archive_slot {
+            Some(max_slot) => {
+                snapshot_utils::get_highest_incremental_snapshot_archive_info_at_or_before(
+                    &snapshot_config.incremental_snapshot_archives_dir,
+                    full_snapshot_archive_info.slot(),
+                    max_slot,
+                )
+            }
+            None => snapshot_utils::get_highest_incremental_snapshot_archive_info(
                 &snapshot_config.incremental_snapshot_archives_dir,
                 full_snapshot_archive_info.slot(),
-            );
+            ),
+        };
 
         Some((
             full_snapshot_archive_info,
@@ -137,7 +151,7 @@ pub fn load_bank_forks(
 
     let (bank_forks, starting_snapshot_hashes) =
         if let Some((full_snapshot_archive_info, incremental_snapshot_archive_info)) =
-            get_snapshots_to_load(snapshot_config)
+            get_snapshots_to_load(snapshot_config, process_options.max_snapshot_archive_slot)
         {
             // SAFETY: Having snapshots to load ensures a snapshot config
             let snapshot_config = snapshot_config.unwrap();
@@ -258,7 +272,7 @@ fn bank_forks_from_snapshot(
             genesis_config,
             &process_options.runtime_config,
             process_options.debug_keys.clone(),
-            None,
+            process_options.additional_builtins.as_deref(),
             process_options.account_indexes.clone(),
             process_options.limit_load_slot_count_from_snapshot,
             process_options.shrink_ratio,
@@ -317,7 +331,7 @@ fn bank_forks_from_snapshot(
             genesis_config,
             &process_options.runtime_config,
             process_options.debug_keys.clone(),
-            None,
+            process_options.additional_builtins.as_deref(),
             process_options.account_indexes.clone(),
             process_options.limit_load_slot_count_from_snapshot,
             process_options.shrink_ratio,
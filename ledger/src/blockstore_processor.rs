@@ -39,6 +39,7 @@ use {
         bank::{Bank, TransactionBalancesSet},
         bank_forks::BankForks,
         bank_utils,
+        builtins::BuiltinPrototype,
         commitment::VOTE_THRESHOLD_SIZE,
         prioritization_fee_cache::PrioritizationFeeCache,
         runtime_config::RuntimeConfig,
@@ -165,6 +166,7 @@ fn execute_batch(
         transaction_status_sender.is_some(),
         transaction_status_sender.is_some(),
         transaction_status_sender.is_some(),
+        transaction_status_sender.map_or(false, |sender| sender.enable_instruction_trace_recording),
         timings,
         log_messages_bytes_limit,
     );
@@ -636,6 +638,11 @@ pub struct ProcessOptions {
     /// This is useful for debugging.
     pub run_final_accounts_hash_calc: bool,
     pub use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup,
+    /// Load the highest snapshot archives at or before this slot, rather than the highest ones.
+    /// This is useful to reconstruct the state of an older slot.
+    pub max_snapshot_archive_slot: Option<Slot>,
+    /// Builtin programs added to the bank in addition to the ones of the runtime.
+    pub additional_builtins: Option<Vec<BuiltinPrototype>>,
 }
 
 pub fn test_process_blockstore(
@@ -723,7 +730,7 @@ pub(crate) fn process_blockstore_for_bank_0(
         Arc::new(opts.runtime_config.clone()),
         account_paths,
         opts.debug_keys.clone(),
-        None,
+        opts.additional_builtins.as_deref(),
         opts.account_indexes.clone(),
         opts.shrink_ratio,
         false,
@@ -1755,6 +1762,9 @@ pub struct TransactionStatusBatch {
 #[derive(Clone)]
 pub struct TransactionStatusSender {
     pub sender: Sender<TransactionStatusMessage>,
+    /// Whether the instruction trace of every transaction is recorded, for the
+    /// Geyser plugins which are interested in transaction traces
+    pub enable_instruction_trace_recording: bool,
 }
 
 impl TransactionStatusSender {
@@ -3866,6 +3876,7 @@ pub mod tests {
             false,
             false,
             false,
+            false,
             &mut ExecuteTimings::default(),
             None,
         );
@@ -4275,6 +4286,7 @@ pub mod tests {
             crossbeam_channel::unbounded();
         let transaction_status_sender = TransactionStatusSender {
             sender: transaction_status_sender,
+            enable_instruction_trace_recording: false,
         };
 
         let blockhash = bank.last_blockhash();
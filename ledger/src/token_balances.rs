@@ -5,7 +5,11 @@ use {
     solana_measure::measure::Measure,
     solana_metrics::datapoint_debug,
     solana_runtime::{bank::Bank, transaction_batch::TransactionBatch},
-    solana_sdk::{account::ReadableAccount, pubkey::Pubkey},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        pubkey::Pubkey,
+        transaction::SanitizedTransaction,
+    },
     solana_transaction_status::{
         token_balances::TransactionTokenBalances, TransactionTokenBalance,
     },
@@ -16,11 +20,14 @@ use {
     std::collections::HashMap,
 };
 
-fn get_mint_decimals(bank: &Bank, mint: &Pubkey) -> Option<u8> {
+fn get_mint_decimals(
+    get_account: &impl Fn(&Pubkey) -> Option<AccountSharedData>,
+    mint: &Pubkey,
+) -> Option<u8> {
     if mint == &spl_token::native_mint::id() {
         Some(spl_token::native_mint::DECIMALS)
     } else {
-        let mint_account = bank.get_account(mint)?;
+        let mint_account = get_account(mint)?;
 
         if !is_known_spl_token_id(mint_account.owner()) {
             return None;
@@ -43,41 +50,55 @@ pub fn collect_token_balances(
     let mut collect_time = Measure::start("collect_token_balances");
 
     for transaction in batch.sanitized_transactions() {
-        let account_keys = transaction.message().account_keys();
-        let has_token_program = account_keys.iter().any(is_known_spl_token_id);
+        balances.push(collect_transaction_token_balances(
+            transaction,
+            |account_id| bank.get_account(account_id),
+            mint_decimals,
+        ));
+    }
+    collect_time.stop();
+    datapoint_debug!(
+        "collect_token_balances",
+        ("collect_time_us", collect_time.as_us(), i64),
+    );
+    balances
+}
 
-        let mut transaction_balances: Vec<TransactionTokenBalance> = vec![];
-        if has_token_program {
-            for (index, account_id) in account_keys.iter().enumerate() {
-                if transaction.message().is_invoked(index) || is_known_spl_token_id(account_id) {
-                    continue;
-                }
+/// Collects the token balances of a transaction, reading its token accounts and their mints
+/// with `get_account`
+pub fn collect_transaction_token_balances(
+    transaction: &SanitizedTransaction,
+    get_account: impl Fn(&Pubkey) -> Option<AccountSharedData>,
+    mint_decimals: &mut HashMap<Pubkey, u8>,
+) -> Vec<TransactionTokenBalance> {
+    let account_keys = transaction.message().account_keys();
+    let has_token_program = account_keys.iter().any(is_known_spl_token_id);
+
+    let mut transaction_balances: Vec<TransactionTokenBalance> = vec![];
+    if has_token_program {
+        for (index, account_id) in account_keys.iter().enumerate() {
+            if transaction.message().is_invoked(index) || is_known_spl_token_id(account_id) {
+                continue;
+            }
 
-                if let Some(TokenBalanceData {
+            if let Some(TokenBalanceData {
+                mint,
+                ui_token_amount,
+                owner,
+                program_id,
+            }) = collect_token_balance_from_account(&get_account, account_id, mint_decimals)
+            {
+                transaction_balances.push(TransactionTokenBalance {
+                    account_index: index as u8,
                     mint,
                     ui_token_amount,
                     owner,
                     program_id,
-                }) = collect_token_balance_from_account(bank, account_id, mint_decimals)
-                {
-                    transaction_balances.push(TransactionTokenBalance {
-                        account_index: index as u8,
-                        mint,
-                        ui_token_amount,
-                        owner,
-                        program_id,
-                    });
-                }
+                });
             }
         }
-        balances.push(transaction_balances);
     }
-    collect_time.stop();
-    datapoint_debug!(
-        "collect_token_balances",
-        ("collect_time_us", collect_time.as_us(), i64),
-    );
-    balances
+    transaction_balances
 }
 
 #[derive(Debug, PartialEq)]
@@ -89,11 +110,11 @@ struct TokenBalanceData {
 }
 
 fn collect_token_balance_from_account(
-    bank: &Bank,
+    get_account: &impl Fn(&Pubkey) -> Option<AccountSharedData>,
     account_id: &Pubkey,
     mint_decimals: &mut HashMap<Pubkey, u8>,
 ) -> Option<TokenBalanceData> {
-    let account = bank.get_account(account_id)?;
+    let account = get_account(account_id)?;
 
     if !is_known_spl_token_id(account.owner()) {
         return None;
@@ -103,7 +124,7 @@ fn collect_token_balance_from_account(
     let mint = token_account.base.mint;
 
     let decimals = mint_decimals.get(&mint).cloned().or_else(|| {
-        let decimals = get_mint_decimals(bank, &mint)?;
+        let decimals = get_mint_decimals(get_account, &mint)?;
         mint_decimals.insert(mint, decimals);
         Some(decimals)
     })?;
@@ -231,24 +252,25 @@ mod test {
         genesis_config.accounts = accounts;
 
         let bank = Bank::new_for_tests(&genesis_config);
+        let get_account = |account_id: &Pubkey| bank.get_account(account_id);
         let mut mint_decimals = HashMap::new();
 
         // Account is not owned by spl_token (nor does it have TokenAccount state)
         assert_eq!(
-            collect_token_balance_from_account(&bank, &account_pubkey, &mut mint_decimals),
+            collect_token_balance_from_account(&get_account, &account_pubkey, &mut mint_decimals),
             None
         );
 
         // Mint does not have TokenAccount state
         assert_eq!(
-            collect_token_balance_from_account(&bank, &mint_pubkey, &mut mint_decimals),
+            collect_token_balance_from_account(&get_account, &mint_pubkey, &mut mint_decimals),
             None
         );
 
         // TokenAccount owned by spl_token::id() works
         assert_eq!(
             collect_token_balance_from_account(
-                &bank,
+                &get_account,
                 &spl_token_account_pubkey,
                 &mut mint_decimals
             ),
@@ -267,14 +289,18 @@ mod test {
 
         // TokenAccount is not owned by known spl-token program_id
         assert_eq!(
-            collect_token_balance_from_account(&bank, &other_account_pubkey, &mut mint_decimals),
+            collect_token_balance_from_account(
+                &get_account,
+                &other_account_pubkey,
+                &mut mint_decimals
+            ),
             None
         );
 
         // TokenAccount's mint is not owned by known spl-token program_id
         assert_eq!(
             collect_token_balance_from_account(
-                &bank,
+                &get_account,
                 &other_mint_account_pubkey,
                 &mut mint_decimals
             ),
@@ -422,24 +448,25 @@ mod test {
         genesis_config.accounts = accounts;
 
         let bank = Bank::new_for_tests(&genesis_config);
+        let get_account = |account_id: &Pubkey| bank.get_account(account_id);
         let mut mint_decimals = HashMap::new();
 
         // Account is not owned by spl_token (nor does it have TokenAccount state)
         assert_eq!(
-            collect_token_balance_from_account(&bank, &account_pubkey, &mut mint_decimals),
+            collect_token_balance_from_account(&get_account, &account_pubkey, &mut mint_decimals),
             None
         );
 
         // Mint does not have TokenAccount state
         assert_eq!(
-            collect_token_balance_from_account(&bank, &mint_pubkey, &mut mint_decimals),
+            collect_token_balance_from_account(&get_account, &mint_pubkey, &mut mint_decimals),
             None
         );
 
         // TokenAccount owned by spl_token_2022::id() works
         assert_eq!(
             collect_token_balance_from_account(
-                &bank,
+                &get_account,
                 &spl_token_account_pubkey,
                 &mut mint_decimals
             ),
@@ -458,14 +485,18 @@ mod test {
 
         // TokenAccount is not owned by known spl-token program_id
         assert_eq!(
-            collect_token_balance_from_account(&bank, &other_account_pubkey, &mut mint_decimals),
+            collect_token_balance_from_account(
+                &get_account,
+                &other_account_pubkey,
+                &mut mint_decimals
+            ),
             None
         );
 
         // TokenAccount's mint is not owned by known spl-token program_id
         assert_eq!(
             collect_token_balance_from_account(
-                &bank,
+                &get_account,
                 &other_mint_account_pubkey,
                 &mut mint_decimals
             ),
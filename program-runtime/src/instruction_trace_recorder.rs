@@ -0,0 +1,71 @@
+use {
+    solana_sdk::transaction_context::TransactionReturnData,
+    std::{cell::RefCell, ops::Range, rc::Rc},
+};
+
+/// Execution details of an instruction, as observed by the `InvokeContext`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionExecutionRecord {
+    /// Compute units consumed by the instruction, including the units
+    /// consumed by the instructions it invoked
+    pub compute_units_consumed: u64,
+    /// The return data of the transaction at the time the instruction completed
+    pub return_data: TransactionReturnData,
+    /// Indexes of the log messages recorded while the instruction was
+    /// executing, including the messages of the instructions it invoked
+    pub log_messages: Range<usize>,
+}
+
+/// Records the execution details of every instruction processed in a
+/// transaction, indexed by the position of the instruction in the
+/// instruction trace.
+///
+/// Instructions which were not processed by the `InvokeContext`, like the
+/// precompiles, have a default record.
+#[derive(Debug, Default)]
+pub struct InstructionTraceRecorder {
+    records: Vec<InstructionExecutionRecord>,
+}
+
+impl InstructionTraceRecorder {
+    pub fn new_ref() -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self::default()))
+    }
+
+    pub fn record(&mut self, index_in_trace: usize, record: InstructionExecutionRecord) {
+        if self.records.len() <= index_in_trace {
+            self.records
+                .resize(index_in_trace.saturating_add(1), Default::default());
+        }
+        self.records[index_in_trace] = record;
+    }
+
+    pub fn into_records(self) -> Vec<InstructionExecutionRecord> {
+        self.records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_out_of_order() {
+        let mut recorder = InstructionTraceRecorder::default();
+        let record = InstructionExecutionRecord {
+            compute_units_consumed: 42,
+            log_messages: 1..3,
+            ..InstructionExecutionRecord::default()
+        };
+        recorder.record(2, record.clone());
+        recorder.record(0, InstructionExecutionRecord::default());
+        assert_eq!(
+            recorder.into_records(),
+            vec![
+                InstructionExecutionRecord::default(),
+                InstructionExecutionRecord::default(),
+                record,
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use {
         accounts_data_meter::AccountsDataMeter,
         compute_budget::ComputeBudget,
         ic_logger_msg, ic_msg,
+        instruction_trace_recorder::{InstructionExecutionRecord, InstructionTraceRecorder},
         loaded_programs::{LoadedProgram, LoadedProgramType, LoadedProgramsForTxBatch},
         log_collector::LogCollector,
         pre_account::PreAccount,
@@ -33,6 +34,7 @@ use {
         stable_layout::stable_instruction::StableInstruction,
         transaction_context::{
             IndexOfAccount, InstructionAccount, TransactionAccount, TransactionContext,
+            TransactionReturnData,
         },
     },
     std::{
@@ -178,6 +180,7 @@ pub struct InvokeContext<'a> {
     pub lamports_per_signature: u64,
     pub syscall_context: Vec<Option<SyscallContext>>,
     traces: Vec<Vec<[u64; 12]>>,
+    pub instruction_trace_recorder: Option<Rc<RefCell<InstructionTraceRecorder>>>,
 }
 
 impl<'a> InvokeContext<'a> {
@@ -215,6 +218,7 @@ impl<'a> InvokeContext<'a> {
             lamports_per_signature,
             syscall_context: Vec::new(),
             traces: Vec::new(),
+            instruction_trace_recorder: None,
         }
     }
 
@@ -684,11 +688,19 @@ impl<'a> InvokeContext<'a> {
             verify_caller_result?;
         }
 
+        let index_in_trace = self.transaction_context.get_instruction_trace_length();
+        let log_messages_start = self.get_log_messages_len();
         self.transaction_context
             .get_next_instruction_context()?
             .configure(program_indices, instruction_accounts, instruction_data);
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
+        let result = self.process_executable_chain(compute_units_consumed, timings);
+        self.record_instruction_execution(
+            index_in_trace,
+            *compute_units_consumed,
+            log_messages_start,
+        );
+        result
             .and_then(|_| {
                 if self
                     .feature_set
@@ -719,6 +731,38 @@ impl<'a> InvokeContext<'a> {
             .and(self.pop())
     }
 
+    fn get_log_messages_len(&self) -> usize {
+        self.log_collector
+            .as_ref()
+            .and_then(|log_collector| log_collector.try_borrow().ok())
+            .map(|log_collector| log_collector.get_recorded_content().len())
+            .unwrap_or_default()
+    }
+
+    /// Records the execution of an instruction, if instruction tracing is enabled
+    fn record_instruction_execution(
+        &self,
+        index_in_trace: usize,
+        compute_units_consumed: u64,
+        log_messages_start: usize,
+    ) {
+        let Some(instruction_trace_recorder) = self.instruction_trace_recorder.as_ref() else {
+            return;
+        };
+        let (program_id, data) = self.transaction_context.get_return_data();
+        let record = InstructionExecutionRecord {
+            compute_units_consumed,
+            return_data: TransactionReturnData {
+                program_id: *program_id,
+                data: data.to_vec(),
+            },
+            log_messages: log_messages_start..self.get_log_messages_len(),
+        };
+        instruction_trace_recorder
+            .borrow_mut()
+            .record(index_in_trace, record);
+    }
+
     /// Calls the instruction's program entrypoint method
     fn process_executable_chain(
         &mut self,
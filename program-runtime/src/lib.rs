@@ -13,6 +13,7 @@ pub use solana_rbpf;
 pub mod accounts_data_meter;
 pub mod compute_budget;
 pub mod invoke_context;
+pub mod instruction_trace_recorder;
 pub mod loaded_programs;
 pub mod log_collector;
 pub mod message_processor;
@@ -1,7 +1,7 @@
 pub use log;
 use std::{cell::RefCell, rc::Rc};
 
-const LOG_MESSAGES_BYTES_LIMIT: usize = 10 * 1000;
+pub const LOG_MESSAGES_BYTES_LIMIT: usize = 10 * 1000;
 
 pub struct LogCollector {
     messages: Vec<String>,
@@ -58,6 +58,22 @@ impl LogCollector {
     pub fn into_messages(self) -> Vec<String> {
         self.messages
     }
+
+    /// Returns the messages which would have been kept, had they been logged to
+    /// a collector created with `new_ref_with_limit(bytes_limit)`
+    pub fn truncate_messages(messages: Vec<String>, bytes_limit: Option<usize>) -> Vec<String> {
+        if bytes_limit.is_none() {
+            return messages;
+        }
+        let mut log_collector = Self {
+            bytes_limit,
+            ..Self::default()
+        };
+        for message in &messages {
+            log_collector.log(message);
+        }
+        log_collector.messages
+    }
 }
 
 /// Convenience macro to log a message with an `Option<Rc<RefCell<LogCollector>>>`
@@ -119,4 +135,24 @@ pub(crate) mod tests {
         }
         assert_eq!(logs.last(), Some(&"Log truncated".to_string()));
     }
+
+    #[test]
+    fn test_truncate_messages() {
+        let messages: Vec<_> = (0..LOG_MESSAGES_BYTES_LIMIT * 2)
+            .map(|_| "x".to_string())
+            .collect();
+
+        let mut lc = LogCollector::default();
+        for message in &messages {
+            lc.log(message);
+        }
+        assert_eq!(
+            LogCollector::truncate_messages(messages.clone(), Some(LOG_MESSAGES_BYTES_LIMIT)),
+            lc.into_messages()
+        );
+        assert_eq!(
+            LogCollector::truncate_messages(messages.clone(), None),
+            messages
+        );
+    }
 }
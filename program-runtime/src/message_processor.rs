@@ -1,6 +1,7 @@
 use {
     crate::{
         compute_budget::ComputeBudget,
+        instruction_trace_recorder::InstructionTraceRecorder,
         invoke_context::InvokeContext,
         loaded_programs::LoadedProgramsForTxBatch,
         log_collector::LogCollector,
@@ -49,6 +50,8 @@ impl MessageProcessor {
     /// For each instruction it calls the program entrypoint method and verifies that the result of
     /// the call does not violate the bank's accounting rules.
     /// The accounts are committed back to the bank only if every instruction succeeds.
+    /// When an `instruction_trace_recorder` is provided, the execution details of every
+    /// instruction, including the failing one, are recorded into it.
     #[allow(clippy::too_many_arguments)]
     pub fn process_message(
         message: &SanitizedMessage,
@@ -56,6 +59,7 @@ impl MessageProcessor {
         transaction_context: &mut TransactionContext,
         rent: Rent,
         log_collector: Option<Rc<RefCell<LogCollector>>>,
+        instruction_trace_recorder: Option<Rc<RefCell<InstructionTraceRecorder>>>,
         programs_loaded_for_tx_batch: &LoadedProgramsForTxBatch,
         programs_modified_by_tx: &mut LoadedProgramsForTxBatch,
         programs_updated_only_for_global_cache: &mut LoadedProgramsForTxBatch,
@@ -82,6 +86,7 @@ impl MessageProcessor {
             lamports_per_signature,
             current_accounts_data_len,
         );
+        invoke_context.instruction_trace_recorder = instruction_trace_recorder;
 
         debug_assert_eq!(program_indices.len(), message.instructions().len());
         for (instruction_index, ((program_id, instruction), program_indices)) in message
@@ -312,6 +317,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -365,6 +371,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -408,6 +415,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -542,6 +550,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -579,6 +588,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -613,6 +623,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -705,6 +716,7 @@ mod tests {
             &mut transaction_context,
             Rent::default(),
             None,
+            None,
             &programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -34,6 +34,7 @@ use {
         genesis_config::{ClusterType, GenesisConfig},
         hash::Hash,
         instruction::{Instruction, InstructionError},
+        loader_v4::{self, LoaderV4Status},
         native_token::sol_to_lamports,
         poh_config::PohConfig,
         program_error::{ProgramError, UNSUPPORTED_SYSVAR},
@@ -707,6 +708,48 @@ impl ProgramTest {
         }
     }
 
+    /// Add a SBF program to the test environment, owned by the v4 loader.
+    ///
+    /// `program_name` will also be used to locate the SBF shared object in the current or fixtures
+    /// directory.  The program is installed with the given `authority` and `status`, so tests can
+    /// exercise programs which are retracted or finalized as well.  Unless the program is
+    /// retracted, its ELF is verified just like a deployment through the loader would.
+    ///
+    /// As loader-v4 is not a runtime builtin yet, it is added to the test environment as a
+    /// builtin program along with the first loader-v4 program.
+    pub fn add_loader_v4_program(
+        &mut self,
+        program_name: &str,
+        program_id: Pubkey,
+        authority: Pubkey,
+        status: LoaderV4Status,
+    ) {
+        let program_file = find_file(&format!("{program_name}.so")).unwrap_or_else(|| {
+            panic!("Program file data not available for {program_name} ({program_id})")
+        });
+        info!(
+            "\"{}\" loader-v4 SBF program from {}",
+            program_name,
+            program_file.display(),
+        );
+        let data = read_file(&program_file);
+        self.accounts.push((
+            program_id,
+            programs::loader_v4_program_account(&Rent::default(), &authority, status, &data),
+        ));
+        if !self
+            .builtin_programs
+            .iter()
+            .any(|(program_id, _, _)| loader_v4::check_id(program_id))
+        {
+            self.add_builtin_program(
+                "loader_v4",
+                loader_v4::id(),
+                solana_loader_v4_program::process_instruction,
+            );
+        }
+    }
+
     /// Add a builtin program to the test environment.
     ///
     /// Note that builtin programs are responsible for their own `stable_log` output.
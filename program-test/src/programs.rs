@@ -1,8 +1,15 @@
-use solana_sdk::{
-    account::{Account, AccountSharedData},
-    bpf_loader_upgradeable::UpgradeableLoaderState,
-    pubkey::Pubkey,
-    rent::Rent,
+use {
+    solana_loader_v4_program::create_program_runtime_environment_v2,
+    solana_program_runtime::{compute_budget::ComputeBudget, invoke_context::InvokeContext},
+    solana_rbpf::{elf::Executable, verifier::RequisiteVerifier},
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        bpf_loader_upgradeable::UpgradeableLoaderState,
+        loader_v4::{self, LoaderV4State, LoaderV4Status},
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    std::sync::Arc,
 };
 
 mod spl_token {
@@ -94,3 +101,44 @@ pub fn spl_programs(rent: &Rent) -> Vec<(Pubkey, AccountSharedData)> {
         })
         .collect()
 }
+
+/// Creates a loader-v4 program account holding `elf`, as if it had been
+/// deployed by `authority` in the genesis slot.
+///
+/// Like a deployment through the loader, a program which is not retracted
+/// is verified against the loader-v4 runtime environment and panics if the
+/// ELF is invalid.  Only deployed and finalized programs are executable.
+pub fn loader_v4_program_account(
+    rent: &Rent,
+    authority: &Pubkey,
+    status: LoaderV4Status,
+    elf: &[u8],
+) -> AccountSharedData {
+    let executable = status != LoaderV4Status::Retracted;
+    if executable {
+        verify_loader_v4_elf(elf);
+    }
+    let mut data = Vec::with_capacity(LoaderV4State::program_data_offset() + elf.len());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(&(status as u64).to_le_bytes());
+    data.extend_from_slice(elf);
+    AccountSharedData::from(Account {
+        lamports: rent.minimum_balance(data.len()).max(1),
+        data,
+        owner: loader_v4::id(),
+        executable,
+        rent_epoch: 0,
+    })
+}
+
+fn verify_loader_v4_elf(elf: &[u8]) {
+    let program_runtime_environment =
+        create_program_runtime_environment_v2(&ComputeBudget::default(), false);
+    let executable =
+        Executable::<InvokeContext>::from_elf(elf, Arc::new(program_runtime_environment))
+            .unwrap_or_else(|err| panic!("Invalid loader-v4 program ELF: {err}"));
+    executable
+        .verify::<RequisiteVerifier>()
+        .unwrap_or_else(|err| panic!("Invalid loader-v4 program ELF: {err}"));
+}
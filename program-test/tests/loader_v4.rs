@@ -0,0 +1,82 @@
+use {
+    solana_program_test::ProgramTest,
+    solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        loader_v4::{self, LoaderV4Status},
+        pubkey::Pubkey,
+        signature::Signer,
+        transaction::{Transaction, TransactionError},
+    },
+};
+
+// Returns the custom error 42 when invoked
+const PROGRAM_NAME: &str = "../programs/loader-v4/test_elfs/out/rodata_section";
+
+async fn invoke_loader_v4_program(status: LoaderV4Status) -> TransactionError {
+    let program_id = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::default();
+    program_test.add_loader_v4_program(PROGRAM_NAME, program_id, authority, status);
+    let mut context = program_test.start_with_context().await;
+
+    let program_account = context
+        .banks_client
+        .get_account(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(program_account.owner, loader_v4::id());
+    assert_eq!(
+        program_account.executable,
+        status != LoaderV4Status::Retracted
+    );
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(program_id, &[], vec![])],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn loader_v4_program_deployed() {
+    assert_eq!(
+        invoke_loader_v4_program(LoaderV4Status::Deployed).await,
+        TransactionError::InstructionError(0, InstructionError::Custom(42))
+    );
+}
+
+#[tokio::test]
+async fn loader_v4_program_finalized() {
+    assert_eq!(
+        invoke_loader_v4_program(LoaderV4Status::Finalized).await,
+        TransactionError::InstructionError(0, InstructionError::Custom(42))
+    );
+}
+
+#[tokio::test]
+async fn loader_v4_program_retracted() {
+    assert_eq!(
+        invoke_loader_v4_program(LoaderV4Status::Retracted).await,
+        TransactionError::InvalidProgramForExecution
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid loader-v4 program ELF")]
+fn loader_v4_program_invalid_elf() {
+    ProgramTest::default().add_loader_v4_program(
+        "../programs/loader-v4/test_elfs/out/invalid",
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        LoaderV4Status::Deployed,
+    );
+}
@@ -105,6 +105,7 @@ fn process_transaction_and_record_inner(
             true,
             true,
             false,
+            false,
             &mut ExecuteTimings::default(),
             None,
         )
@@ -153,6 +154,7 @@ fn execute_transactions(
         true,
         true,
         true,
+        false,
         &mut timings,
         None,
     );
@@ -181,6 +183,7 @@ fn execute_transactions(
                         status,
                         log_messages,
                         inner_instructions,
+                        instruction_trace: None,
                         durable_nonce_fee,
                         return_data,
                         executed_units,
@@ -732,7 +735,7 @@ fn test_return_data_and_log_data_syscall() {
         let transaction = Transaction::new(&[&mint_keypair], message, blockhash);
         let sanitized_tx = SanitizedTransaction::from_transaction_for_tests(transaction);
 
-        let result = bank.simulate_transaction(sanitized_tx);
+        let result = bank.simulate_transaction(sanitized_tx, false);
 
         assert!(result.result.is_ok());
 
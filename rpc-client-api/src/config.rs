@@ -1,11 +1,12 @@
 use {
     crate::filter::RpcFilterType,
-    solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig},
+    solana_account_decoder::{UiAccountData, UiAccountEncoding, UiDataSliceConfig},
     solana_sdk::{
         clock::{Epoch, Slot},
         commitment_config::{CommitmentConfig, CommitmentLevel},
     },
     solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
+    std::collections::HashMap,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,6 +45,38 @@ pub struct RpcSimulateTransactionConfig {
     pub encoding: Option<UiTransactionEncoding>,
     pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
     pub min_context_slot: Option<Slot>,
+    /// Return the inner instructions, token balances and loaded addresses of the transaction
+    #[serde(default)]
+    pub inner_instructions: bool,
+    /// Encoding of the returned inner instructions, `json` (the default) or `jsonParsed`
+    pub inner_instructions_encoding: Option<UiTransactionEncoding>,
+}
+
+/// Replaces fields of an account for the duration of a simulation; the account
+/// is created if it does not exist
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: Option<u64>,
+    pub data: Option<UiAccountData>,
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleConfig {
+    #[serde(default)]
+    pub sig_verify: bool,
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Accounts to return after each transaction
+    pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
+    /// Overrides applied before executing the bundle, keyed by address
+    pub account_overrides: Option<HashMap<String, RpcAccountOverride>>,
+    pub min_context_slot: Option<Slot>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -164,12 +197,40 @@ pub struct RpcProgramAccountsConfig {
     pub with_context: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPaginatedConfig {
+    pub filters: Option<Vec<RpcFilterType>>,
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    /// Maximum number of accounts to return
+    pub limit: Option<usize>,
+    /// Return the accounts following this cursor, as returned with the previous page
+    pub cursor: Option<String>,
+    /// Only return the addresses of the accounts
+    pub keys_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenAccountsByMintConfig {
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    /// Only return token accounts holding at least this raw amount of tokens
+    pub min_balance: Option<u64>,
+    /// Maximum number of token accounts to return
+    pub limit: Option<usize>,
+    /// Return the token accounts following this cursor, as returned with the previous page
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcTransactionLogsFilter {
     All,
     AllWithVotes,
-    Mentions(Vec<String>), // base58-encoded list of addresses
+    Mentions(Vec<String>), // base58-encoded list of addresses, any of which is mentioned
+    MentionsAll(Vec<String>), // base58-encoded list of addresses, all of which are mentioned
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -199,6 +260,8 @@ pub struct RpcSignatureSubscribeConfig {
 pub enum RpcBlockSubscribeFilter {
     All,
     MentionsAccountOrProgram(String),
+    MentionsAnyAccountOrProgram(Vec<String>), // base58-encoded list of addresses
+    MentionsAllAccountsOrPrograms(Vec<String>), // base58-encoded list of addresses
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
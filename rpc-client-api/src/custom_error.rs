@@ -24,6 +24,8 @@ pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013
 pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
 pub const JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: i64 = -32015;
 pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+pub const JSON_RPC_SERVER_ERROR_PAGINATION_CURSOR_EXPIRED: i64 = -32017;
+pub const JSON_RPC_SERVER_ERROR_RATE_LIMITED: i64 = -32018;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -65,6 +67,10 @@ pub enum RpcCustomError {
     UnsupportedTransactionVersion(u8),
     #[error("MinContextSlotNotReached")]
     MinContextSlotNotReached { context_slot: Slot },
+    #[error("PaginationCursorExpired")]
+    PaginationCursorExpired { slot: Slot },
+    #[error("RateLimited")]
+    RateLimited { method: String },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -206,6 +212,19 @@ impl From<RpcCustomError> for Error {
                     context_slot,
                 })),
             },
+            RpcCustomError::PaginationCursorExpired { slot } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_PAGINATION_CURSOR_EXPIRED),
+                message: format!(
+                    "The pagination cursor of slot {slot} has expired, \
+                    please restart from the first page"
+                ),
+                data: None,
+            },
+            RpcCustomError::RateLimited { method } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_RATE_LIMITED),
+                message: format!("Rate limit exceeded for {method}, please retry later"),
+                data: None,
+            },
         }
     }
 }
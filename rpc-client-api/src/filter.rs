@@ -1,7 +1,7 @@
 #![allow(deprecated)]
 use {
     crate::version_req::VersionReq,
-    solana_sdk::account::{AccountSharedData, ReadableAccount},
+    solana_sdk::account::ReadableAccount,
     spl_token_2022::{generic_token_account::GenericTokenAccount, state::Account},
     std::borrow::Cow,
     thiserror::Error,
@@ -10,6 +10,8 @@ use {
 const MAX_DATA_SIZE: usize = 128;
 const MAX_DATA_BASE58_SIZE: usize = 175;
 const MAX_DATA_BASE64_SIZE: usize = 172;
+const MAX_FILTER_NESTING: usize = 3;
+const MAX_ANY_OF_FILTERS: usize = 4;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,10 +19,26 @@ pub enum RpcFilterType {
     DataSize(u64),
     Memcmp(Memcmp),
     TokenAccountState,
+    /// Account data size within an inclusive range
+    DataSizeRange(RpcFilterRange),
+    /// Account lamports within an inclusive range
+    Lamports(RpcFilterRange),
+    /// Account rent epoch within an inclusive range
+    RentEpoch(RpcFilterRange),
+    Executable(bool),
+    MaskedMemcmp(MaskedMemcmp),
+    /// Passes if the inner filter does not
+    Not(Box<RpcFilterType>),
+    /// Passes if any of the inner filters does
+    AnyOf(Vec<RpcFilterType>),
 }
 
 impl RpcFilterType {
     pub fn verify(&self) -> Result<(), RpcFilterError> {
+        self.verify_nested(0)
+    }
+
+    fn verify_nested(&self, nesting: usize) -> Result<(), RpcFilterError> {
         match self {
             RpcFilterType::DataSize(_) => Ok(()),
             RpcFilterType::Memcmp(compare) => {
@@ -76,16 +94,187 @@ impl RpcFilterType {
                 }
             }
             RpcFilterType::TokenAccountState => Ok(()),
+            RpcFilterType::DataSizeRange(range)
+            | RpcFilterType::Lamports(range)
+            | RpcFilterType::RentEpoch(range) => range.verify(),
+            RpcFilterType::Executable(_) => Ok(()),
+            RpcFilterType::MaskedMemcmp(compare) => compare.verify(),
+            RpcFilterType::Not(filter) => {
+                if nesting >= MAX_FILTER_NESTING {
+                    return Err(RpcFilterError::NestingTooDeep);
+                }
+                filter.verify_nested(nesting + 1)
+            }
+            RpcFilterType::AnyOf(filters) => {
+                if nesting >= MAX_FILTER_NESTING {
+                    return Err(RpcFilterError::NestingTooDeep);
+                }
+                if filters.is_empty() || filters.len() > MAX_ANY_OF_FILTERS {
+                    return Err(RpcFilterError::InvalidAnyOfLength);
+                }
+                filters
+                    .iter()
+                    .try_for_each(|filter| filter.verify_nested(nesting + 1))
+            }
         }
     }
 
-    pub fn allows(&self, account: &AccountSharedData) -> bool {
+    pub fn allows(&self, account: &impl ReadableAccount) -> bool {
         match self {
             RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
             RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
             RpcFilterType::TokenAccountState => Account::valid_account_data(account.data()),
+            RpcFilterType::DataSizeRange(range) => range.contains(account.data().len() as u64),
+            RpcFilterType::Lamports(range) => range.contains(account.lamports()),
+            RpcFilterType::RentEpoch(range) => range.contains(account.rent_epoch()),
+            RpcFilterType::Executable(executable) => account.executable() == *executable,
+            RpcFilterType::MaskedMemcmp(compare) => compare.bytes_match(account.data()),
+            RpcFilterType::Not(filter) => !filter.allows(account),
+            RpcFilterType::AnyOf(filters) => filters.iter().any(|filter| filter.allows(account)),
+        }
+    }
+}
+
+/// An inclusive range of values, unbounded on the sides without a bound
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcFilterRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u64>,
+}
+
+impl RpcFilterRange {
+    pub fn new(min: Option<u64>, max: Option<u64>) -> Self {
+        Self { min, max }
+    }
+
+    fn verify(&self) -> Result<(), RpcFilterError> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min > max => Err(RpcFilterError::InvalidRange),
+            _ => Ok(()),
         }
     }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.min.map(|min| min <= value).unwrap_or(true)
+            && self.max.map(|max| value <= max).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaskedMemcmpEncoding {
+    #[default]
+    Base58,
+    Base64,
+}
+
+/// The bytes or the mask of a `MaskedMemcmp`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MaskedMemcmpBytes {
+    /// Encoded with the `encoding` of the filter
+    Encoded(String),
+    /// Decoded, see `MaskedMemcmp::convert_to_raw_bytes()`
+    Raw(Vec<u8>),
+}
+
+/// Compares the bits of the account data selected by `mask` with the same
+/// bits of `bytes`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaskedMemcmp {
+    /// Data offset to begin match
+    pub offset: usize,
+    /// Bytes to match
+    pub bytes: MaskedMemcmpBytes,
+    /// Mask of the bits to match, of the same length as `bytes`
+    pub mask: MaskedMemcmpBytes,
+    #[serde(default)]
+    pub encoding: MaskedMemcmpEncoding,
+}
+
+impl MaskedMemcmp {
+    pub fn new_base58_encoded(offset: usize, bytes: &[u8], mask: &[u8]) -> Self {
+        Self {
+            offset,
+            bytes: MaskedMemcmpBytes::Encoded(bs58::encode(bytes).into_string()),
+            mask: MaskedMemcmpBytes::Encoded(bs58::encode(mask).into_string()),
+            encoding: MaskedMemcmpEncoding::Base58,
+        }
+    }
+
+    pub fn new_raw_bytes(offset: usize, bytes: Vec<u8>, mask: Vec<u8>) -> Self {
+        Self {
+            offset,
+            bytes: MaskedMemcmpBytes::Raw(bytes),
+            mask: MaskedMemcmpBytes::Raw(mask),
+            encoding: MaskedMemcmpEncoding::default(),
+        }
+    }
+
+    fn decode<'a>(&self, bytes: &'a MaskedMemcmpBytes) -> Result<Cow<'a, [u8]>, RpcFilterError> {
+        let encoded = match bytes {
+            MaskedMemcmpBytes::Encoded(encoded) => encoded,
+            MaskedMemcmpBytes::Raw(bytes) => return Ok(Cow::Borrowed(bytes)),
+        };
+        match self.encoding {
+            MaskedMemcmpEncoding::Base58 => {
+                if encoded.len() > MAX_DATA_BASE58_SIZE {
+                    return Err(RpcFilterError::DataTooLarge);
+                }
+                Ok(Cow::Owned(bs58::decode(encoded).into_vec()?))
+            }
+            MaskedMemcmpEncoding::Base64 => {
+                if encoded.len() > MAX_DATA_BASE64_SIZE {
+                    return Err(RpcFilterError::DataTooLarge);
+                }
+                Ok(Cow::Owned(base64::decode(encoded)?))
+            }
+        }
+    }
+
+    /// Decodes the bytes and the mask once, instead of for every account
+    /// the filter is applied to
+    pub fn convert_to_raw_bytes(&mut self) -> Result<(), RpcFilterError> {
+        let bytes = self.decode(&self.bytes)?.into_owned();
+        let mask = self.decode(&self.mask)?.into_owned();
+        self.bytes = MaskedMemcmpBytes::Raw(bytes);
+        self.mask = MaskedMemcmpBytes::Raw(mask);
+        Ok(())
+    }
+
+    fn verify(&self) -> Result<(), RpcFilterError> {
+        let bytes = self.decode(&self.bytes)?;
+        let mask = self.decode(&self.mask)?;
+        if bytes.len() > MAX_DATA_SIZE {
+            return Err(RpcFilterError::DataTooLarge);
+        }
+        if bytes.len() != mask.len() {
+            return Err(RpcFilterError::MaskLengthMismatch);
+        }
+        Ok(())
+    }
+
+    pub fn bytes_match(&self, data: &[u8]) -> bool {
+        let (Ok(bytes), Ok(mask)) = (self.decode(&self.bytes), self.decode(&self.mask)) else {
+            return false;
+        };
+        if bytes.len() != mask.len() {
+            return false;
+        }
+        let Some(data) = data
+            .get(self.offset..)
+            .and_then(|data| data.get(..bytes.len()))
+        else {
+            return false;
+        };
+        data.iter()
+            .zip(bytes.iter().zip(mask.iter()))
+            .all(|(data, (bytes, mask))| data & mask == bytes & mask)
+    }
 }
 
 #[derive(Error, PartialEq, Eq, Debug)]
@@ -108,6 +297,14 @@ pub enum RpcFilterError {
     Base58DecodeError(#[from] bs58::decode::Error),
     #[error("base64 decode error")]
     Base64DecodeError(#[from] base64::DecodeError),
+    #[error("filter range minimum should not exceed its maximum")]
+    InvalidRange,
+    #[error("masked memcmp bytes and mask should have the same length")]
+    MaskLengthMismatch,
+    #[error("not and anyOf filters should be nested at most 3 deep")]
+    NestingTooDeep,
+    #[error("anyOf filter should have between 1 and 4 filters")]
+    InvalidAnyOfLength,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -427,4 +624,154 @@ mod tests {
             Err(RpcFilterError::DataTooLarge)
         );
     }
+
+    #[test]
+    fn test_masked_memcmp() {
+        let data = vec![0b1010_1010, 0b1111_0000, 3];
+
+        // only the bits of the mask are compared
+        assert!(
+            MaskedMemcmp::new_base58_encoded(0, &[0b1010_0000], &[0b1111_0000]).bytes_match(&data)
+        );
+        assert!(
+            MaskedMemcmp::new_base58_encoded(1, &[0b0000_0000, 3], &[0b0000_1111, 0xff])
+                .bytes_match(&data)
+        );
+        assert!(
+            !MaskedMemcmp::new_base58_encoded(1, &[0b0000_0000], &[0b1000_0000]).bytes_match(&data)
+        );
+
+        // bytes overrun data fails
+        assert!(!MaskedMemcmp::new_base58_encoded(2, &[3, 0], &[0, 0]).bytes_match(&data));
+
+        assert_eq!(
+            RpcFilterType::MaskedMemcmp(MaskedMemcmp::new_base58_encoded(0, &[1, 2], &[1]))
+                .verify(),
+            Err(RpcFilterError::MaskLengthMismatch)
+        );
+        assert_eq!(
+            RpcFilterType::MaskedMemcmp(MaskedMemcmp {
+                offset: 0,
+                bytes: MaskedMemcmpBytes::Encoded(base64::encode([1, 2])),
+                mask: MaskedMemcmpBytes::Encoded(base64::encode([0xff, 0])),
+                encoding: MaskedMemcmpEncoding::Base64,
+            })
+            .verify(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_masked_memcmp_convert_to_raw_bytes() {
+        let data = vec![0b1010_1010, 0b1111_0000, 3];
+
+        let mut base58 = MaskedMemcmp::new_base58_encoded(1, &[0b0000_0000, 3], &[0x0f, 0xff]);
+        base58.convert_to_raw_bytes().unwrap();
+        assert_eq!(
+            base58,
+            MaskedMemcmp::new_raw_bytes(1, vec![0b0000_0000, 3], vec![0x0f, 0xff])
+        );
+        assert!(base58.bytes_match(&data));
+
+        let mut base64 = MaskedMemcmp {
+            offset: 0,
+            bytes: MaskedMemcmpBytes::Encoded(base64::encode([0b1010_0000])),
+            mask: MaskedMemcmpBytes::Encoded(base64::encode([0b1111_0000])),
+            encoding: MaskedMemcmpEncoding::Base64,
+        };
+        base64.convert_to_raw_bytes().unwrap();
+        assert_eq!(base64.bytes, MaskedMemcmpBytes::Raw(vec![0b1010_0000]));
+        assert_eq!(base64.mask, MaskedMemcmpBytes::Raw(vec![0b1111_0000]));
+        assert!(base64.bytes_match(&data));
+
+        // converting raw bytes again is a no-op
+        let raw = base64.clone();
+        base64.convert_to_raw_bytes().unwrap();
+        assert_eq!(base64, raw);
+
+        let mut invalid = MaskedMemcmp {
+            offset: 0,
+            bytes: MaskedMemcmpBytes::Encoded("I".to_string()),
+            mask: MaskedMemcmpBytes::Encoded("I".to_string()),
+            encoding: MaskedMemcmpEncoding::Base58,
+        };
+        assert!(invalid.convert_to_raw_bytes().is_err());
+    }
+
+    #[test]
+    fn test_account_field_filters() {
+        use solana_sdk::{
+            account::{AccountSharedData, WritableAccount},
+            pubkey::Pubkey,
+        };
+
+        let mut account = AccountSharedData::new(100, 10, &Pubkey::new_unique());
+        account.set_rent_epoch(5);
+
+        assert!(
+            RpcFilterType::Lamports(RpcFilterRange::new(Some(100), Some(100))).allows(&account)
+        );
+        assert!(RpcFilterType::Lamports(RpcFilterRange::new(None, Some(1000))).allows(&account));
+        assert!(!RpcFilterType::Lamports(RpcFilterRange::new(Some(101), None)).allows(&account));
+        assert!(
+            RpcFilterType::DataSizeRange(RpcFilterRange::new(Some(1), Some(10))).allows(&account)
+        );
+        assert!(
+            !RpcFilterType::DataSizeRange(RpcFilterRange::new(Some(11), None)).allows(&account)
+        );
+        assert!(RpcFilterType::RentEpoch(RpcFilterRange::new(None, Some(5))).allows(&account));
+        assert!(!RpcFilterType::RentEpoch(RpcFilterRange::new(Some(6), None)).allows(&account));
+        assert!(RpcFilterType::Executable(false).allows(&account));
+        assert!(!RpcFilterType::Executable(true).allows(&account));
+
+        assert_eq!(
+            RpcFilterType::Lamports(RpcFilterRange::new(Some(2), Some(1))).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+    }
+
+    #[test]
+    fn test_filter_combinators() {
+        use solana_sdk::{account::AccountSharedData, pubkey::Pubkey};
+
+        let account = AccountSharedData::new(100, 10, &Pubkey::new_unique());
+        let executable = RpcFilterType::Executable(true);
+        let data_size = RpcFilterType::DataSize(10);
+
+        assert!(RpcFilterType::Not(Box::new(executable.clone())).allows(&account));
+        assert!(!RpcFilterType::Not(Box::new(data_size.clone())).allows(&account));
+        assert!(RpcFilterType::AnyOf(vec![executable.clone(), data_size]).allows(&account));
+        assert!(!RpcFilterType::AnyOf(vec![executable.clone()]).allows(&account));
+
+        assert_eq!(
+            RpcFilterType::AnyOf(vec![]).verify(),
+            Err(RpcFilterError::InvalidAnyOfLength)
+        );
+        assert_eq!(
+            RpcFilterType::AnyOf(vec![executable.clone(); 5]).verify(),
+            Err(RpcFilterError::InvalidAnyOfLength)
+        );
+        let nested = (0..MAX_FILTER_NESTING)
+            .fold(executable, |filter, _| RpcFilterType::Not(Box::new(filter)));
+        assert_eq!(nested.verify(), Ok(()));
+        assert_eq!(
+            RpcFilterType::Not(Box::new(nested)).verify(),
+            Err(RpcFilterError::NestingTooDeep)
+        );
+    }
+
+    #[test]
+    fn test_filter_serde() {
+        let filter: RpcFilterType = serde_json::from_str(
+            r#"{"anyOf": [{"lamports": {"min": 1}}, {"not": {"executable": true}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            RpcFilterType::AnyOf(vec![
+                RpcFilterType::Lamports(RpcFilterRange::new(Some(1), None)),
+                RpcFilterType::Not(Box::new(RpcFilterType::Executable(true))),
+            ])
+        );
+    }
 }
@@ -3,21 +3,27 @@ use {
     crate::{
         max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
         parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        rpc_rate_limiter::RpcRateLimitConfig,
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::{config::Options, serialize},
     crossbeam_channel::{unbounded, Receiver, Sender},
+    hmac::{Hmac, Mac},
     jsonrpc_core::{futures::future, types::error, BoxFuture, Error, Metadata, Result},
     jsonrpc_derive::rpc,
+    sha2::Sha256,
     solana_account_decoder::{
         parse_token::{is_known_spl_token_id, token_amount_to_ui_amount, UiTokenAmount},
         UiAccount, UiAccountEncoding, UiDataSliceConfig, MAX_BASE58_BYTES,
     },
     solana_accounts_db::{
+        account_overrides::AccountOverrides,
         accounts::AccountAddressFilter,
+        accounts_db::LoadedAccount,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig},
         inline_spl_token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
         inline_spl_token_2022::{self, ACCOUNTTYPE_ACCOUNT},
+        transaction_results::InnerInstructionsList,
     },
     solana_client::connection_cache::{ConnectionCache, Protocol},
     solana_entry::entry::Entry,
@@ -29,6 +35,7 @@ use {
         blockstore_meta::{PerfSample, PerfSampleV1, PerfSampleV2},
         get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
+        token_balances::collect_transaction_token_balances,
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
@@ -40,14 +47,17 @@ use {
         request::{
             TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
-            MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE,
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS,
+            MAX_GET_TOKEN_ACCOUNTS_BY_MINT_LIMIT, MAX_MULTIPLE_ACCOUNTS,
+            MAX_PAGINATION_CURSOR_AGE_MS, MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY,
+            MAX_SIMULATE_BUNDLE_TRANSACTIONS, NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
     solana_runtime::{
-        bank::{Bank, TransactionSimulationResult},
+        bank::{Bank, TransactionBundleSimulationResult, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
         non_circulating_supply::calculate_non_circulating_supply,
@@ -56,7 +66,7 @@ use {
         snapshot_utils,
     },
     solana_sdk::{
-        account::{AccountSharedData, ReadableAccount},
+        account::{AccountSharedData, ReadableAccount, WritableAccount},
         account_utils::StateMut,
         clock::{Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
         commitment_config::{CommitmentConfig, CommitmentLevel},
@@ -65,14 +75,15 @@ use {
         exit::Exit,
         feature_set,
         fee_calculator::FeeCalculator,
-        hash::Hash,
-        message::SanitizedMessage,
+        hash::{hashv, Hash},
+        message::{AccountKeys, SanitizedMessage},
         pubkey::{Pubkey, PUBKEY_BYTES},
         signature::{Keypair, Signature, Signer},
         stake::state::{StakeActivationStatus, StakeStateV2},
         stake_history::StakeHistory,
         system_instruction,
         sysvar::stake_history,
+        timing::timestamp,
         transaction::{
             self, AddressLoader, MessageHash, SanitizedTransaction, TransactionError,
             VersionedTransaction, MAX_TX_ACCOUNT_LOCKS,
@@ -87,9 +98,11 @@ use {
     solana_streamer::socket::SocketAddrSpace,
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
-        ConfirmedTransactionWithStatusMeta, EncodedConfirmedTransactionWithStatusMeta, Reward,
-        RewardType, TransactionBinaryEncoding, TransactionConfirmationStatus, TransactionStatus,
-        UiConfirmedBlock, UiTransactionEncoding,
+        ConfirmedTransactionWithStatusMeta, EncodedConfirmedTransactionWithStatusMeta,
+        InnerInstruction, InnerInstructions, Reward, RewardType, TransactionBinaryEncoding,
+        TransactionConfirmationStatus, TransactionStatus, TransactionTokenBalance,
+        UiConfirmedBlock, UiInnerInstructions, UiLoadedAddresses, UiTransactionEncoding,
+        UiTransactionTokenBalance,
     },
     solana_vote_program::vote_state::{VoteState, MAX_LOCKOUT_HISTORY},
     spl_token_2022::{
@@ -102,7 +115,9 @@ use {
         cmp::{max, min},
         collections::{HashMap, HashSet},
         convert::TryFrom,
-        net::SocketAddr,
+        net::{IpAddr, SocketAddr},
+        ops::Bound,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -149,6 +164,7 @@ pub struct JsonRpcConfig {
     pub obsolete_v1_7_api: bool,
     pub rpc_scan_and_fix_roots: bool,
     pub max_request_body_size: Option<usize>,
+    pub rpc_rate_limit_config: Option<RpcRateLimitConfig>,
 }
 
 impl JsonRpcConfig {
@@ -166,6 +182,7 @@ pub struct RpcBigtableConfig {
     pub bigtable_instance_name: String,
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
+    pub file_system_path: Option<PathBuf>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -177,6 +194,7 @@ impl Default for RpcBigtableConfig {
             bigtable_instance_name,
             bigtable_app_profile_id,
             timeout: None,
+            file_system_path: None,
         }
     }
 }
@@ -202,6 +220,8 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    pagination_cursor_key: PaginationCursorKey,
+    client_ip: Option<IpAddr>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -331,6 +351,8 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                pagination_cursor_key: rand::random(),
+                client_ip: None,
             },
             receiver,
         )
@@ -405,9 +427,23 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            pagination_cursor_key: rand::random(),
+            client_ip: None,
         }
     }
 
+    /// Returns the processor of a request from `client_ip`
+    pub(crate) fn with_client_ip(&self, client_ip: Option<IpAddr>) -> Self {
+        Self {
+            client_ip,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
     pub fn get_account_info(
         &self,
         pubkey: &Pubkey,
@@ -481,15 +517,7 @@ impl JsonRpcRequestProcessor {
         })?;
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         optimize_filters(&mut filters);
-        let keyed_accounts = {
-            if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_owner(&bank, program_id, &owner, filters)?
-            } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
-                self.get_filtered_spl_token_accounts_by_mint(&bank, program_id, &mint, filters)?
-            } else {
-                self.get_filtered_program_accounts(&bank, program_id, filters)?
-            }
-        };
+        let keyed_accounts = self.get_keyed_program_accounts(&bank, program_id, filters)?;
         let accounts = if is_known_spl_token_id(program_id)
             && encoding == UiAccountEncoding::JsonParsed
         {
@@ -511,6 +539,141 @@ impl JsonRpcRequestProcessor {
         })
     }
 
+    pub fn get_program_accounts_paginated(
+        &self,
+        program_id: &Pubkey,
+        config: Option<RpcProgramAccountsPaginatedConfig>,
+    ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+        let RpcProgramAccountsPaginatedConfig {
+            filters,
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice: data_slice_config,
+                    commitment,
+                    min_context_slot,
+                },
+            limit,
+            cursor,
+            keys_only,
+        } = config.unwrap_or_default();
+        let limit = limit.unwrap_or(MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT);
+        if limit == 0 || limit > MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT {
+            return Err(Error::invalid_params(format!(
+                "Invalid limit; max {MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT}"
+            )));
+        }
+        let mut filters = filters.unwrap_or_default();
+        optimize_filters(&mut filters);
+        let query = pagination_query_hash(
+            "getProgramAccountsPaginated",
+            program_id,
+            &serialize(&filters).unwrap(),
+        );
+        let cursor = cursor
+            .as_deref()
+            .map(|cursor| {
+                ProgramAccountsCursor::decode(cursor, &self.pagination_cursor_key, &query)
+            })
+            .transpose()?;
+        let bank = self.get_bank_for_cursor(
+            cursor.as_ref(),
+            RpcContextConfig {
+                commitment,
+                min_context_slot,
+            },
+        )?;
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let index_key = self.get_program_accounts_index_key(program_id, &mut filters)?;
+        let filter_closure = |account: &LoadedAccount| {
+            filters
+                .iter()
+                .all(|filter_type| filter_type.allows(account))
+        };
+        // One more account than the page holds is loaded to know whether another page follows
+        let range = (
+            cursor
+                .as_ref()
+                .map_or(Bound::Unbounded, |cursor| Bound::Excluded(cursor.last_key)),
+            Bound::Unbounded,
+        );
+        let created_at = cursor.map_or_else(timestamp, |cursor| cursor.created_at);
+        let new_cursor = |last_key| {
+            ProgramAccountsCursor {
+                slot: bank.slot(),
+                last_key,
+                created_at,
+                query,
+            }
+            .encode(&self.pagination_cursor_key)
+        };
+
+        let page = if keys_only.unwrap_or_default() {
+            // The accounts are not loaded when only the keys are returned
+            let mut keys = bank
+                .get_filtered_program_account_keys_page(
+                    program_id,
+                    index_key.as_ref(),
+                    range,
+                    filter_closure,
+                    limit + 1,
+                )
+                .map_err(|e| RpcCustomError::ScanError {
+                    message: e.to_string(),
+                })?;
+            let cursor = (keys.len() > limit).then(|| {
+                keys.truncate(limit);
+                new_cursor(keys[limit - 1])
+            });
+            RpcProgramAccountsPage {
+                accounts: None,
+                keys: Some(keys.iter().map(|pubkey| pubkey.to_string()).collect()),
+                cursor,
+            }
+        } else {
+            let mut keyed_accounts = bank
+                .get_filtered_program_accounts_page(
+                    program_id,
+                    index_key.as_ref(),
+                    range,
+                    filter_closure,
+                    limit + 1,
+                )
+                .map_err(|e| RpcCustomError::ScanError {
+                    message: e.to_string(),
+                })?;
+            let cursor = (keyed_accounts.len() > limit).then(|| {
+                keyed_accounts.truncate(limit);
+                new_cursor(keyed_accounts[limit - 1].0)
+            });
+            let accounts =
+                if is_known_spl_token_id(program_id) && encoding == UiAccountEncoding::JsonParsed {
+                    get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
+                } else {
+                    keyed_accounts
+                        .into_iter()
+                        .map(|(pubkey, account)| {
+                            Ok(RpcKeyedAccount {
+                                pubkey: pubkey.to_string(),
+                                account: encode_account(
+                                    &account,
+                                    &pubkey,
+                                    encoding,
+                                    data_slice_config,
+                                )?,
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                };
+            RpcProgramAccountsPage {
+                accounts: Some(accounts),
+                keys: None,
+                cursor,
+            }
+        };
+        Ok(new_response(&bank, page))
+    }
+
     pub async fn get_inflation_reward(
         &self,
         addresses: Vec<Pubkey>,
@@ -1981,6 +2144,217 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, accounts))
     }
 
+    pub fn get_token_accounts_by_mint(
+        &self,
+        mint: &Pubkey,
+        config: Option<RpcTokenAccountsByMintConfig>,
+    ) -> Result<RpcResponse<RpcKeyedAccountsPage>> {
+        let RpcTokenAccountsByMintConfig {
+            account_config:
+                RpcAccountInfoConfig {
+                    encoding,
+                    data_slice: data_slice_config,
+                    commitment,
+                    min_context_slot,
+                },
+            min_balance,
+            limit,
+            cursor,
+        } = config.unwrap_or_default();
+        let limit = limit.unwrap_or(MAX_GET_TOKEN_ACCOUNTS_BY_MINT_LIMIT);
+        if limit == 0 || limit > MAX_GET_TOKEN_ACCOUNTS_BY_MINT_LIMIT {
+            return Err(Error::invalid_params(format!(
+                "Invalid limit; max {MAX_GET_TOKEN_ACCOUNTS_BY_MINT_LIMIT}"
+            )));
+        }
+        let query = pagination_query_hash(
+            "getTokenAccountsByMint",
+            mint,
+            &serialize(&min_balance).unwrap(),
+        );
+        let cursor = cursor
+            .as_deref()
+            .map(|cursor| {
+                ProgramAccountsCursor::decode(cursor, &self.pagination_cursor_key, &query)
+            })
+            .transpose()?;
+        let bank = self.get_bank_for_cursor(
+            cursor.as_ref(),
+            RpcContextConfig {
+                commitment,
+                min_context_slot,
+            },
+        )?;
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let (mint_owner, _) = get_mint_owner_and_decimals(&bank, mint)?;
+        if !is_known_spl_token_id(&mint_owner) {
+            return Err(Error::invalid_params(
+                "Invalid param: not a Token mint".to_string(),
+            ));
+        }
+
+        // The token accounts are scanned in address order from the cursor, through the by-mint
+        // accounts index if enabled, until the page is full
+        let mut filters = vec![
+            RpcFilterType::TokenAccountState,
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                SPL_TOKEN_ACCOUNT_MINT_OFFSET,
+                mint.to_bytes().into(),
+            )),
+        ];
+        let index_key = self.get_program_accounts_index_key(&mint_owner, &mut filters)?;
+        let filter_closure = |account: &LoadedAccount| {
+            filters
+                .iter()
+                .all(|filter_type| filter_type.allows(account))
+                && min_balance.map_or(true, |min_balance| {
+                    StateWithExtensions::<TokenAccount>::unpack(account.data())
+                        .map(|account| account.base.amount >= min_balance)
+                        .unwrap_or(false)
+                })
+        };
+        let range = (
+            cursor
+                .as_ref()
+                .map_or(Bound::Unbounded, |cursor| Bound::Excluded(cursor.last_key)),
+            Bound::Unbounded,
+        );
+        // One more account than the page holds is loaded to know whether another page follows
+        let mut keyed_accounts = bank
+            .get_filtered_program_accounts_page(
+                &mint_owner,
+                index_key.as_ref(),
+                range,
+                filter_closure,
+                limit + 1,
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?;
+        let cursor = (keyed_accounts.len() > limit).then(|| {
+            keyed_accounts.truncate(limit);
+            ProgramAccountsCursor {
+                slot: bank.slot(),
+                last_key: keyed_accounts[limit - 1].0,
+                created_at: cursor.map_or_else(timestamp, |cursor| cursor.created_at),
+                query,
+            }
+            .encode(&self.pagination_cursor_key)
+        });
+
+        let accounts = if encoding == UiAccountEncoding::JsonParsed {
+            get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
+        } else {
+            keyed_accounts
+                .into_iter()
+                .map(|(pubkey, account)| {
+                    Ok(RpcKeyedAccount {
+                        pubkey: pubkey.to_string(),
+                        account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+        Ok(new_response(
+            &bank,
+            RpcKeyedAccountsPage { accounts, cursor },
+        ))
+    }
+
+    /// Get the bank all the pages of a paginated accounts listing are read from, for a
+    /// consistent result set: the bank of the first page. A cursor expires once the bank of
+    /// its slot is pruned from the bank forks, or `MAX_PAGINATION_CURSOR_AGE_MS` after the
+    /// first page was returned, whichever comes first.
+    fn get_bank_for_cursor(
+        &self,
+        cursor: Option<&ProgramAccountsCursor>,
+        config: RpcContextConfig,
+    ) -> Result<Arc<Bank>> {
+        let Some(cursor) = cursor else {
+            return self.get_bank_with_config(config);
+        };
+        if timestamp().saturating_sub(cursor.created_at) > MAX_PAGINATION_CURSOR_AGE_MS {
+            return Err(RpcCustomError::PaginationCursorExpired { slot: cursor.slot }.into());
+        }
+        self.bank_forks
+            .read()
+            .unwrap()
+            .get(cursor.slot)
+            .ok_or_else(|| RpcCustomError::PaginationCursorExpired { slot: cursor.slot }.into())
+    }
+
+    /// Get the secondary index to scan for the accounts of `program_id` matching a set of
+    /// optimized filters, if any. The filters redundant with a selected spl-token account index
+    /// are added to `filters`.
+    fn get_program_accounts_index_key(
+        &self,
+        program_id: &Pubkey,
+        filters: &mut Vec<RpcFilterType>,
+    ) -> RpcCustomResult<Option<IndexKey>> {
+        let account_indexes = &self.config.account_indexes;
+        let (key, index_key, key_offset) = if let Some(owner) =
+            get_spl_token_owner_filter(program_id, filters)
+                .filter(|_| account_indexes.contains(&AccountIndex::SplTokenOwner))
+        {
+            (
+                owner,
+                IndexKey::SplTokenOwner(owner),
+                SPL_TOKEN_ACCOUNT_OWNER_OFFSET,
+            )
+        } else if let Some(mint) = get_spl_token_mint_filter(program_id, filters)
+            .filter(|_| account_indexes.contains(&AccountIndex::SplTokenMint))
+        {
+            (
+                mint,
+                IndexKey::SplTokenMint(mint),
+                SPL_TOKEN_ACCOUNT_MINT_OFFSET,
+            )
+        } else if let Some(index_key) =
+            get_program_offset_index_key(program_id, filters, account_indexes)
+        {
+            return Ok(Some(index_key));
+        } else if account_indexes.contains(&AccountIndex::ProgramId) {
+            if !account_indexes.include_key(program_id) {
+                return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                    index_key: program_id.to_string(),
+                });
+            }
+            return Ok(Some(IndexKey::ProgramId(*program_id)));
+        } else {
+            return Ok(None);
+        };
+        if !account_indexes.include_key(&key) {
+            return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                index_key: key.to_string(),
+            });
+        }
+        // The spl-token account indexes check for Token Account state and the indexed address on
+        // inclusion, but may still hold the outdated keys of a wiped and reinitialized account
+        filters.push(RpcFilterType::TokenAccountState);
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            key_offset,
+            key.to_bytes().into(),
+        )));
+        Ok(Some(index_key))
+    }
+
+    /// Get the keyed program accounts matching a set of optimized filters from a bank, through
+    /// the spl-token account indexes when the filters select accounts by owner or mint
+    fn get_keyed_program_accounts(
+        &self,
+        bank: &Bank,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        if let Some(owner) = get_spl_token_owner_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_owner(bank, program_id, &owner, filters)
+        } else if let Some(mint) = get_spl_token_mint_filter(program_id, &filters) {
+            self.get_filtered_spl_token_accounts_by_mint(bank, program_id, &mint, filters)
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters)
+        }
+    }
+
     /// Use a set of filters to get an iterator of keyed program accounts from a bank
     fn get_filtered_program_accounts(
         &self,
@@ -1994,7 +2368,24 @@ impl JsonRpcRequestProcessor {
                 .iter()
                 .all(|filter_type| filter_type.allows(account))
         };
-        if self
+        if let Some(index_key) =
+            get_program_offset_index_key(program_id, &filters, &self.config.account_indexes)
+        {
+            Ok(bank
+                .get_filtered_indexed_accounts(
+                    &index_key,
+                    |account| {
+                        // The program-offset account index may hold outdated keys of an account,
+                        // which are discarded by the memcmp filter the index key is taken from
+                        account.owner() == program_id && filter_closure(account)
+                    },
+                    &ScanConfig::default(),
+                    bank.byte_limit_for_scans(),
+                )
+                .map_err(|e| RpcCustomError::ScanError {
+                    message: e.to_string(),
+                })?)
+        } else if self
             .config
             .account_indexes
             .contains(&AccountIndex::ProgramId)
@@ -2181,14 +2572,88 @@ impl JsonRpcRequestProcessor {
 }
 
 fn optimize_filters(filters: &mut [RpcFilterType]) {
-    filters.iter_mut().for_each(|filter_type| {
-        if let RpcFilterType::Memcmp(compare) = filter_type {
-            if let Err(err) = compare.convert_to_raw_bytes() {
-                // All filters should have been previously verified
-                warn!("Invalid filter: bytes could not be decoded, {err}");
+    filters
+        .iter_mut()
+        .for_each(|filter_type| match filter_type {
+            RpcFilterType::Memcmp(compare) => {
+                if let Err(err) = compare.convert_to_raw_bytes() {
+                    // All filters should have been previously verified
+                    warn!("Invalid filter: bytes could not be decoded, {err}");
+                }
             }
+            RpcFilterType::MaskedMemcmp(compare) => {
+                if let Err(err) = compare.convert_to_raw_bytes() {
+                    // All filters should have been previously verified
+                    warn!("Invalid filter: bytes or mask could not be decoded, {err}");
+                }
+            }
+            RpcFilterType::Not(filter) => optimize_filters(std::slice::from_mut(filter.as_mut())),
+            RpcFilterType::AnyOf(filters) => optimize_filters(filters),
+            _ => {}
+        })
+}
+
+/// Node-local secret key authenticating the pagination cursors handed to clients
+type PaginationCursorKey = [u8; 32];
+
+/// Length of the HMAC-SHA256 tag appended to an encoded pagination cursor
+const PAGINATION_CURSOR_MAC_LEN: usize = 32;
+
+/// Hash of the method and the parameters selecting the accounts of a paginated listing, so that
+/// a cursor only resumes the listing it was returned for
+fn pagination_query_hash(method: &str, key: &Pubkey, parameters: &[u8]) -> Hash {
+    hashv(&[method.as_bytes(), key.as_ref(), parameters])
+}
+
+fn pagination_cursor_mac(key: &PaginationCursorKey, cursor: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(cursor);
+    mac
+}
+
+/// Position of a `getProgramAccountsPaginated` or `getTokenAccountsByMint` page, handed to clients
+/// as an opaque string authenticated with the node's pagination cursor key
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ProgramAccountsCursor {
+    /// Slot of the bank all the pages are read from
+    slot: Slot,
+    /// Address of the last account of the previous page
+    last_key: Pubkey,
+    /// Wallclock time the first page was returned at, in milliseconds
+    created_at: u64,
+    /// Hash of the listing query, see `pagination_query_hash()`
+    query: Hash,
+}
+
+impl ProgramAccountsCursor {
+    fn encode(&self, key: &PaginationCursorKey) -> String {
+        let cursor = serialize(self).unwrap();
+        let mac = pagination_cursor_mac(key, &cursor).finalize().into_bytes();
+        bs58::encode([cursor.as_slice(), mac.as_slice()].concat()).into_string()
+    }
+
+    /// Decode a cursor returned by this node for `query`
+    fn decode(cursor: &str, key: &PaginationCursorKey, query: &Hash) -> Result<Self> {
+        let invalid_cursor = || Error::invalid_params("Invalid param: invalid cursor".to_string());
+        let bytes = bs58::decode(cursor)
+            .into_vec()
+            .map_err(|_| invalid_cursor())?;
+        let mac_offset = bytes
+            .len()
+            .checked_sub(PAGINATION_CURSOR_MAC_LEN)
+            .ok_or_else(invalid_cursor)?;
+        let (cursor, mac) = bytes.split_at(mac_offset);
+        pagination_cursor_mac(key, cursor)
+            .verify_slice(mac)
+            .map_err(|_| invalid_cursor())?;
+        let cursor: Self = bincode::deserialize(cursor).map_err(|_| invalid_cursor())?;
+        if cursor.query != *query {
+            return Err(Error::invalid_params(
+                "Invalid param: cursor of another query".to_string(),
+            ));
         }
-    })
+        Ok(cursor)
+    }
 }
 
 fn verify_transaction(
@@ -2319,6 +2784,38 @@ fn encode_account<T: ReadableAccount>(
     }
 }
 
+/// Returns the key of a program-offset account index matching one of the memcmp `filters`, if any
+fn get_program_offset_index_key(
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+    account_indexes: &AccountSecondaryIndexes,
+) -> Option<IndexKey> {
+    filters.iter().find_map(|filter| {
+        let RpcFilterType::Memcmp(memcmp) = filter else {
+            return None;
+        };
+        #[allow(deprecated)]
+        let offset = memcmp.offset;
+        let index = AccountIndex::ProgramOffset {
+            program_id: *program_id,
+            offset,
+        };
+        if !account_indexes.contains(&index) {
+            return None;
+        }
+        // the index is keyed on the pubkey at the offset, which the filter bytes must start with
+        let key = Pubkey::try_from(memcmp.bytes()?.get(..PUBKEY_BYTES)?).ok()?;
+        if !account_indexes.include_key(&key) {
+            return None;
+        }
+        Some(IndexKey::ProgramOffset {
+            program_id: *program_id,
+            offset,
+            key,
+        })
+    })
+}
+
 /// Analyze custom filters to determine if the result will be a subset of spl-token accounts by
 /// owner.
 /// NOTE: `optimize_filters()` should almost always be called before using this method because of
@@ -3110,6 +3607,14 @@ pub mod rpc_accounts_scan {
             config: Option<RpcProgramAccountsConfig>,
         ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>>;
 
+        #[rpc(meta, name = "getProgramAccountsPaginated")]
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>>;
+
         #[rpc(meta, name = "getLargestAccounts")]
         fn get_largest_accounts(
             &self,
@@ -3153,6 +3658,14 @@ pub mod rpc_accounts_scan {
             token_account_filter: RpcTokenAccountsFilter,
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Vec<RpcKeyedAccount>>>;
+
+        #[rpc(meta, name = "getTokenAccountsByMint")]
+        fn get_token_accounts_by_mint(
+            &self,
+            meta: Self::Metadata,
+            mint_str: String,
+            config: Option<RpcTokenAccountsByMintConfig>,
+        ) -> Result<RpcResponse<RpcKeyedAccountsPage>>;
     }
 
     pub struct AccountsScanImpl;
@@ -3190,6 +3703,30 @@ pub mod rpc_accounts_scan {
             meta.get_program_accounts(&program_id, config, filters, with_context)
         }
 
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+            debug!(
+                "get_program_accounts_paginated rpc request received: {:?}",
+                program_id_str
+            );
+            let program_id = verify_pubkey(&program_id_str)?;
+            if let Some(filters) = config.as_ref().and_then(|config| config.filters.as_ref()) {
+                if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                    return Err(Error::invalid_params(format!(
+                        "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+                    )));
+                }
+                for filter in filters {
+                    verify_filter(filter)?;
+                }
+            }
+            meta.get_program_accounts_paginated(&program_id, config)
+        }
+
         fn get_largest_accounts(
             &self,
             meta: Self::Metadata,
@@ -3253,6 +3790,20 @@ pub mod rpc_accounts_scan {
             let token_account_filter = verify_token_account_filter(token_account_filter)?;
             meta.get_token_accounts_by_delegate(&delegate, token_account_filter, config)
         }
+
+        fn get_token_accounts_by_mint(
+            &self,
+            meta: Self::Metadata,
+            mint_str: String,
+            config: Option<RpcTokenAccountsByMintConfig>,
+        ) -> Result<RpcResponse<RpcKeyedAccountsPage>> {
+            debug!(
+                "get_token_accounts_by_mint rpc request received: {:?}",
+                mint_str
+            );
+            let mint = verify_pubkey(&mint_str)?;
+            meta.get_token_accounts_by_mint(&mint, config)
+        }
     }
 }
 
@@ -3324,6 +3875,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3672,7 +4231,8 @@ pub mod rpc_full {
                     post_simulation_accounts: _,
                     units_consumed,
                     return_data,
-                } = preflight_bank.simulate_transaction(transaction)
+                    inner_instructions: _,
+                } = preflight_bank.simulate_transaction(transaction, false)
                 {
                     match err {
                         TransactionError::BlockhashNotFound => {
@@ -3690,6 +4250,10 @@ pub mod rpc_full {
                             accounts: None,
                             units_consumed: Some(units_consumed),
                             return_data: return_data.map(|return_data| return_data.into()),
+                            inner_instructions: None,
+                            pre_token_balances: None,
+                            post_token_balances: None,
+                            loaded_addresses: None,
                         },
                     }
                     .into());
@@ -3720,6 +4284,8 @@ pub mod rpc_full {
                 encoding,
                 accounts: config_accounts,
                 min_context_slot,
+                inner_instructions: enable_inner_instructions,
+                inner_instructions_encoding,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3727,6 +4293,17 @@ pub mod rpc_full {
                     "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
                 ))
             })?;
+            let inner_instructions_encoding =
+                match inner_instructions_encoding.unwrap_or(UiTransactionEncoding::Json) {
+                    encoding
+                    @ (UiTransactionEncoding::Json | UiTransactionEncoding::JsonParsed) => encoding,
+                    encoding => {
+                        return Err(Error::invalid_params(format!(
+                            "unsupported inner instructions encoding: {encoding}. Supported \
+                             encodings: json, jsonParsed"
+                        )))
+                    }
+                };
             let (_, mut unsanitized_tx) =
                 decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
 
@@ -3751,13 +4328,59 @@ pub mod rpc_full {
             }
             let number_of_accounts = transaction.message().account_keys().len();
 
+            let mut mint_decimals = HashMap::new();
+            let pre_token_balances = enable_inner_instructions.then(|| {
+                collect_transaction_token_balances(
+                    &transaction,
+                    |account_id| bank.get_account(account_id),
+                    &mut mint_decimals,
+                )
+            });
+            let simulated_transaction = enable_inner_instructions.then(|| transaction.clone());
+
             let TransactionSimulationResult {
                 result,
                 logs,
                 post_simulation_accounts,
                 units_consumed,
                 return_data,
-            } = bank.simulate_transaction(transaction);
+                inner_instructions,
+            } = bank.simulate_transaction(transaction, enable_inner_instructions);
+
+            let (inner_instructions, post_token_balances, loaded_addresses) =
+                match simulated_transaction {
+                    Some(transaction) => {
+                        // a failed transaction leaves the token balances unchanged
+                        let post_token_balances = if result.is_ok() {
+                            collect_transaction_token_balances(
+                                &transaction,
+                                |account_id| {
+                                    post_simulation_accounts
+                                        .iter()
+                                        .find(|(pubkey, _account)| pubkey == account_id)
+                                        .map(|(_pubkey, account)| account.clone())
+                                        .or_else(|| bank.get_account(account_id))
+                                },
+                                &mut mint_decimals,
+                            )
+                        } else {
+                            pre_token_balances.clone().unwrap_or_default()
+                        };
+                        let inner_instructions = inner_instructions.map(|inner_instructions| {
+                            encode_inner_instructions(
+                                inner_instructions,
+                                inner_instructions_encoding,
+                                &transaction.message().account_keys(),
+                            )
+                        });
+                        (
+                            inner_instructions,
+                            Some(post_token_balances),
+                            Some(UiLoadedAddresses::from(&transaction.get_loaded_addresses())),
+                        )
+                    }
+                    None => (None, None, None),
+                };
 
             let accounts = if let Some(config_accounts) = config_accounts {
                 let accounts_encoding = config_accounts
@@ -3808,37 +4431,204 @@ pub mod rpc_full {
                     accounts,
                     units_consumed: Some(units_consumed),
                     return_data: return_data.map(|return_data| return_data.into()),
+                    inner_instructions,
+                    pre_token_balances: pre_token_balances.map(into_ui_token_balances),
+                    post_token_balances: post_token_balances.map(into_ui_token_balances),
+                    loaded_addresses,
                 },
             ))
         }
 
-        fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
-            debug!("minimum_ledger_slot rpc request received");
-            meta.minimum_ledger_slot()
-        }
-
-        fn get_block(
-            &self,
-            meta: Self::Metadata,
-            slot: Slot,
-            config: Option<RpcEncodingConfigWrapper<RpcBlockConfig>>,
-        ) -> BoxFuture<Result<Option<UiConfirmedBlock>>> {
-            debug!("get_block rpc request received: {:?}", slot);
-            Box::pin(async move { meta.get_block(slot, config).await })
-        }
-
-        fn get_blocks(
+        fn simulate_bundle(
             &self,
             meta: Self::Metadata,
-            start_slot: Slot,
-            config: Option<RpcBlocksConfigWrapper>,
-            commitment: Option<CommitmentConfig>,
-        ) -> BoxFuture<Result<Vec<Slot>>> {
-            let (end_slot, maybe_commitment) =
-                config.map(|config| config.unzip()).unwrap_or_default();
-            debug!(
-                "get_blocks rpc request received: {}-{:?}",
-                start_slot, end_slot
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateTransactionResult>>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                account_overrides: config_account_overrides,
+                min_context_slot,
+            } = config.unwrap_or_default();
+            if data.is_empty() || data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Bundle must contain between 1 and {MAX_SIMULATE_BUNDLE_TRANSACTIONS} transactions"
+                )));
+            }
+            if replace_recent_blockhash && sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if replace_recent_blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(bank.last_blockhash());
+                    }
+                    let transaction = sanitize_transaction(unsanitized_tx, bank)?;
+                    if sig_verify {
+                        verify_transaction(&transaction, &bank.feature_set)?;
+                    }
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let config_account_overrides = config_account_overrides.unwrap_or_default();
+            if config_account_overrides.len() > MAX_MULTIPLE_ACCOUNTS {
+                return Err(Error::invalid_params(format!(
+                    "Too many account overrides provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                )));
+            }
+            let mut account_overrides = AccountOverrides::default();
+            for (address, account_override) in config_account_overrides {
+                let pubkey = verify_pubkey(&address)?;
+                let account = override_account(bank, &pubkey, account_override)?;
+                account_overrides.set_account(&pubkey, Some(account));
+            }
+
+            let (accounts_encoding, post_account_addresses) = match &config_accounts {
+                Some(config_accounts) => {
+                    let accounts_encoding = config_accounts
+                        .encoding
+                        .unwrap_or(UiAccountEncoding::Base64);
+                    if accounts_encoding == UiAccountEncoding::Binary
+                        || accounts_encoding == UiAccountEncoding::Base58
+                    {
+                        return Err(Error::invalid_params("base58 encoding not supported"));
+                    }
+                    if config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+                        return Err(Error::invalid_params(format!(
+                            "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                        )));
+                    }
+                    let addresses = config_accounts
+                        .addresses
+                        .iter()
+                        .map(|address| verify_pubkey(address))
+                        .collect::<Result<Vec<_>>>()?;
+                    (accounts_encoding, addresses)
+                }
+                None => (UiAccountEncoding::Base64, vec![]),
+            };
+
+            let results = bank
+                .simulate_transaction_bundle(
+                    &transactions,
+                    account_overrides,
+                    &post_account_addresses,
+                )
+                .into_iter()
+                .zip(&transactions)
+                .map(
+                    |(
+                        TransactionBundleSimulationResult {
+                            simulation_result,
+                            post_accounts,
+                        },
+                        transaction,
+                    )| {
+                        let TransactionSimulationResult {
+                            result,
+                            logs,
+                            post_simulation_accounts: _,
+                            units_consumed,
+                            return_data,
+                            inner_instructions,
+                        } = simulation_result;
+                        let accounts = config_accounts
+                            .is_some()
+                            .then(|| {
+                                post_accounts
+                                    .iter()
+                                    .zip(&post_account_addresses)
+                                    .map(|(account, pubkey)| {
+                                        account
+                                            .as_ref()
+                                            .map(|account| {
+                                                encode_account(
+                                                    account,
+                                                    pubkey,
+                                                    accounts_encoding,
+                                                    None,
+                                                )
+                                            })
+                                            .transpose()
+                                    })
+                                    .collect::<Result<Vec<_>>>()
+                            })
+                            .transpose()?;
+                        Ok(RpcSimulateTransactionResult {
+                            err: result.err(),
+                            logs: Some(logs),
+                            accounts,
+                            units_consumed: Some(units_consumed),
+                            return_data: return_data.map(|return_data| return_data.into()),
+                            inner_instructions: inner_instructions.map(|inner_instructions| {
+                                encode_inner_instructions(
+                                    inner_instructions,
+                                    UiTransactionEncoding::Json,
+                                    &transaction.message().account_keys(),
+                                )
+                            }),
+                            pre_token_balances: None,
+                            post_token_balances: None,
+                            loaded_addresses: None,
+                        })
+                    },
+                )
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(new_response(bank, results))
+        }
+
+        fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
+            debug!("minimum_ledger_slot rpc request received");
+            meta.minimum_ledger_slot()
+        }
+
+        fn get_block(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+            config: Option<RpcEncodingConfigWrapper<RpcBlockConfig>>,
+        ) -> BoxFuture<Result<Option<UiConfirmedBlock>>> {
+            debug!("get_block rpc request received: {:?}", slot);
+            Box::pin(async move { meta.get_block(slot, config).await })
+        }
+
+        fn get_blocks(
+            &self,
+            meta: Self::Metadata,
+            start_slot: Slot,
+            config: Option<RpcBlocksConfigWrapper>,
+            commitment: Option<CommitmentConfig>,
+        ) -> BoxFuture<Result<Vec<Slot>>> {
+            let (end_slot, maybe_commitment) =
+                config.map(|config| config.unzip()).unwrap_or_default();
+            debug!(
+                "get_blocks rpc request received: {}-{:?}",
+                start_slot, end_slot
             );
             Box::pin(async move {
                 meta.get_blocks(start_slot, end_slot, commitment.or(maybe_commitment))
@@ -4521,6 +5311,66 @@ fn sanitize_transaction(
         .map_err(|err| Error::invalid_params(format!("invalid transaction: {err}")))
 }
 
+/// Applies a simulation override to the current state of an account
+fn override_account(
+    bank: &Bank,
+    pubkey: &Pubkey,
+    account_override: RpcAccountOverride,
+) -> Result<AccountSharedData> {
+    let mut account = bank.get_account(pubkey).unwrap_or_default();
+    if let Some(lamports) = account_override.lamports {
+        account.set_lamports(lamports);
+    }
+    if let Some(owner) = account_override.owner {
+        account.set_owner(verify_pubkey(&owner)?);
+    }
+    if let Some(data) = account_override.data {
+        let data = data.decode().ok_or_else(|| {
+            Error::invalid_params(format!("Invalid data in the override of account {pubkey}"))
+        })?;
+        account.set_data_from_slice(&data);
+    }
+    Ok(account)
+}
+
+fn encode_inner_instructions(
+    inner_instructions: InnerInstructionsList,
+    encoding: UiTransactionEncoding,
+    account_keys: &AccountKeys,
+) -> Vec<UiInnerInstructions> {
+    inner_instructions
+        .into_iter()
+        .enumerate()
+        .map(|(index, instructions)| InnerInstructions {
+            index: index as u8,
+            instructions: instructions
+                .into_iter()
+                .map(|info| InnerInstruction {
+                    instruction: info.instruction,
+                    stack_height: Some(u32::from(info.stack_height)),
+                })
+                .collect(),
+        })
+        .filter(|inner_instructions| !inner_instructions.instructions.is_empty())
+        .map(|inner_instructions| {
+            if encoding == UiTransactionEncoding::JsonParsed {
+                UiInnerInstructions::parse(inner_instructions, account_keys)
+            } else {
+                UiInnerInstructions::from(inner_instructions)
+            }
+        })
+        .collect()
+}
+
+fn into_ui_token_balances(
+    token_balances: Vec<TransactionTokenBalance>,
+) -> Vec<UiTransactionTokenBalance> {
+    token_balances
+        .into_iter()
+        .map(UiTransactionTokenBalance::from)
+        .collect()
+}
+
 pub fn create_validator_exit(exit: Arc<AtomicBool>) -> Arc<RwLock<Exit>> {
     let mut validator_exit = Exit::default();
     validator_exit.register_exit(Box::new(move || exit.store(true, Ordering::Relaxed)));
@@ -4603,6 +5453,7 @@ pub fn populate_blockstore_for_tests(
             Some(
                 &solana_ledger::blockstore_processor::TransactionStatusSender {
                     sender: transaction_status_sender,
+                    enable_instruction_trace_recording: false,
                 },
             ),
             Some(&replay_vote_sender),
@@ -4630,7 +5481,10 @@ pub mod tests {
         jsonrpc_core::{futures, ErrorCode, MetaIoHandler, Output, Response, Value},
         jsonrpc_core_client::transports::local,
         serde::de::DeserializeOwned,
-        solana_accounts_db::{inline_spl_token, inline_spl_token_2022},
+        solana_accounts_db::{
+            accounts_index::AccountSecondaryIndexesIncludeExclude, inline_spl_token,
+            inline_spl_token_2022,
+        },
         solana_entry::entry::next_versioned_entry,
         solana_gossip::socketaddr,
         solana_ledger::{
@@ -4641,6 +5495,7 @@ pub mod tests {
         solana_rpc_client_api::{
             custom_error::{
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_PAGINATION_CURSOR_EXPIRED,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -4656,11 +5511,12 @@ pub mod tests {
                 self,
                 state::{AddressLookupTable, LookupTableMeta},
             },
+            bpf_loader,
             clock::MAX_RECENT_BLOCKHASHES,
             compute_budget::ComputeBudgetInstruction,
             fee_calculator::{FeeRateGovernor, DEFAULT_BURN_PERCENT},
             hash::{hash, Hash},
-            instruction::InstructionError,
+            instruction::{AccountMeta, Instruction, InstructionError},
             message::{
                 v0::{self, MessageAddressTableLookup},
                 Message, MessageHeader, VersionedMessage,
@@ -5835,6 +6691,165 @@ pub mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_paginated() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let new_program_id = Pubkey::new_unique();
+        let mut new_program_accounts = (0..3)
+            .map(|_| {
+                let pubkey = Pubkey::new_unique();
+                let account = AccountSharedData::new(42, 0, &new_program_id);
+                bank.store_account(&pubkey, &account);
+                (pubkey, account)
+            })
+            .collect::<Vec<_>>();
+        new_program_accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([new_program_id.to_string(), {"limit": 2}])),
+        );
+        let result: RpcResponse<RpcProgramAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let expected_accounts = new_program_accounts[..2]
+            .iter()
+            .map(|(pubkey, account)| RpcKeyedAccount {
+                pubkey: pubkey.to_string(),
+                account: UiAccount::encode(pubkey, account, UiAccountEncoding::Binary, None, None),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(result.value.accounts, Some(expected_accounts));
+        assert_eq!(result.value.keys, None);
+        let cursor = result.value.cursor.unwrap();
+        let query = pagination_query_hash(
+            "getProgramAccountsPaginated",
+            &new_program_id,
+            &serialize(&Vec::<RpcFilterType>::new()).unwrap(),
+        );
+        let decoded_cursor =
+            ProgramAccountsCursor::decode(&cursor, &rpc.meta.pagination_cursor_key, &query)
+                .unwrap();
+        assert_eq!(decoded_cursor.slot, bank.slot());
+        assert_eq!(decoded_cursor.last_key, new_program_accounts[1].0);
+
+        // Test the last page, with the keys only
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([
+                new_program_id.to_string(),
+                {"limit": 2, "cursor": cursor, "keysOnly": true},
+            ])),
+        );
+        let result: RpcResponse<RpcProgramAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result.value,
+            RpcProgramAccountsPage {
+                accounts: None,
+                keys: Some(vec![new_program_accounts[2].0.to_string()]),
+                cursor: None,
+            }
+        );
+
+        // Test the pages of a filtered listing
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([
+                new_program_id.to_string(),
+                {"limit": 2, "filters": [{"dataSize": 0}], "keysOnly": true},
+            ])),
+        );
+        let result: RpcResponse<RpcProgramAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result.value.keys,
+            Some(vec![
+                new_program_accounts[0].0.to_string(),
+                new_program_accounts[1].0.to_string(),
+            ])
+        );
+        let filtered_cursor = result.value.cursor.unwrap();
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([
+                new_program_id.to_string(),
+                {"limit": 2, "cursor": filtered_cursor, "filters": [{"dataSize": 0}]},
+            ])),
+        );
+        let result: RpcResponse<RpcProgramAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result.value.accounts.unwrap()[0].pubkey,
+            new_program_accounts[2].0.to_string()
+        );
+        assert_eq!(result.value.cursor, None);
+
+        // Test cursors resuming another listing
+        for (program_id, config) in [
+            (new_program_id, json!({"cursor": filtered_cursor})),
+            (
+                new_program_id,
+                json!({"cursor": cursor, "filters": [{"dataSize": 0}]}),
+            ),
+            (Pubkey::new_unique(), json!({"cursor": cursor})),
+        ] {
+            let request = create_test_request(
+                "getProgramAccountsPaginated",
+                Some(json!([program_id.to_string(), config])),
+            );
+            let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+            assert_eq!(message, "Invalid param: cursor of another query");
+        }
+
+        // Test cursors pinned to a slot no longer available, or too old
+        for cursor in [
+            ProgramAccountsCursor {
+                slot: bank.slot() + 1,
+                last_key: new_program_accounts[0].0,
+                created_at: timestamp(),
+                query,
+            },
+            ProgramAccountsCursor {
+                slot: bank.slot(),
+                last_key: new_program_accounts[0].0,
+                created_at: timestamp() - MAX_PAGINATION_CURSOR_AGE_MS - 1,
+                query,
+            },
+        ] {
+            let cursor = cursor.encode(&rpc.meta.pagination_cursor_key);
+            let request = create_test_request(
+                "getProgramAccountsPaginated",
+                Some(json!([new_program_id.to_string(), {"cursor": cursor}])),
+            );
+            let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, JSON_RPC_SERVER_ERROR_PAGINATION_CURSOR_EXPIRED);
+        }
+
+        // Test invalid cursor and limit, and a cursor not authenticated by this node
+        let forged_cursor = ProgramAccountsCursor {
+            slot: bank.slot(),
+            last_key: new_program_accounts[0].0,
+            created_at: timestamp(),
+            query,
+        }
+        .encode(&[0; 32]);
+        for config in [
+            json!({"cursor": "not a cursor"}),
+            json!({"cursor": forged_cursor}),
+            json!({"limit": 0}),
+        ] {
+            let request = create_test_request(
+                "getProgramAccountsPaginated",
+                Some(json!([new_program_id.to_string(), config])),
+            );
+            let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+        }
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -5905,6 +6920,10 @@ pub mod tests {
                     ],
                     "returnData":null,
                     "unitsConsumed":150,
+                    "innerInstructions":null,
+                    "preTokenBalances":null,
+                    "postTokenBalances":null,
+                    "loadedAddresses":null,
                 }
             },
             "id": 1,
@@ -5989,6 +7008,10 @@ pub mod tests {
                     ],
                     "returnData":null,
                     "unitsConsumed":150,
+                    "innerInstructions":null,
+                    "preTokenBalances":null,
+                    "postTokenBalances":null,
+                    "loadedAddresses":null,
                 }
             },
             "id": 1,
@@ -6017,6 +7040,10 @@ pub mod tests {
                     ],
                     "returnData":null,
                     "unitsConsumed":150,
+                    "innerInstructions":null,
+                    "preTokenBalances":null,
+                    "postTokenBalances":null,
+                    "loadedAddresses":null,
                 }
             },
             "id": 1,
@@ -6066,6 +7093,10 @@ pub mod tests {
                     "logs":[],
                     "returnData":null,
                     "unitsConsumed":0,
+                    "innerInstructions":null,
+                    "preTokenBalances":null,
+                    "postTokenBalances":null,
+                    "loadedAddresses":null,
                 }
             },
             "id":1
@@ -6095,6 +7126,10 @@ pub mod tests {
                     ],
                     "returnData":null,
                     "unitsConsumed":150,
+                    "innerInstructions":null,
+                    "preTokenBalances":null,
+                    "postTokenBalances":null,
+                    "loadedAddresses":null,
                 }
             },
             "id": 1,
@@ -6107,6 +7142,214 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_transaction_with_inner_instructions() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let token_program_id = inline_spl_token::id();
+        let associated_token_program_id =
+            solana_transaction_status::parse_associated_token::spl_associated_token_id();
+
+        // Deploy the token programs, which only become visible in the slot after deployment
+        for (program_id, elf) in [
+            (
+                token_program_id,
+                &include_bytes!("../../program-test/src/programs/spl_token-3.5.0.so")[..],
+            ),
+            (
+                associated_token_program_id,
+                &include_bytes!(
+                    "../../program-test/src/programs/spl_associated_token_account-1.1.1.so"
+                )[..],
+            ),
+        ] {
+            let program_account = AccountSharedData::from(Account {
+                lamports: bank.get_minimum_balance_for_rent_exemption(elf.len()),
+                data: elf.to_vec(),
+                owner: bpf_loader::id(),
+                executable: true,
+                rent_epoch: 0,
+            });
+            bank.store_account(&program_id, &program_account);
+        }
+
+        // Add the mint and a token account holding 420 tokens
+        let owner = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let mut mint_data = vec![0; Mint::get_packed_len()];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::Some(owner.pubkey()),
+                supply: 420,
+                decimals: 2,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut mint_data,
+        )
+        .unwrap();
+        let mint_account = AccountSharedData::from(Account {
+            lamports: bank.get_minimum_balance_for_rent_exemption(mint_data.len()),
+            data: mint_data,
+            owner: token_program_id,
+            ..Account::default()
+        });
+        bank.store_account(&mint, &mint_account);
+
+        let source = Pubkey::new_unique();
+        let mut account_data = vec![0; TokenAccount::get_packed_len()];
+        TokenAccount::pack(
+            TokenAccount {
+                mint,
+                owner: owner.pubkey(),
+                delegate: COption::None,
+                amount: 420,
+                state: TokenAccountState::Initialized,
+                is_native: COption::None,
+                delegated_amount: 0,
+                close_authority: COption::None,
+            },
+            &mut account_data,
+        )
+        .unwrap();
+        let token_account = AccountSharedData::from(Account {
+            lamports: bank.get_minimum_balance_for_rent_exemption(account_data.len()),
+            data: account_data,
+            owner: token_program_id,
+            ..Account::default()
+        });
+        bank.store_account(&source, &token_account);
+
+        // Simulate on the processed slot 1, where the deployed programs are visible
+        let bank = rpc.advance_bank_to_confirmed_slot(1);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta, ref io, ..
+        } = rpc;
+
+        // Create the associated token account of the recipient, which invokes the system and
+        // token programs, and transfer 100 tokens to it
+        let recipient = Pubkey::new_unique();
+        let (destination, _bump_seed) = Pubkey::find_program_address(
+            &[recipient.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+            &associated_token_program_id,
+        );
+        let create_instruction = Instruction::new_with_bytes(
+            associated_token_program_id,
+            &[],
+            vec![
+                AccountMeta::new(rpc.mint_keypair.pubkey(), true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new_readonly(recipient, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(token_program_id, false),
+            ],
+        );
+        let transfer_instruction = spl_token_2022::instruction::transfer_checked(
+            &token_program_id,
+            &source,
+            &mint,
+            &destination,
+            &owner.pubkey(),
+            &[],
+            100,
+            2,
+        )
+        .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[create_instruction, transfer_instruction],
+            Some(&rpc.mint_keypair.pubkey()),
+            &[&rpc.mint_keypair, &owner],
+            recent_blockhash,
+        );
+        let tx_serialized_encoded = bs58::encode(serialize(&tx).unwrap()).into_string();
+        let account_index = |pubkey: &Pubkey| {
+            tx.message
+                .account_keys
+                .iter()
+                .position(|key| key == pubkey)
+                .unwrap()
+        };
+
+        // Simulation bank must be frozen
+        bank.freeze();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_serialized_encoded}", {{"commitment": "processed", "innerInstructions": true, "innerInstructionsEncoding": "jsonParsed"}}]}}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let value = &result["result"]["value"];
+        assert_eq!(value["err"], Value::Null);
+
+        // Only the associated token account creation has inner instructions
+        let inner_instructions = value["innerInstructions"].as_array().unwrap();
+        assert_eq!(inner_instructions.len(), 1);
+        assert_eq!(inner_instructions[0]["index"], 0);
+        let parsed_inner_instructions: Vec<_> = inner_instructions[0]["instructions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|instruction| {
+                (
+                    instruction["program"].as_str().unwrap(),
+                    instruction["parsed"]["type"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            parsed_inner_instructions,
+            vec![
+                ("spl-token", "getAccountDataSize"),
+                ("system", "createAccount"),
+                ("spl-token", "initializeImmutableOwner"),
+                ("spl-token", "initializeAccount3"),
+            ]
+        );
+        let create_account = &inner_instructions[0]["instructions"][1]["parsed"]["info"];
+        assert_eq!(create_account["newAccount"], destination.to_string());
+        assert_eq!(create_account["owner"], token_program_id.to_string());
+
+        let token_balance =
+            |pubkey: &Pubkey, token_owner: &Pubkey, ui_amount: f64, amount: &str| {
+                json!({
+                    "accountIndex": account_index(pubkey),
+                    "mint": mint.to_string(),
+                    "owner": token_owner.to_string(),
+                    "programId": token_program_id.to_string(),
+                    "uiTokenAmount": {
+                        "uiAmount": ui_amount,
+                        "decimals": 2,
+                        "amount": amount,
+                        "uiAmountString": ui_amount.to_string(),
+                    },
+                })
+            };
+        assert_eq!(
+            value["preTokenBalances"],
+            json!([token_balance(&source, &owner.pubkey(), 4.2, "420")])
+        );
+        let mut post_token_balances = vec![
+            token_balance(&source, &owner.pubkey(), 3.2, "320"),
+            token_balance(&destination, &recipient, 1.0, "100"),
+        ];
+        post_token_balances.sort_by_key(|balance| balance["accountIndex"].as_u64());
+        assert_eq!(value["postTokenBalances"], json!(post_token_balances));
+        assert_eq!(
+            value["loadedAddresses"],
+            json!({"writable": [], "readonly": []})
+        );
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateTransaction","params":["{tx_serialized_encoded}", {{"innerInstructions": true, "innerInstructionsEncoding": "base64"}}]}}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"unsupported inner instructions encoding: base64. Supported encodings: json, jsonParsed"},"id":1}"#;
+        assert_eq!(res, Some(expected.to_string()));
+    }
+
     #[test]
     #[should_panic(expected = "simulation bank must be frozen")]
     fn test_rpc_simulate_transaction_panic_on_unfrozen_bank() {
@@ -6134,6 +7377,100 @@ pub mod tests {
         let _ = io.handle_request_sync(&req, meta);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta, ref io, ..
+        } = rpc;
+
+        // alice is funded by the first transaction, and carol only by the override
+        let alice = Keypair::new();
+        let bob_pubkey = Pubkey::new_unique();
+        let carol = Keypair::new();
+        let transactions = [
+            system_transaction::transfer(
+                &rpc.mint_keypair,
+                &alice.pubkey(),
+                3 * rent_exempt_amount,
+                recent_blockhash,
+            ),
+            system_transaction::transfer(&alice, &bob_pubkey, rent_exempt_amount, recent_blockhash),
+            system_transaction::transfer(&carol, &bob_pubkey, rent_exempt_amount, recent_blockhash),
+        ]
+        .iter()
+        .map(|tx| {
+            format!(
+                r#""{}""#,
+                bs58::encode(serialize(tx).unwrap()).into_string()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+        // Simulation bank must be frozen
+        bank.freeze();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0",
+                 "id":1,
+                 "method":"simulateBundle",
+                 "params":[
+                   [{transactions}],
+                   {{
+                     "sigVerify": true,
+                     "accounts": {{"addresses": ["{bob_pubkey}"]}},
+                     "accountOverrides": {{
+                       "{}": {{"lamports": {}}}
+                     }}
+                   }}
+                 ]
+            }}"#,
+            carol.pubkey(),
+            3 * rent_exempt_amount,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let results = result["result"]["value"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        let bob_lamports = results
+            .iter()
+            .map(|result| {
+                assert_eq!(result["err"], Value::Null);
+                assert_eq!(result["innerInstructions"], json!([]));
+                result["accounts"][0]["lamports"].as_u64()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bob_lamports,
+            vec![None, Some(rent_exempt_amount), Some(2 * rent_exempt_amount)]
+        );
+        assert_eq!(bank.get_balance(&bob_pubkey), 0);
+
+        // Without the override, carol cannot pay for her transfer
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateBundle","params":[[{transactions}]]}}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let results = result["result"]["value"].as_array().unwrap();
+        assert_eq!(results[1]["err"], Value::Null);
+        assert_eq!(results[2]["err"], json!("AccountNotFound"));
+
+        // Empty bundle
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"simulateBundle","params":[[]]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let expected = format!(
+            r#"{{"jsonrpc":"2.0","error":{{"code":-32602,"message":"Bundle must contain between 1 and {MAX_SIMULATE_BUNDLE_TRANSACTIONS} transactions"}},"id":1}}"#
+        );
+        assert_eq!(res, Some(expected));
+    }
+
     #[test]
     fn test_rpc_get_signature_statuses() {
         let rpc = RpcHandler::start();
@@ -6465,7 +7802,7 @@ pub mod tests {
         assert_eq!(
             res,
             Some(
-                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","logs":[],"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
+                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"loadedAddresses":null,"logs":[],"postTokenBalances":null,"preTokenBalances":null,"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
             )
         );
 
@@ -7876,7 +9213,7 @@ pub mod tests {
             let req = format!(
                 r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenLargestAccounts","params":["{new_mint}"]}}"#,
             );
-            let res = io.handle_request_sync(&req, meta);
+            let res = io.handle_request_sync(&req, meta.clone());
             let result: Value = serde_json::from_str(&res.expect("actual response"))
                 .expect("actual response deserialization");
             let largest_accounts: Vec<RpcTokenAccountBalance> =
@@ -7904,6 +9241,102 @@ pub mod tests {
                     }
                 ]
             );
+
+            // Test paging through the token accounts of a mint
+            let mut new_mint_accounts = vec![
+                token_with_different_mint_pubkey.to_string(),
+                token_with_smaller_balance.to_string(),
+            ];
+            new_mint_accounts.sort_by_key(|pubkey| Pubkey::from_str(pubkey).unwrap());
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByMint",
+                    "params":["{new_mint}", {{"encoding":"base64", "limit":1}}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let page: RpcKeyedAccountsPage =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(page.accounts.len(), 1);
+            assert_eq!(page.accounts[0].pubkey, new_mint_accounts[0]);
+            let cursor = ProgramAccountsCursor::decode(
+                page.cursor.as_ref().unwrap(),
+                &meta.pagination_cursor_key,
+                &pagination_query_hash(
+                    "getTokenAccountsByMint",
+                    &Pubkey::new_from_array(new_mint.to_bytes()),
+                    &serialize(&None::<u64>).unwrap(),
+                ),
+            )
+            .unwrap();
+            assert_eq!(cursor.last_key.to_string(), new_mint_accounts[0]);
+
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByMint",
+                    "params":["{}", {{"encoding":"jsonParsed", "limit":1, "cursor":"{}"}}]
+                }}"#,
+                new_mint,
+                page.cursor.unwrap(),
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let page: RpcKeyedAccountsPage =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(page.accounts.len(), 1);
+            assert_eq!(page.accounts[0].pubkey, new_mint_accounts[1]);
+            assert!(
+                result["result"]["value"]["accounts"][0]["account"]["data"]["parsed"].is_object()
+            );
+            assert_eq!(page.cursor, None);
+
+            // Test minimum balance filter
+            let req = format!(
+                r#"{{
+                    "jsonrpc":"2.0",
+                    "id":1,
+                    "method":"getTokenAccountsByMint",
+                    "params":["{new_mint}", {{"encoding":"base64", "minBalance":20}}]
+                }}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let page: RpcKeyedAccountsPage =
+                serde_json::from_value(result["result"]["value"].clone()).unwrap();
+            assert_eq!(page.accounts.len(), 1);
+            assert_eq!(
+                page.accounts[0].pubkey,
+                token_with_different_mint_pubkey.to_string()
+            );
+            assert_eq!(page.cursor, None);
+
+            // Test invalid limit and cursor
+            for config in [r#"{"limit":0}"#, r#"{"cursor":"not a cursor"}"#] {
+                let req = format!(
+                    r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenAccountsByMint","params":["{new_mint}", {config}]}}"#,
+                );
+                let res = io.handle_request_sync(&req, meta.clone());
+                let result: Value = serde_json::from_str(&res.expect("actual response"))
+                    .expect("actual response deserialization");
+                assert!(result.get("error").is_some());
+            }
+
+            // Test non-mint account
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"getTokenAccountsByMint","params":["{token_account_pubkey}"]}}"#,
+            );
+            let res = io.handle_request_sync(&req, meta);
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            assert!(result.get("error").is_some());
         }
     }
 
@@ -8119,6 +9552,98 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_get_program_offset_index_key() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let mut account_indexes = AccountSecondaryIndexes::default();
+        account_indexes.indexes.insert(AccountIndex::ProgramOffset {
+            program_id,
+            offset: 8,
+        });
+        let assert_index_key = |program_id: &Pubkey, filters: &[RpcFilterType], expected| {
+            let index_key = get_program_offset_index_key(program_id, filters, &account_indexes);
+            match (index_key, expected) {
+                (
+                    Some(IndexKey::ProgramOffset {
+                        program_id: index_program_id,
+                        offset,
+                        key,
+                    }),
+                    Some(expected_key),
+                ) => {
+                    assert_eq!(index_program_id, *program_id);
+                    assert_eq!(offset, 8);
+                    assert_eq!(key, expected_key);
+                }
+                (None, None) => {}
+                (index_key, _) => panic!("unexpected index key {index_key:?}"),
+            }
+        };
+
+        // Filtering on the indexed offset, with the key or a longer prefix
+        assert_index_key(
+            &program_id,
+            &[
+                RpcFilterType::DataSize(72),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, authority.to_bytes().to_vec())),
+            ],
+            Some(authority),
+        );
+        let mut bytes = authority.to_bytes().to_vec();
+        bytes.push(1);
+        assert_index_key(
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, &bytes))],
+            Some(authority),
+        );
+
+        // Too few bytes to hold the key
+        assert_index_key(
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                authority.to_bytes()[..31].to_vec(),
+            ))],
+            None,
+        );
+
+        // Wrong offset
+        assert_index_key(
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                authority.to_bytes().to_vec(),
+            ))],
+            None,
+        );
+
+        // Wrong program id
+        assert_index_key(
+            &Pubkey::new_unique(),
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                authority.to_bytes().to_vec(),
+            ))],
+            None,
+        );
+
+        // Excluded key
+        account_indexes.keys = Some(AccountSecondaryIndexesIncludeExclude {
+            exclude: true,
+            keys: HashSet::from([authority]),
+        });
+        assert!(get_program_offset_index_key(
+            &program_id,
+            &[RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                8,
+                authority.to_bytes().to_vec(),
+            ))],
+            &account_indexes,
+        )
+        .is_none());
+    }
+
     #[test]
     fn test_get_spl_token_owner_filter() {
         // Filtering on token-v3 length
@@ -33,6 +33,9 @@ use {
     std::{str::FromStr, sync::Arc},
 };
 
+/// Maximum number of addresses a logs or block subscription can mention
+pub const MAX_SUBSCRIPTION_MENTIONED_ADDRESSES: usize = 32;
+
 // We have to keep both of the following traits to not break backwards compatibility.
 // `RpcSolPubSubInternal` is actually used by the current PubSub API implementation.
 // `RpcSolPubSub` and the corresponding `gen_client` module are preserved
@@ -419,6 +422,27 @@ fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
     })
 }
 
+/// Parses the addresses mentioned by a subscription filter, sorted and deduplicated
+fn param_addresses(keys: &[String], thing: &str) -> Result<Vec<Pubkey>> {
+    if keys.is_empty() || keys.len() > MAX_SUBSCRIPTION_MENTIONED_ADDRESSES {
+        return Err(Error {
+            code: ErrorCode::InvalidParams,
+            message: format!(
+                "Invalid Request: Between 1 and {MAX_SUBSCRIPTION_MENTIONED_ADDRESSES} addresses \
+                 supported"
+            ),
+            data: None,
+        });
+    }
+    let mut addresses = keys
+        .iter()
+        .map(|key| param::<Pubkey>(key, thing))
+        .collect::<Result<Vec<_>>>()?;
+    addresses.sort_unstable();
+    addresses.dedup();
+    Ok(addresses)
+}
+
 impl RpcSolPubSubInternal for RpcSolPubSubImpl {
     fn account_subscribe(
         &self,
@@ -450,9 +474,17 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         config: Option<RpcProgramAccountsConfig>,
     ) -> Result<SubscriptionId> {
         let config = config.unwrap_or_default();
+        let filters = config.filters.unwrap_or_default();
+        for filter in &filters {
+            filter.verify().map_err(|err| Error {
+                code: ErrorCode::InvalidParams,
+                message: format!("Invalid Request: Invalid filter provided, {err}"),
+                data: None,
+            })?;
+        }
         let params = ProgramSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
-            filters: config.filters.unwrap_or_default(),
+            filters,
             encoding: config
                 .account_config
                 .encoding
@@ -478,14 +510,16 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
                 RpcTransactionLogsFilter::All => LogsSubscriptionKind::All,
                 RpcTransactionLogsFilter::AllWithVotes => LogsSubscriptionKind::AllWithVotes,
                 RpcTransactionLogsFilter::Mentions(keys) => {
-                    if keys.len() != 1 {
-                        return Err(Error {
-                            code: ErrorCode::InvalidParams,
-                            message: "Invalid Request: Only 1 address supported".into(),
-                            data: None,
-                        });
+                    match param_addresses(&keys, "mentions")?.as_slice() {
+                        [key] => LogsSubscriptionKind::Single(*key),
+                        keys => LogsSubscriptionKind::AnyOf(keys.to_vec()),
+                    }
+                }
+                RpcTransactionLogsFilter::MentionsAll(keys) => {
+                    match param_addresses(&keys, "mentions_all")?.as_slice() {
+                        [key] => LogsSubscriptionKind::Single(*key),
+                        keys => LogsSubscriptionKind::AllOf(keys.to_vec()),
                     }
-                    LogsSubscriptionKind::Single(param::<Pubkey>(&keys[0], "mentions")?)
                 }
             },
             commitment: config.and_then(|c| c.commitment).unwrap_or_default(),
@@ -553,6 +587,18 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
                         "mentions_account_or_program",
                     )?)
                 }
+                RpcBlockSubscribeFilter::MentionsAnyAccountOrProgram(keys) => {
+                    match param_addresses(&keys, "mentions_any_account_or_program")?.as_slice() {
+                        [key] => BlockSubscriptionKind::MentionsAccountOrProgram(*key),
+                        keys => BlockSubscriptionKind::MentionsAnyAccountOrProgram(keys.to_vec()),
+                    }
+                }
+                RpcBlockSubscribeFilter::MentionsAllAccountsOrPrograms(keys) => {
+                    match param_addresses(&keys, "mentions_all_accounts_or_programs")?.as_slice() {
+                        [key] => BlockSubscriptionKind::MentionsAccountOrProgram(*key),
+                        keys => BlockSubscriptionKind::MentionsAllAccountsOrPrograms(keys.to_vec()),
+                    }
+                }
             },
             transaction_details: config.transaction_details.unwrap_or_default(),
             show_rewards: config.show_rewards.unwrap_or_default(),
@@ -1128,6 +1174,89 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_program_subscribe_invalid_filter() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(Bank::new_for_tests(
+            &genesis_config,
+        ))));
+
+        let mut io = IoHandler::<()>::default();
+        let subscriptions = Arc::new(RpcSubscriptions::default_with_bank_forks(
+            Arc::new(AtomicU64::default()),
+            Arc::new(AtomicU64::default()),
+            bank_forks,
+        ));
+        let (rpc, _receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        io.extend_with(rpc.to_delegate());
+
+        let program_id = system_program::id();
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"programSubscribe","params":["{program_id}",{{"filters":[{{"not":{{"lamports":{{"min":1}}}}}}]}}]}}"#
+        );
+        let res = io.handle_request_sync(&req);
+        let expected = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+        let expected: Response = serde_json::from_str(expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(result, expected);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"programSubscribe","params":["{program_id}",{{"filters":[{{"lamports":{{"min":2,"max":1}}}}]}}]}}"#
+        );
+        let res = io.handle_request_sync(&req);
+        let expected = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid Request: Invalid filter provided, filter range minimum should not exceed its maximum"},"id":1}"#;
+        let expected: Response = serde_json::from_str(expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_logs_subscribe_mentions_limit() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(Bank::new_for_tests(
+            &genesis_config,
+        ))));
+
+        let mut io = IoHandler::<()>::default();
+        let subscriptions = Arc::new(RpcSubscriptions::default_with_bank_forks(
+            Arc::new(AtomicU64::default()),
+            Arc::new(AtomicU64::default()),
+            bank_forks,
+        ));
+        let (rpc, _receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+        io.extend_with(rpc.to_delegate());
+
+        let addresses = |count: usize| {
+            serde_json::to_string(
+                &(0..count)
+                    .map(|_| Pubkey::new_unique().to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap()
+        };
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":[{{"mentions":{}}}]}}"#,
+            addresses(2),
+        );
+        let res = io.handle_request_sync(&req);
+        let expected = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+        let expected: Response = serde_json::from_str(expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(result, expected);
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"logsSubscribe","params":[{{"mentionsAll":{}}}]}}"#,
+            addresses(MAX_SUBSCRIPTION_MENTIONED_ADDRESSES + 1),
+        );
+        let res = io.handle_request_sync(&req);
+        let expected = format!(
+            r#"{{"jsonrpc":"2.0","error":{{"code":-32602,"message":"Invalid Request: Between 1 and {MAX_SUBSCRIPTION_MENTIONED_ADDRESSES} addresses supported"}},"id":1}}"#
+        );
+        let expected: Response = serde_json::from_str(&expected).unwrap();
+        let result: Response = serde_json::from_str(&res.unwrap()).unwrap();
+        assert_eq!(result, expected);
+    }
+
     #[test]
     #[should_panic]
     fn test_account_commitment_not_fulfilled() {
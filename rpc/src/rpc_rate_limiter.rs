@@ -0,0 +1,495 @@
+//! The `rpc_rate_limiter` module implements the per-client rate limiting of the JSON RPC calls.
+
+use {
+    crate::rpc::JsonRpcRequestProcessor,
+    jsonrpc_core::{
+        futures::future::{self, Either},
+        middleware::{Middleware, NoopFuture},
+        Call, Output,
+    },
+    jsonrpc_http_server::hyper,
+    solana_rpc_client_api::custom_error::RpcCustomError,
+    std::{
+        collections::HashMap,
+        future::Future,
+        net::IpAddr,
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+/// Cost of a call to a method without a configured cost
+pub const DEFAULT_METHOD_COST: u64 = 1;
+
+/// Default cost of the calls to the methods scanning accounts, simulating transactions or
+/// reading from the ledger
+pub const DEFAULT_METHOD_COSTS: &[(&str, u64)] = &[
+    ("getProgramAccounts", 100),
+    ("getProgramAccountsPaginated", 100),
+    ("getTokenAccountsByMint", 100),
+    ("getTokenAccountsByOwner", 100),
+    ("getTokenAccountsByDelegate", 100),
+    ("getTokenLargestAccounts", 100),
+    ("simulateBundle", 100),
+    ("getSignaturesForAddress", 10),
+    ("getBlock", 10),
+];
+
+/// Default number of clients with their own limits, past which the new clients share the limits
+/// of the unidentified clients
+pub const DEFAULT_MAX_CLIENTS: usize = 100_000;
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Metric name of the rejected calls to the methods without a configured cost or call limit
+const OTHER_METHODS: &str = "other";
+
+/// Limits of the calls of each client, identified by the address of its requests.
+///
+/// The limits are rates, so neither `cost_per_second` nor the `method_calls_per_second` values
+/// can be 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRateLimitConfig {
+    /// Cost units each client can spend per second, and in a single burst
+    pub cost_per_second: u64,
+    /// Cost of a call to each method, `DEFAULT_METHOD_COST` if missing
+    pub method_costs: HashMap<String, u64>,
+    /// Calls to each method each client can make per second, on top of `cost_per_second`
+    pub method_calls_per_second: HashMap<String, u64>,
+    /// Identify the clients by the `X-Forwarded-For` header of their requests, which must then
+    /// be set by a trusted proxy in front of the RPC service
+    pub trust_forwarded_for: bool,
+    /// Number of clients with their own limits
+    pub max_clients: usize,
+}
+
+impl RpcRateLimitConfig {
+    pub fn new(cost_per_second: u64) -> Self {
+        Self {
+            cost_per_second,
+            method_costs: DEFAULT_METHOD_COSTS
+                .iter()
+                .map(|(method, cost)| (method.to_string(), *cost))
+                .collect(),
+            method_calls_per_second: HashMap::new(),
+            trust_forwarded_for: false,
+            max_clients: DEFAULT_MAX_CLIENTS,
+        }
+    }
+
+    fn method_cost(&self, method: &str) -> u64 {
+        self.method_costs
+            .get(method)
+            .copied()
+            .unwrap_or(DEFAULT_METHOD_COST)
+    }
+}
+
+/// Token bucket refilled at `rate` tokens per second, up to `rate` tokens
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        Self {
+            tokens: rate as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, rate: u64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate as f64).min(rate as f64);
+        self.last_refill = now;
+    }
+
+    /// Whether `cost` tokens can be taken. A call costing more than the bucket can hold is
+    /// allowed once the bucket is full, and leaves it in debt.
+    fn can_take(&mut self, rate: u64, cost: u64, now: Instant) -> bool {
+        self.refill(rate, now);
+        self.tokens >= cost.min(rate) as f64
+    }
+
+    fn take(&mut self, cost: u64) {
+        self.tokens -= cost as f64;
+    }
+
+    fn is_full(&mut self, rate: u64, now: Instant) -> bool {
+        self.refill(rate, now);
+        self.tokens >= rate as f64
+    }
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    cost_buckets: HashMap<Option<IpAddr>, Bucket>,
+    method_buckets: HashMap<(Option<IpAddr>, String), Bucket>,
+    rejected_calls: HashMap<String, u64>,
+    last_report: Instant,
+}
+
+impl RateLimiterState {
+    fn new(now: Instant) -> Self {
+        Self {
+            cost_buckets: HashMap::new(),
+            method_buckets: HashMap::new(),
+            rejected_calls: HashMap::new(),
+            last_report: now,
+        }
+    }
+
+    /// Reports the rejected calls, and drops the buckets of the clients that stopped calling
+    fn maybe_report(&mut self, config: &RpcRateLimitConfig, now: Instant) {
+        if now.saturating_duration_since(self.last_report) < REPORT_INTERVAL {
+            return;
+        }
+        self.last_report = now;
+        for (method, rejected_calls) in self.rejected_calls.drain() {
+            datapoint_info!(
+                "rpc-rate-limiter",
+                "method" => method,
+                ("rejected_calls", rejected_calls, i64),
+            );
+        }
+        self.cost_buckets
+            .retain(|_, bucket| !bucket.is_full(config.cost_per_second, now));
+        self.method_buckets.retain(|(_, method), bucket| {
+            let rate = config.method_calls_per_second[method];
+            !bucket.is_full(rate, now)
+        });
+    }
+}
+
+/// Middleware of the JSON RPC service rejecting the calls of the clients exceeding the limits
+/// of an `RpcRateLimitConfig`.
+///
+/// With `trust_forwarded_for`, clients are identified by the last address of the
+/// `X-Forwarded-For` header of their requests, set by the proxy in front of the RPC service.
+/// The HTTP server does not expose the peer address of a request, so the requests without
+/// that header, or all the requests otherwise, share the limits of the unidentified clients:
+/// the validator only enables the limits with `trust_forwarded_for`.
+/// So do the requests of the new clients once `max_clients` clients are tracked, until the
+/// clients that stopped calling are dropped.
+pub(crate) struct RpcRateLimiter {
+    config: Option<RpcRateLimitConfig>,
+    state: Mutex<RateLimiterState>,
+}
+
+impl RpcRateLimiter {
+    pub(crate) fn new(config: Option<RpcRateLimitConfig>) -> Self {
+        Self {
+            config,
+            state: Mutex::new(RateLimiterState::new(Instant::now())),
+        }
+    }
+
+    /// Whether `client_ip` can call `method` at `now`, taking the cost of the call if so
+    fn check(&self, client_ip: Option<IpAddr>, method: &str, now: Instant) -> bool {
+        let Some(config) = &self.config else {
+            return true;
+        };
+        let mut state = self.state.lock().unwrap();
+        state.maybe_report(config, now);
+
+        let state = &mut *state;
+        let client_ip = if state.cost_buckets.len() < config.max_clients
+            || state.cost_buckets.contains_key(&client_ip)
+        {
+            client_ip
+        } else {
+            None
+        };
+        let cost = config.method_cost(method);
+        let cost_bucket = state
+            .cost_buckets
+            .entry(client_ip)
+            .or_insert_with(|| Bucket::new(config.cost_per_second, now));
+        let mut allowed = cost_bucket.can_take(config.cost_per_second, cost, now);
+        let mut method_bucket = None;
+        if let Some(&rate) = config.method_calls_per_second.get(method) {
+            let bucket = state
+                .method_buckets
+                .entry((client_ip, method.to_string()))
+                .or_insert_with(|| Bucket::new(rate, now));
+            allowed &= bucket.can_take(rate, 1, now);
+            method_bucket = Some(bucket);
+        }
+
+        if allowed {
+            cost_bucket.take(cost);
+            if let Some(bucket) = method_bucket {
+                bucket.take(1);
+            }
+        } else {
+            // keep the metrics of the unconfigured methods under a single name, as the method
+            // names of the rejected calls are chosen by the clients
+            let method = if config.method_costs.contains_key(method)
+                || config.method_calls_per_second.contains_key(method)
+            {
+                method
+            } else {
+                OTHER_METHODS
+            };
+            *state.rejected_calls.entry(method.to_string()).or_default() += 1;
+        }
+        allowed
+    }
+}
+
+impl Middleware<JsonRpcRequestProcessor> for RpcRateLimiter {
+    type Future = NoopFuture;
+    type CallFuture = future::Ready<Option<Output>>;
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        if let Call::MethodCall(method_call) = &call {
+            if !self.check(meta.client_ip(), &method_call.method, Instant::now()) {
+                let error = RpcCustomError::RateLimited {
+                    method: method_call.method.clone(),
+                };
+                return Either::Left(future::ready(Some(Output::from(
+                    Err(error.into()),
+                    method_call.id.clone(),
+                    method_call.jsonrpc,
+                ))));
+            }
+        }
+        Either::Right(next(call, meta))
+    }
+}
+
+/// Returns the address of the client of `request`, as set by the last proxy of the
+/// `X-Forwarded-For` header if trusted
+pub(crate) fn forwarded_client_ip(
+    request: &hyper::Request<hyper::Body>,
+    trust_forwarded_for: bool,
+) -> Option<IpAddr> {
+    if !trust_forwarded_for {
+        return None;
+    }
+    request
+        .headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .last()?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rpc::rpc_minimal::{self, *},
+        jsonrpc_core::MetaIoHandler,
+        serde_json::Value,
+        solana_client::connection_cache::ConnectionCache,
+        solana_ledger::genesis_utils::create_genesis_config,
+        solana_rpc_client_api::custom_error::JSON_RPC_SERVER_ERROR_RATE_LIMITED,
+        solana_runtime::bank::Bank,
+        solana_streamer::socket::SocketAddrSpace,
+        std::{net::Ipv4Addr, sync::Arc},
+    };
+
+    fn client(last_octet: u8) -> Option<IpAddr> {
+        Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet)))
+    }
+
+    #[test]
+    fn test_rate_limiter_disabled() {
+        let rate_limiter = RpcRateLimiter::new(None);
+        let now = Instant::now();
+        for _ in 0..1_000 {
+            assert!(rate_limiter.check(None, "getProgramAccounts", now));
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_method_costs() {
+        let rate_limiter = RpcRateLimiter::new(Some(RpcRateLimitConfig::new(100)));
+        let now = Instant::now();
+
+        // a call costing the whole budget empties it
+        assert!(rate_limiter.check(client(1), "getProgramAccounts", now));
+        assert!(!rate_limiter.check(client(1), "getBalance", now));
+        assert!(!rate_limiter.check(client(1), "getProgramAccounts", now));
+
+        // other clients have their own budget
+        for _ in 0..100 {
+            assert!(rate_limiter.check(client(2), "getBalance", now));
+        }
+        assert!(!rate_limiter.check(client(2), "getBalance", now));
+        assert!(rate_limiter.check(None, "getBlock", now));
+
+        // the budget is refilled over time
+        let now = now + Duration::from_millis(100);
+        for _ in 0..10 {
+            assert!(rate_limiter.check(client(1), "getBalance", now));
+        }
+        assert!(!rate_limiter.check(client(1), "getBalance", now));
+        assert!(!rate_limiter.check(client(1), "getSignaturesForAddress", now));
+        let now = now + Duration::from_millis(100);
+        assert!(rate_limiter.check(client(1), "getSignaturesForAddress", now));
+    }
+
+    #[test]
+    fn test_rate_limiter_cost_above_budget() {
+        let rate_limiter = RpcRateLimiter::new(Some(RpcRateLimitConfig::new(50)));
+        let now = Instant::now();
+
+        // a call costing more than the budget is allowed with a full budget, and has to be paid
+        // back before the next call
+        assert!(rate_limiter.check(client(1), "getProgramAccounts", now));
+        let now = now + Duration::from_secs(1);
+        assert!(!rate_limiter.check(client(1), "getBalance", now));
+        let now = now + Duration::from_millis(20);
+        assert!(rate_limiter.check(client(1), "getBalance", now));
+    }
+
+    #[test]
+    fn test_rate_limiter_method_calls_per_second() {
+        let mut config = RpcRateLimitConfig::new(100);
+        config
+            .method_calls_per_second
+            .insert("getBlock".to_string(), 2);
+        let rate_limiter = RpcRateLimiter::new(Some(config));
+        let now = Instant::now();
+
+        assert!(rate_limiter.check(client(1), "getBlock", now));
+        assert!(rate_limiter.check(client(1), "getBlock", now));
+        assert!(!rate_limiter.check(client(1), "getBlock", now));
+        // the rejected call did not take from the cost budget
+        for _ in 0..80 {
+            assert!(rate_limiter.check(client(1), "getBalance", now));
+        }
+        assert!(!rate_limiter.check(client(1), "getBalance", now));
+        assert!(rate_limiter.check(client(2), "getBlock", now));
+
+        let now = now + Duration::from_millis(500);
+        assert!(rate_limiter.check(client(1), "getBlock", now));
+        assert!(!rate_limiter.check(client(1), "getBlock", now));
+    }
+
+    #[test]
+    fn test_rate_limiter_report() {
+        let rate_limiter = RpcRateLimiter::new(Some(RpcRateLimitConfig::new(1)));
+        let now = Instant::now();
+
+        assert!(rate_limiter.check(client(1), "getBalance", now));
+        assert!(!rate_limiter.check(client(1), "getBalance", now));
+        assert!(!rate_limiter.check(client(1), "getBlock", now));
+        assert!(rate_limiter.check(client(2), "getProgramAccounts", now));
+        {
+            let state = rate_limiter.state.lock().unwrap();
+            assert_eq!(state.cost_buckets.len(), 2);
+            assert_eq!(
+                state.rejected_calls,
+                HashMap::from([("other".to_string(), 1), ("getBlock".to_string(), 1)])
+            );
+        }
+
+        // client 1 is back to a full budget, client 2 is still in debt
+        let now = now + REPORT_INTERVAL;
+        assert!(rate_limiter.check(client(3), "getBalance", now));
+        let state = rate_limiter.state.lock().unwrap();
+        assert!(state.rejected_calls.is_empty());
+        assert_eq!(state.cost_buckets.len(), 2);
+        assert!(!state.cost_buckets.contains_key(&client(1)));
+    }
+
+    #[test]
+    fn test_rate_limiter_max_clients() {
+        let mut config = RpcRateLimitConfig::new(1);
+        config.max_clients = 2;
+        let rate_limiter = RpcRateLimiter::new(Some(config));
+        let now = Instant::now();
+
+        assert!(rate_limiter.check(client(1), "getBalance", now));
+        assert!(rate_limiter.check(client(2), "getBalance", now));
+        // the new clients share the budget of the unidentified clients
+        assert!(rate_limiter.check(client(3), "getBalance", now));
+        assert!(!rate_limiter.check(client(4), "getBalance", now));
+        assert!(!rate_limiter.check(None, "getBalance", now));
+        assert_eq!(rate_limiter.state.lock().unwrap().cost_buckets.len(), 3);
+
+        // once the idle clients are dropped, new clients have their own budget again
+        let now = now + REPORT_INTERVAL;
+        assert!(rate_limiter.check(client(4), "getBalance", now));
+        assert!(rate_limiter.check(client(5), "getBalance", now));
+        assert!(!rate_limiter.check(client(4), "getBalance", now));
+    }
+
+    #[test]
+    fn test_rate_limiter_middleware() {
+        let genesis = create_genesis_config(20);
+        let bank = Arc::new(Bank::new_for_tests(&genesis.genesis_config));
+        let connection_cache = Arc::new(ConnectionCache::new("connection_cache_test"));
+        let meta = JsonRpcRequestProcessor::new_from_bank(
+            bank,
+            SocketAddrSpace::Unspecified,
+            connection_cache,
+        );
+        let mut io =
+            MetaIoHandler::with_middleware(RpcRateLimiter::new(Some(RpcRateLimitConfig::new(1))));
+        io.extend_with(rpc_minimal::MinimalImpl.to_delegate());
+
+        let get_slot = |client_ip| {
+            let req = r#"{"jsonrpc":"2.0","id":1,"method":"getSlot"}"#;
+            let res = io.handle_request_sync(req, meta.with_client_ip(client_ip));
+            serde_json::from_str::<Value>(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+        assert_eq!(get_slot(client(1))["result"], 0);
+        let result = get_slot(client(1));
+        assert_eq!(result["error"]["code"], JSON_RPC_SERVER_ERROR_RATE_LIMITED);
+        assert_eq!(result["id"], 1);
+        assert_eq!(get_slot(client(2))["result"], 0);
+    }
+
+    #[test]
+    fn test_forwarded_client_ip() {
+        let request = |forwarded_for: &[&str]| {
+            let mut builder = hyper::Request::builder();
+            for value in forwarded_for {
+                builder = builder.header("X-Forwarded-For", *value);
+            }
+            builder.body(hyper::Body::empty()).unwrap()
+        };
+        assert_eq!(forwarded_client_ip(&request(&[]), true), None);
+        assert_eq!(
+            forwarded_client_ip(&request(&["10.0.0.1"]), true),
+            client(1)
+        );
+        assert_eq!(
+            forwarded_client_ip(&request(&["192.168.0.1, 10.0.0.2"]), true),
+            client(2)
+        );
+        assert_eq!(
+            forwarded_client_ip(&request(&["192.168.0.1", "10.0.0.3,10.0.0.4"]), true),
+            client(4)
+        );
+        assert_eq!(
+            forwarded_client_ip(&request(&["::1"]), true),
+            Some("::1".parse().unwrap())
+        );
+        assert_eq!(forwarded_client_ip(&request(&["unknown"]), true), None);
+        // the header is ignored unless trusted, as any client can set it
+        assert_eq!(forwarded_client_ip(&request(&["10.0.0.1"]), false), None);
+    }
+}
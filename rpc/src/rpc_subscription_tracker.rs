@@ -144,6 +144,10 @@ pub struct BlockSubscriptionParams {
 pub enum BlockSubscriptionKind {
     All,
     MentionsAccountOrProgram(Pubkey),
+    /// Transactions mentioning any of the addresses, which are sorted and deduplicated
+    MentionsAnyAccountOrProgram(Vec<Pubkey>),
+    /// Transactions mentioning all of the addresses, which are sorted and deduplicated
+    MentionsAllAccountsOrPrograms(Vec<Pubkey>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -157,6 +161,21 @@ pub enum LogsSubscriptionKind {
     All,
     AllWithVotes,
     Single(Pubkey),
+    /// Transactions mentioning any of the addresses, which are sorted and deduplicated
+    AnyOf(Vec<Pubkey>),
+    /// Transactions mentioning all of the addresses, which are sorted and deduplicated
+    AllOf(Vec<Pubkey>),
+}
+
+impl LogsSubscriptionKind {
+    /// The addresses whose logs the bank must collect for this subscription
+    pub fn mentioned_addresses(&self) -> &[Pubkey] {
+        match self {
+            LogsSubscriptionKind::All | LogsSubscriptionKind::AllWithVotes => &[],
+            LogsSubscriptionKind::Single(pubkey) => std::slice::from_ref(pubkey),
+            LogsSubscriptionKind::AnyOf(pubkeys) | LogsSubscriptionKind::AllOf(pubkeys) => pubkeys,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -292,8 +311,8 @@ impl SubscriptionControl {
         self.0.subscriptions.iter().any(|item| {
             if let SubscriptionParams::Logs(params) = item.key() {
                 let subscribed_pubkey = match &params.kind {
-                    LogsSubscriptionKind::All | LogsSubscriptionKind::AllWithVotes => None,
                     LogsSubscriptionKind::Single(pubkey) => Some(pubkey),
+                    _ => None,
                 };
                 subscribed_pubkey == pubkey
             } else {
@@ -360,9 +379,10 @@ impl LogsSubscriptionsIndex {
         match params.kind {
             LogsSubscriptionKind::All => self.all_count += 1,
             LogsSubscriptionKind::AllWithVotes => self.all_with_votes_count += 1,
-            LogsSubscriptionKind::Single(key) => {
-                *self.single_count.entry(key).or_default() += 1;
-            }
+            _ => {}
+        }
+        for key in params.kind.mentioned_addresses() {
+            *self.single_count.entry(*key).or_default() += 1;
         }
         self.update_config();
     }
@@ -371,7 +391,10 @@ impl LogsSubscriptionsIndex {
         match params.kind {
             LogsSubscriptionKind::All => self.all_count -= 1,
             LogsSubscriptionKind::AllWithVotes => self.all_with_votes_count -= 1,
-            LogsSubscriptionKind::Single(key) => match self.single_count.entry(key) {
+            _ => {}
+        }
+        for key in params.kind.mentioned_addresses() {
+            match self.single_count.entry(*key) {
                 Entry::Occupied(mut entry) => {
                     *entry.get_mut() -= 1;
                     if *entry.get() == 0 {
@@ -379,7 +402,7 @@ impl LogsSubscriptionsIndex {
                     }
                 }
                 Entry::Vacant(_) => error!("missing entry in single_count"),
-            },
+            }
         }
         self.update_config();
     }
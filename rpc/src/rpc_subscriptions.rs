@@ -63,11 +63,18 @@ fn get_transaction_logs(
     bank: &Bank,
     params: &LogsSubscriptionParams,
 ) -> Option<Vec<TransactionLogInfo>> {
-    let pubkey = match &params.kind {
-        LogsSubscriptionKind::All | LogsSubscriptionKind::AllWithVotes => None,
-        LogsSubscriptionKind::Single(pubkey) => Some(pubkey),
+    let mut logs = match &params.kind {
+        LogsSubscriptionKind::All | LogsSubscriptionKind::AllWithVotes => {
+            bank.get_transaction_logs(None)
+        }
+        LogsSubscriptionKind::Single(pubkey) => bank.get_transaction_logs(Some(pubkey)),
+        LogsSubscriptionKind::AnyOf(pubkeys) => {
+            bank.get_transaction_logs_for_addresses(pubkeys, false)
+        }
+        LogsSubscriptionKind::AllOf(pubkeys) => {
+            bank.get_transaction_logs_for_addresses(pubkeys, true)
+        }
     };
-    let mut logs = bank.get_transaction_logs(pubkey);
     if matches!(params.kind, LogsSubscriptionKind::All) {
         // Filter out votes if the subscriber doesn't want them
         if let Some(logs) = &mut logs {
@@ -327,19 +334,42 @@ fn filter_block_result_txs(
     last_modified_slot: Slot,
     params: &BlockSubscriptionParams,
 ) -> Result<Option<RpcBlockUpdate>, RpcBlockUpdateError> {
-    block.transactions = match params.kind {
+    block.transactions = match &params.kind {
         BlockSubscriptionKind::All => block.transactions,
         BlockSubscriptionKind::MentionsAccountOrProgram(pk) => block
             .transactions
             .into_iter()
-            .filter(|tx| tx.account_keys().iter().any(|key| key == &pk))
+            .filter(|tx| tx.account_keys().iter().any(|key| key == pk))
+            .collect(),
+        BlockSubscriptionKind::MentionsAnyAccountOrProgram(pks) => block
+            .transactions
+            .into_iter()
+            .filter(|tx| {
+                tx.account_keys()
+                    .iter()
+                    .any(|key| pks.binary_search(key).is_ok())
+            })
+            .collect(),
+        BlockSubscriptionKind::MentionsAllAccountsOrPrograms(pks) => block
+            .transactions
+            .into_iter()
+            .filter(|tx| {
+                let account_keys = tx.account_keys();
+                // an account key may be repeated across the loaded addresses,
+                // so count the distinct subscribed keys that are mentioned
+                let mut mentioned: Vec<&Pubkey> = account_keys
+                    .iter()
+                    .filter(|key| pks.binary_search(key).is_ok())
+                    .collect();
+                mentioned.sort_unstable();
+                mentioned.dedup();
+                mentioned.len() == pks.len()
+            })
             .collect(),
     };
 
-    if block.transactions.is_empty() {
-        if let BlockSubscriptionKind::MentionsAccountOrProgram(_) = params.kind {
-            return Ok(None);
-        }
+    if block.transactions.is_empty() && params.kind != BlockSubscriptionKind::All {
+        return Ok(None);
     }
 
     let block = ConfirmedBlock::from(block)
@@ -3047,6 +3077,106 @@ pub(crate) mod tests {
         assert!(!subscriptions.control.logs_subscribed(Some(&alice.pubkey())));
     }
 
+    #[test]
+    fn test_logs_subscribe_with_multiple_mentions() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(100);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+
+        let alice = Keypair::new();
+        let bob = Keypair::new();
+
+        let exit = Arc::new(AtomicBool::new(false));
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let max_complete_rewards_slot = Arc::new(AtomicU64::default());
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests(
+            exit,
+            max_complete_transaction_status_slot,
+            max_complete_rewards_slot,
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+
+        let sub_config = RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::processed()),
+        };
+
+        let (rpc_any, mut receiver_any) = rpc_pubsub_service::test_connection(&subscriptions);
+        let sub_id_for_any = rpc_any
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![
+                    alice.pubkey().to_string(),
+                    bob.pubkey().to_string(),
+                ]),
+                Some(sub_config.clone()),
+            )
+            .unwrap();
+        let (rpc_all, mut receiver_all) = rpc_pubsub_service::test_connection(&subscriptions);
+        let sub_id_for_all = rpc_all
+            .logs_subscribe(
+                RpcTransactionLogsFilter::MentionsAll(vec![
+                    alice.pubkey().to_string(),
+                    mint_keypair.pubkey().to_string(),
+                ]),
+                Some(sub_config.clone()),
+            )
+            .unwrap();
+        let (rpc_none, mut receiver_none) = rpc_pubsub_service::test_connection(&subscriptions);
+        rpc_none
+            .logs_subscribe(
+                RpcTransactionLogsFilter::MentionsAll(vec![
+                    alice.pubkey().to_string(),
+                    bob.pubkey().to_string(),
+                ]),
+                Some(sub_config),
+            )
+            .unwrap();
+        rpc_none.block_until_processed(&subscriptions);
+
+        let tx = system_transaction::create_account(
+            &mint_keypair,
+            &alice,
+            blockhash,
+            1,
+            0,
+            &system_program::id(),
+        );
+        assert!(bank_forks
+            .read()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .process_transaction_with_metadata(tx.clone())
+            .was_executed());
+
+        subscriptions.notify_subscribers(CommitmentSlots::new_from_slot(0));
+
+        let expected_response_any =
+            make_logs_result(&tx.signatures[0].to_string(), u64::from(sub_id_for_any));
+        assert_eq!(
+            expected_response_any,
+            serde_json::from_str::<serde_json::Value>(&receiver_any.recv()).unwrap(),
+        );
+        let expected_response_all =
+            make_logs_result(&tx.signatures[0].to_string(), u64::from(sub_id_for_all));
+        assert_eq!(
+            expected_response_all,
+            serde_json::from_str::<serde_json::Value>(&receiver_all.recv()).unwrap(),
+        );
+        // bob is not mentioned by the transaction
+        assert!(receiver_none
+            .recv_timeout(Duration::from_millis(300))
+            .is_err());
+    }
+
     #[test]
     fn test_total_subscriptions() {
         let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(100);
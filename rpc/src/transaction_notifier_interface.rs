@@ -1,6 +1,6 @@
 use {
     solana_sdk::{clock::Slot, signature::Signature, transaction::SanitizedTransaction},
-    solana_transaction_status::TransactionStatusMeta,
+    solana_transaction_status::{InstructionTrace, TransactionStatusMeta},
     std::sync::{Arc, RwLock},
 };
 
@@ -13,6 +13,18 @@ pub trait TransactionNotifier {
         transaction_status_meta: &TransactionStatusMeta,
         transaction: &SanitizedTransaction,
     );
+
+    /// Notify the execution trace of a transaction: every instruction it
+    /// executed, including inner instructions, in invocation order
+    fn notify_transaction_trace(
+        &self,
+        _slot: Slot,
+        _transaction_slot_index: usize,
+        _signature: &Signature,
+        _transaction: &SanitizedTransaction,
+        _instruction_trace: &[InstructionTrace],
+    ) {
+    }
 }
 
 pub type TransactionNotifierLock = Arc<RwLock<dyn TransactionNotifier + Sync + Send>>;
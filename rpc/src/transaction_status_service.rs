@@ -99,6 +99,7 @@ impl TransactionStatusService {
                             status,
                             log_messages,
                             inner_instructions,
+                            instruction_trace,
                             durable_nonce_fee,
                             return_data,
                             executed_units,
@@ -176,6 +177,19 @@ impl TransactionStatusService {
                                 &transaction_status_meta,
                                 &transaction,
                             );
+
+                            if let Some(instruction_trace) = instruction_trace {
+                                transaction_notifier
+                                    .write()
+                                    .unwrap()
+                                    .notify_transaction_trace(
+                                        slot,
+                                        transaction_index,
+                                        transaction.signature(),
+                                        &transaction,
+                                        &instruction_trace,
+                                    );
+                            }
                         }
 
                         if !(enable_extended_tx_metadata_storage || transaction_notifier.is_some())
@@ -376,6 +390,7 @@ pub(crate) mod tests {
             status: Ok(()),
             log_messages: None,
             inner_instructions: None,
+            instruction_trace: None,
             durable_nonce_fee: Some(DurableNonceFee::from(
                 &NonceFull::from_partial(
                     rollback_partial,
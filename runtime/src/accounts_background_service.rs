@@ -400,6 +400,16 @@ impl SnapshotRequestHandler {
                         self.snapshot_config.snapshot_version,
                         status_cache_slot_deltas,
                     )?;
+                    // the accounts were just cleaned up to the snapshot, as required
+                    if accounts_package_kind
+                        == AccountsPackageKind::Snapshot(SnapshotKind::FullSnapshot)
+                    {
+                        snapshot_root_bank
+                            .rc
+                            .accounts
+                            .accounts_db
+                            .persist_secondary_indexes(snapshot_root_bank.slot());
+                    }
                     AccountsPackage::new_for_snapshot(
                         accounts_package_kind,
                         &snapshot_root_bank,
@@ -75,8 +75,9 @@ use {
         },
         accounts_db::{
             AccountShrinkThreshold, AccountStorageEntry, AccountsDbConfig,
-            CalcAccountsHashDataSource, IncludeSlotInHash, VerifyAccountsHashAndLamportsConfig,
-            ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS, ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            CalcAccountsHashDataSource, IncludeSlotInHash, LoadedAccount,
+            VerifyAccountsHashAndLamportsConfig, ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS,
+            ACCOUNTS_DB_CONFIG_FOR_TESTING,
         },
         accounts_hash::{AccountsHash, CalcAccountsHashConfig, HashStats, IncrementalAccountsHash},
         accounts_index::{AccountSecondaryIndexes, IndexKey, ScanConfig, ScanResult, ZeroLamport},
@@ -94,7 +95,8 @@ use {
         storable_accounts::StorableAccounts,
         transaction_error_metrics::TransactionErrorMetrics,
         transaction_results::{
-            inner_instructions_list_from_instruction_trace, DurableNonceFee,
+            inner_instructions_list_from_instruction_trace,
+            instruction_trace_list_from_instruction_trace, DurableNonceFee, InnerInstructionsList,
             TransactionCheckResult, TransactionExecutionDetails, TransactionExecutionResult,
             TransactionResults,
         },
@@ -106,12 +108,13 @@ use {
     solana_program_runtime::{
         accounts_data_meter::MAX_ACCOUNTS_DATA_LEN,
         compute_budget::{self, ComputeBudget},
+        instruction_trace_recorder::InstructionTraceRecorder,
         invoke_context::ProcessInstructionWithContext,
         loaded_programs::{
             LoadProgramMetrics, LoadedProgram, LoadedProgramMatchCriteria, LoadedProgramType,
             LoadedPrograms, LoadedProgramsForTxBatch, WorkingSlot, DELAY_VISIBILITY_SLOT_OFFSET,
         },
-        log_collector::LogCollector,
+        log_collector::{LogCollector, LOG_MESSAGES_BYTES_LIMIT},
         message_processor::MessageProcessor,
         sysvar_cache::SysvarCache,
         timings::{ExecuteDetailsTimings, ExecuteTimingType, ExecuteTimings},
@@ -185,7 +188,7 @@ use {
         collections::{HashMap, HashSet},
         convert::{TryFrom, TryInto},
         fmt, mem,
-        ops::{AddAssign, RangeInclusive},
+        ops::{AddAssign, RangeBounds, RangeInclusive},
         path::PathBuf,
         rc::Rc,
         slice,
@@ -225,6 +228,11 @@ pub const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
 
 pub const MAX_LEADER_SCHEDULE_STAKES: Epoch = 5;
 
+/// Bytes limit of the log messages collected for the instruction trace of a
+/// transaction.  The trace is built before the regular log messages bytes
+/// limit is applied, so it needs a bound of its own.
+const INSTRUCTION_TRACE_LOG_MESSAGES_BYTES_LIMIT: usize = 100 * LOG_MESSAGES_BYTES_LIMIT;
+
 #[derive(Default)]
 struct RentMetrics {
     hold_range_us: AtomicU64,
@@ -334,7 +342,17 @@ pub struct TransactionSimulationResult {
     pub post_simulation_accounts: Vec<TransactionAccount>,
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
+    /// Only recorded when requested
+    pub inner_instructions: Option<InnerInstructionsList>,
+}
+
+/// The result of simulating one transaction of a bundle
+pub struct TransactionBundleSimulationResult {
+    pub simulation_result: TransactionSimulationResult,
+    /// The requested accounts, as left by the transaction and the ones before it
+    pub post_accounts: Vec<Option<AccountSharedData>>,
 }
+
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
     pub post_balances: TransactionBalances,
@@ -408,6 +426,55 @@ impl TransactionLogCollector {
             }),
         }
     }
+
+    /// Returns the logs from transactions that mentioned any of `addresses`,
+    /// or all of them if `mentions_all` is set, in execution order.
+    ///
+    /// Every address must be one of the collected `mentioned_addresses`.
+    pub fn get_logs_for_addresses(
+        &self,
+        addresses: &[Pubkey],
+        mentions_all: bool,
+    ) -> Option<Vec<TransactionLogInfo>> {
+        let mut indices_per_address = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            match self.mentioned_address_map.get(address) {
+                Some(log_indices) => indices_per_address.push(log_indices),
+                // no transaction mentioned the address, so none mentioned all of them
+                None if mentions_all => return None,
+                None => {}
+            }
+        }
+        let log_indices: Vec<usize> = if mentions_all {
+            // each list of indices is sorted, intersect them from the shortest one
+            indices_per_address.sort_unstable_by_key(|log_indices| log_indices.len());
+            let (shortest, others) = indices_per_address.split_first()?;
+            shortest
+                .iter()
+                .filter(|i| {
+                    others
+                        .iter()
+                        .all(|log_indices| log_indices.binary_search(i).is_ok())
+                })
+                .copied()
+                .collect()
+        } else {
+            let mut log_indices: Vec<usize> =
+                indices_per_address.into_iter().flatten().copied().collect();
+            log_indices.sort_unstable();
+            log_indices.dedup();
+            log_indices
+        };
+        if log_indices.is_empty() {
+            return None;
+        }
+        Some(
+            log_indices
+                .into_iter()
+                .filter_map(|i| self.logs.get(i).cloned())
+                .collect(),
+        )
+    }
 }
 
 /// Bank's common fields shared by all supported snapshot versions for deserialization.
@@ -4319,10 +4386,11 @@ impl Bank {
     pub fn simulate_transaction(
         &self,
         transaction: SanitizedTransaction,
+        enable_cpi_recording: bool,
     ) -> TransactionSimulationResult {
         assert!(self.is_frozen(), "simulation bank must be frozen");
 
-        self.simulate_transaction_unchecked(transaction)
+        self.simulate_transaction_unchecked(transaction, enable_cpi_recording)
     }
 
     /// Run transactions against a bank without committing the results; does not check if the bank
@@ -4330,11 +4398,76 @@ impl Bank {
     pub fn simulate_transaction_unchecked(
         &self,
         transaction: SanitizedTransaction,
+        enable_cpi_recording: bool,
+    ) -> TransactionSimulationResult {
+        let mut account_overrides = AccountOverrides::default();
+        self.add_account_overrides_for_simulation(
+            &transaction.message().account_keys(),
+            &mut account_overrides,
+        );
+        self.simulate_transaction_with_overrides(
+            &transaction,
+            &account_overrides,
+            enable_cpi_recording,
+        )
+    }
+
+    /// Run a bundle of transactions sequentially against a frozen bank without committing the
+    /// results. Each transaction observes the accounts written by the successful transactions
+    /// before it, and `account_overrides` replace the bank's accounts for the whole bundle.
+    /// The state of `post_account_addresses` is returned after every transaction.
+    pub fn simulate_transaction_bundle(
+        &self,
+        transactions: &[SanitizedTransaction],
+        mut account_overrides: AccountOverrides,
+        post_account_addresses: &[Pubkey],
+    ) -> Vec<TransactionBundleSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        transactions
+            .iter()
+            .map(|transaction| {
+                let message = transaction.message();
+                self.add_account_overrides_for_simulation(
+                    &message.account_keys(),
+                    &mut account_overrides,
+                );
+                let simulation_result =
+                    self.simulate_transaction_with_overrides(transaction, &account_overrides, true);
+                if simulation_result.result.is_ok() {
+                    for (i, (pubkey, account)) in simulation_result
+                        .post_simulation_accounts
+                        .iter()
+                        .enumerate()
+                    {
+                        if message.is_writable(i) {
+                            account_overrides.set_account(pubkey, Some(account.clone()));
+                        }
+                    }
+                }
+                let post_accounts = post_account_addresses
+                    .iter()
+                    .map(|pubkey| match account_overrides.get(pubkey) {
+                        Some(account) => (account.lamports() > 0).then(|| account.clone()),
+                        None => self.get_account(pubkey),
+                    })
+                    .collect();
+                TransactionBundleSimulationResult {
+                    simulation_result,
+                    post_accounts,
+                }
+            })
+            .collect()
+    }
+
+    fn simulate_transaction_with_overrides(
+        &self,
+        transaction: &SanitizedTransaction,
+        account_overrides: &AccountOverrides,
+        enable_cpi_recording: bool,
     ) -> TransactionSimulationResult {
-        let account_keys = transaction.message().account_keys();
-        let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
-        let batch = self.prepare_unlocked_batch_from_single_tx(&transaction);
+        let number_of_accounts = transaction.message().account_keys().len();
+        let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
         let mut timings = ExecuteTimings::default();
 
         let LoadAndExecuteTransactionsOutput {
@@ -4347,11 +4480,12 @@ impl Bank {
             // for processing. During forwarding, the transaction could expire if the
             // delay is not accounted for.
             MAX_PROCESSING_AGE - MAX_TRANSACTION_FORWARDING_DELAY,
-            false,
+            enable_cpi_recording,
             true,
             true,
+            false,
             &mut timings,
-            Some(&account_overrides),
+            Some(account_overrides),
             None,
         );
 
@@ -4382,11 +4516,13 @@ impl Bank {
 
         let execution_result = execution_results.pop().unwrap();
         let flattened_result = execution_result.flattened_result();
-        let (logs, return_data) = match execution_result {
-            TransactionExecutionResult::Executed { details, .. } => {
-                (details.log_messages, details.return_data)
-            }
-            TransactionExecutionResult::NotExecuted(_) => (None, None),
+        let (logs, return_data, inner_instructions) = match execution_result {
+            TransactionExecutionResult::Executed { details, .. } => (
+                details.log_messages,
+                details.return_data,
+                details.inner_instructions,
+            ),
+            TransactionExecutionResult::NotExecuted(_) => (None, None, None),
         };
         let logs = logs.unwrap_or_default();
 
@@ -4396,13 +4532,21 @@ impl Bank {
             post_simulation_accounts,
             units_consumed,
             return_data,
+            inner_instructions,
         }
     }
 
-    fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
-        let mut account_overrides = AccountOverrides::default();
+    /// Adds the overrides a simulation needs for `account_keys`, unless they are already
+    /// overridden
+    fn add_account_overrides_for_simulation(
+        &self,
+        account_keys: &AccountKeys,
+        account_overrides: &mut AccountOverrides,
+    ) {
         let slot_history_id = sysvar::slot_history::id();
-        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id) {
+        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id)
+            && account_overrides.get(&slot_history_id).is_none()
+        {
             let current_account = self.get_account_with_fixed_root(&slot_history_id);
             let slot_history = current_account
                 .as_ref()
@@ -4417,7 +4561,6 @@ impl Bank {
                 }
             }
         }
-        account_overrides
     }
 
     pub fn unlock_accounts(&self, batch: &mut TransactionBatch) {
@@ -4798,6 +4941,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_instruction_trace_recording: bool,
         timings: &mut ExecuteTimings,
         error_counters: &mut TransactionErrorMetrics,
         log_messages_bytes_limit: Option<usize>,
@@ -4837,12 +4981,25 @@ impl Bank {
         let pre_account_state_info =
             self.get_transaction_account_state_info(&transaction_context, tx.message());
 
-        let log_collector = if enable_log_recording {
-            match log_messages_bytes_limit {
-                None => Some(LogCollector::new_ref()),
-                Some(log_messages_bytes_limit) => Some(LogCollector::new_ref_with_limit(Some(
-                    log_messages_bytes_limit,
-                ))),
+        // The instruction trace needs the log messages even if they are not recorded
+        // otherwise.  They are collected up to the larger instruction trace bytes limit,
+        // and the regular bytes limit is only applied once the messages have been
+        // attributed to the instructions which emitted them.
+        let instruction_trace_recorder =
+            enable_instruction_trace_recording.then(InstructionTraceRecorder::new_ref);
+        let log_collector = if enable_log_recording || enable_instruction_trace_recording {
+            if enable_instruction_trace_recording {
+                Some(LogCollector::new_ref_with_limit(Some(
+                    INSTRUCTION_TRACE_LOG_MESSAGES_BYTES_LIMIT
+                        .max(log_messages_bytes_limit.unwrap_or(LOG_MESSAGES_BYTES_LIMIT)),
+                )))
+            } else {
+                match log_messages_bytes_limit {
+                    None => Some(LogCollector::new_ref()),
+                    Some(log_messages_bytes_limit) => Some(LogCollector::new_ref_with_limit(Some(
+                        log_messages_bytes_limit,
+                    ))),
+                }
             }
         } else {
             None
@@ -4866,6 +5023,7 @@ impl Bank {
             &mut transaction_context,
             self.rent_collector.rent,
             log_collector.clone(),
+            instruction_trace_recorder.clone(),
             programs_loaded_for_tx_batch,
             &mut programs_modified_by_tx,
             &mut programs_updated_only_for_global_cache,
@@ -4931,6 +5089,35 @@ impl Bank {
             None
         };
 
+        let instruction_trace = instruction_trace_recorder
+            .and_then(|recorder| Rc::try_unwrap(recorder).ok())
+            .and_then(|recorder| {
+                instruction_trace_list_from_instruction_trace(
+                    &transaction_context,
+                    &recorder.into_inner().into_records(),
+                    log_messages.as_deref().unwrap_or_default(),
+                )
+                .map_err(|err| {
+                    warn!(
+                        "Failed to extract the instruction trace of transaction {}: {err}",
+                        tx.signature()
+                    )
+                })
+                .ok()
+            });
+        let log_messages = if !enable_log_recording {
+            None
+        } else if enable_instruction_trace_recording {
+            log_messages.map(|log_messages| {
+                LogCollector::truncate_messages(
+                    log_messages,
+                    Some(log_messages_bytes_limit.unwrap_or(LOG_MESSAGES_BYTES_LIMIT)),
+                )
+            })
+        } else {
+            log_messages
+        };
+
         let ExecutionRecord {
             accounts,
             mut return_data,
@@ -4967,6 +5154,7 @@ impl Bank {
                 status,
                 log_messages,
                 inner_instructions,
+                instruction_trace,
                 durable_nonce_fee,
                 return_data,
                 executed_units,
@@ -5063,6 +5251,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_instruction_trace_recording: bool,
         timings: &mut ExecuteTimings,
         account_overrides: Option<&AccountOverrides>,
         log_messages_bytes_limit: Option<usize>,
@@ -5205,6 +5394,7 @@ impl Bank {
                         enable_cpi_recording,
                         enable_log_recording,
                         enable_return_data_recording,
+                        enable_instruction_trace_recording,
                         timings,
                         &mut error_counters,
                         log_messages_bytes_limit,
@@ -6389,6 +6579,7 @@ impl Bank {
         enable_cpi_recording: bool,
         enable_log_recording: bool,
         enable_return_data_recording: bool,
+        enable_instruction_trace_recording: bool,
         timings: &mut ExecuteTimings,
         log_messages_bytes_limit: Option<usize>,
     ) -> (TransactionResults, TransactionBalancesSet) {
@@ -6412,6 +6603,7 @@ impl Bank {
             enable_cpi_recording,
             enable_log_recording,
             enable_return_data_recording,
+            enable_instruction_trace_recording,
             timings,
             None,
             log_messages_bytes_limit,
@@ -6481,6 +6673,7 @@ impl Bank {
             false, // enable_cpi_recording
             true,  // enable_log_recording
             true,  // enable_return_data_recording
+            false, // enable_instruction_trace_recording
             &mut ExecuteTimings::default(),
             Some(1000 * 1000),
         );
@@ -6542,6 +6735,7 @@ impl Bank {
             false,
             false,
             false,
+            false,
             &mut ExecuteTimings::default(),
             None,
         )
@@ -6905,6 +7099,57 @@ impl Bank {
         )
     }
 
+    /// Returns, in pubkey order, the first `limit` accounts with a pubkey in `range` that are
+    /// owned by `program_id` and match `filter`, found through the `index_key` secondary index
+    /// if any
+    pub fn get_filtered_program_accounts_page<R, F>(
+        &self,
+        program_id: &Pubkey,
+        index_key: Option<&IndexKey>,
+        range: R,
+        filter: F,
+        limit: usize,
+    ) -> ScanResult<Vec<TransactionAccount>>
+    where
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+        F: Fn(&LoadedAccount) -> bool,
+    {
+        self.rc.accounts.load_page_by_program_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            program_id,
+            index_key,
+            range,
+            filter,
+            limit,
+        )
+    }
+
+    /// Same as `get_filtered_program_accounts_page()`, but only returns the pubkeys of the
+    /// accounts
+    pub fn get_filtered_program_account_keys_page<R, F>(
+        &self,
+        program_id: &Pubkey,
+        index_key: Option<&IndexKey>,
+        range: R,
+        filter: F,
+        limit: usize,
+    ) -> ScanResult<Vec<Pubkey>>
+    where
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+        F: Fn(&LoadedAccount) -> bool,
+    {
+        self.rc.accounts.load_page_keys_by_program_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            program_id,
+            index_key,
+            range,
+            filter,
+            limit,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
@@ -6943,6 +7188,19 @@ impl Bank {
             .get_logs_for_address(address)
     }
 
+    /// Returns the logs from transactions that mentioned any of `addresses`,
+    /// or all of them if `mentions_all` is set
+    pub fn get_transaction_logs_for_addresses(
+        &self,
+        addresses: &[Pubkey],
+        mentions_all: bool,
+    ) -> Option<Vec<TransactionLogInfo>> {
+        self.transaction_log_collector
+            .read()
+            .unwrap()
+            .get_logs_for_addresses(addresses, mentions_all)
+    }
+
     /// Returns all the accounts stored in this slot
     pub fn get_all_accounts_modified_since_parent(&self) -> Vec<TransactionAccount> {
         self.rc.accounts.load_by_program_slot(self.slot(), None)
@@ -194,6 +194,7 @@ fn new_execution_result(
             status,
             log_messages: None,
             inner_instructions: None,
+            instruction_trace: None,
             durable_nonce_fee: nonce.map(DurableNonceFee::from),
             return_data: None,
             executed_units: 0,
@@ -3280,6 +3281,7 @@ fn test_interleaving_locks() {
             false,
             false,
             false,
+            false,
             &mut ExecuteTimings::default(),
             None,
         )
@@ -6025,6 +6027,7 @@ fn test_pre_post_transaction_balances() {
             false,
             false,
             false,
+            false,
             &mut ExecuteTimings::default(),
             None,
         );
@@ -9285,6 +9288,7 @@ fn test_tx_log_order() {
             false,
             true,
             false,
+            false,
             &mut ExecuteTimings::default(),
             None,
         )
@@ -9328,6 +9332,57 @@ fn test_tx_log_order() {
     assert!(failure_log.contains(&"failed".to_string()));
 }
 
+#[test]
+fn test_instruction_trace_recording() {
+    let GenesisConfigInfo {
+        genesis_config,
+        mint_keypair,
+        ..
+    } = create_genesis_config_with_leader(
+        1_000_000_000_000_000,
+        &Pubkey::new_unique(),
+        bootstrap_validator_stake_lamports(),
+    );
+    let bank = Bank::new_for_tests(&genesis_config);
+    let blockhash = bank.last_blockhash();
+
+    let process_transfer = |enable_cpi_recording, enable_instruction_trace_recording| {
+        let tx = system_transaction::transfer(&mint_keypair, &Pubkey::new_unique(), 1, blockhash);
+        let batch = bank.prepare_batch_for_tests(vec![tx]);
+        bank.load_execute_and_commit_transactions(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            enable_cpi_recording,
+            false,
+            false,
+            enable_instruction_trace_recording,
+            &mut ExecuteTimings::default(),
+            None,
+        )
+        .0
+        .execution_results[0]
+            .details()
+            .unwrap()
+            .clone()
+    };
+
+    // cpi recording alone does not record the instruction trace
+    let details = process_transfer(true, false);
+    assert!(details.inner_instructions.is_some());
+    assert!(details.instruction_trace.is_none());
+
+    // the instruction trace carries the log messages even if they are not recorded
+    let details = process_transfer(false, true);
+    assert!(details.inner_instructions.is_none());
+    assert!(details.log_messages.is_none());
+    let instruction_trace = details.instruction_trace.unwrap();
+    assert_eq!(instruction_trace.len(), 1);
+    assert_eq!(instruction_trace[0].program_id, system_program::id());
+    assert_eq!(instruction_trace[0].stack_height, 1);
+    assert!(!instruction_trace[0].log_messages.is_empty());
+}
+
 #[test]
 fn test_tx_return_data() {
     solana_logger::setup();
@@ -9392,6 +9447,7 @@ fn test_tx_return_data() {
                 false,
                 false,
                 true,
+                false,
                 &mut ExecuteTimings::default(),
                 None,
             )
@@ -10391,6 +10447,117 @@ fn test_transaction_log_collector_get_logs_for_address() {
     );
 }
 
+#[test]
+fn test_transaction_log_collector_get_logs_for_addresses() {
+    let (address0, address1, address2) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let logs = (0..4)
+        .map(|i| TransactionLogInfo {
+            signature: Signature::new_unique(),
+            result: Ok(()),
+            is_vote: false,
+            log_messages: vec![format!("log {i}")],
+        })
+        .collect::<Vec<_>>();
+    let transaction_log_collector = TransactionLogCollector {
+        logs: logs.clone(),
+        mentioned_address_map: HashMap::from([
+            (address0, vec![0, 1, 3]),
+            (address1, vec![1, 2, 3]),
+        ]),
+    };
+
+    assert_eq!(
+        transaction_log_collector.get_logs_for_addresses(&[address0, address1], false),
+        Some(logs.clone()),
+    );
+    assert_eq!(
+        transaction_log_collector.get_logs_for_addresses(&[address1, address2], false),
+        Some(logs[1..].to_vec()),
+    );
+    assert_eq!(
+        transaction_log_collector.get_logs_for_addresses(&[address0, address1], true),
+        Some(vec![logs[1].clone(), logs[3].clone()]),
+    );
+    assert_eq!(
+        transaction_log_collector.get_logs_for_addresses(&[address0, address2], true),
+        None,
+    );
+    assert_eq!(
+        transaction_log_collector.get_logs_for_addresses(&[address2], false),
+        None,
+    );
+}
+
+#[test]
+fn test_simulate_transaction_bundle() {
+    let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(100.));
+    let bank = Bank::new_for_tests(&genesis_config);
+    bank.freeze();
+    let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+    let blockhash = bank.last_blockhash();
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let carol = Keypair::new();
+
+    // alice only exists after the first transaction, and carol only in the overrides
+    let transactions = [
+        system_transaction::transfer(
+            &mint_keypair,
+            &alice.pubkey(),
+            3 * rent_exempt_amount,
+            blockhash,
+        ),
+        system_transaction::transfer(&alice, &bob.pubkey(), rent_exempt_amount, blockhash),
+        system_transaction::transfer(&carol, &bob.pubkey(), rent_exempt_amount, blockhash),
+    ]
+    .into_iter()
+    .map(SanitizedTransaction::from_transaction_for_tests)
+    .collect::<Vec<_>>();
+    let mut account_overrides = AccountOverrides::default();
+    account_overrides.set_account(
+        &carol.pubkey(),
+        Some(AccountSharedData::new(
+            3 * rent_exempt_amount,
+            0,
+            &system_program::id(),
+        )),
+    );
+
+    let results =
+        bank.simulate_transaction_bundle(&transactions, account_overrides, &[bob.pubkey()]);
+    assert_eq!(results.len(), 3);
+    let bob_lamports = results
+        .iter()
+        .map(|result| {
+            assert_eq!(result.simulation_result.result, Ok(()));
+            assert!(result.simulation_result.inner_instructions.is_some());
+            result.post_accounts[0]
+                .as_ref()
+                .map(|account| account.lamports())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bob_lamports,
+        vec![None, Some(rent_exempt_amount), Some(2 * rent_exempt_amount)]
+    );
+
+    // nothing was committed to the bank
+    assert_eq!(bank.get_balance(&alice.pubkey()), 0);
+    assert_eq!(bank.get_balance(&bob.pubkey()), 0);
+
+    // without the state of the first transaction, alice cannot pay for the second one
+    let results =
+        bank.simulate_transaction_bundle(&transactions[1..2], AccountOverrides::default(), &[]);
+    assert_eq!(
+        results[0].simulation_result.result,
+        Err(TransactionError::AccountNotFound)
+    );
+}
+
 /// Test processing a good transaction correctly modifies the accounts data size
 #[test]
 fn test_accounts_data_size_with_good_transaction() {
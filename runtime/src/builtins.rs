@@ -6,6 +6,7 @@ use {
 };
 
 /// Transitions of built-in programs at epoch bondaries when features are activated.
+#[derive(Clone)]
 pub struct BuiltinPrototype {
     pub feature_id: Option<Pubkey>,
     pub program_id: Pubkey,
@@ -666,18 +666,27 @@ fn remap_append_vec_file(
     // due to full snapshots and incremental snapshots generated from different nodes
     let (remapped_append_vec_id, remapped_append_vec_path) = loop {
         let remapped_append_vec_id = next_append_vec_id.fetch_add(1, Ordering::AcqRel);
-        let remapped_file_name = AccountsFile::file_name(slot, remapped_append_vec_id);
+        // Keep the format of the accounts file, which is told by its name.
+        let remapped_file_name = if AccountsFile::is_tiered_storage_path(append_vec_path) {
+            AccountsFile::tiered_file_name(slot, remapped_append_vec_id)
+        } else {
+            AccountsFile::file_name(slot, remapped_append_vec_id)
+        };
         let remapped_append_vec_path = append_vec_path.parent().unwrap().join(remapped_file_name);
 
         // Break out of the loop in the following situations:
         // 1. The new ID is the same as the original ID.  This means we do not need to
         //    rename the file, since the ID is the "correct" one already.
-        // 2. There is not a file already at the new path.  This means it is safe to
-        //    rename the file to this new path.
+        // 2. There is not a file of any format already with the new ID.  This means it
+        //    is safe to rename the file to this new path.
         //    **DEVELOPER NOTE:**  Keep this check last so that it can short-circuit if
         //    possible.
         if old_append_vec_id == remapped_append_vec_id as SerializedAppendVecId
-            || std::fs::metadata(&remapped_append_vec_path).is_err()
+            || !accounts_file_exists(
+                append_vec_path.parent().unwrap(),
+                slot,
+                remapped_append_vec_id,
+            )
         {
             break (remapped_append_vec_id, remapped_append_vec_path);
         }
@@ -694,6 +703,16 @@ fn remap_append_vec_file(
     Ok((remapped_append_vec_id, remapped_append_vec_path))
 }
 
+/// Returns true if an accounts file of any format exists in `dir` for the specified slot and id.
+pub(crate) fn accounts_file_exists(dir: &Path, slot: Slot, id: AppendVecId) -> bool {
+    [
+        AccountsFile::file_name(slot, id),
+        AccountsFile::tiered_file_name(slot, id),
+    ]
+    .iter()
+    .any(|file_name| std::fs::metadata(dir.join(file_name)).is_ok())
+}
+
 pub(crate) fn remap_and_reconstruct_single_storage(
     slot: Slot,
     old_append_vec_id: SerializedAppendVecId,
@@ -860,6 +879,8 @@ where
         }
     }
 
+    let AccountsDbFields(_, _, full_snapshot_slot, _, _, _) =
+        snapshot_accounts_db_fields.full_snapshot_accounts_db_fields;
     let AccountsDbFields(
         _snapshot_storages,
         snapshot_version,
@@ -914,6 +935,11 @@ where
     accounts_db
         .write_version
         .fetch_add(snapshot_version, Ordering::Release);
+    // secondary indexes persisted at the full snapshot replace adding the accounts stored up to
+    // it, only the accounts of an incremental snapshot are still added
+    if limit_load_slot_count_from_snapshot.is_none() {
+        accounts_db.restore_secondary_indexes(full_snapshot_slot);
+    }
 
     let mut measure_notify = Measure::start("accounts_notify");
 
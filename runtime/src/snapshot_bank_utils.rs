@@ -1270,9 +1270,11 @@ mod tests {
         solana_accounts_db::{
             accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING,
             accounts_hash::{CalcAccountsHashConfig, HashStats},
+            accounts_index::{AccountIndex, IndexKey, ScanConfig},
             sorted_storages::SortedStorages,
         },
         solana_sdk::{
+            account::AccountSharedData,
             genesis_config::create_genesis_config,
             native_token::{sol_to_lamports, LAMPORTS_PER_SOL},
             signature::{Keypair, Signer},
@@ -1577,6 +1579,145 @@ mod tests {
         assert_eq!(*bank4, roundtrip_bank);
     }
 
+    /// Test that the secondary indexes persisted at a full snapshot are restored when booting
+    /// from the full snapshot and an incremental snapshot, and that the accounts of the
+    /// incremental snapshot are added to them
+    #[test]
+    fn test_restore_secondary_indexes_from_full_and_incremental_snapshots() {
+        let collector = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let key1 = Pubkey::new_unique();
+        let key2 = Pubkey::new_unique();
+        let mut account_indexes = AccountSecondaryIndexes::default();
+        account_indexes.indexes.insert(AccountIndex::ProgramId);
+        let base_working_dir = tempfile::TempDir::new().unwrap();
+        let accounts_db_config = AccountsDbConfig {
+            base_working_path: Some(base_working_dir.path().to_path_buf()),
+            persist_secondary_indexes: true,
+            ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+        };
+
+        let (genesis_config, _mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let (_tmp_dir, original_accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank0 = Arc::new(Bank::new_with_paths(
+            &genesis_config,
+            Arc::<RuntimeConfig>::default(),
+            vec![original_accounts_dir],
+            None,
+            None,
+            account_indexes.clone(),
+            AccountShrinkThreshold::default(),
+            false,
+            Some(accounts_db_config.clone()),
+            None,
+            Arc::default(),
+        ));
+        bank0.store_account(&key1, &AccountSharedData::new(1, 0, &program_id));
+        while !bank0.is_complete() {
+            bank0.register_tick(&Hash::new_unique());
+        }
+
+        let full_snapshot_slot = 1;
+        let bank1 = Arc::new(Bank::new_from_parent(bank0, &collector, full_snapshot_slot));
+        while !bank1.is_complete() {
+            bank1.register_tick(&Hash::new_unique());
+        }
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = ArchiveFormat::Tar;
+
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+            snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        let accounts_db = &bank1.rc.accounts.accounts_db;
+        accounts_db.persist_secondary_indexes(full_snapshot_slot);
+        accounts_db.seal_persisted_secondary_indexes(
+            full_snapshot_slot,
+            &bank1.get_accounts_hash().unwrap(),
+        );
+
+        let bank2 = Arc::new(Bank::new_from_parent(
+            bank1,
+            &collector,
+            full_snapshot_slot + 1,
+        ));
+        bank2.store_account(&key2, &AccountSharedData::new(1, 0, &program_id));
+        while !bank2.is_complete() {
+            bank2.register_tick(&Hash::new_unique());
+        }
+
+        let incremental_snapshot_archive_info = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank2,
+            full_snapshot_slot,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+            snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+
+        let (roundtrip_bank, _) = bank_from_snapshot_archives(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            Some(&incremental_snapshot_archive_info),
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            account_indexes,
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            Some(accounts_db_config),
+            None,
+            Arc::default(),
+        )
+        .unwrap();
+        roundtrip_bank.wait_for_initial_accounts_hash_verification_completed_for_tests();
+        assert_eq!(*bank2, roundtrip_bank);
+        assert_eq!(
+            roundtrip_bank
+                .rc
+                .accounts
+                .accounts_db
+                .secondary_indexes_restored_slot(),
+            Some(full_snapshot_slot)
+        );
+
+        let mut program_accounts: Vec<_> = roundtrip_bank
+            .get_filtered_indexed_accounts(
+                &IndexKey::ProgramId(program_id),
+                |_| true,
+                &ScanConfig::default(),
+                None,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|(pubkey, _account)| pubkey)
+            .collect();
+        program_accounts.sort_unstable();
+        let mut expected_program_accounts = vec![key1, key2];
+        expected_program_accounts.sort_unstable();
+        assert_eq!(program_accounts, expected_program_accounts);
+    }
+
     /// Test rebuilding bank from the latest snapshot archives
     #[test]
     fn test_bank_from_latest_snapshot_archives() {
@@ -24,7 +24,6 @@ use {
             self, create_accounts_run_and_snapshot_dirs, AccountStorageEntry, AtomicAppendVecId,
         },
         accounts_file::AccountsFileError,
-        append_vec::AppendVec,
         hardened_unpack::{
             streaming_unpack_snapshot, unpack_snapshot, ParallelSelector, UnpackError,
             UnpackedAppendVecMap,
@@ -739,10 +738,11 @@ pub fn archive_snapshot_package(
     for storage in snapshot_package.snapshot_storages.iter() {
         storage.flush()?;
         let storage_path = storage.get_path();
-        let output_path = staging_accounts_dir.join(AppendVec::file_name(
-            storage.slot(),
-            storage.append_vec_id(),
-        ));
+        let output_path = staging_accounts_dir.join(
+            storage
+                .accounts
+                .file_name_for(storage.slot(), storage.append_vec_id()),
+        );
 
         // `storage_path` - The file path where the AppendVec itself is located
         // `output_path` - The file path where the AppendVec will be placed in the staging directory.
@@ -1196,7 +1196,9 @@ pub fn hard_link_storages_to_snapshot(
         )?;
         // The appendvec could be recycled, so its filename may not be consistent to the slot and id.
         // Use the storage slot and id to compose a consistent file name for the hard-link file.
-        let hardlink_filename = AppendVec::file_name(storage.slot(), storage.append_vec_id());
+        let hardlink_filename = storage
+            .accounts
+            .file_name_for(storage.slot(), storage.append_vec_id());
         let hard_link_path = snapshot_hardlink_dir.join(hardlink_filename);
         fs_err::hard_link(&storage_path, &hard_link_path)
             .map_err(HardLinkStoragesToSnapshotError::HardLinkStorage)?;
@@ -1781,6 +1783,34 @@ pub fn get_highest_incremental_snapshot_archive_info(
     incremental_snapshot_archives.into_iter().next_back()
 }
 
+/// Get the path (and metadata) for the full snapshot archive with the highest slot at or before
+/// `max_slot` in a directory
+pub fn get_highest_full_snapshot_archive_info_at_or_before(
+    full_snapshot_archives_dir: impl AsRef<Path>,
+    max_slot: Slot,
+) -> Option<FullSnapshotArchiveInfo> {
+    get_full_snapshot_archives(full_snapshot_archives_dir)
+        .into_iter()
+        .filter(|full_snapshot_archive_info| full_snapshot_archive_info.slot() <= max_slot)
+        .max()
+}
+
+/// Get the path for the incremental snapshot archive with the highest slot at or before
+/// `max_slot`, for a given full snapshot slot, in a directory
+pub fn get_highest_incremental_snapshot_archive_info_at_or_before(
+    incremental_snapshot_archives_dir: impl AsRef<Path>,
+    full_snapshot_slot: Slot,
+    max_slot: Slot,
+) -> Option<IncrementalSnapshotArchiveInfo> {
+    get_incremental_snapshot_archives(incremental_snapshot_archives_dir)
+        .into_iter()
+        .filter(|incremental_snapshot_archive_info| {
+            incremental_snapshot_archive_info.base_slot() == full_snapshot_slot
+                && incremental_snapshot_archive_info.slot() <= max_slot
+        })
+        .max()
+}
+
 pub fn purge_old_snapshot_archives(
     full_snapshot_archives_dir: impl AsRef<Path>,
     incremental_snapshot_archives_dir: impl AsRef<Path>,
@@ -2791,6 +2821,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_highest_snapshot_archive_info_at_or_before() {
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        common_create_snapshot_archive_files(
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            10,
+            20,
+            30,
+            40,
+        );
+
+        let full_snapshot_slot = |max_slot| {
+            get_highest_full_snapshot_archive_info_at_or_before(
+                full_snapshot_archives_dir.path(),
+                max_slot,
+            )
+            .map(|full_snapshot_archive_info| full_snapshot_archive_info.slot())
+        };
+        assert_eq!(full_snapshot_slot(9), None);
+        assert_eq!(full_snapshot_slot(15), Some(15));
+        assert_eq!(full_snapshot_slot(100), Some(19));
+
+        let incremental_snapshot_slot = |full_snapshot_slot, max_slot| {
+            get_highest_incremental_snapshot_archive_info_at_or_before(
+                incremental_snapshot_archives_dir.path(),
+                full_snapshot_slot,
+                max_slot,
+            )
+            .map(|incremental_snapshot_archive_info| incremental_snapshot_archive_info.slot())
+        };
+        assert_eq!(incremental_snapshot_slot(15, 29), None);
+        assert_eq!(incremental_snapshot_slot(15, 35), Some(35));
+        assert_eq!(incremental_snapshot_slot(15, 100), Some(39));
+        assert_eq!(incremental_snapshot_slot(20, 100), None);
+    }
+
     fn common_test_purge_old_snapshot_archives(
         snapshot_names: &[&String],
         maximum_full_snapshot_archives_to_retain: NonZeroUsize,
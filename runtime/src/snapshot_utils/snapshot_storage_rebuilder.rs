@@ -5,8 +5,9 @@ use {
         get_io_error, snapshot_version_from_file, SnapshotError, SnapshotFrom, SnapshotVersion,
     },
     crate::serde_snapshot::{
-        self, reconstruct_single_storage, remap_and_reconstruct_single_storage,
-        snapshot_storage_lengths_from_fields, SerdeStyle, SerializedAppendVecId,
+        self, accounts_file_exists, reconstruct_single_storage,
+        remap_and_reconstruct_single_storage, snapshot_storage_lengths_from_fields, SerdeStyle,
+        SerializedAppendVecId,
     },
     crossbeam_channel::{select, unbounded, Receiver, Sender},
     dashmap::DashMap,
@@ -19,7 +20,6 @@ use {
     solana_accounts_db::{
         account_storage::{AccountStorageMap, AccountStorageReference},
         accounts_db::{AccountStorageEntry, AccountsDb, AppendVecId, AtomicAppendVecId},
-        append_vec::AppendVec,
     },
     solana_sdk::clock::Slot,
     std::{
@@ -39,7 +39,7 @@ lazy_static! {
     static ref VERSION_FILE_REGEX: Regex = Regex::new(r"^version$").unwrap();
     static ref BANK_FIELDS_FILE_REGEX: Regex = Regex::new(r"^[0-9]+(\.pre)?$").unwrap();
     static ref STORAGE_FILE_REGEX: Regex =
-        Regex::new(r"^(?P<slot>[0-9]+)\.(?P<id>[0-9]+)$").unwrap();
+        Regex::new(r"^(?P<slot>[0-9]+)\.(?P<id>[0-9]+)(\.tiered)?$").unwrap();
 }
 
 /// Convenient wrapper for snapshot version and rebuilt storages
@@ -375,10 +375,7 @@ impl SnapshotStorageRebuilder {
     ) -> AppendVecId {
         loop {
             let remapped_append_vec_id = next_append_vec_id.fetch_add(1, Ordering::AcqRel);
-            let remapped_file_name = AppendVec::file_name(slot, remapped_append_vec_id);
-            let remapped_append_vec_path = parent_folder.join(remapped_file_name);
-            if std::fs::metadata(&remapped_append_vec_path).is_err() {
-                // getting an err here means that there is no existing file here
+            if !accounts_file_exists(parent_folder, slot, remapped_append_vec_id) {
                 return remapped_append_vec_id;
             }
         }
@@ -464,8 +461,9 @@ pub(crate) fn get_slot_and_append_vec_id(filename: &str) -> (Slot, usize) {
 #[cfg(test)]
 mod tests {
     use {
-        super::*, crate::snapshot_utils::SNAPSHOT_VERSION_FILENAME,
-        solana_accounts_db::append_vec::AppendVec,
+        super::*,
+        crate::snapshot_utils::SNAPSHOT_VERSION_FILENAME,
+        solana_accounts_db::{accounts_file::AccountsFile, append_vec::AppendVec},
     };
 
     #[test]
@@ -504,6 +502,11 @@ mod tests {
             Some(SnapshotFileKind::Storage),
             get_snapshot_file_kind("1000.999")
         );
+        assert_eq!(
+            Some(SnapshotFileKind::Storage),
+            get_snapshot_file_kind("1000.999.tiered")
+        );
+        assert_eq!(None, get_snapshot_file_kind("1000.999.other"));
     }
 
     #[test]
@@ -514,5 +517,10 @@ mod tests {
             get_slot_and_append_vec_id(&AppendVec::file_name(expected_slot, expected_id));
         assert_eq!(expected_slot, slot);
         assert_eq!(expected_id, id);
+
+        let (slot, id) =
+            get_slot_and_append_vec_id(&AccountsFile::tiered_file_name(expected_slot, expected_id));
+        assert_eq!(expected_slot, slot);
+        assert_eq!(expected_id, id);
     }
 }
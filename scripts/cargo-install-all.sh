@@ -97,6 +97,7 @@ else
     solana
     solana-bench-tps
     solana-faucet
+    solana-geyser-host
     solana-gossip
     solana-install
     solana-keygen
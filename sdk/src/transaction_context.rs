@@ -477,6 +477,26 @@ pub struct TransactionReturnData {
     pub data: Vec<u8>,
 }
 
+/// Execution details of an instruction, invoked either by the transaction or
+/// by another instruction via CPI
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct InstructionTrace {
+    /// Program invoked by the instruction
+    pub program_id: Pubkey,
+    /// Invocation stack height of the instruction, starting at 1 for
+    /// transaction instructions
+    pub stack_height: u32,
+    /// Compute units consumed by the instruction, including the units consumed
+    /// by the instructions it invoked
+    pub compute_units_consumed: u64,
+    /// Return data of the transaction when the instruction completed
+    pub return_data: Option<TransactionReturnData>,
+    /// Log messages emitted by the instruction itself, excluding the messages
+    /// of the instructions it invoked.  Messages collected past the trace
+    /// bytes limit of the transaction are dropped.
+    pub log_messages: Vec<String>,
+}
+
 /// Loaded instruction shared between runtime and programs.
 ///
 /// This context is valid for the entire duration of a (possibly cross program) instruction being processed.
@@ -0,0 +1,514 @@
+// Ledger storage backend keeping the rows as files of a local directory
+
+use {
+    crate::{
+        backend::LedgerStorageBackend,
+        bigtable::{Error, Result, RowData, RowKey},
+    },
+    async_trait::async_trait,
+    log::*,
+    std::{
+        fs,
+        io::{self, ErrorKind},
+        path::{Path, PathBuf},
+    },
+};
+
+// Every row key component is stored under this many levels of shard directories
+const SHARD_LEVELS: usize = 3;
+// Each level adds 3 hex digits, so a directory holds at most 4096 entries and rows of 4096
+// consecutive slots share a directory
+const SLOT_KEY_SHARD_LENS: [usize; SHARD_LEVELS] = [7, 10, 13];
+// Each level adds 2 characters, 3364 entries at most for base58 keys
+const KEY_SHARD_LENS: [usize; SHARD_LEVELS] = [2, 4, 6];
+
+/// Keeps each row in a file under `<root>/<table>`, holding the bincode encoding of the row
+/// cells. The cells are the same compressed protobuf or bincode objects stored in BigTable.
+///
+/// Each `/` separated component of a row key is stored under three levels of shard directories
+/// named after its leading characters, so that no directory grows with the number of rows. The
+/// shards of a slot key are its first 7, 10 and 13 hex digits, and the shards of any other
+/// component are its first 2, 4 and 6 characters. For example the `tx-by-addr` row
+/// `<address>/<slot key>` is kept in the file
+/// `<root>/tx-by-addr/<address[..2]>/<address[..4]>/<address[..6]>/<address>/<slot key[..7]>/<slot key[..10]>/<slot key[..13]>/<slot key>`.
+///
+/// As shards are prefixes of their components, a depth-first walk of the sorted directories
+/// yields the row keys in lexical order, and range listings only visit the directories
+/// overlapping the range.
+#[derive(Debug, Clone)]
+pub struct FileSystemStorage {
+    root: PathBuf,
+}
+
+impl FileSystemStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn row_path(&self, table_name: &str, row_key: &str) -> Result<PathBuf> {
+        // keys are file paths relative to the table directory, and must not escape it
+        if row_key.is_empty()
+            || row_key.starts_with('/')
+            || row_key
+                .split('/')
+                .any(|component| component.is_empty() || component.starts_with('.'))
+        {
+            return Err(Error::ObjectCorrupt(format!(
+                "Invalid row key: {table_name}/{row_key}"
+            )));
+        }
+        let mut path = self.root.join(table_name);
+        for component in row_key.split('/') {
+            for shard in shards(component) {
+                path.push(shard);
+            }
+            path.push(component);
+        }
+        Ok(path)
+    }
+
+    /// Runs the file system operation `f` on the blocking thread pool
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Self) -> Result<T> + Send + 'static,
+    {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || f(storage))
+            .await
+            .map_err(io::Error::from)?
+    }
+
+    fn read_row(&self, table_name: &str, row_key: &str) -> Result<Option<RowData>> {
+        let data = match fs::read(self.row_path(table_name, row_key)?) {
+            Ok(data) => data,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        bincode::deserialize(&data).map(Some).map_err(|err| {
+            warn!("Failed to deserialize {}/{}: {}", table_name, row_key, err);
+            Error::ObjectCorrupt(format!("{table_name}/{row_key}"))
+        })
+    }
+
+    fn write_row(&self, table_name: &str, row_key: &str, row_data: &RowData) -> Result<()> {
+        let path = self.row_path(table_name, row_key)?;
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir)?;
+        // write to a temporary file first so that readers never see a partially written row
+        let temp_path = dir.join(format!(
+            ".{}.tmp",
+            path.file_name().unwrap().to_string_lossy()
+        ));
+        fs::write(&temp_path, bincode::serialize(row_data).unwrap())?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Returns the keys of the first `limit` rows of `table_name` in the `[start_at, end_at]`
+    /// range, in lexical order
+    fn list_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<&str>,
+        end_at: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RowKey>> {
+        let mut row_keys = vec![];
+        collect_row_keys(
+            &self.root.join(table_name),
+            "",
+            SHARD_LEVELS,
+            &KeyRange {
+                start_at,
+                end_at,
+                limit,
+            },
+            &mut row_keys,
+        )?;
+        Ok(row_keys)
+    }
+}
+
+/// Returns the names of the nested shard directories holding the row key `component`, each a
+/// prefix of the component
+fn shards(component: &str) -> impl Iterator<Item = &str> {
+    let is_slot_key = component.len() == 16 && component.bytes().all(|b| b.is_ascii_hexdigit());
+    let shard_lens = if is_slot_key {
+        SLOT_KEY_SHARD_LENS
+    } else {
+        KEY_SHARD_LENS
+    };
+    shard_lens
+        .into_iter()
+        .map(|shard_len| component.get(..shard_len).unwrap_or(component))
+}
+
+struct KeyRange<'a> {
+    start_at: Option<&'a str>,
+    end_at: Option<&'a str>,
+    limit: usize,
+}
+
+impl KeyRange<'_> {
+    /// Returns true if every key starting with `prefix` is before the range
+    fn is_before(&self, prefix: &str) -> bool {
+        self.start_at.map_or(false, |start_at| {
+            prefix < start_at && !start_at.starts_with(prefix)
+        })
+    }
+
+    fn contains(&self, row_key: &str) -> bool {
+        self.start_at.map_or(true, |start_at| row_key >= start_at)
+            && self.end_at.map_or(true, |end_at| row_key <= end_at)
+    }
+
+    /// Returns true if every key starting with `prefix` is after the range
+    fn is_after(&self, prefix: &str) -> bool {
+        self.end_at.map_or(false, |end_at| prefix > end_at)
+    }
+}
+
+/// Returns the sorted names of the entries of `dir`, with a `/` appended to the directories so
+/// that they sort like the row keys they hold. Temporary files of rows being written are skipped.
+fn sorted_entries(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+    let mut names = vec![];
+    for entry in entries {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            names.push(format!("{name}/"));
+        } else {
+            names.push(name);
+        }
+    }
+    names.sort_unstable();
+    Ok(names)
+}
+
+/// Walks `levels` levels of shard directories below `dir`, then the row key components they hold,
+/// and collects the keys in `range`. `prefix` is the part of the keys before the components of
+/// `dir`. Returns false once the walk is complete.
+fn collect_row_keys(
+    dir: &Path,
+    prefix: &str,
+    levels: usize,
+    range: &KeyRange,
+    row_keys: &mut Vec<RowKey>,
+) -> io::Result<bool> {
+    for name in sorted_entries(dir)? {
+        if levels > 0 {
+            let Some(shard) = name.strip_suffix('/') else {
+                continue;
+            };
+            let shard_prefix = format!("{prefix}{shard}");
+            if range.is_before(&shard_prefix) {
+                continue;
+            }
+            if range.is_after(&shard_prefix) {
+                return Ok(false);
+            }
+            if !collect_row_keys(&dir.join(shard), prefix, levels - 1, range, row_keys)? {
+                return Ok(false);
+            }
+            continue;
+        }
+        // `row_key` is the common prefix of the keys of a component directory
+        let row_key = format!("{prefix}{name}");
+        if range.is_after(&row_key) {
+            return Ok(false);
+        }
+        if let Some(component) = name.strip_suffix('/') {
+            if !range.is_before(&row_key)
+                && !collect_row_keys(
+                    &dir.join(component),
+                    &row_key,
+                    SHARD_LEVELS,
+                    range,
+                    row_keys,
+                )?
+            {
+                return Ok(false);
+            }
+        } else if range.contains(&row_key) {
+            row_keys.push(row_key);
+            if row_keys.len() >= range.limit {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[async_trait]
+impl LedgerStorageBackend for FileSystemStorage {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        if rows_limit == 0 {
+            return Ok(vec![]);
+        }
+        let limit = usize::try_from(rows_limit).unwrap_or(usize::MAX);
+        let table_name = table_name.to_string();
+        self.run_blocking(move |storage| {
+            storage.list_row_keys(&table_name, start_at.as_deref(), end_at.as_deref(), limit)
+        })
+        .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        let path = self.row_path(table_name, &row_key)?;
+        self.run_blocking(move |_storage| Ok(path.is_file())).await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let row_keys = self
+            .get_row_keys(table_name, start_at, end_at, rows_limit)
+            .await?;
+        self.get_multi_row_data(table_name, &row_keys).await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run_blocking(move |storage| {
+            let mut rows = vec![];
+            for row_key in row_keys {
+                if let Some(row_data) = storage.read_row(&table_name, &row_key)? {
+                    rows.push((row_key, row_data));
+                }
+            }
+            Ok(rows)
+        })
+        .await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |storage| {
+            storage
+                .read_row(&table_name, &row_key)?
+                .ok_or(Error::RowNotFound)
+        })
+        .await
+    }
+
+    async fn put_row_data(&self, table_name: &str, row_data: &[(&RowKey, RowData)]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_data: Vec<_> = row_data
+            .iter()
+            .map(|(row_key, row_data)| ((*row_key).clone(), row_data.clone()))
+            .collect();
+        self.run_blocking(move |storage| {
+            for (row_key, row_data) in row_data {
+                storage.write_row(&table_name, &row_key, &row_data)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let paths = row_keys
+            .iter()
+            .map(|row_key| self.row_path(table_name, row_key))
+            .collect::<Result<Vec<_>>>()?;
+        self.run_blocking(move |_storage| {
+            for path in paths {
+                if let Err(err) = fs::remove_file(path) {
+                    if err.kind() != ErrorKind::NotFound {
+                        return Err(err.into());
+                    }
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn row(value: u8) -> RowData {
+        vec![("bin".to_string(), vec![value])]
+    }
+
+    #[tokio::test]
+    async fn test_file_system_storage_rows() {
+        let dir = TempDir::new().unwrap();
+        let storage = FileSystemStorage::new(dir.path());
+
+        let keys: Vec<RowKey> = ["a/02", "a/01", "b/01", "a0"]
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        let rows: Vec<_> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key, row(i as u8)))
+            .collect();
+        storage.put_row_data("table", &rows).await.unwrap();
+
+        assert_eq!(
+            storage.get_row_keys("table", None, None, 10).await.unwrap(),
+            vec!["a/01", "a/02", "a0", "b/01"]
+        );
+        assert_eq!(
+            storage.get_row_keys("table", None, None, 2).await.unwrap(),
+            vec!["a/01", "a/02"]
+        );
+        assert!(storage
+            .get_row_keys("table", None, None, 0)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            storage
+                .get_row_data("table", Some("a/02".to_string()), Some("b".to_string()), 10)
+                .await
+                .unwrap(),
+            vec![("a/02".to_string(), row(0)), ("a0".to_string(), row(3))]
+        );
+        assert_eq!(
+            storage
+                .get_row_data(
+                    "table",
+                    Some("a/00".to_string()),
+                    Some("a/99".to_string()),
+                    10
+                )
+                .await
+                .unwrap(),
+            vec![("a/01".to_string(), row(1)), ("a/02".to_string(), row(0))]
+        );
+        assert!(storage
+            .get_row_keys("missing", None, None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(storage
+            .row_key_exists("table", "b/01".to_string())
+            .await
+            .unwrap());
+        assert_eq!(
+            storage
+                .get_single_row_data("table", "b/01".to_string())
+                .await
+                .unwrap(),
+            row(2)
+        );
+        assert_eq!(
+            storage
+                .get_multi_row_data("table", &["a0".to_string(), "c".to_string()])
+                .await
+                .unwrap(),
+            vec![("a0".to_string(), row(3))]
+        );
+
+        storage
+            .put_row_data("table", &[(&keys[2], row(4))])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .get_single_row_data("table", "b/01".to_string())
+                .await
+                .unwrap(),
+            row(4)
+        );
+
+        storage
+            .delete_rows("table", &["b/01".to_string(), "c".to_string()])
+            .await
+            .unwrap();
+        assert!(!storage
+            .row_key_exists("table", "b/01".to_string())
+            .await
+            .unwrap());
+        assert!(matches!(
+            storage
+                .get_single_row_data("table", "b/01".to_string())
+                .await,
+            Err(Error::RowNotFound)
+        ));
+
+        assert!(storage
+            .get_single_row_data("table", "../table/a0".to_string())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_system_storage_shards() {
+        let dir = TempDir::new().unwrap();
+        let storage = FileSystemStorage::new(dir.path());
+
+        let keys: Vec<RowKey> = [0x0ffe, 0x0fff, 0x1000, 0x1001]
+            .iter()
+            .map(|slot: &u64| format!("address/{slot:016x}"))
+            .collect();
+        let rows: Vec<_> = keys.iter().map(|key| (key, row(0))).collect();
+        storage.put_row_data("table", &rows).await.unwrap();
+        assert!(dir
+            .path()
+            .join("table/ad/addr/addres/address/0000000/0000000000/0000000000001/0000000000001000")
+            .is_file());
+        assert!(dir
+            .path()
+            .join("table/ad/addr/addres/address/0000000/0000000000/0000000000000/0000000000000fff")
+            .is_file());
+
+        // the listing crosses the shards in order and stops at the limit
+        assert_eq!(
+            storage
+                .get_row_keys("table", Some(keys[1].clone()), None, 2)
+                .await
+                .unwrap(),
+            keys[1..3].to_vec()
+        );
+        assert_eq!(
+            storage
+                .get_row_keys(
+                    "table",
+                    Some("address/".to_string()),
+                    Some(keys[2].clone()),
+                    10
+                )
+                .await
+                .unwrap(),
+            keys[..3].to_vec()
+        );
+        assert!(storage
+            .get_row_keys("table", Some("b".to_string()), None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
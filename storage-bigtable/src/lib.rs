@@ -1,7 +1,6 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
-    crate::bigtable::RowKey,
     log::*,
     serde::{Deserialize, Serialize},
     solana_metrics::datapoint_info,
@@ -25,6 +24,7 @@ use {
     std::{
         collections::{HashMap, HashSet},
         convert::TryInto,
+        path::PathBuf,
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc,
@@ -42,10 +42,18 @@ extern crate solana_metrics;
 extern crate serde_derive;
 
 mod access_token;
+mod backend;
 mod bigtable;
 mod compression;
+mod file_system;
 mod root_ca_certificate;
 
+pub use crate::{
+    backend::LedgerStorageBackend,
+    bigtable::{CellName, CellValue, Error as BackendError, RowData, RowKey},
+    file_system::FileSystemStorage,
+};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
@@ -390,6 +398,8 @@ pub struct LedgerStorageConfig {
     pub credential_type: CredentialType,
     pub instance_name: String,
     pub app_profile_id: String,
+    /// Keep the ledger data as files under this directory instead of a BigTable instance
+    pub file_system_path: Option<PathBuf>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -400,6 +410,7 @@ impl Default for LedgerStorageConfig {
             credential_type: CredentialType::Filepath(None),
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
+            file_system_path: None,
         }
     }
 }
@@ -434,7 +445,7 @@ impl LedgerStorageStats {
 
 #[derive(Clone)]
 pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
+    backend: Arc<dyn LedgerStorageBackend>,
     stats: Arc<LedgerStorageStats>,
 }
 
@@ -459,27 +470,30 @@ impl LedgerStorage {
         endpoint: &str,
         timeout: Option<Duration>,
     ) -> Result<Self> {
-        let stats = Arc::new(LedgerStorageStats::default());
-        Ok(Self {
-            connection: bigtable::BigTableConnection::new_for_emulator(
-                instance_name,
-                app_profile_id,
-                endpoint,
-                timeout,
-            )?,
-            stats,
-        })
+        let connection = bigtable::BigTableConnection::new_for_emulator(
+            instance_name,
+            app_profile_id,
+            endpoint,
+            timeout,
+        )?;
+        Ok(Self::new_with_backend(Arc::new(connection)))
     }
 
     pub async fn new_with_config(config: LedgerStorageConfig) -> Result<Self> {
-        let stats = Arc::new(LedgerStorageStats::default());
         let LedgerStorageConfig {
             read_only,
             timeout,
             instance_name,
             app_profile_id,
             credential_type,
+            file_system_path,
         } = config;
+        if let Some(file_system_path) = file_system_path {
+            info!("Using ledger storage at {:?}", file_system_path);
+            return Ok(Self::new_with_backend(Arc::new(FileSystemStorage::new(
+                file_system_path,
+            ))));
+        }
         let connection = bigtable::BigTableConnection::new(
             instance_name.as_str(),
             app_profile_id.as_str(),
@@ -488,7 +502,14 @@ impl LedgerStorage {
             credential_type,
         )
         .await?;
-        Ok(Self { stats, connection })
+        Ok(Self::new_with_backend(Arc::new(connection)))
+    }
+
+    pub fn new_with_backend(backend: Arc<dyn LedgerStorageBackend>) -> Self {
+        Self {
+            backend,
+            stats: Arc::new(LedgerStorageStats::default()),
+        }
     }
 
     pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
@@ -503,8 +524,7 @@ impl LedgerStorage {
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         trace!("LedgerStorage::get_first_available_block request received");
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable.get_row_keys("blocks", None, None, 1).await?;
+        let blocks = self.backend.get_row_keys("blocks", None, None, 1).await?;
         if blocks.is_empty() {
             return Ok(None);
         }
@@ -522,8 +542,8 @@ impl LedgerStorage {
             limit
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let blocks = bigtable
+        let blocks = self
+            .backend
             .get_row_keys(
                 "blocks",
                 Some(slot_to_blocks_key(start_slot)),
@@ -544,9 +564,9 @@ impl LedgerStorage {
             slots
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
         let row_keys = slots.iter().copied().map(slot_to_blocks_key);
-        let data = bigtable
+        let data = self
+            .backend
             .get_protobuf_or_bincode_cells("blocks", row_keys)
             .await?
             .filter_map(
@@ -571,8 +591,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let block_cell_data = bigtable
+        let block_cell_data = self
+            .backend
             .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 "blocks",
                 slot_to_blocks_key(slot),
@@ -597,9 +617,8 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-
-        let block_exists = bigtable
+        let block_exists = self
+            .backend
             .row_key_exists("blocks", slot_to_blocks_key(slot))
             .await?;
 
@@ -612,8 +631,8 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let transaction_info = bigtable
+        let transaction_info = self
+            .backend
             .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -633,11 +652,10 @@ impl LedgerStorage {
             signatures
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-
         // Fetch transactions info
         let keys = signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>();
-        let cells = bigtable
+        let cells = self
+            .backend
             .get_bincode_cells::<TransactionInfo>("tx", &keys)
             .await?;
 
@@ -695,10 +713,9 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-
         // Figure out which block the transaction is located in
-        let TransactionInfo { slot, index, .. } = bigtable
+        let TransactionInfo { slot, index, .. } = self
+            .backend
             .get_bincode_cell("tx", signature.to_string())
             .await
             .map_err(|err| match err {
@@ -755,14 +772,14 @@ impl LedgerStorage {
             address
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
         let address_prefix = format!("{address}/");
 
         // Figure out where to start listing from based on `before_signature`
         let (first_slot, before_transaction_index) = match before_signature {
             None => (Slot::MAX, 0),
             Some(before_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
+                    .backend
                     .get_bincode_cell("tx", before_signature.to_string())
                     .await?;
 
@@ -774,7 +791,8 @@ impl LedgerStorage {
         let (last_slot, until_transaction_index) = match until_signature {
             None => (0, u32::MAX),
             Some(until_signature) => {
-                let TransactionInfo { slot, index, .. } = bigtable
+                let TransactionInfo { slot, index, .. } = self
+                    .backend
                     .get_bincode_cell("tx", until_signature.to_string())
                     .await?;
 
@@ -784,7 +802,7 @@ impl LedgerStorage {
 
         let mut infos = vec![];
 
-        let starting_slot_tx_len = bigtable
+        let starting_slot_tx_len = self.backend
             .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
                 "tx-by-addr",
                 format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot)),
@@ -800,7 +818,8 @@ impl LedgerStorage {
 
         // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
         // number that might be flitered out
-        let tx_by_addr_data = bigtable
+        let tx_by_addr_data = self
+            .backend
             .get_row_data(
                 "tx-by-addr",
                 Some(format!(
@@ -937,21 +956,23 @@ impl LedgerStorage {
         let mut tasks = vec![];
 
         if !tx_cells.is_empty() {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
+                backend
+                    .put_bincode_cells::<TransactionInfo>("tx", &tx_cells)
                     .await
             }));
         }
 
         if !tx_by_addr_cells.is_empty() {
-            let conn = self.connection.clone();
+            let backend = self.backend.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
-                    "tx-by-addr",
-                    &tx_by_addr_cells,
-                )
-                .await
+                backend
+                    .put_protobuf_cells::<tx_by_addr::TransactionByAddr>(
+                        "tx-by-addr",
+                        &tx_by_addr_cells,
+                    )
+                    .await
             }));
         }
 
@@ -988,8 +1009,8 @@ impl LedgerStorage {
         // `get_confirmed_block()` and `get_confirmed_blocks()`
         let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
         bytes_written += self
-            .connection
-            .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
+            .backend
+            .put_protobuf_cells::<generated::ConfirmedBlock>("blocks", &blocks_cells)
             .await?;
         datapoint_info!(
             "storage-bigtable-upload-block",
@@ -1051,8 +1072,8 @@ impl LedgerStorage {
         let tx_deletion_rows = if !expected_tx_infos.is_empty() {
             let signatures = expected_tx_infos.keys().cloned().collect::<Vec<_>>();
             let fetched_tx_infos: HashMap<String, std::result::Result<UploadedTransaction, _>> =
-                self.connection
-                    .get_bincode_cells_with_retry::<TransactionInfo>("tx", &signatures)
+                self.backend
+                    .get_bincode_cells::<TransactionInfo>("tx", &signatures)
                     .await?
                     .into_iter()
                     .map(|(signature, tx_info_res)| (signature, tx_info_res.map(Into::into)))
@@ -1090,19 +1111,17 @@ impl LedgerStorage {
 
         if !dry_run {
             if !address_slot_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx-by-addr", &address_slot_rows)
+                self.backend
+                    .delete_rows("tx-by-addr", &address_slot_rows)
                     .await?;
             }
 
             if !tx_deletion_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx", &tx_deletion_rows)
-                    .await?;
+                self.backend.delete_rows("tx", &tx_deletion_rows).await?;
             }
 
-            self.connection
-                .delete_rows_with_retry("blocks", &[slot_to_blocks_key(slot)])
+            self.backend
+                .delete_rows("blocks", &[slot_to_blocks_key(slot)])
                 .await?;
         }
 
@@ -1120,11 +1139,115 @@ impl LedgerStorage {
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, signature::Keypair, system_transaction},
+    };
 
     #[test]
     fn test_slot_to_key() {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    #[tokio::test]
+    async fn test_file_system_ledger_storage() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = LedgerStorage::new_with_config(LedgerStorageConfig {
+            read_only: false,
+            file_system_path: Some(dir.path().to_path_buf()),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+        .unwrap();
+        assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+
+        let from = Keypair::new();
+        let recipient = solana_sdk::pubkey::new_rand();
+        let new_block = |slot: Slot| {
+            let transaction =
+                system_transaction::transfer(&from, &recipient, slot, Hash::new_unique());
+            VersionedConfirmedBlock {
+                previous_blockhash: Hash::default().to_string(),
+                blockhash: Hash::new_unique().to_string(),
+                parent_slot: slot - 1,
+                transactions: vec![VersionedTransactionWithStatusMeta {
+                    transaction: VersionedTransaction::from(transaction),
+                    meta: TransactionStatusMeta {
+                        pre_balances: vec![43, 0, 1],
+                        post_balances: vec![0, 42, 1],
+                        ..TransactionStatusMeta::default()
+                    },
+                }],
+                rewards: vec![],
+                block_time: Some(slot as UnixTimestamp),
+                block_height: Some(slot),
+            }
+        };
+        let blocks: Vec<_> = [3, 5, 9]
+            .into_iter()
+            .map(|slot| (slot, new_block(slot)))
+            .collect();
+        for (slot, block) in &blocks {
+            storage
+                .upload_confirmed_block(*slot, block.clone())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(3));
+        assert_eq!(
+            storage.get_confirmed_blocks(4, 10).await.unwrap(),
+            vec![5, 9]
+        );
+        assert!(storage.confirmed_block_exists(5).await.unwrap());
+        assert!(!storage.confirmed_block_exists(4).await.unwrap());
+        assert_eq!(
+            storage.get_confirmed_block(5).await.unwrap(),
+            ConfirmedBlock::from(blocks[1].1.clone())
+        );
+        assert!(matches!(
+            storage.get_confirmed_block(4).await,
+            Err(Error::BlockNotFound(4))
+        ));
+
+        let signatures: Vec<_> = blocks
+            .iter()
+            .map(|(_, block)| block.transactions[0].transaction.signatures[0])
+            .collect();
+        let transaction = storage
+            .get_confirmed_transaction(&signatures[2])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, 9);
+        assert_eq!(transaction.block_time, Some(9));
+
+        let history = storage
+            .get_confirmed_signatures_for_address(&recipient, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            history
+                .iter()
+                .map(|(status, _)| (status.slot, status.signature))
+                .collect::<Vec<_>>(),
+            vec![(9, signatures[2]), (5, signatures[1]), (3, signatures[0])]
+        );
+        let history = storage
+            .get_confirmed_signatures_for_address(&recipient, Some(&signatures[2]), None, 1)
+            .await
+            .unwrap();
+        assert_eq!(history[0].0.signature, signatures[1]);
+
+        storage.delete_confirmed_block(5, false).await.unwrap();
+        assert_eq!(
+            storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![3, 9]
+        );
+        assert!(matches!(
+            storage.get_signature_status(&signatures[1]).await,
+            Err(Error::SignatureNotFound)
+        ));
+    }
 }
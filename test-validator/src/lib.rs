@@ -33,8 +33,9 @@ use {
     solana_rpc::{rpc::JsonRpcConfig, rpc_pubsub_service::PubSubConfig},
     solana_rpc_client::{nonblocking, rpc_client::RpcClient},
     solana_runtime::{
-        bank_forks::BankForks, genesis_utils::create_genesis_config_with_leader_ex,
-        runtime_config::RuntimeConfig, snapshot_config::SnapshotConfig,
+        bank_forks::BankForks, builtins::BuiltinPrototype,
+        genesis_utils::create_genesis_config_with_leader_ex, runtime_config::RuntimeConfig,
+        snapshot_config::SnapshotConfig,
     },
     solana_sdk::{
         account::{Account, AccountSharedData},
@@ -47,6 +48,7 @@ use {
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         hash::Hash,
         instruction::{AccountMeta, Instruction},
+        loader_v4::{self, LoaderV4Status},
         message::Message,
         native_token::sol_to_lamports,
         pubkey::Pubkey,
@@ -94,6 +96,14 @@ pub struct UpgradeableProgramInfo {
     pub program_path: PathBuf,
 }
 
+#[derive(Clone)]
+pub struct LoaderV4ProgramInfo {
+    pub program_id: Pubkey,
+    pub authority: Pubkey,
+    pub status: LoaderV4Status,
+    pub program_path: PathBuf,
+}
+
 #[derive(Debug)]
 pub struct TestValidatorNodeConfig {
     gossip_addr: SocketAddr,
@@ -130,6 +140,7 @@ pub struct TestValidatorGenesis {
     #[allow(deprecated)]
     programs: Vec<ProgramInfo>,
     upgradeable_programs: Vec<UpgradeableProgramInfo>,
+    loader_v4_programs: Vec<LoaderV4ProgramInfo>,
     ticks_per_slot: Option<u64>,
     epoch_schedule: Option<EpochSchedule>,
     node_config: TestValidatorNodeConfig,
@@ -164,6 +175,7 @@ impl Default for TestValidatorGenesis {
             #[allow(deprecated)]
             programs: Vec::<ProgramInfo>::default(),
             upgradeable_programs: Vec::<UpgradeableProgramInfo>::default(),
+            loader_v4_programs: Vec::<LoaderV4ProgramInfo>::default(),
             ticks_per_slot: Option::<u64>::default(),
             epoch_schedule: Option::<EpochSchedule>::default(),
             node_config: TestValidatorNodeConfig::default(),
@@ -525,6 +537,17 @@ impl TestValidatorGenesis {
         self
     }
 
+    /// Add a list of loader-v4 programs to the test environment.
+    pub fn add_loader_v4_programs_with_path(
+        &mut self,
+        programs: &[LoaderV4ProgramInfo],
+    ) -> &mut Self {
+        for program in programs {
+            self.loader_v4_programs.push(program.clone());
+        }
+        self
+    }
+
     /// Start a test validator with the address of the mint account that will receive tokens
     /// created at genesis.
     ///
@@ -767,6 +790,18 @@ impl TestValidator {
                 }),
             );
         }
+        for loader_v4_program in &config.loader_v4_programs {
+            let data = solana_program_test::read_file(&loader_v4_program.program_path);
+            accounts.insert(
+                loader_v4_program.program_id,
+                solana_program_test::programs::loader_v4_program_account(
+                    &Rent::default(),
+                    &loader_v4_program.authority,
+                    loader_v4_program.status,
+                    &data,
+                ),
+            );
+        }
 
         let mut genesis_config = create_genesis_config_with_leader_ex(
             mint_lamports,
@@ -964,6 +999,14 @@ impl TestValidator {
             accounts_db_config,
             runtime_config,
             account_indexes: config.rpc_config.account_indexes.clone(),
+            // loader-v4 is not a runtime builtin yet, but is needed to deploy and invoke
+            // loader-v4 programs on the test validator
+            additional_builtins: Some(vec![BuiltinPrototype {
+                feature_id: None,
+                program_id: loader_v4::id(),
+                name: "loader_v4",
+                entrypoint: solana_loader_v4_program::process_instruction,
+            }]),
             ..ValidatorConfig::default_for_test()
         };
         if let Some(ref tower_storage) = config.tower_storage {
@@ -1,6 +1,9 @@
 #![allow(clippy::arithmetic_side_effects)]
 
-pub use {crate::extract_memos::extract_and_fmt_memos, solana_sdk::reward_type::RewardType};
+pub use {
+    crate::extract_memos::extract_and_fmt_memos,
+    solana_sdk::{reward_type::RewardType, transaction_context::InstructionTrace},
+};
 use {
     crate::{
         option_serializer::OptionSerializer,
@@ -239,7 +242,7 @@ pub struct UiInnerInstructions {
 }
 
 impl UiInnerInstructions {
-    fn parse(inner_instructions: InnerInstructions, account_keys: &AccountKeys) -> Self {
+    pub fn parse(inner_instructions: InnerInstructions, account_keys: &AccountKeys) -> Self {
         Self {
             index: inner_instructions.index,
             instructions: inner_instructions
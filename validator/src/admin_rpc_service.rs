@@ -12,7 +12,11 @@ use {
     solana_accounts_db::accounts_index::AccountIndex,
     solana_core::{
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
-        consensus::{tower_storage::TowerStorage, Tower},
+        consensus::{
+            fork_choice_snapshot::{ForkChoiceSnapshot, ForkChoiceSnapshotRequest},
+            tower_storage::TowerStorage,
+            Tower,
+        },
         validator::ValidatorStartProgress,
     },
     solana_geyser_plugin_manager::GeyserPluginManagerRequest,
@@ -201,6 +205,21 @@ pub trait AdminRpc {
         require_tower: bool,
     ) -> Result<()>;
 
+    #[rpc(meta, name = "exportTowerAndSetIdentity")]
+    fn export_tower_and_set_identity(
+        &self,
+        meta: Self::Metadata,
+        keypair_file: String,
+    ) -> Result<Vec<u8>>;
+
+    #[rpc(meta, name = "importTowerAndSetIdentity")]
+    fn import_tower_and_set_identity(
+        &self,
+        meta: Self::Metadata,
+        keypair_file: String,
+        exported_tower: Vec<u8>,
+    ) -> Result<()>;
+
     #[rpc(meta, name = "setStakedNodesOverrides")]
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()>;
 
@@ -213,6 +232,12 @@ pub trait AdminRpc {
     #[rpc(meta, name = "setRepairWhitelist")]
     fn set_repair_whitelist(&self, meta: Self::Metadata, whitelist: Vec<Pubkey>) -> Result<()>;
 
+    #[rpc(meta, name = "dumpCrds")]
+    fn dump_crds(&self, meta: Self::Metadata, path: String) -> Result<usize>;
+
+    #[rpc(meta, name = "forkChoiceSnapshot")]
+    fn fork_choice_snapshot(&self, meta: Self::Metadata) -> BoxFuture<Result<ForkChoiceSnapshot>>;
+
     #[rpc(meta, name = "getSecondaryIndexKeySize")]
     fn get_secondary_index_key_size(
         &self,
@@ -465,6 +490,99 @@ impl AdminRpc for AdminRpcImpl {
         AdminRpcImpl::set_identity_keypair(meta, identity_keypair, require_tower)
     }
 
+    fn export_tower_and_set_identity(
+        &self,
+        meta: Self::Metadata,
+        keypair_file: String,
+    ) -> Result<Vec<u8>> {
+        debug!("export_tower_and_set_identity request received");
+
+        let identity_keypair = read_keypair_file(&keypair_file).map_err(|err| {
+            jsonrpc_core::error::Error::invalid_params(format!(
+                "Failed to read identity keypair from {keypair_file}: {err}"
+            ))
+        })?;
+
+        meta.with_post_init(|post_init| {
+            let previous_identity_keypair = post_init.cluster_info.keypair().clone();
+            if previous_identity_keypair.pubkey() == identity_keypair.pubkey() {
+                return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                    "The identity is already {}",
+                    identity_keypair.pubkey()
+                )));
+            }
+
+            // The votes of the previous identity stop once the identity changed, leaving the
+            // tower saved for its last vote in the tower storage
+            solana_metrics::set_host_id(identity_keypair.pubkey().to_string());
+            post_init
+                .cluster_info
+                .set_keypair(Arc::new(identity_keypair));
+            warn!(
+                "Identity set to {}, stopped voting as {}",
+                post_init.cluster_info.id(),
+                previous_identity_keypair.pubkey()
+            );
+
+            Tower::restore(
+                meta.tower_storage.as_ref(),
+                &previous_identity_keypair.pubkey(),
+            )
+            .and_then(|tower| tower.export(&previous_identity_keypair))
+            .map_err(|err| {
+                jsonrpc_core::error::Error::invalid_params(format!(
+                    "Unable to export tower for identity {}: {}",
+                    previous_identity_keypair.pubkey(),
+                    err
+                ))
+            })
+        })
+    }
+
+    fn import_tower_and_set_identity(
+        &self,
+        meta: Self::Metadata,
+        keypair_file: String,
+        exported_tower: Vec<u8>,
+    ) -> Result<()> {
+        debug!("import_tower_and_set_identity request received");
+
+        let identity_keypair = read_keypair_file(&keypair_file).map_err(|err| {
+            jsonrpc_core::error::Error::invalid_params(format!(
+                "Failed to read identity keypair from {keypair_file}: {err}"
+            ))
+        })?;
+        let identity = identity_keypair.pubkey();
+
+        meta.with_post_init(|post_init| {
+            let tower = Tower::import(&exported_tower, &identity).map_err(|err| {
+                jsonrpc_core::error::Error::invalid_params(format!(
+                    "Invalid tower for identity {identity}: {err}"
+                ))
+            })?;
+            match Tower::restore(meta.tower_storage.as_ref(), &identity) {
+                Ok(local_tower) => tower
+                    .check_handover(&local_tower, &post_init.bank_forks.read().unwrap())
+                    .map_err(|err| jsonrpc_core::error::Error::invalid_params(err.to_string()))?,
+                Err(err) if err.is_file_missing() => (),
+                Err(err) => {
+                    return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                        "Unable to load tower file for identity {identity}: {err}"
+                    )))
+                }
+            }
+            tower
+                .save(meta.tower_storage.as_ref(), &identity_keypair)
+                .map_err(|err| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "Unable to save tower for identity {identity}: {err}"
+                    ))
+                })
+        })?;
+
+        AdminRpcImpl::set_identity_keypair(meta, identity_keypair, true)
+    }
+
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()> {
         let loaded_config = load_staked_nodes_overrides(&path)
             .map_err(|err| {
@@ -516,6 +634,43 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn dump_crds(&self, meta: Self::Metadata, path: String) -> Result<usize> {
+        debug!("dump_crds request received");
+
+        let crds_dump = meta.with_post_init(|post_init| Ok(post_init.cluster_info.crds_dump()))?;
+        crds_dump.write(Path::new(&path)).map_err(|err| {
+            error!("Failed to dump gossip values to {}: {}", path, err);
+            jsonrpc_core::error::Error::internal_error()
+        })?;
+        info!(
+            "Dumped {} gossip values to {}",
+            crds_dump.entries.len(),
+            path
+        );
+        Ok(crds_dump.entries.len())
+    }
+
+    fn fork_choice_snapshot(&self, meta: Self::Metadata) -> BoxFuture<Result<ForkChoiceSnapshot>> {
+        debug!("fork_choice_snapshot request received");
+
+        Box::pin(async move {
+            let (response_sender, response_receiver) = oneshot_channel();
+
+            // The replay stage answers the request between two iterations of its loop
+            meta.with_post_init(|post_init| {
+                post_init
+                    .fork_choice_snapshot_sender
+                    .send(ForkChoiceSnapshotRequest { response_sender })
+                    .map_err(|_| jsonrpc_core::error::Error::internal_error())
+            })?;
+            response_receiver.await.map_err(|_| jsonrpc_core::Error {
+                code: ErrorCode::InternalError,
+                message: "Replay stage exited".to_string(),
+                data: None,
+            })
+        })
+    }
+
     fn get_secondary_index_key_size(
         &self,
         meta: Self::Metadata,
@@ -554,9 +709,10 @@ impl AdminRpc for AdminRpcImpl {
                 .indexes
                 .iter()
                 .filter_map(|index| {
+                    let rpc_account_index = rpc_account_index_from_account_index(index)?;
                     accounts_index
                         .get_index_key_size(index, &index_key)
-                        .map(|size| (rpc_account_index_from_account_index(index), size))
+                        .map(|size| (rpc_account_index, size))
                 })
                 .collect::<HashMap<_, _>>();
 
@@ -692,11 +848,13 @@ impl AdminRpcImpl {
     }
 }
 
-fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> RpcAccountIndex {
+// Program offset indexes are configured per program and offset, so have no `RpcAccountIndex`
+fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> Option<RpcAccountIndex> {
     match account_index {
-        AccountIndex::ProgramId => RpcAccountIndex::ProgramId,
-        AccountIndex::SplTokenOwner => RpcAccountIndex::SplTokenOwner,
-        AccountIndex::SplTokenMint => RpcAccountIndex::SplTokenMint,
+        AccountIndex::ProgramId => Some(RpcAccountIndex::ProgramId),
+        AccountIndex::SplTokenOwner => Some(RpcAccountIndex::SplTokenOwner),
+        AccountIndex::SplTokenMint => Some(RpcAccountIndex::SplTokenMint),
+        AccountIndex::ProgramOffset { .. } => None,
     }
 }
 
@@ -818,6 +976,7 @@ pub fn load_staked_nodes_overrides(
 mod tests {
     use {
         super::*,
+        crossbeam_channel::unbounded,
         serde_json::Value,
         solana_accounts_db::{accounts_index::AccountSecondaryIndexes, inline_spl_token},
         solana_core::consensus::tower_storage::NullTowerStorage,
@@ -888,6 +1047,7 @@ mod tests {
                     bank_forks: bank_forks.clone(),
                     vote_account,
                     repair_whitelist,
+                    fork_choice_snapshot_sender: unbounded().0,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
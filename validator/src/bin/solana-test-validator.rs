@@ -20,6 +20,7 @@ use {
         clock::Slot,
         epoch_schedule::EpochSchedule,
         feature_set,
+        loader_v4::LoaderV4Status,
         native_token::sol_to_lamports,
         pubkey::Pubkey,
         rent::Rent,
@@ -248,6 +249,33 @@ fn main() {
         }
     }
 
+    let mut loader_v4_programs_to_load = vec![];
+    if let Some(values) = matches.values_of("bpf_program_v4") {
+        for (address, program, authority) in values.into_iter().tuples::<(&str, &str, &str)>() {
+            let address = parse_address(address, "address");
+            let program_path = parse_program_path(program);
+            let (authority, status) = if authority == "none" {
+                (Pubkey::default(), LoaderV4Status::Finalized)
+            } else {
+                let authority = authority
+                    .parse::<Pubkey>()
+                    .or_else(|_| read_keypair_file(authority).map(|keypair| keypair.pubkey()))
+                    .unwrap_or_else(|err| {
+                        println!("Error: invalid authority {authority}: {err}");
+                        exit(1);
+                    });
+                (authority, LoaderV4Status::Deployed)
+            };
+
+            loader_v4_programs_to_load.push(LoaderV4ProgramInfo {
+                program_id: address,
+                authority,
+                status,
+                program_path,
+            });
+        }
+    }
+
     let mut accounts_to_load = vec![];
     if let Some(values) = matches.values_of("account") {
         for (address, filename) in values.into_iter().tuples() {
@@ -356,6 +384,7 @@ fn main() {
     if TestValidatorGenesis::ledger_exists(&ledger_path) {
         for (name, long) in &[
             ("bpf_program", "--bpf-program"),
+            ("bpf_program_v4", "--bpf-program-v4"),
             ("clone_account", "--clone"),
             ("account", "--account"),
             ("mint_address", "--mint"),
@@ -430,6 +459,7 @@ fn main() {
                 String
             ),
             timeout: None,
+            file_system_path: None,
         })
     } else {
         None
@@ -449,6 +479,7 @@ fn main() {
         })
         .rpc_port(rpc_port)
         .add_upgradeable_programs_with_path(&upgradeable_programs_to_load)
+        .add_loader_v4_programs_with_path(&loader_v4_programs_to_load)
         .add_accounts_from_json_files(&accounts_to_load)
         .unwrap_or_else(|e| {
             println!("Error: add_accounts_from_json_files failed: {e}");
@@ -7,6 +7,7 @@ use {
         accounts_db::{
             DEFAULT_ACCOUNTS_SHRINK_OPTIMIZE_TOTAL_SPACE, DEFAULT_ACCOUNTS_SHRINK_RATIO,
         },
+        accounts_file::AccountsFileProvider,
         hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
     },
     solana_clap_utils::{
@@ -40,8 +41,8 @@ use {
         },
     },
     solana_sdk::{
-        clock::Slot, epoch_schedule::MINIMUM_SLOTS_PER_EPOCH, hash::Hash, quic::QUIC_PORT_OFFSET,
-        rpc_port,
+        clock::Slot, epoch_schedule::MINIMUM_SLOTS_PER_EPOCH, hash::Hash, pubkey::Pubkey,
+        quic::QUIC_PORT_OFFSET, rpc_port,
     },
     solana_send_transaction_service::send_transaction_service::{
         self, MAX_BATCH_SEND_RATE_MS, MAX_TRANSACTION_BATCH_SIZE,
@@ -325,7 +326,9 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .long("tower")
                 .value_name("DIR")
                 .takes_value(true)
-                .help("Use DIR as file tower storage location [default: --ledger value]"),
+                .multiple(true)
+                .help("Use DIR as file tower storage location [default: --ledger value]. \
+                       May be specified multiple times to store the tower in each DIR"),
         )
         .arg(
             Arg::with_name("tower_storage")
@@ -333,7 +336,20 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .possible_values(&["file", "etcd"])
                 .default_value(&default_args.tower_storage)
                 .takes_value(true)
-                .help("Where to store the tower"),
+                .multiple(true)
+                .help("Where to store the tower. May be specified multiple times to store \
+                       the tower in each of the storages, the newest tower found in them \
+                       being restored at startup"),
+        )
+        .arg(
+            Arg::with_name("tower_write_quorum")
+                .long("tower-write-quorum")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .help("Number of tower storages the tower must be stored in before voting, \
+                       when storing the tower in multiple storages \
+                       [default: all the storages]"),
         )
         .arg(
             Arg::with_name("etcd_endpoint")
@@ -880,6 +896,14 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_bigtable_app_profile_id)
                 .help("Bigtable application profile id to use in requests")
         )
+        .arg(
+            Arg::with_name("rpc_bigtable_file_system_path")
+                .long("rpc-bigtable-file-system-path")
+                .takes_value(true)
+                .value_name("DIR")
+                .help("Keep the historical ledger data as files under this directory \
+                       instead of a Bigtable instance")
+        )
         .arg(
             Arg::with_name("rpc_pubsub_worker_threads")
                 .long("rpc-pubsub-worker-threads")
@@ -1054,6 +1078,54 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_max_request_body_size)
                 .help("The maximum request body size accepted by rpc service"),
         )
+        .arg(
+            Arg::with_name("rpc_rate_limit")
+                .long("rpc-rate-limit")
+                .value_name("COST_PER_SECOND")
+                .takes_value(true)
+                .validator(|s| is_within_range(s, 1..=usize::MAX))
+                .requires("rpc_rate_limit_trust_forwarded_for")
+                .help("Limit the cost of the RPC calls of each client to COST_PER_SECOND. \
+                       Requires --rpc-rate-limit-trust-forwarded-for, as the clients are \
+                       identified by their X-Forwarded-For header. A call costs 1 unless \
+                       configured otherwise with --rpc-rate-limit-method-cost; \
+                       getProgramAccounts, getProgramAccountsPaginated, getTokenAccountsByMint, \
+                       getTokenAccountsByOwner, getTokenAccountsByDelegate, \
+                       getTokenLargestAccounts and simulateBundle calls cost 100, \
+                       getSignaturesForAddress and getBlock calls cost 10 by default"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_trust_forwarded_for")
+                .long("rpc-rate-limit-trust-forwarded-for")
+                .takes_value(false)
+                .requires("rpc_rate_limit")
+                .help("Identify the clients of the --rpc-rate-limit by the last address of \
+                       the X-Forwarded-For header of their requests. Only use behind a proxy \
+                       setting that header, as clients can otherwise spoof it. The requests \
+                       without that header share the same limit"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_method_costs")
+                .long("rpc-rate-limit-method-cost")
+                .value_name("METHOD:COST")
+                .takes_value(true)
+                .multiple(true)
+                .requires("rpc_rate_limit")
+                .validator(method_limit_validator)
+                .help("Cost of a call to METHOD, counted against the --rpc-rate-limit of \
+                       the client"),
+        )
+        .arg(
+            Arg::with_name("rpc_rate_limit_method_calls")
+                .long("rpc-rate-limit-method-calls")
+                .value_name("METHOD:CALLS_PER_SECOND")
+                .takes_value(true)
+                .multiple(true)
+                .requires("rpc_rate_limit")
+                .validator(method_calls_limit_validator)
+                .help("Limit the calls to METHOD of each client to CALLS_PER_SECOND, on top \
+                       of the --rpc-rate-limit"),
+        )
         .arg(
             Arg::with_name("enable_accountsdb_repl")
                 .long("enable-accountsdb-repl")
@@ -1171,6 +1243,26 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
+        .arg(
+            Arg::with_name("account_index_program_offsets")
+                .long("account-index-program-offset")
+                .takes_value(true)
+                .multiple(true)
+                .validator(program_offset_validator)
+                .value_name("PROGRAM_ID:OFFSET")
+                .help("Enable an accounts index of the accounts owned by PROGRAM_ID, indexed by \
+                       the pubkey stored at byte OFFSET of the account data. \
+                       getProgramAccounts requests with a memcmp filter at that offset are \
+                       served from the index"),
+        )
+        .arg(
+            Arg::with_name("account_index_persist")
+                .long("account-index-persist")
+                .takes_value(false)
+                .help("Persist the account indexes with each full snapshot, and restore them \
+                       instead of rebuilding them from every account when starting from that \
+                       snapshot"),
+        )
         .arg(
             Arg::with_name("account_index_exclude_key")
                 .long(EXCLUDE_KEY)
@@ -1202,6 +1294,22 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Create ancient storages in one shot instead of appending.")
                 .hidden(hidden_unless_forced()),
             )
+        .arg(
+            Arg::with_name("accounts_db_create_ancient_storage_cold")
+                .long("accounts-db-create-ancient-storage-cold")
+                .conflicts_with("accounts_db_create_ancient_storage_packed")
+                .help("Create ancient storages in one shot using the compressed cold tiered storage format.")
+                .hidden(hidden_unless_forced()),
+            )
+        .arg(
+            Arg::with_name("accounts_db_accounts_file_provider")
+                .long("accounts-db-accounts-file-provider")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(AccountsFileProvider::cli_names())
+                .help(AccountsFileProvider::cli_message())
+                .hidden(hidden_unless_forced()),
+        )
         .arg(
             Arg::with_name("accounts_db_ancient_append_vecs")
                 .long("accounts-db-ancient-append-vecs")
@@ -1457,6 +1565,34 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .help("Output display mode")
                 )
         )
+        .subcommand(
+            SubCommand::with_name("dump-crds")
+                .about("Dump all the gossip values known to the validator to a file")
+                .after_help("The dump can be inspected with \
+                         `solana-gossip analyze-crds` and `solana-gossip diff-crds`")
+                .arg(
+                    Arg::with_name("path")
+                        .index(1)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Output file"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("fork-choice")
+                .about("Display a snapshot of the validator's fork choice state and tower")
+                .after_help("The snapshot can be rendered with `solana-ledger-tool fork-choice-graph`")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .default_value("json")
+                        .help("Output display mode")
+                )
+        )
         .subcommand(
             SubCommand::with_name("repair-whitelist")
                 .about("Manage the validator's repair protocol whitelist")
@@ -1573,6 +1709,55 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .after_help("Note: the new identity only applies to the \
                          currently running validator instance")
         )
+        .subcommand(
+            SubCommand::with_name("export-tower")
+                .about("Stop voting and export the tower, to hand the validator identity over \
+                        to a spare validator with the import-tower command")
+                .arg(
+                    Arg::with_name("identity")
+                        .index(1)
+                        .value_name("KEYPAIR")
+                        .required(true)
+                        .takes_value(true)
+                        .validator(is_keypair)
+                        .help("Path to the keypair of the new, non-voting, validator identity")
+                )
+                .arg(
+                    Arg::with_name("tower_file")
+                        .index(2)
+                        .value_name("FILE")
+                        .required(true)
+                        .takes_value(true)
+                        .help("File to write the tower of the current validator identity to")
+                )
+                .after_help("Note: the validator stops voting as soon as its identity is \
+                         changed, and the exported tower includes its last vote")
+        )
+        .subcommand(
+            SubCommand::with_name("import-tower")
+                .about("Import the tower exported by the export-tower command of another \
+                        validator and set the validator identity")
+                .arg(
+                    Arg::with_name("identity")
+                        .index(1)
+                        .value_name("KEYPAIR")
+                        .required(true)
+                        .takes_value(true)
+                        .validator(is_keypair)
+                        .help("Path to validator identity keypair")
+                )
+                .arg(
+                    Arg::with_name("tower_file")
+                        .index(2)
+                        .value_name("FILE")
+                        .required(true)
+                        .takes_value(true)
+                        .help("File holding the tower exported for the validator identity")
+                )
+                .after_help("Note: the tower is rejected if it is older than the tower of \
+                         this validator for the identity, or if voting with it would violate \
+                         the lockouts of that tower")
+        )
         .subcommand(
             SubCommand::with_name("set-log-filter")
                 .about("Adjust the validator log filter")
@@ -2035,6 +2220,39 @@ pub fn port_range_validator(port_range: String) -> Result<(), String> {
     }
 }
 
+/// Parses a `PROGRAM_ID:OFFSET` program offset account index
+pub fn parse_program_offset(program_offset: &str) -> Result<(Pubkey, usize), String> {
+    let (program_id, offset) = program_offset
+        .split_once(':')
+        .ok_or_else(|| format!("Expected PROGRAM_ID:OFFSET, got {program_offset}"))?;
+    let program_id = Pubkey::from_str(program_id).map_err(|e| format!("{e:?}"))?;
+    let offset = offset.parse::<usize>().map_err(|e| format!("{e:?}"))?;
+    Ok((program_id, offset))
+}
+
+fn program_offset_validator(program_offset: String) -> Result<(), String> {
+    parse_program_offset(&program_offset).map(|_| ())
+}
+
+pub fn parse_method_limit(method_limit: &str) -> Result<(String, u64), String> {
+    let (method, limit) = method_limit
+        .rsplit_once(':')
+        .ok_or_else(|| format!("Expected METHOD:LIMIT, got {method_limit}"))?;
+    let limit = limit.parse::<u64>().map_err(|e| format!("{e:?}"))?;
+    Ok((method.to_string(), limit))
+}
+
+fn method_limit_validator(method_limit: String) -> Result<(), String> {
+    parse_method_limit(&method_limit).map(|_| ())
+}
+
+fn method_calls_limit_validator(method_limit: String) -> Result<(), String> {
+    match parse_method_limit(&method_limit)? {
+        (_, 0) => Err(format!("Expected a non-zero limit, got {method_limit}")),
+        _ => Ok(()),
+    }
+}
+
 fn hash_validator(hash: String) -> Result<(), String> {
     Hash::from_str(&hash)
         .map(|_| ())
@@ -2211,6 +2429,20 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                        Upgrade authority set to \"none\" disables upgrades",
                 ),
         )
+        .arg(
+            Arg::with_name("bpf_program_v4")
+                .long("bpf-program-v4")
+                .value_names(&["ADDRESS_OR_KEYPAIR", "SBF_PROGRAM.SO", "AUTHORITY"])
+                .takes_value(true)
+                .number_of_values(3)
+                .multiple(true)
+                .help(
+                    "Add a SBF program owned by the v4 loader to the genesis configuration. \
+                       If the ledger already exists then this parameter is silently ignored. \
+                       First and third arguments can be a pubkey string or path to a keypair. \
+                       Authority set to \"none\" deploys the program as finalized",
+                ),
+        )
         .arg(
             Arg::with_name("account")
                 .long("account")
@@ -12,6 +12,7 @@ use {
             AccountShrinkThreshold, AccountsDb, AccountsDbConfig, CreateAncientStorage,
             FillerAccountsConfig,
         },
+        accounts_file::AccountsFileProvider,
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
             AccountsIndexConfig, IndexLimitMb,
@@ -43,6 +44,7 @@ use {
     solana_rpc::{
         rpc::{JsonRpcConfig, RpcBigtableConfig},
         rpc_pubsub_service::PubSubConfig,
+        rpc_rate_limiter::RpcRateLimitConfig,
     },
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::config::RpcLeaderScheduleConfig,
@@ -69,7 +71,10 @@ use {
         admin_rpc_service,
         admin_rpc_service::{load_staked_nodes_overrides, StakedNodesOverrides},
         bootstrap,
-        cli::{app, warn_for_deprecated_arguments, DefaultArgs},
+        cli::{
+            app, parse_method_limit, parse_program_offset, warn_for_deprecated_arguments,
+            DefaultArgs,
+        },
         dashboard::Dashboard,
         ledger_lockfile, lock_ledger, new_spinner_progress_bar, println_name_value,
         redirect_stderr_to_file,
@@ -642,6 +647,45 @@ pub fn main() {
             }
             return;
         }
+        ("dump-crds", Some(subcommand_matches)) => {
+            // the validator process writes the file, possibly from another working directory
+            let path = env::current_dir()
+                .unwrap()
+                .join(subcommand_matches.value_of("path").unwrap());
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let num_values = admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .dump_crds(path.display().to_string())
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    eprintln!("dumpCrds request failed: {err}");
+                    exit(1);
+                });
+            println!("Wrote {num_values} gossip values");
+            return;
+        }
+        ("fork-choice", Some(subcommand_matches)) => {
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let fork_choice_snapshot = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.fork_choice_snapshot().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Fork choice snapshot query failed: {err}");
+                    exit(1);
+                });
+            match subcommand_matches.value_of("output") {
+                Some("json-compact") => {
+                    print!("{}", serde_json::to_string(&fork_choice_snapshot).unwrap())
+                }
+                _ => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&fork_choice_snapshot).unwrap()
+                ),
+            }
+            return;
+        }
         ("init", _) => Operation::Initialize,
         ("exit", Some(subcommand_matches)) => {
             let min_idle_time = value_t_or_exit!(subcommand_matches, "min_idle_time", usize);
@@ -759,6 +803,63 @@ pub fn main() {
 
             return;
         }
+        ("export-tower", Some(subcommand_matches)) => {
+            let identity_keypair = value_t_or_exit!(subcommand_matches, "identity", String);
+            let identity_keypair = fs::canonicalize(&identity_keypair).unwrap_or_else(|err| {
+                println!("Unable to access path: {identity_keypair}: {err:?}");
+                exit(1);
+            });
+            let tower_file = value_t_or_exit!(subcommand_matches, "tower_file", PathBuf);
+
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let exported_tower = admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .export_tower_and_set_identity(identity_keypair.display().to_string())
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("exportTowerAndSetIdentity request failed: {err}");
+                    exit(1);
+                });
+            fs::write(&tower_file, exported_tower).unwrap_or_else(|err| {
+                println!("Unable to write {}: {err}", tower_file.display());
+                exit(1);
+            });
+            println!("Voting stopped, tower written to {}", tower_file.display());
+            return;
+        }
+        ("import-tower", Some(subcommand_matches)) => {
+            let identity_keypair = value_t_or_exit!(subcommand_matches, "identity", String);
+            let identity_keypair = fs::canonicalize(&identity_keypair).unwrap_or_else(|err| {
+                println!("Unable to access path: {identity_keypair}: {err:?}");
+                exit(1);
+            });
+            let tower_file = value_t_or_exit!(subcommand_matches, "tower_file", PathBuf);
+            let exported_tower = fs::read(&tower_file).unwrap_or_else(|err| {
+                println!("Unable to read {}: {err}", tower_file.display());
+                exit(1);
+            });
+
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .import_tower_and_set_identity(
+                            identity_keypair.display().to_string(),
+                            exported_tower,
+                        )
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("importTowerAndSetIdentity request failed: {err}");
+                    exit(1);
+                });
+            println!("Tower imported, identity set");
+            return;
+        }
         ("set-log-filter", Some(subcommand_matches)) => {
             let filter = value_t_or_exit!(subcommand_matches, "filter", String);
             let admin_client = admin_rpc_service::connect(&ledger_path);
@@ -1092,14 +1193,20 @@ pub fn main() {
         .ok()
         .or_else(|| get_cluster_shred_version(&entrypoint_addrs));
 
-    let tower_storage: Arc<dyn tower_storage::TowerStorage> =
-        match value_t_or_exit!(matches, "tower_storage", String).as_str() {
+    let mut tower_storages: Vec<Arc<dyn tower_storage::TowerStorage>> = vec![];
+    let mut tower_storage_kinds = values_t_or_exit!(matches, "tower_storage", String);
+    tower_storage_kinds.sort();
+    tower_storage_kinds.dedup();
+    for tower_storage_kind in tower_storage_kinds {
+        match tower_storage_kind.as_str() {
             "file" => {
-                let tower_path = value_t!(matches, "tower", PathBuf)
+                let tower_paths = values_t!(matches, "tower", PathBuf)
                     .ok()
-                    .unwrap_or_else(|| ledger_path.clone());
+                    .unwrap_or_else(|| vec![ledger_path.clone()]);
 
-                Arc::new(tower_storage::FileTowerStorage::new(tower_path))
+                for tower_path in tower_paths {
+                    tower_storages.push(Arc::new(tower_storage::FileTowerStorage::new(tower_path)));
+                }
             }
             "etcd" => {
                 let endpoints = values_t_or_exit!(matches, "etcd_endpoint", String);
@@ -1122,16 +1229,34 @@ pub fn main() {
                     identity_private_key: read(identity_private_key_file),
                 };
 
-                Arc::new(
+                tower_storages.push(Arc::new(
                     tower_storage::EtcdTowerStorage::new(endpoints, Some(tls_config))
                         .unwrap_or_else(|err| {
                             eprintln!("Failed to connect to etcd: {err}");
                             exit(1);
                         }),
-                )
+                ));
             }
             _ => unreachable!(),
-        };
+        }
+    }
+    let tower_storage: Arc<dyn tower_storage::TowerStorage> = if tower_storages.len() == 1 {
+        tower_storages.pop().unwrap()
+    } else {
+        let write_quorum =
+            value_t!(matches, "tower_write_quorum", usize).unwrap_or(tower_storages.len());
+        if write_quorum == 0 || write_quorum > tower_storages.len() {
+            eprintln!(
+                "--tower-write-quorum must be between 1 and the number of tower storages ({})",
+                tower_storages.len()
+            );
+            exit(1);
+        }
+        Arc::new(tower_storage::QuorumTowerStorage::new(
+            tower_storages,
+            write_quorum,
+        ))
+    };
 
     let mut accounts_index_config = AccountsIndexConfig {
         started_from_validator: true, // this is the only place this is set
@@ -1195,11 +1320,21 @@ pub fn main() {
             .map(|mb| mb * MB as u64),
         ancient_append_vec_offset: value_t!(matches, "accounts_db_ancient_append_vecs", i64).ok(),
         exhaustively_verify_refcounts: matches.is_present("accounts_db_verify_refcounts"),
-        create_ancient_storage: matches
-            .is_present("accounts_db_create_ancient_storage_packed")
-            .then_some(CreateAncientStorage::Pack)
-            .unwrap_or_default(),
+        create_ancient_storage: if matches.is_present("accounts_db_create_ancient_storage_cold") {
+            CreateAncientStorage::PackCold
+        } else if matches.is_present("accounts_db_create_ancient_storage_packed") {
+            CreateAncientStorage::Pack
+        } else {
+            CreateAncientStorage::default()
+        },
         test_partitioned_epoch_rewards,
+        accounts_file_provider: value_t!(
+            matches,
+            "accounts_db_accounts_file_provider",
+            AccountsFileProvider
+        )
+        .unwrap_or_default(),
+        persist_secondary_indexes: matches.is_present("account_index_persist"),
         ..AccountsDbConfig::default()
     };
 
@@ -1231,11 +1366,38 @@ pub fn main() {
             timeout: value_t!(matches, "rpc_bigtable_timeout", u64)
                 .ok()
                 .map(Duration::from_secs),
+            file_system_path: matches
+                .value_of("rpc_bigtable_file_system_path")
+                .map(PathBuf::from),
         })
     } else {
         None
     };
 
+    let rpc_rate_limit_config = if matches.is_present("rpc_rate_limit") {
+        let mut config = RpcRateLimitConfig::new(value_t_or_exit!(matches, "rpc_rate_limit", u64));
+        for value in matches
+            .values_of("rpc_rate_limit_method_costs")
+            .unwrap_or_default()
+        {
+            let (method, cost) = parse_method_limit(value).unwrap();
+            config.method_costs.insert(method, cost);
+        }
+        for value in matches
+            .values_of("rpc_rate_limit_method_calls")
+            .unwrap_or_default()
+        {
+            let (method, calls_per_second) = parse_method_limit(value).unwrap();
+            config
+                .method_calls_per_second
+                .insert(method, calls_per_second);
+        }
+        config.trust_forwarded_for = matches.is_present("rpc_rate_limit_trust_forwarded_for");
+        Some(config)
+    } else {
+        None
+    };
+
     let rpc_send_retry_rate_ms = value_t_or_exit!(matches, "rpc_send_transaction_retry_ms", u64);
     let rpc_send_batch_size = value_t_or_exit!(matches, "rpc_send_transaction_batch_size", usize);
     let rpc_send_batch_send_rate_ms =
@@ -1302,6 +1464,7 @@ pub fn main() {
                 "rpc_max_request_body_size",
                 usize
             )),
+            rpc_rate_limit_config,
         },
         on_start_geyser_plugin_config_files,
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
@@ -1906,6 +2069,15 @@ fn process_account_indexes(matches: &ArgMatches) -> AccountSecondaryIndexes {
             "spl-token-owner" => AccountIndex::SplTokenOwner,
             _ => unreachable!(),
         })
+        .chain(
+            matches
+                .values_of("account_index_program_offsets")
+                .unwrap_or_default()
+                .map(|value| {
+                    let (program_id, offset) = parse_program_offset(value).unwrap();
+                    AccountIndex::ProgramOffset { program_id, offset }
+                }),
+        )
         .collect();
 
     let account_indexes_include_keys: HashSet<Pubkey> =